@@ -11,6 +11,7 @@ use rust_embed::RustEmbed;
 #[include = "images/**/*"]
 #[include = "themes/**/*"]
 #[exclude = "themes/src/*"]
+#[include = "dictionaries/**/*"]
 #[include = "sounds/**/*"]
 #[include = "prompts/**/*"]
 #[include = "*.md"]
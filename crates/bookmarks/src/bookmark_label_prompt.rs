@@ -0,0 +1,147 @@
+use editor::Editor;
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Styled, div,
+    prelude::*,
+};
+use language::Buffer;
+use project::bookmark_store::BookmarkStore;
+use theme::ActiveTheme;
+use ui::prelude::*;
+use workspace::ModalView;
+
+/// A prompt for naming the bookmark on the line containing the newest cursor, adding the bookmark
+/// if the line doesn't have one yet.
+pub struct BookmarkLabelPrompt {
+    label_editor: Entity<Editor>,
+    active_editor: Entity<Editor>,
+    bookmark_store: Entity<BookmarkStore>,
+    buffer: Entity<Buffer>,
+    position: text::Anchor,
+}
+
+impl ModalView for BookmarkLabelPrompt {}
+
+impl Focusable for BookmarkLabelPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.label_editor.focus_handle(cx)
+    }
+}
+impl EventEmitter<DismissEvent> for BookmarkLabelPrompt {}
+
+impl BookmarkLabelPrompt {
+    pub(crate) fn register(
+        editor: &mut Editor,
+        _window: Option<&mut Window>,
+        cx: &mut Context<Editor>,
+    ) {
+        let handle = cx.entity().downgrade();
+        editor
+            .register_action(move |_: &editor::actions::EditBookmarkLabel, window, cx| {
+                let Some(editor_handle) = handle.upgrade() else {
+                    return;
+                };
+                let editor = editor_handle.read(cx);
+                let Some(workspace) = editor.workspace() else {
+                    return;
+                };
+                let Some(bookmark_store) = editor.bookmark_store() else {
+                    return;
+                };
+                let Some((buffer, position)) = editor.bookmark_position_at_cursor(cx) else {
+                    return;
+                };
+                workspace.update(cx, |workspace, cx| {
+                    workspace.toggle_modal(window, cx, move |window, cx| {
+                        BookmarkLabelPrompt::new(
+                            editor_handle,
+                            bookmark_store,
+                            buffer,
+                            position,
+                            window,
+                            cx,
+                        )
+                    });
+                })
+            })
+            .detach();
+    }
+
+    fn new(
+        active_editor: Entity<Editor>,
+        bookmark_store: Entity<BookmarkStore>,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let current_label = bookmark_store
+            .read(cx)
+            .bookmark_at(&buffer, position, cx)
+            .and_then(|bookmark| bookmark.label.clone());
+        let label_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Bookmark label", cx);
+            if let Some(label) = current_label {
+                editor.set_text(label.to_string(), window, cx);
+                editor.select_all(&editor::actions::SelectAll, window, cx);
+            }
+            editor
+        });
+
+        Self {
+            label_editor,
+            active_editor,
+            bookmark_store,
+            buffer,
+            position,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let label = self.label_editor.read(cx).text(cx);
+        self.bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.set_bookmark_label(
+                self.buffer.clone(),
+                self.position,
+                Some(label.into()),
+                cx,
+            )
+        });
+        self.active_editor.update(cx, |editor, cx| {
+            editor.focus_handle(cx).focus(window);
+        });
+
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for BookmarkLabelPrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let row = self
+            .position
+            .summary::<text::Point>(&self.buffer.read(cx).snapshot())
+            .row;
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("BookmarkLabelPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.label_editor.clone()),
+            )
+            .child(h_flex().px_2().py_1().gap_1().child(
+                Label::new(format!("Label bookmark on line {}", row + 1)).color(Color::Muted),
+            ))
+    }
+}
@@ -0,0 +1,293 @@
+mod bookmark_label_prompt;
+
+use bookmark_label_prompt::BookmarkLabelPrompt;
+use editor::Editor;
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ParentElement,
+    Render, Styled, Task, WeakEntity, Window, actions,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use text::Point;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(
+    bookmarks,
+    [
+        /// Toggles the picker listing all bookmarks in the project.
+        Toggle
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(BookmarksPicker::register).detach();
+    cx.observe_new(BookmarkLabelPrompt::register).detach();
+}
+
+pub struct BookmarksPicker {
+    picker: Entity<Picker<BookmarksPickerDelegate>>,
+}
+
+impl BookmarksPicker {
+    fn register(
+        workspace: &mut Workspace,
+        _window: Option<&mut Window>,
+        _: &mut Context<Workspace>,
+    ) {
+        workspace.register_action(|workspace, _: &Toggle, window, cx| {
+            let weak_workspace = cx.entity().downgrade();
+            let entries = bookmark_entries(workspace, cx);
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                BookmarksPicker::new(weak_workspace, entries, window, cx)
+            });
+        });
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        entries: Vec<BookmarkEntry>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = BookmarksPickerDelegate::new(cx.entity().downgrade(), workspace, entries);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+impl Render for BookmarksPicker {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("BookmarksPicker")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for BookmarksPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarksPicker {}
+impl ModalView for BookmarksPicker {}
+
+struct BookmarkEntry {
+    buffer: Entity<Buffer>,
+    row: u32,
+    label: Option<Arc<str>>,
+    line_text: String,
+    location: String,
+}
+
+fn bookmark_entries(workspace: &Workspace, cx: &App) -> Vec<BookmarkEntry> {
+    let bookmark_store = workspace.project().read(cx).bookmark_store();
+    bookmark_store
+        .read(cx)
+        .all_bookmarks()
+        .map(|(path, buffer, bookmark)| {
+            let buffer_ref = buffer.read(cx);
+            let snapshot = buffer_ref.snapshot();
+            let row = bookmark.position.summary::<Point>(&snapshot).row;
+            let line_text = snapshot
+                .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+                .collect::<String>()
+                .trim()
+                .to_string();
+            let file_path = buffer_ref
+                .file()
+                .map(|file| file.full_path(cx))
+                .unwrap_or_else(|| path.to_path_buf());
+            BookmarkEntry {
+                buffer: buffer.clone(),
+                row,
+                label: bookmark.label.clone(),
+                line_text,
+                location: format!("{}:{}", file_path.display(), row + 1),
+            }
+        })
+        .collect()
+}
+
+pub struct BookmarksPickerDelegate {
+    bookmarks_picker: WeakEntity<BookmarksPicker>,
+    workspace: WeakEntity<Workspace>,
+    entries: Vec<BookmarkEntry>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl BookmarksPickerDelegate {
+    fn new(
+        bookmarks_picker: WeakEntity<BookmarksPicker>,
+        workspace: WeakEntity<Workspace>,
+        entries: Vec<BookmarkEntry>,
+    ) -> Self {
+        let candidates = entries
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, entry)| {
+                StringMatchCandidate::new(candidate_id, &Self::entry_title(entry))
+            })
+            .collect();
+        Self {
+            bookmarks_picker,
+            workspace,
+            entries,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    fn entry_title(entry: &BookmarkEntry) -> String {
+        match &entry.label {
+            Some(label) => label.to_string(),
+            None => entry.line_text.clone(),
+        }
+    }
+}
+
+impl PickerDelegate for BookmarksPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Search bookmarks…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No bookmarks in this project".into())
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let entry = &self.entries[mat.candidate_id];
+            let buffer = entry.buffer.clone();
+            let point = Point::new(entry.row, 0);
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    let pane = workspace.active_pane().clone();
+                    let editor =
+                        workspace.open_project_item::<Editor>(pane, buffer, true, true, window, cx);
+                    editor.update(cx, |editor, cx| {
+                        editor.go_to_singleton_buffer_point(point, window, cx);
+                    });
+                })
+                .log_err();
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.bookmarks_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                let mut matches = match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await;
+                // Keep bookmarks in path order rather than by score, like the gutter shows them.
+                matches.sort_unstable_by_key(|mat| mat.candidate_id);
+                matches
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let entry = &self.entries[mat.candidate_id];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(
+                    Icon::new(IconName::Bookmark)
+                        .size(IconSize::Small)
+                        .color(Color::Accent),
+                )
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            mat.string.clone(),
+                            mat.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(entry.location.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        ),
+                ),
+        )
+    }
+}
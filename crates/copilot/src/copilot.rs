@@ -1403,9 +1403,21 @@ mod tests {
             unimplemented!()
         }
 
+        fn load_with_encoding(&self, _: fs::Encoding, _: &App) -> Task<Result<String>> {
+            unimplemented!()
+        }
+
         fn load_bytes(&self, _cx: &App) -> Task<Result<Vec<u8>>> {
             unimplemented!()
         }
+
+        fn load_large_with_encoding(
+            &self,
+            _: fs::Encoding,
+            _: &App,
+        ) -> Task<Result<(language::Rope, language::LineEnding)>> {
+            unimplemented!()
+        }
     }
 }
 
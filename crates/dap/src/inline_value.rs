@@ -2,6 +2,8 @@
 pub enum VariableLookupKind {
     Variable,
     Expression,
+    /// The value is already known (e.g. provided by a language server) and is displayed as is.
+    Text(String),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
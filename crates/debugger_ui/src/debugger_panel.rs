@@ -7,7 +7,8 @@ use crate::{
     ClearAllBreakpoints, Continue, CopyDebugAdapterArguments, Detach, FocusBreakpointList,
     FocusConsole, FocusFrames, FocusLoadedSources, FocusModules, FocusTerminal, FocusVariables,
     NewProcessModal, NewProcessMode, Pause, RerunSession, StepInto, StepOut, StepOver, Stop,
-    ToggleExpandItem, ToggleSessionPicker, ToggleThreadPicker, persistence, spawn_task_or_modal,
+    ToggleExpandItem, ToggleSessionPicker, ToggleSessionRecording, ToggleThreadPicker, persistence,
+    spawn_task_or_modal,
 };
 use anyhow::{Context as _, Result, anyhow};
 use collections::IndexMap;
@@ -682,6 +683,8 @@ impl DebugPanel {
                                     let capabilities = running_state.read(cx).capabilities(cx);
                                     let supports_detach =
                                         running_state.read(cx).session().read(cx).is_attached();
+                                    let is_recording = running_state.read(cx).is_recording();
+                                    let can_record = running_state.read(cx).can_record(cx);
 
                                     this.map(|this| {
                                         if thread_status == ThreadStatus::Running {
@@ -877,37 +880,69 @@ impl DebugPanel {
                                                 }
                                             }),
                                     )
+                                    .when(supports_detach, |div| {
+                                        div.child(
+                                            IconButton::new(
+                                                "debug-disconnect",
+                                                IconName::DebugDetach,
+                                            )
+                                            .disabled(
+                                                thread_status != ThreadStatus::Stopped
+                                                    && thread_status != ThreadStatus::Running,
+                                            )
+                                            .icon_size(IconSize::Small)
+                                            .on_click(window.listener_for(
+                                                running_state,
+                                                |this, _, _, cx| {
+                                                    this.detach_client(cx);
+                                                },
+                                            ))
+                                            .tooltip({
+                                                let focus_handle = focus_handle.clone();
+                                                move |window, cx| {
+                                                    Tooltip::for_action_in(
+                                                        "Detach",
+                                                        &Detach,
+                                                        &focus_handle,
+                                                        window,
+                                                        cx,
+                                                    )
+                                                }
+                                            }),
+                                        )
+                                    })
                                     .when(
-                                        supports_detach,
+                                        is_recording || can_record,
                                         |div| {
                                             div.child(
-                                                IconButton::new(
-                                                    "debug-disconnect",
-                                                    IconName::DebugDetach,
-                                                )
-                                                .disabled(
-                                                    thread_status != ThreadStatus::Stopped
-                                                        && thread_status != ThreadStatus::Running,
-                                                )
-                                                .icon_size(IconSize::Small)
-                                                .on_click(window.listener_for(
-                                                    running_state,
-                                                    |this, _, _, cx| {
-                                                        this.detach_client(cx);
-                                                    },
-                                                ))
-                                                .tooltip({
-                                                    let focus_handle = focus_handle.clone();
-                                                    move |window, cx| {
-                                                        Tooltip::for_action_in(
-                                                            "Detach",
-                                                            &Detach,
-                                                            &focus_handle,
-                                                            window,
-                                                            cx,
-                                                        )
-                                                    }
-                                                }),
+                                                IconButton::new("debug-record", IconName::Circle)
+                                                    .icon_size(IconSize::Small)
+                                                    .toggle_state(is_recording)
+                                                    .when(is_recording, |this| {
+                                                        this.icon_color(Color::Error)
+                                                    })
+                                                    .on_click(window.listener_for(
+                                                        running_state,
+                                                        |this, _, window, cx| {
+                                                            this.toggle_recording(window, cx);
+                                                        },
+                                                    ))
+                                                    .tooltip({
+                                                        let focus_handle = focus_handle.clone();
+                                                        move |window, cx| {
+                                                            Tooltip::for_action_in(
+                                                                if is_recording {
+                                                                    "Stop Recording Session"
+                                                                } else {
+                                                                    "Record Session"
+                                                                },
+                                                                &ToggleSessionRecording,
+                                                                &focus_handle,
+                                                                window,
+                                                                cx,
+                                                            )
+                                                        }
+                                                    }),
                                             )
                                         },
                                     )
@@ -1787,7 +1822,7 @@ impl Render for DebugPanel {
                                 .child(Label::new("Breakpoints").size(LabelSize::Small))
                                 .child(
                                     h_flex().visible_on_hover("base-breakpoint-list").child(
-                                        self.breakpoint_list.read(cx).render_control_strip(),
+                                        self.breakpoint_list.read(cx).render_control_strip(cx),
                                     ),
                                 ),
                         )
@@ -1,12 +1,19 @@
 use std::any::TypeId;
 
+use anyhow::Context as _;
 use dap::debugger_settings::DebuggerSettings;
 use debugger_panel::DebugPanel;
 use editor::Editor;
-use gpui::{Action, App, DispatchPhase, EntityInputHandler, actions};
+use gpui::{
+    Action, App, AppContext as _, DispatchPhase, EntityInputHandler, PathPromptOptions, actions,
+};
 use new_process_modal::{NewProcessModal, NewProcessMode};
 use onboarding_modal::DebuggerOnboardingModal;
-use project::debugger::{self, breakpoint_store::SourceBreakpoint, session::ThreadStatus};
+use project::{
+    DirectoryLister,
+    debugger::{self, breakpoint_store::SourceBreakpoint, session::ThreadStatus},
+};
+use recording::SessionReplay;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use session::DebugSession;
@@ -25,6 +32,7 @@ mod dropdown_menus;
 mod new_process_modal;
 mod onboarding_modal;
 mod persistence;
+mod recording;
 pub(crate) mod session;
 mod stack_trace_view;
 
@@ -52,6 +60,10 @@ actions!(
         StepOver,
         /// Steps out of the current function.
         StepOut,
+        /// Steps over the current machine instruction.
+        StepOverInstruction,
+        /// Steps into the current machine instruction.
+        StepIntoInstruction,
         /// Steps back to the previous statement.
         StepBack,
         /// Stops the debugging session.
@@ -89,6 +101,10 @@ actions!(
         /// When toggled on, only frames from the user's code are shown
         /// When toggled off, all frames are shown
         ToggleUserFrames,
+        /// Starts or stops recording the state of the debug session at each stop to a file.
+        ToggleSessionRecording,
+        /// Opens a debug session recording to inspect the stops captured in it.
+        OpenSessionRecording,
     ]
 );
 
@@ -148,6 +164,7 @@ pub fn init(cx: &mut App) {
             .register_action(|workspace, _: &OpenOnboardingModal, window, cx| {
                 DebuggerOnboardingModal::toggle(workspace, window, cx)
             })
+            .register_action(open_session_recording)
             .register_action_renderer(|div, workspace, _, cx| {
                 let Some(debug_panel) = workspace.panel::<DebugPanel>(cx) else {
                     return div;
@@ -191,6 +208,22 @@ pub fn init(cx: &mut App) {
                             active_item.update(cx, |item, cx| item.step_over(cx)).ok();
                         }
                     })
+                    .on_action({
+                        let active_item = active_item.clone();
+                        move |_: &StepOverInstruction, _, cx| {
+                            active_item
+                                .update(cx, |item, cx| item.step_over_instruction(cx))
+                                .ok();
+                        }
+                    })
+                    .on_action({
+                        let active_item = active_item.clone();
+                        move |_: &StepIntoInstruction, _, cx| {
+                            active_item
+                                .update(cx, |item, cx| item.step_into_instruction(cx))
+                                .ok();
+                        }
+                    })
                     .on_action({
                         let active_item = active_item.clone();
                         move |_: &StepOut, _, cx| {
@@ -269,6 +302,14 @@ pub fn init(cx: &mut App) {
                             .ok();
                     }
                 })
+                .on_action({
+                    let active_item = active_item.clone();
+                    move |_: &ToggleSessionRecording, window, cx| {
+                        active_item
+                            .update(cx, |item, cx| item.toggle_recording(window, cx))
+                            .ok();
+                    }
+                })
                 .on_action({
                     let active_item = active_item.clone();
                     move |_: &Stop, _, cx| {
@@ -385,6 +426,59 @@ pub fn init(cx: &mut App) {
                         },
                     );
 
+                    window.on_action_when(
+                        session.any_stopped_thread()
+                            && session
+                                .capabilities()
+                                .supports_goto_targets_request
+                                .unwrap_or_default(),
+                        TypeId::of::<editor::actions::SetNextStatement>(),
+                        {
+                            let editor = editor.clone();
+                            let active_session = active_session.clone();
+                            move |_, phase, _, cx| {
+                                if phase != DispatchPhase::Bubble {
+                                    return;
+                                }
+                                maybe!({
+                                    let (buffer, position, _) = editor
+                                        .update(cx, |editor, cx| {
+                                            let cursor_point: language::Point =
+                                                editor.selections.newest(cx).head();
+
+                                            editor
+                                                .buffer()
+                                                .read(cx)
+                                                .point_to_buffer_point(cursor_point, cx)
+                                        })
+                                        .ok()??;
+
+                                    let path =
+                                debugger::breakpoint_store::BreakpointStore::abs_path_from_buffer(
+                                    &buffer, cx,
+                                )?;
+
+                                    active_session.update(cx, |session, cx| {
+                                        session.running_state().update(cx, |state, cx| {
+                                            if let Some(thread_id) = state.selected_thread_id() {
+                                                state.session().update(cx, |session, cx| {
+                                                    session.goto_position(
+                                                        path,
+                                                        position.row,
+                                                        thread_id,
+                                                        cx,
+                                                    );
+                                                })
+                                            }
+                                        });
+                                    });
+
+                                    Some(())
+                                });
+                            }
+                        },
+                    );
+
                     window.on_action(
                         TypeId::of::<editor::actions::EvaluateSelectedText>(),
                         move |_, _, window, cx| {
@@ -423,6 +517,41 @@ pub fn init(cx: &mut App) {
     .detach();
 }
 
+fn open_session_recording(
+    workspace: &mut Workspace,
+    _: &OpenSessionRecording,
+    window: &mut ui::Window,
+    cx: &mut ui::Context<Workspace>,
+) {
+    let fs = workspace.app_state().fs.clone();
+    let paths = workspace.prompt_for_open_path(
+        PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: None,
+        },
+        DirectoryLister::Local(workspace.project().clone(), fs.clone()),
+        window,
+        cx,
+    );
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let Some(path) = paths.await.ok().flatten().into_iter().flatten().next() else {
+            return Ok(());
+        };
+        let recording = recording::load_recording(&path, fs)
+            .await
+            .with_context(|| format!("loading debug session recording from {path:?}"))?;
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            let replay = cx.new(|cx| SessionReplay::new(recording, path, cx));
+            workspace.add_item_to_active_pane(Box::new(replay), None, true, window, cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
 fn spawn_task_or_modal(
     workspace: &mut Workspace,
     action: &Spawn,
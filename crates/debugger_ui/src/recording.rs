@@ -0,0 +1,500 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use gpui::{
+    AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, MouseButton, Stateful, Task,
+    UniformListScrollHandle, uniform_list,
+};
+use project::{
+    Fs,
+    debugger::{
+        recording::{SessionRecording, StopSnapshot, VariableSnapshot},
+        session::{Session, ThreadId},
+    },
+};
+use serde::{Deserialize, Serialize};
+use ui::{ListItem, Scrollbar, ScrollbarState, Tooltip, prelude::*};
+use workspace::Item;
+
+/// How many levels of nested variables are captured at each stop.
+const MAX_RECORDED_VARIABLE_DEPTH: usize = 3;
+
+/// The first line of a recording file. Each line after it is a [`StopSnapshot`], so that stops can be
+/// appended to the file as they're captured.
+#[derive(Serialize, Deserialize)]
+struct RecordingHeader {
+    label: String,
+    adapter: String,
+}
+
+/// Captures every stop of a debug session and writes the recording to a file as it grows.
+pub(crate) struct SessionRecorder {
+    path: PathBuf,
+    fs: Arc<dyn Fs>,
+    /// Resolves once all stops captured so far have been written.
+    writes: Task<()>,
+}
+
+impl SessionRecorder {
+    pub(crate) fn new(
+        path: PathBuf,
+        fs: Arc<dyn Fs>,
+        recording: SessionRecording,
+        cx: &mut App,
+    ) -> Self {
+        let writes = cx.background_spawn({
+            let path = path.clone();
+            let fs = fs.clone();
+            async move {
+                if let Err(error) = write_recording(&recording, path.clone(), fs).await {
+                    log::error!("Failed to write debug session recording to {path:?}: {error:?}");
+                }
+            }
+        });
+        Self { path, fs, writes }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn record_stop(
+        &mut self,
+        session: &Entity<Session>,
+        thread_id: Option<ThreadId>,
+        cx: &mut App,
+    ) {
+        // Start capturing right away, before the user gets a chance to resume the session.
+        let capture = session
+            .read(cx)
+            .capture_stop(thread_id, MAX_RECORDED_VARIABLE_DEPTH, cx);
+        let previous = std::mem::replace(&mut self.writes, Task::ready(()));
+        let path = self.path.clone();
+        let fs = self.fs.clone();
+
+        self.writes = cx.background_spawn(async move {
+            // Stops are appended in the order they happened.
+            previous.await;
+            match capture.await {
+                Ok(stop) => {
+                    if let Err(error) = append_stop(&stop, &path, fs).await {
+                        log::error!(
+                            "Failed to write debug session recording to {path:?}: {error:?}"
+                        );
+                    }
+                }
+                Err(error) => log::error!("Failed to capture debug session stop: {error:?}"),
+            }
+        });
+    }
+
+    /// Stops recording, letting the stops that are still being captured finish writing.
+    pub(crate) fn finish(self) {
+        self.writes.detach();
+    }
+}
+
+/// Replaces the contents of the file at `path` with the recording.
+async fn write_recording(
+    recording: &SessionRecording,
+    path: PathBuf,
+    fs: Arc<dyn Fs>,
+) -> Result<()> {
+    let header = RecordingHeader {
+        label: recording.label.clone(),
+        adapter: recording.adapter.clone(),
+    };
+    let mut text = serde_json::to_string(&header)?;
+    text.push('\n');
+    for stop in &recording.stops {
+        text.push_str(&serde_json::to_string(stop)?);
+        text.push('\n');
+    }
+    fs.atomic_write(path, text).await
+}
+
+async fn append_stop(stop: &StopSnapshot, path: &Path, fs: Arc<dyn Fs>) -> Result<()> {
+    let mut line = serde_json::to_string(stop)?;
+    line.push('\n');
+    fs.append(path, line.as_bytes()).await
+}
+
+pub(crate) async fn load_recording(path: &Path, fs: Arc<dyn Fs>) -> Result<SessionRecording> {
+    let text = fs.load(path).await?;
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let header: RecordingHeader =
+        serde_json::from_str(lines.next().context("recording is empty")?)?;
+    let stops = lines
+        .enumerate()
+        .map(|(ix, line)| {
+            serde_json::from_str(line).with_context(|| format!("reading stop {}", ix + 1))
+        })
+        .collect::<Result<_>>()?;
+    Ok(SessionRecording {
+        label: header.label,
+        adapter: header.adapter,
+        stops,
+    })
+}
+
+#[derive(Clone, Debug)]
+struct ReplayEntry {
+    /// Indices of the thread, stack frame, scope and variables leading to this entry.
+    path: Arc<[usize]>,
+    name: SharedString,
+    detail: Option<SharedString>,
+    has_children: bool,
+}
+
+impl ReplayEntry {
+    fn depth(&self) -> usize {
+        self.path.len() - 1
+    }
+}
+
+/// A read-only view over a debug session recording, showing the state of the session at each of its stops.
+pub(crate) struct SessionReplay {
+    recording: SessionRecording,
+    path: PathBuf,
+    selected_stop: usize,
+    entries: Vec<ReplayEntry>,
+    expanded: HashSet<Arc<[usize]>>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    scrollbar_state: ScrollbarState,
+}
+
+impl SessionReplay {
+    pub(crate) fn new(recording: SessionRecording, path: PathBuf, cx: &mut Context<Self>) -> Self {
+        let scroll_handle = UniformListScrollHandle::new();
+        let mut this = Self {
+            recording,
+            path,
+            selected_stop: 0,
+            entries: Vec::new(),
+            expanded: HashSet::default(),
+            focus_handle: cx.focus_handle(),
+            scrollbar_state: ScrollbarState::new(scroll_handle.clone()),
+            scroll_handle,
+        };
+        this.select_stop(0, cx);
+        this
+    }
+
+    fn select_stop(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.selected_stop = ix;
+        self.expanded.clear();
+
+        // Expand the stopped thread, down to the variables of its topmost stack frame.
+        if let Some(stop) = self.recording.stops.get(ix) {
+            let thread_ix = stop
+                .threads
+                .iter()
+                .position(|thread| Some(thread.thread.id) == stop.thread_id)
+                .or_else(|| {
+                    stop.threads
+                        .iter()
+                        .position(|thread| !thread.stack_frames.is_empty())
+                });
+            if let Some(thread_ix) = thread_ix {
+                self.expanded.insert(Arc::from([thread_ix]));
+                self.expanded.insert(Arc::from([thread_ix, 0]));
+                if let Some(frame) = stop.threads[thread_ix].stack_frames.first() {
+                    for (scope_ix, scope) in frame.scopes.iter().enumerate() {
+                        if !scope.scope.expensive {
+                            self.expanded.insert(Arc::from([thread_ix, 0, scope_ix]));
+                        }
+                    }
+                }
+            }
+        }
+
+        self.build_entries();
+        cx.notify();
+    }
+
+    fn selected_stop(&self) -> Option<&StopSnapshot> {
+        self.recording.stops.get(self.selected_stop)
+    }
+
+    fn toggle_entry(&mut self, path: &Arc<[usize]>, cx: &mut Context<Self>) {
+        if !self.expanded.remove(path) {
+            self.expanded.insert(path.clone());
+        }
+        self.build_entries();
+        cx.notify();
+    }
+
+    fn build_entries(&mut self) {
+        let mut entries = Vec::new();
+        let Some(stop) = self.selected_stop() else {
+            self.entries = entries;
+            return;
+        };
+
+        for (thread_ix, thread) in stop.threads.iter().enumerate() {
+            let thread_path: Arc<[usize]> = Arc::from([thread_ix]);
+            entries.push(ReplayEntry {
+                path: thread_path.clone(),
+                name: thread.thread.name.clone().into(),
+                detail: (Some(thread.thread.id) == stop.thread_id).then(|| "stopped".into()),
+                has_children: !thread.stack_frames.is_empty(),
+            });
+            if !self.expanded.contains(&thread_path) {
+                continue;
+            }
+
+            for (frame_ix, frame) in thread.stack_frames.iter().enumerate() {
+                let frame_path: Arc<[usize]> = Arc::from([thread_ix, frame_ix]);
+                let location = frame
+                    .stack_frame
+                    .source
+                    .as_ref()
+                    .and_then(|source| source.path.as_deref().or(source.name.as_deref()))
+                    .map(|path| format!("{path}:{}", frame.stack_frame.line).into());
+                entries.push(ReplayEntry {
+                    path: frame_path.clone(),
+                    name: frame.stack_frame.name.clone().into(),
+                    detail: location,
+                    has_children: !frame.scopes.is_empty(),
+                });
+                if !self.expanded.contains(&frame_path) {
+                    continue;
+                }
+
+                for (scope_ix, scope) in frame.scopes.iter().enumerate() {
+                    let scope_path: Arc<[usize]> = Arc::from([thread_ix, frame_ix, scope_ix]);
+                    entries.push(ReplayEntry {
+                        path: scope_path.clone(),
+                        name: scope.scope.name.clone().into(),
+                        detail: scope
+                            .scope
+                            .expensive
+                            .then(|| "not recorded, expensive to fetch".into()),
+                        has_children: !scope.variables.is_empty(),
+                    });
+                    if self.expanded.contains(&scope_path) {
+                        Self::build_variable_entries(
+                            &scope.variables,
+                            &scope_path,
+                            &self.expanded,
+                            &mut entries,
+                        );
+                    }
+                }
+            }
+        }
+
+        self.entries = entries;
+    }
+
+    fn build_variable_entries(
+        variables: &[VariableSnapshot],
+        parent_path: &[usize],
+        expanded: &HashSet<Arc<[usize]>>,
+        entries: &mut Vec<ReplayEntry>,
+    ) {
+        for (ix, variable) in variables.iter().enumerate() {
+            let path: Arc<[usize]> = parent_path.iter().copied().chain([ix]).collect();
+            entries.push(ReplayEntry {
+                path: path.clone(),
+                name: variable.variable.name.clone().into(),
+                detail: Some(variable.variable.value.clone().into()),
+                has_children: !variable.children.is_empty(),
+            });
+            if expanded.contains(&path) {
+                Self::build_variable_entries(&variable.children, &path, expanded, entries);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn visible_entries(&self) -> Vec<String> {
+        self.entries
+            .iter()
+            .map(|entry| {
+                let indent = " ".repeat(entry.depth());
+                match &entry.detail {
+                    Some(detail) => format!("{indent}{} {detail}", entry.name),
+                    None => format!("{indent}{}", entry.name),
+                }
+            })
+            .collect()
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let entry = &self.entries[ix];
+        let is_expanded = self.expanded.contains(&entry.path);
+        let is_variable = entry.depth() > 2;
+
+        ListItem::new(("replay-entry", ix))
+            .selectable(false)
+            .indent_level(entry.depth())
+            .indent_step_size(px(10.))
+            .when(entry.has_children, |list_item| {
+                list_item.toggle(is_expanded).on_toggle(cx.listener({
+                    let path = entry.path.clone();
+                    move |this, _, _, cx| this.toggle_entry(&path, cx)
+                }))
+            })
+            .child(
+                h_flex()
+                    .gap_1()
+                    .text_ui_sm(cx)
+                    .w_full()
+                    .child(Label::new(entry.name.clone()))
+                    .when_some(entry.detail.clone(), |this, detail| {
+                        this.child(
+                            Label::new(detail)
+                                .color(Color::Muted)
+                                .when(is_variable, |label| label.buffer_font(cx))
+                                .truncate(),
+                        )
+                    }),
+            )
+            .into_any_element()
+    }
+
+    fn render_controls(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let stop_count = self.recording.stops.len();
+        let selected_stop = self.selected_stop;
+
+        h_flex()
+            .w_full()
+            .p_1()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Label::new(self.recording.label.clone()).size(LabelSize::Small))
+                    .child(
+                        Label::new(self.recording.adapter.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("replay-previous-stop", IconName::ChevronLeft)
+                            .icon_size(IconSize::Small)
+                            .disabled(selected_stop == 0)
+                            .tooltip(Tooltip::text("Previous Stop"))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.select_stop(selected_stop.saturating_sub(1), cx);
+                            })),
+                    )
+                    .child(
+                        Label::new(if stop_count == 0 {
+                            "No stops recorded".to_string()
+                        } else {
+                            format!("Stop {} of {stop_count}", selected_stop + 1)
+                        })
+                        .size(LabelSize::Small),
+                    )
+                    .child(
+                        IconButton::new("replay-next-stop", IconName::ChevronRight)
+                            .icon_size(IconSize::Small)
+                            .disabled(selected_stop + 1 >= stop_count)
+                            .tooltip(Tooltip::text("Next Stop"))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.select_stop(selected_stop + 1, cx);
+                            })),
+                    ),
+            )
+    }
+
+    fn render_vertical_scrollbar(&self, cx: &mut Context<Self>) -> Stateful<Div> {
+        div()
+            .occlude()
+            .id("session-replay-vertical-scrollbar")
+            .on_mouse_move(cx.listener(|_, _, _, cx| {
+                cx.notify();
+                cx.stop_propagation()
+            }))
+            .on_hover(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_any_mouse_down(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| {
+                    cx.stop_propagation();
+                }),
+            )
+            .on_scroll_wheel(cx.listener(|_, _, _, cx| {
+                cx.notify();
+            }))
+            .h_full()
+            .absolute()
+            .right_1()
+            .top_1()
+            .bottom_0()
+            .w(px(12.))
+            .cursor_default()
+            .children(Scrollbar::vertical(self.scrollbar_state.clone()))
+    }
+}
+
+impl Focusable for SessionReplay {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<()> for SessionReplay {}
+
+impl Item for SessionReplay {
+    type Event = ();
+
+    fn tab_content_text(&self, _detail: usize, _: &App) -> SharedString {
+        format!("Recording: {}", self.recording.label).into()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(self.path.to_string_lossy().to_string().into())
+    }
+
+    fn tab_icon(&self, _: &Window, _: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Debug))
+    }
+}
+
+impl Render for SessionReplay {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .key_context("SessionReplay")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_controls(cx))
+            .child(
+                div()
+                    .size_full()
+                    .p_1()
+                    .child(
+                        uniform_list(
+                            "session-replay",
+                            self.entries.len(),
+                            cx.processor(move |this, range: Range<usize>, _window, cx| {
+                                range.map(|ix| this.render_entry(ix, cx)).collect()
+                            }),
+                        )
+                        .track_scroll(self.scroll_handle.clone())
+                        .size_full(),
+                    )
+                    .child(self.render_vertical_scrollbar(cx)),
+            )
+    }
+}
@@ -1,5 +1,6 @@
 pub(crate) mod breakpoint_list;
 pub(crate) mod console;
+pub(crate) mod disassembly_view;
 pub(crate) mod loaded_source_list;
 pub(crate) mod memory_view;
 pub(crate) mod module_list;
@@ -11,7 +12,8 @@ use crate::{
     ToggleExpandItem,
     new_process_modal::resolve_path,
     persistence::{self, DebuggerPaneItem, SerializedLayout},
-    session::running::memory_view::MemoryView,
+    recording::SessionRecorder,
+    session::running::{disassembly_view::DisassemblyView, memory_view::MemoryView},
 };
 
 use super::DebugPanelItemEvent;
@@ -20,7 +22,7 @@ use breakpoint_list::BreakpointList;
 use collections::{HashMap, IndexMap};
 use console::Console;
 use dap::{
-    Capabilities, DapRegistry, RunInTerminalRequestArguments, Thread,
+    Capabilities, DapRegistry, RunInTerminalRequestArguments, SteppingGranularity, Thread,
     adapters::{DebugAdapterName, DebugTaskDefinition},
     client::SessionId,
     debugger_settings::DebuggerSettings,
@@ -34,8 +36,11 @@ use language::Buffer;
 use loaded_source_list::LoadedSourceList;
 use module_list::ModuleList;
 use project::{
-    DebugScenarioContext, Project, WorktreeId,
-    debugger::session::{self, Session, SessionEvent, SessionStateEvent, ThreadId, ThreadStatus},
+    DebugScenarioContext, DirectoryLister, Project, WorktreeId,
+    debugger::{
+        recording::SessionRecording,
+        session::{self, Session, SessionEvent, SessionStateEvent, ThreadId, ThreadStatus},
+    },
 };
 use rpc::proto::ViewId;
 use serde_json::Value;
@@ -80,6 +85,8 @@ pub struct RunningState {
     pub(crate) scenario: Option<DebugScenario>,
     pub(crate) scenario_context: Option<DebugScenarioContext>,
     memory_view: Entity<MemoryView>,
+    disassembly_view: Entity<DisassemblyView>,
+    session_recorder: Option<SessionRecorder>,
 }
 
 impl RunningState {
@@ -211,7 +218,7 @@ impl SubView {
         this.update(cx, |this, _| {
             this.with_actions(Box::new(move |_, cx| {
                 weak_list
-                    .update(cx, |this, _| this.render_control_strip())
+                    .update(cx, |this, cx| this.render_control_strip(cx))
                     .unwrap_or_else(|_| div().into_any_element())
             }));
         });
@@ -718,6 +725,15 @@ impl RunningState {
                 cx,
             )
         });
+        let disassembly_view = cx.new(|cx| {
+            DisassemblyView::new(
+                session.clone(),
+                workspace.clone(),
+                &stack_frame_list,
+                weak_state.clone(),
+                cx,
+            )
+        });
         let variable_list = cx.new(|cx| {
             VariableList::new(
                 session.clone(),
@@ -766,6 +782,10 @@ impl RunningState {
             cx.subscribe_in(&session, window, |this, _, event, window, cx| {
                 match event {
                     SessionEvent::Stopped(thread_id) => {
+                        if let Some(recorder) = this.session_recorder.as_mut() {
+                            recorder.record_stop(&this.session, *thread_id, cx);
+                        }
+
                         let panel = this
                             .workspace
                             .update(cx, |workspace, cx| {
@@ -840,6 +860,7 @@ impl RunningState {
                 &loaded_source_list,
                 &debug_terminal,
                 &memory_view,
+                &disassembly_view,
                 &mut pane_close_subscriptions,
                 window,
                 cx,
@@ -869,6 +890,8 @@ impl RunningState {
 
         Self {
             memory_view,
+            disassembly_view,
+            session_recorder: None,
             session,
             workspace,
             focus_handle,
@@ -1205,6 +1228,7 @@ impl RunningState {
             show_summary: false,
             show_command: false,
             show_rerun: false,
+            problem_matchers: Vec::new(),
         };
 
         let workspace = self.workspace.clone();
@@ -1286,6 +1310,12 @@ impl RunningState {
                 item_kind,
                 cx,
             )),
+            DebuggerPaneItem::Disassembly => Box::new(SubView::new(
+                self.disassembly_view.focus_handle(cx),
+                self.disassembly_view.clone().into(),
+                item_kind,
+                cx,
+            )),
         }
     }
 
@@ -1470,6 +1500,11 @@ impl RunningState {
         &self.module_list
     }
 
+    #[cfg(test)]
+    pub(crate) fn disassembly_view(&self) -> &Entity<DisassemblyView> {
+        &self.disassembly_view
+    }
+
     pub(crate) fn activate_item(
         &mut self,
         item: DebuggerPaneItem,
@@ -1588,6 +1623,26 @@ impl RunningState {
         });
     }
 
+    pub(crate) fn step_over_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.step_over(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
+    pub(crate) fn step_into_instruction(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.step_in(thread_id, SteppingGranularity::Instruction, cx);
+        });
+    }
+
     pub(crate) fn step_out(&mut self, cx: &mut Context<Self>) {
         let Some(thread_id) = self.thread_id else {
             return;
@@ -1714,6 +1769,71 @@ impl RunningState {
         });
     }
 
+    pub(crate) fn is_recording(&self) -> bool {
+        self.session_recorder.is_some()
+    }
+
+    /// Stops are captured by querying the debug adapter directly, so only sessions whose adapter runs
+    /// locally can be recorded.
+    pub(crate) fn can_record(&self, cx: &App) -> bool {
+        self.session.read(cx).adapter_client().is_some()
+    }
+
+    /// Starts recording each stop of this session to a file picked by the user, or stops an ongoing recording.
+    pub(crate) fn toggle_recording(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(recorder) = self.session_recorder.take() {
+            log::info!("Stopped recording debug session to {:?}", recorder.path());
+            recorder.finish();
+            cx.notify();
+            return;
+        }
+        if !self.can_record(cx) {
+            return;
+        }
+
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let session = self.session.read(cx);
+        let label = session
+            .label()
+            .map(|label| label.to_string())
+            .unwrap_or_else(|| "debug-session".to_string());
+        let recording = SessionRecording {
+            adapter: session.adapter().to_string(),
+            label,
+            stops: Vec::new(),
+        };
+        let (fs, path) = workspace.update(cx, |workspace, cx| {
+            let fs = workspace.app_state().fs.clone();
+            let lister = DirectoryLister::Local(workspace.project().clone(), fs.clone());
+            let path = workspace.prompt_for_new_path(
+                lister,
+                Some(format!("{}.debug-recording.jsonl", recording.label)),
+                window,
+                cx,
+            );
+            (fs, path)
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Some(path) = path.await.ok().flatten().into_iter().flatten().next() else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                let mut recorder = SessionRecorder::new(path, fs, recording, cx);
+                // Capture the current stop right away, as there won't be a stop event for it.
+                if this.thread_status(cx) == Some(ThreadStatus::Stopped) {
+                    recorder.record_stop(&this.session, this.thread_id, cx);
+                }
+                this.session_recorder = Some(recorder);
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
     pub fn toggle_ignore_breakpoints(&mut self, cx: &mut Context<Self>) {
         self.session.update(cx, |session, cx| {
             session.toggle_ignore_breakpoints(cx).detach();
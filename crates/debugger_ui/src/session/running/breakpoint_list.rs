@@ -9,8 +9,8 @@ use dap::{Capabilities, ExceptionBreakpointsFilter, adapters::DebugAdapterName};
 use db::kvp::KEY_VALUE_STORE;
 use editor::Editor;
 use gpui::{
-    Action, AppContext, ClickEvent, Entity, FocusHandle, Focusable, MouseButton, ScrollStrategy,
-    Stateful, Task, UniformListScrollHandle, WeakEntity, actions, uniform_list,
+    Action, App, AppContext, ClickEvent, Entity, FocusHandle, Focusable, MouseButton,
+    ScrollStrategy, Stateful, Task, UniformListScrollHandle, WeakEntity, actions, uniform_list,
 };
 use language::Point;
 use project::{
@@ -35,12 +35,15 @@ actions!(
         /// Navigates to the previous breakpoint property in the list.
         PreviousBreakpointProperty,
         /// Navigates to the next breakpoint property in the list.
-        NextBreakpointProperty
+        NextBreakpointProperty,
+        /// Adds a breakpoint on a function, by its name.
+        AddFunctionBreakpoint
     ]
 );
 #[derive(Clone, Copy, PartialEq)]
 pub(crate) enum SelectedBreakpointKind {
     Source,
+    Function,
     Exception,
     Data,
 }
@@ -56,6 +59,7 @@ pub(crate) struct BreakpointList {
     scroll_handle: UniformListScrollHandle,
     selected_ix: Option<usize>,
     input: Entity<Editor>,
+    function_breakpoint_input: Option<Entity<Editor>>,
     strip_mode: Option<ActiveBreakpointStripMode>,
     serialize_exception_breakpoints_task: Option<Task<anyhow::Result<()>>>,
 }
@@ -103,6 +107,7 @@ impl BreakpointList {
                 scroll_handle,
                 selected_ix: None,
                 input: cx.new(|cx| Editor::single_line(window, cx)),
+                function_breakpoint_input: None,
                 strip_mode: None,
                 serialize_exception_breakpoints_task: None,
             };
@@ -178,6 +183,9 @@ impl BreakpointList {
                     bp.breakpoint.state
                         == project::debugger::breakpoint_store::BreakpointState::Enabled,
                 ),
+                BreakpointEntryKind::FunctionBreakpoint(bp) => {
+                    (SelectedBreakpointKind::Function, bp.0.state.is_enabled())
+                }
                 BreakpointEntryKind::ExceptionBreakpoint(bp) => {
                     (SelectedBreakpointKind::Exception, bp.is_enabled)
                 }
@@ -200,11 +208,11 @@ impl BreakpointList {
             ActiveBreakpointStripMode::Condition => "Set Condition",
             ActiveBreakpointStripMode::HitCondition => "Set Hit Condition",
         };
-        let mut is_exception_breakpoint = true;
+        let mut is_read_only = true;
         let active_value = self.selected_ix.and_then(|ix| {
-            self.breakpoints.get(ix).and_then(|bp| {
-                if let BreakpointEntryKind::LineBreakpoint(bp) = &bp.kind {
-                    is_exception_breakpoint = false;
+            self.breakpoints.get(ix).and_then(|bp| match &bp.kind {
+                BreakpointEntryKind::LineBreakpoint(bp) => {
+                    is_read_only = false;
                     match prop {
                         ActiveBreakpointStripMode::Log => bp.breakpoint.message.clone(),
                         ActiveBreakpointStripMode::Condition => bp.breakpoint.condition.clone(),
@@ -212,15 +220,24 @@ impl BreakpointList {
                             bp.breakpoint.hit_condition.clone()
                         }
                     }
-                } else {
-                    None
                 }
+                BreakpointEntryKind::FunctionBreakpoint(bp) => {
+                    // Function breakpoints can't log messages.
+                    is_read_only = prop == ActiveBreakpointStripMode::Log;
+                    match prop {
+                        ActiveBreakpointStripMode::Log => None,
+                        ActiveBreakpointStripMode::Condition => bp.0.condition.clone(),
+                        ActiveBreakpointStripMode::HitCondition => bp.0.hit_condition.clone(),
+                    }
+                }
+                BreakpointEntryKind::ExceptionBreakpoint(_)
+                | BreakpointEntryKind::DataBreakpoint(_) => None,
             })
         });
 
         self.input.update(cx, |this, cx| {
             this.set_placeholder_text(placeholder, cx);
-            this.set_read_only(is_exception_breakpoint);
+            this.set_read_only(is_read_only);
             this.set_text(active_value.as_deref().unwrap_or(""), window, cx);
         });
     }
@@ -308,7 +325,10 @@ impl BreakpointList {
     }
 
     fn dismiss(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
-        if self.input.focus_handle(cx).contains_focused(window, cx) {
+        if self.function_breakpoint_input.take().is_some() {
+            self.focus_handle.focus(window);
+            cx.notify();
+        } else if self.input.focus_handle(cx).contains_focused(window, cx) {
             self.focus_handle.focus(window);
         } else if self.strip_mode.is_some() {
             self.strip_mode.take();
@@ -318,6 +338,15 @@ impl BreakpointList {
         }
     }
     fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(input) = self.function_breakpoint_input.take() {
+            let name = input.read(cx).text(cx);
+            self.breakpoint_store.update(cx, |breakpoint_store, cx| {
+                breakpoint_store.add_function_breakpoint(Arc::from(name.trim()), cx);
+            });
+            self.focus_handle.focus(window);
+            cx.notify();
+            return;
+        }
         let Some(entry) = self.selected_ix.and_then(|ix| self.breakpoints.get_mut(ix)) else {
             return;
         };
@@ -326,42 +355,35 @@ impl BreakpointList {
             let handle = self.input.focus_handle(cx);
             if handle.is_focused(window) {
                 // Go back to the main strip. Save the result as well.
-                let text = self.input.read(cx).text(cx);
-
-                match mode {
-                    ActiveBreakpointStripMode::Log => {
-                        if let BreakpointEntryKind::LineBreakpoint(line_breakpoint) = &entry.kind {
-                            Self::edit_line_breakpoint_inner(
-                                &self.breakpoint_store,
-                                line_breakpoint.breakpoint.path.clone(),
-                                line_breakpoint.breakpoint.row,
-                                BreakpointEditAction::EditLogMessage(Arc::from(text)),
-                                cx,
-                            );
-                        }
-                    }
+                let text = Arc::from(self.input.read(cx).text(cx));
+                let action = match mode {
+                    ActiveBreakpointStripMode::Log => BreakpointEditAction::EditLogMessage(text),
                     ActiveBreakpointStripMode::Condition => {
-                        if let BreakpointEntryKind::LineBreakpoint(line_breakpoint) = &entry.kind {
-                            Self::edit_line_breakpoint_inner(
-                                &self.breakpoint_store,
-                                line_breakpoint.breakpoint.path.clone(),
-                                line_breakpoint.breakpoint.row,
-                                BreakpointEditAction::EditCondition(Arc::from(text)),
-                                cx,
-                            );
-                        }
+                        BreakpointEditAction::EditCondition(text)
                     }
                     ActiveBreakpointStripMode::HitCondition => {
-                        if let BreakpointEntryKind::LineBreakpoint(line_breakpoint) = &entry.kind {
-                            Self::edit_line_breakpoint_inner(
-                                &self.breakpoint_store,
-                                line_breakpoint.breakpoint.path.clone(),
-                                line_breakpoint.breakpoint.row,
-                                BreakpointEditAction::EditHitCondition(Arc::from(text)),
-                                cx,
-                            );
-                        }
+                        BreakpointEditAction::EditHitCondition(text)
                     }
+                };
+
+                match &entry.kind {
+                    BreakpointEntryKind::LineBreakpoint(line_breakpoint) => {
+                        Self::edit_line_breakpoint_inner(
+                            &self.breakpoint_store,
+                            line_breakpoint.breakpoint.path.clone(),
+                            line_breakpoint.breakpoint.row,
+                            action,
+                            cx,
+                        );
+                    }
+                    BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                        let name = function_breakpoint.0.name.clone();
+                        self.breakpoint_store.update(cx, |breakpoint_store, cx| {
+                            breakpoint_store.edit_function_breakpoint(&name, action, cx);
+                        });
+                    }
+                    BreakpointEntryKind::ExceptionBreakpoint(_)
+                    | BreakpointEntryKind::DataBreakpoint(_) => {}
                 }
                 self.focus_handle.focus(window);
             } else {
@@ -376,7 +398,8 @@ impl BreakpointList {
                 let row = line_breakpoint.breakpoint.row;
                 self.go_to_line_breakpoint(path, row, window, cx);
             }
-            BreakpointEntryKind::DataBreakpoint(_)
+            BreakpointEntryKind::FunctionBreakpoint(_)
+            | BreakpointEntryKind::DataBreakpoint(_)
             | BreakpointEntryKind::ExceptionBreakpoint(_) => {}
         }
     }
@@ -401,6 +424,10 @@ impl BreakpointList {
                 let row = line_breakpoint.breakpoint.row;
                 self.edit_line_breakpoint(path, row, BreakpointEditAction::InvertState, cx);
             }
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                let name = function_breakpoint.0.name.clone();
+                self.edit_function_breakpoint(&name, BreakpointEditAction::InvertState, cx);
+            }
             BreakpointEntryKind::ExceptionBreakpoint(exception_breakpoint) => {
                 let id = exception_breakpoint.id.clone();
                 self.toggle_exception_breakpoint(&id, cx);
@@ -423,14 +450,62 @@ impl BreakpointList {
             return;
         };
 
-        if let BreakpointEntryKind::LineBreakpoint(line_breakpoint) = &mut entry.kind {
-            let path = line_breakpoint.breakpoint.path.clone();
-            let row = line_breakpoint.breakpoint.row;
-            self.edit_line_breakpoint(path, row, BreakpointEditAction::Toggle, cx);
+        match &mut entry.kind {
+            BreakpointEntryKind::LineBreakpoint(line_breakpoint) => {
+                let path = line_breakpoint.breakpoint.path.clone();
+                let row = line_breakpoint.breakpoint.row;
+                self.edit_line_breakpoint(path, row, BreakpointEditAction::Toggle, cx);
+            }
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                let name = function_breakpoint.0.name.clone();
+                self.edit_function_breakpoint(&name, BreakpointEditAction::Toggle, cx);
+            }
+            BreakpointEntryKind::ExceptionBreakpoint(_)
+            | BreakpointEntryKind::DataBreakpoint(_) => {}
+        }
+        cx.notify();
+    }
+
+    fn supports_function_breakpoints(&self, cx: &App) -> bool {
+        self.session.as_ref().is_some_and(|session| {
+            session
+                .read(cx)
+                .capabilities()
+                .supports_function_breakpoints
+                .unwrap_or_default()
+        })
+    }
+
+    fn add_function_breakpoint(
+        &mut self,
+        _: &AddFunctionBreakpoint,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.supports_function_breakpoints(cx) {
+            return;
         }
+        let input = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Function Name", cx);
+            editor
+        });
+        input.focus_handle(cx).focus(window);
+        self.function_breakpoint_input = Some(input);
         cx.notify();
     }
 
+    fn edit_function_breakpoint(
+        &self,
+        name: &str,
+        action: BreakpointEditAction,
+        cx: &mut Context<Self>,
+    ) {
+        self.breakpoint_store.update(cx, |breakpoint_store, cx| {
+            breakpoint_store.edit_function_breakpoint(name, action, cx);
+        });
+    }
+
     fn previous_breakpoint_property(
         &mut self,
         _: &PreviousBreakpointProperty,
@@ -609,12 +684,14 @@ impl BreakpointList {
             .children(Scrollbar::vertical(self.scrollbar_state.clone()).map(|s| s.auto_hide(cx)))
     }
 
-    pub(crate) fn render_control_strip(&self) -> AnyElement {
+    pub(crate) fn render_control_strip(&self, cx: &App) -> AnyElement {
         let selection_kind = self.selection_kind();
         let focus_handle = self.focus_handle.clone();
+        let supports_function_breakpoints = self.supports_function_breakpoints(cx);
 
         let remove_breakpoint_tooltip = selection_kind.map(|(kind, _)| match kind {
             SelectedBreakpointKind::Source => "Remove breakpoint from a breakpoint list",
+            SelectedBreakpointKind::Function => "Remove function breakpoint from a breakpoint list",
             SelectedBreakpointKind::Exception => {
                 "Exception Breakpoints cannot be removed from the breakpoint list"
             }
@@ -633,6 +710,31 @@ impl BreakpointList {
         });
 
         h_flex()
+            .when(supports_function_breakpoints, |this| {
+                this.child(
+                    IconButton::new("add-function-breakpoint-breakpoint-list", IconName::Plus)
+                        .icon_size(IconSize::Small)
+                        .tooltip({
+                            let focus_handle = focus_handle.clone();
+                            move |window, cx| {
+                                Tooltip::for_action_in(
+                                    "Add Function Breakpoint",
+                                    &AddFunctionBreakpoint,
+                                    &focus_handle,
+                                    window,
+                                    cx,
+                                )
+                            }
+                        })
+                        .on_click({
+                            let focus_handle = focus_handle.clone();
+                            move |_, window, cx| {
+                                focus_handle.focus(window);
+                                window.dispatch_action(AddFunctionBreakpoint.boxed_clone(), cx)
+                            }
+                        }),
+                )
+            })
             .child(
                 IconButton::new(
                     "disable-breakpoint-breakpoint-list",
@@ -681,9 +783,10 @@ impl BreakpointList {
                             }
                         })
                     })
-                    .disabled(
-                        selection_kind.map(|kind| kind.0) != Some(SelectedBreakpointKind::Source),
-                    )
+                    .disabled(!matches!(
+                        selection_kind.map(|kind| kind.0),
+                        Some(SelectedBreakpointKind::Source | SelectedBreakpointKind::Function)
+                    ))
                     .on_click({
                         move |_, window, cx| {
                             focus_handle.focus(window);
@@ -744,6 +847,18 @@ impl Render for BreakpointList {
                 })
             })
         });
+        let function_breakpoints = self
+            .breakpoint_store
+            .read(cx)
+            .function_breakpoints()
+            .iter()
+            .map(|breakpoint| BreakpointEntry {
+                kind: BreakpointEntryKind::FunctionBreakpoint(FunctionBreakpoint(
+                    breakpoint.clone(),
+                )),
+                weak: weak.clone(),
+            })
+            .collect::<Vec<_>>();
         let exception_breakpoints = self.session.as_ref().into_iter().flat_map(|session| {
             session
                 .read(cx)
@@ -768,6 +883,7 @@ impl Render for BreakpointList {
         });
         self.breakpoints.extend(
             breakpoints
+                .chain(function_breakpoints)
                 .chain(data_breakpoints)
                 .chain(exception_breakpoints),
         );
@@ -784,6 +900,7 @@ impl Render for BreakpointList {
             .on_action(cx.listener(Self::confirm))
             .on_action(cx.listener(Self::toggle_enable_breakpoint))
             .on_action(cx.listener(Self::unset_breakpoint))
+            .on_action(cx.listener(Self::add_function_breakpoint))
             .on_action(cx.listener(Self::next_breakpoint_property))
             .on_action(cx.listener(Self::previous_breakpoint_property))
             .size_full()
@@ -815,6 +932,24 @@ impl Render for BreakpointList {
                             .child(self.input.clone()),
                     )
             })
+            .when_some(self.function_breakpoint_input.clone(), |this, input| {
+                this.child(Divider::horizontal().color(DividerColor::Border))
+                    .child(
+                        h_flex()
+                            .p_1()
+                            .gap_1()
+                            .rounded_sm()
+                            .bg(cx.theme().colors().editor_background)
+                            .border_1()
+                            .border_color(cx.theme().colors().border_transparent)
+                            .child(
+                                Icon::new(IconName::Code)
+                                    .color(Color::Muted)
+                                    .size(IconSize::Small),
+                            )
+                            .child(input),
+                    )
+            })
     }
 }
 
@@ -978,6 +1113,121 @@ struct ExceptionBreakpoint {
     is_enabled: bool,
 }
 
+#[derive(Clone, Debug)]
+struct FunctionBreakpoint(project::debugger::breakpoint_store::FunctionBreakpoint);
+
+impl FunctionBreakpoint {
+    fn render(
+        &self,
+        props: SupportedBreakpointProperties,
+        strip_mode: Option<ActiveBreakpointStripMode>,
+        ix: usize,
+        is_selected: bool,
+        focus_handle: FocusHandle,
+        list: WeakEntity<BreakpointList>,
+    ) -> ListItem {
+        let is_enabled = self.0.state.is_enabled();
+        let icon_name = if is_enabled {
+            IconName::DebugBreakpoint
+        } else {
+            IconName::DebugDisabledBreakpoint
+        };
+        let name = self.0.name.clone();
+
+        ListItem::new(SharedString::from(format!(
+            "function-breakpoint-ui-item-{}",
+            self.0.name
+        )))
+        .toggle_state(is_selected)
+        .inset(true)
+        .on_click({
+            let list = list.clone();
+            move |_, window, cx| {
+                list.update(cx, |list, cx| list.select_ix(Some(ix), window, cx))
+                    .ok();
+            }
+        })
+        .on_secondary_mouse_down(|_, _, cx| {
+            cx.stop_propagation();
+        })
+        .start_slot(
+            div()
+                .id(SharedString::from(format!(
+                    "function-breakpoint-ui-item-{}-click-handler",
+                    self.0.name
+                )))
+                .child(
+                    Icon::new(icon_name)
+                        .color(Color::Debugger)
+                        .size(IconSize::XSmall),
+                )
+                .tooltip({
+                    let focus_handle = focus_handle.clone();
+                    move |window, cx| {
+                        Tooltip::for_action_in(
+                            if is_enabled {
+                                "Disable Function Breakpoint"
+                            } else {
+                                "Enable Function Breakpoint"
+                            },
+                            &ToggleEnableBreakpoint,
+                            &focus_handle,
+                            window,
+                            cx,
+                        )
+                    }
+                })
+                .on_click({
+                    let list = list.clone();
+                    move |_, _, cx| {
+                        list.update(cx, |this, cx| {
+                            this.edit_function_breakpoint(
+                                &name,
+                                BreakpointEditAction::InvertState,
+                                cx,
+                            );
+                        })
+                        .ok();
+                    }
+                }),
+        )
+        .child(
+            h_flex()
+                .w_full()
+                .gap_1()
+                .min_h(rems_from_px(26.))
+                .justify_between()
+                .child(
+                    h_flex()
+                        .id(("function-breakpoint-label", ix))
+                        .gap_0p5()
+                        .child(
+                            Label::new(self.0.name.to_string())
+                                .size(LabelSize::Small)
+                                .line_height_style(ui::LineHeightStyle::UiLabel),
+                        )
+                        .child(
+                            Label::new("function")
+                                .color(Color::Muted)
+                                .size(LabelSize::Small)
+                                .line_height_style(ui::LineHeightStyle::UiLabel),
+                        ),
+                )
+                .child(BreakpointOptionsStrip {
+                    props,
+                    breakpoint: BreakpointEntry {
+                        kind: BreakpointEntryKind::FunctionBreakpoint(self.clone()),
+                        weak: list,
+                    },
+                    is_selected,
+                    focus_handle,
+                    strip_mode,
+                    index: ix,
+                }),
+        )
+    }
+}
+
 #[derive(Clone, Debug)]
 struct DataBreakpoint(project::debugger::session::DataBreakpointState);
 
@@ -1184,6 +1434,7 @@ impl ExceptionBreakpoint {
 #[derive(Clone, Debug)]
 enum BreakpointEntryKind {
     LineBreakpoint(LineBreakpoint),
+    FunctionBreakpoint(FunctionBreakpoint),
     ExceptionBreakpoint(ExceptionBreakpoint),
     DataBreakpoint(DataBreakpoint),
 }
@@ -1212,6 +1463,15 @@ impl BreakpointEntry {
                 focus_handle,
                 self.weak.clone(),
             ),
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => function_breakpoint
+                .render(
+                    props.for_function_breakpoints(),
+                    strip_mode,
+                    ix,
+                    is_selected,
+                    focus_handle,
+                    self.weak.clone(),
+                ),
             BreakpointEntryKind::ExceptionBreakpoint(exception_breakpoint) => exception_breakpoint
                 .render(
                     props.for_exception_breakpoints(),
@@ -1239,6 +1499,11 @@ impl BreakpointEntry {
                 line_breakpoint.breakpoint.path, line_breakpoint.breakpoint.row
             )
             .into(),
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => format!(
+                "function-breakpoint-control-strip--{}",
+                function_breakpoint.0.name
+            )
+            .into(),
             BreakpointEntryKind::ExceptionBreakpoint(exception_breakpoint) => format!(
                 "exception-breakpoint-control-strip--{}",
                 exception_breakpoint.id
@@ -1266,6 +1531,9 @@ impl BreakpointEntry {
             BreakpointEntryKind::LineBreakpoint(line_breakpoint) => {
                 line_breakpoint.breakpoint.condition.is_some()
             }
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                function_breakpoint.0.condition.is_some()
+            }
             // We don't support conditions on exception/data breakpoints
             _ => false,
         }
@@ -1276,6 +1544,9 @@ impl BreakpointEntry {
             BreakpointEntryKind::LineBreakpoint(line_breakpoint) => {
                 line_breakpoint.breakpoint.hit_condition.is_some()
             }
+            BreakpointEntryKind::FunctionBreakpoint(function_breakpoint) => {
+                function_breakpoint.0.hit_condition.is_some()
+            }
             _ => false,
         }
     }
@@ -1316,6 +1587,10 @@ impl From<&Capabilities> for SupportedBreakpointProperties {
 }
 
 impl SupportedBreakpointProperties {
+    fn for_function_breakpoints(self) -> Self {
+        // Function breakpoints can't log messages.
+        self.difference(Self::LOG)
+    }
     fn for_exception_breakpoints(self) -> Self {
         // TODO: we don't yet support conditions for exception breakpoints at the data layer, hence all props are disabled here.
         Self::empty()
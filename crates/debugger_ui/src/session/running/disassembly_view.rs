@@ -0,0 +1,589 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+use collections::HashMap;
+use dap::DisassembledInstruction;
+use gpui::{
+    AnyElement, Entity, FocusHandle, Focusable, MouseButton, ScrollStrategy, Stateful,
+    Subscription, Task, UniformListScrollHandle, WeakEntity, uniform_list,
+};
+use language::{Buffer, Point};
+use project::debugger::session::{Session, ThreadStatus};
+use ui::{Scrollbar, ScrollbarState, Tooltip, VisibleOnHover, prelude::*};
+use workspace::Workspace;
+
+use crate::{
+    StepIntoInstruction, StepOverInstruction,
+    session::running::{
+        RunningState,
+        stack_frame_list::{StackFrameList, StackFrameListEvent},
+    },
+};
+
+/// How many instructions are disassembled before the instruction pointer.
+const INSTRUCTIONS_BEFORE: u64 = 32;
+/// How many instructions are disassembled from the instruction pointer onwards.
+const INSTRUCTIONS_AFTER: u64 = 96;
+
+enum DisassemblyRow {
+    /// The source line the instructions below it were compiled from.
+    Source {
+        path: Option<Arc<Path>>,
+        name: SharedString,
+        line: u64,
+    },
+    Instruction(DisassembledInstruction),
+}
+
+#[derive(Clone, Copy)]
+struct RowState {
+    is_stopped: bool,
+    supports_breakpoints: bool,
+}
+
+/// Shows the instructions around the instruction pointer of the selected stack frame,
+/// interleaved with the source lines they belong to.
+pub(crate) struct DisassemblyView {
+    session: Entity<Session>,
+    workspace: WeakEntity<Workspace>,
+    stack_frame_list: WeakEntity<StackFrameList>,
+    running_state: WeakEntity<RunningState>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    scrollbar_state: ScrollbarState,
+    /// The memory reference of the instruction the selected stack frame is at.
+    instruction_pointer: Option<String>,
+    rows: Vec<DisassemblyRow>,
+    source_buffers: HashMap<Arc<Path>, Entity<Buffer>>,
+    _fetch_task: Task<()>,
+    _subscription: Subscription,
+}
+
+impl DisassemblyView {
+    pub(crate) fn new(
+        session: Entity<Session>,
+        workspace: WeakEntity<Workspace>,
+        stack_frame_list: &Entity<StackFrameList>,
+        running_state: WeakEntity<RunningState>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscription = cx.subscribe(stack_frame_list, |this, _, event, cx| match event {
+            StackFrameListEvent::SelectedStackFrameChanged(_)
+            | StackFrameListEvent::BuiltEntries => this.refresh(cx),
+        });
+        let scroll_handle = UniformListScrollHandle::new();
+
+        let mut this = Self {
+            session,
+            workspace,
+            stack_frame_list: stack_frame_list.downgrade(),
+            running_state,
+            focus_handle: cx.focus_handle(),
+            scrollbar_state: ScrollbarState::new(scroll_handle.clone()),
+            scroll_handle,
+            instruction_pointer: None,
+            rows: Vec::new(),
+            source_buffers: HashMap::default(),
+            _fetch_task: Task::ready(()),
+            _subscription,
+        };
+        this.refresh(cx);
+        this
+    }
+
+    /// Follows the instruction pointer of the selected stack frame, disassembling the memory around it
+    /// unless it's already shown.
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let instruction_pointer = self
+            .stack_frame_list
+            .read_with(cx, |list, _| list.opened_stack_frame_id())
+            .ok()
+            .flatten()
+            .and_then(|stack_frame_id| {
+                self.session
+                    .read(cx)
+                    .stack_frame(stack_frame_id)
+                    .and_then(|stack_frame| stack_frame.dap.instruction_pointer_reference.clone())
+            });
+        if instruction_pointer == self.instruction_pointer {
+            return;
+        }
+        self.instruction_pointer = instruction_pointer.clone();
+        cx.notify();
+
+        let Some(instruction_pointer) = instruction_pointer else {
+            return;
+        };
+        if let Some(ix) = self.row_of_instruction(&instruction_pointer) {
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Center);
+            return;
+        }
+
+        let instructions = self.session.update(cx, |session, cx| {
+            session.disassemble(
+                instruction_pointer,
+                -(INSTRUCTIONS_BEFORE as i64),
+                INSTRUCTIONS_BEFORE + INSTRUCTIONS_AFTER,
+                cx,
+            )
+        });
+        self._fetch_task = cx.spawn(async move |this, cx| {
+            let Some(instructions) = instructions.await else {
+                return;
+            };
+            this.update(cx, |this, cx| this.set_instructions(instructions, cx))
+                .ok();
+        });
+    }
+
+    fn set_instructions(
+        &mut self,
+        instructions: Vec<DisassembledInstruction>,
+        cx: &mut Context<Self>,
+    ) {
+        self.rows.clear();
+        // Adapters only send the location of an instruction when it differs from the previous one's.
+        let mut location = None;
+        let mut last_source = None;
+        for instruction in instructions {
+            if let Some(source) = &instruction.location {
+                location = Some(source.clone());
+            }
+            if let Some((source, line)) = location.as_ref().zip(instruction.line)
+                && last_source.as_ref() != Some(&(source.path.clone(), line))
+            {
+                last_source = Some((source.path.clone(), line));
+                let path = source
+                    .path
+                    .as_deref()
+                    .map(|path| Arc::<Path>::from(Path::new(path)))
+                    .filter(|path| path.is_absolute());
+                let name = source
+                    .name
+                    .clone()
+                    .or_else(|| source.path.clone())
+                    .unwrap_or_default();
+                if let Some(path) = &path {
+                    self.load_source(path.clone(), cx);
+                }
+                self.rows.push(DisassemblyRow::Source {
+                    path,
+                    name: name.into(),
+                    line,
+                });
+            }
+            self.rows.push(DisassemblyRow::Instruction(instruction));
+        }
+
+        if let Some(ix) = self
+            .instruction_pointer
+            .clone()
+            .and_then(|instruction_pointer| self.row_of_instruction(&instruction_pointer))
+        {
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Center);
+        }
+        cx.notify();
+    }
+
+    fn load_source(&mut self, path: Arc<Path>, cx: &mut Context<Self>) {
+        if self.source_buffers.contains_key(&path) {
+            return;
+        }
+        let Ok(buffer) = self.workspace.update(cx, |workspace, cx| {
+            workspace
+                .project()
+                .update(cx, |project, cx| project.open_local_buffer(&path, cx))
+        }) else {
+            return;
+        };
+        cx.spawn(async move |this, cx| {
+            let buffer = buffer.await?;
+            this.update(cx, |this, cx| {
+                this.source_buffers.insert(path, buffer);
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn row_of_instruction(&self, memory_reference: &str) -> Option<usize> {
+        let address = parse_address(memory_reference);
+        self.rows.iter().position(|row| match row {
+            DisassemblyRow::Instruction(instruction) => {
+                instruction.address == memory_reference
+                    || address.is_some() && parse_address(&instruction.address) == address
+            }
+            DisassemblyRow::Source { .. } => false,
+        })
+    }
+
+    fn is_instruction_pointer(&self, address: &str) -> bool {
+        self.instruction_pointer
+            .as_deref()
+            .is_some_and(|instruction_pointer| {
+                instruction_pointer == address
+                    || parse_address(instruction_pointer)
+                        .is_some_and(|ip| parse_address(address) == Some(ip))
+            })
+    }
+
+    fn toggle_instruction_breakpoint(&mut self, address: String, cx: &mut Context<Self>) {
+        self.session.update(cx, |session, cx| {
+            session.toggle_instruction_breakpoint(address, cx);
+        });
+        cx.notify();
+    }
+
+    fn step_over_instruction(
+        &mut self,
+        _: &StepOverInstruction,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.running_state
+            .update(cx, |state, cx| state.step_over_instruction(cx))
+            .ok();
+    }
+
+    fn step_into_instruction(
+        &mut self,
+        _: &StepIntoInstruction,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.running_state
+            .update(cx, |state, cx| state.step_into_instruction(cx))
+            .ok();
+    }
+
+    /// The text of a source line, once its file has been opened.
+    fn source_text(&self, path: Option<&Arc<Path>>, line: u64, cx: &App) -> Option<String> {
+        let buffer = self.source_buffers.get(path?)?.read(cx);
+        let row = (line as u32).checked_sub(1)?;
+        (row <= buffer.max_point().row).then(|| {
+            let len = buffer.line_len(row);
+            buffer
+                .text_for_range(Point::new(row, 0)..Point::new(row, len))
+                .collect::<String>()
+        })
+    }
+
+    fn render_source_row(
+        &self,
+        ix: usize,
+        path: Option<&Arc<Path>>,
+        name: &SharedString,
+        line: u64,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let text = self.source_text(path, line, cx);
+
+        h_flex()
+            .id(("disassembly-source", ix))
+            .w_full()
+            .pl_6()
+            .gap_2()
+            .text_ui_sm(cx)
+            .text_color(cx.theme().colors().text_muted)
+            .child(
+                Label::new(format!("{name}:{line}"))
+                    .size(LabelSize::Small)
+                    .color(Color::Accent),
+            )
+            .when_some(text, |this, text| {
+                this.child(
+                    Label::new(text.trim().to_owned())
+                        .buffer_font(cx)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .into_any()
+    }
+
+    fn render_instruction_row(
+        &self,
+        ix: usize,
+        instruction: &DisassembledInstruction,
+        row_state: RowState,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let RowState {
+            is_stopped,
+            supports_breakpoints,
+        } = row_state;
+        let is_current = is_stopped && self.is_instruction_pointer(&instruction.address);
+        let breakpoint = self
+            .session
+            .read(cx)
+            .instruction_breakpoints()
+            .find(|state| state.dap.instruction_reference == instruction.address)
+            .map(|state| state.is_enabled);
+        let address = instruction.address.clone();
+
+        h_flex()
+            .id(("disassembly-instruction", ix))
+            .group("disassembly-instruction")
+            .w_full()
+            .gap_2()
+            .when(is_current, |this| {
+                this.bg(cx.theme().colors().editor_debugger_active_line_background)
+            })
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .child(
+                div()
+                    .id(("disassembly-breakpoint", ix))
+                    .w_4()
+                    .flex_none()
+                    .when(supports_breakpoints, |this| {
+                        this.cursor_pointer()
+                            .tooltip(Tooltip::text("Toggle Instruction Breakpoint"))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.toggle_instruction_breakpoint(address.clone(), cx);
+                            }))
+                    })
+                    .map(|this| match breakpoint {
+                        Some(is_enabled) => this.child(
+                            Icon::new(if is_enabled {
+                                IconName::DebugBreakpoint
+                            } else {
+                                IconName::DebugDisabledBreakpoint
+                            })
+                            .size(IconSize::XSmall)
+                            .color(Color::Debugger),
+                        ),
+                        None if supports_breakpoints => this.child(
+                            div().visible_on_hover("disassembly-instruction").child(
+                                Icon::new(IconName::DebugBreakpoint)
+                                    .size(IconSize::XSmall)
+                                    .color(Color::Hint),
+                            ),
+                        ),
+                        None => this,
+                    }),
+            )
+            .child(div().w_4().flex_none().when(is_current, |this| {
+                this.child(
+                    Icon::new(IconName::ArrowRight)
+                        .size(IconSize::XSmall)
+                        .color(Color::Warning),
+                )
+            }))
+            .child(
+                Label::new(instruction.address.clone())
+                    .buffer_font(cx)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .when_some(instruction.instruction_bytes.clone(), |this, bytes| {
+                this.child(
+                    Label::new(bytes)
+                        .buffer_font(cx)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .child(
+                Label::new(instruction.instruction.clone())
+                    .buffer_font(cx)
+                    .size(LabelSize::Small),
+            )
+            .when_some(instruction.symbol.clone(), |this, symbol| {
+                this.child(
+                    Label::new(format!("<{symbol}>"))
+                        .buffer_font(cx)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .into_any()
+    }
+
+    fn render_row(&self, ix: usize, row_state: RowState, cx: &mut Context<Self>) -> AnyElement {
+        match &self.rows[ix] {
+            DisassemblyRow::Source { path, name, line } => {
+                self.render_source_row(ix, path.as_ref(), name, *line, cx)
+            }
+            DisassemblyRow::Instruction(instruction) => {
+                self.render_instruction_row(ix, instruction, row_state, cx)
+            }
+        }
+    }
+
+    fn is_stopped(&self, cx: &App) -> bool {
+        self.running_state
+            .read_with(cx, |state, cx| state.thread_status(cx))
+            .ok()
+            .flatten()
+            == Some(ThreadStatus::Stopped)
+    }
+
+    fn render_controls(&self, is_stopped: bool, cx: &mut Context<Self>) -> impl IntoElement {
+        let supports_stepping_granularity = self
+            .session
+            .read(cx)
+            .capabilities()
+            .supports_stepping_granularity
+            .unwrap_or_default();
+        let focus_handle = self.focus_handle.clone();
+
+        h_flex()
+            .w_full()
+            .gap_1()
+            .child(
+                IconButton::new("disassembly-step-over", IconName::DebugStepOver)
+                    .icon_size(IconSize::Small)
+                    .disabled(!is_stopped || !supports_stepping_granularity)
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.step_over_instruction(&StepOverInstruction, window, cx);
+                    }))
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |window, cx| {
+                            Tooltip::for_action_in(
+                                "Step Over Instruction",
+                                &StepOverInstruction,
+                                &focus_handle,
+                                window,
+                                cx,
+                            )
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("disassembly-step-into", IconName::DebugStepInto)
+                    .icon_size(IconSize::Small)
+                    .disabled(!is_stopped || !supports_stepping_granularity)
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.step_into_instruction(&StepIntoInstruction, window, cx);
+                    }))
+                    .tooltip(move |window, cx| {
+                        Tooltip::for_action_in(
+                            "Step Into Instruction",
+                            &StepIntoInstruction,
+                            &focus_handle,
+                            window,
+                            cx,
+                        )
+                    }),
+            )
+    }
+
+    #[cfg(test)]
+    pub(crate) fn assert_visual_rows(&self, expected: Vec<&str>, cx: &App) {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| match row {
+                DisassemblyRow::Source { path, name, line } => {
+                    match self.source_text(path.as_ref(), *line, cx) {
+                        Some(text) => format!("{name}:{line} {}", text.trim()),
+                        None => format!("{name}:{line}"),
+                    }
+                }
+                DisassemblyRow::Instruction(instruction) => {
+                    let pointer = if self.is_instruction_pointer(&instruction.address) {
+                        " <=== instruction pointer"
+                    } else {
+                        ""
+                    };
+                    format!(
+                        "    {} {}{pointer}",
+                        instruction.address, instruction.instruction
+                    )
+                }
+            })
+            .collect::<Vec<_>>();
+        pretty_assertions::assert_eq!(expected, rows);
+    }
+
+    fn render_vertical_scrollbar(&self, cx: &mut Context<Self>) -> Stateful<Div> {
+        div()
+            .occlude()
+            .id("disassembly-view-vertical-scrollbar")
+            .on_mouse_move(cx.listener(|_, _, _, cx| {
+                cx.notify();
+                cx.stop_propagation()
+            }))
+            .on_hover(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_any_mouse_down(|_, _, cx| {
+                cx.stop_propagation();
+            })
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|_, _, _, cx| {
+                    cx.stop_propagation();
+                }),
+            )
+            .on_scroll_wheel(cx.listener(|_, _, _, cx| {
+                cx.notify();
+            }))
+            .h_full()
+            .absolute()
+            .right_1()
+            .top_1()
+            .bottom_0()
+            .w(px(12.))
+            .cursor_default()
+            .children(Scrollbar::vertical(self.scrollbar_state.clone()))
+    }
+}
+
+/// Parses an address like `0x7ff6a1b2` the way adapters send them in memory references.
+fn parse_address(memory_reference: &str) -> Option<u64> {
+    parse_int::parse::<u64>(memory_reference).ok()
+}
+
+impl Focusable for DisassemblyView {
+    fn focus_handle(&self, _: &gpui::App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DisassemblyView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_stopped = self.is_stopped(cx);
+        let row_state = RowState {
+            is_stopped,
+            supports_breakpoints: self
+                .session
+                .read(cx)
+                .capabilities()
+                .supports_instruction_breakpoints
+                .unwrap_or_default(),
+        };
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .key_context("DisassemblyView")
+            .on_action(cx.listener(Self::step_over_instruction))
+            .on_action(cx.listener(Self::step_into_instruction))
+            .size_full()
+            .p_1()
+            .child(self.render_controls(is_stopped, cx))
+            .child(
+                div()
+                    .size_full()
+                    .child(if !self.rows.is_empty() {
+                        uniform_list(
+                            "disassembly-view",
+                            self.rows.len(),
+                            cx.processor(move |this, range: Range<usize>, _window, cx| {
+                                range.map(|ix| this.render_row(ix, row_state, cx)).collect()
+                            }),
+                        )
+                        .track_scroll(self.scroll_handle.clone())
+                        .size_full()
+                        .into_any_element()
+                    } else {
+                        Label::new("No disassembly for the selected stack frame")
+                            .color(Color::Muted)
+                            .into_any_element()
+                    })
+                    .child(self.render_vertical_scrollbar(cx)),
+            )
+    }
+}
@@ -508,6 +508,24 @@ impl VariableList {
 
     fn confirm(&mut self, _: &menu::Confirm, _window: &mut Window, cx: &mut Context<Self>) {
         if let Some((var_path, editor)) = self.edited_path.take() {
+            let watcher_expression = self
+                .entries
+                .iter()
+                .find(|entry| entry.path == var_path)
+                .and_then(|entry| entry.as_watcher())
+                .map(|watcher| watcher.expression.to_string());
+            if let Some(expression) = watcher_expression {
+                let Some(stack_frame_id) = self.selected_stack_frame_id else {
+                    return;
+                };
+                let value = editor.read(cx).text(cx);
+
+                self.session.update(cx, |session, cx| {
+                    session.set_expression(stack_frame_id, expression, value, cx)
+                });
+                return;
+            }
+
             let Some(state) = self.entry_states.get(&var_path) else {
                 return;
             };
@@ -629,23 +647,31 @@ impl VariableList {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let (supports_set_variable, supports_data_breakpoints, supports_go_to_memory) =
-            self.session.read_with(cx, |session, _| {
-                (
-                    session
-                        .capabilities()
-                        .supports_set_variable
-                        .unwrap_or_default(),
-                    session
-                        .capabilities()
-                        .supports_data_breakpoints
-                        .unwrap_or_default(),
-                    session
-                        .capabilities()
-                        .supports_read_memory_request
-                        .unwrap_or_default(),
-                )
-            });
+        let (
+            supports_set_variable,
+            supports_set_expression,
+            supports_data_breakpoints,
+            supports_go_to_memory,
+        ) = self.session.read_with(cx, |session, _| {
+            (
+                session
+                    .capabilities()
+                    .supports_set_variable
+                    .unwrap_or_default(),
+                session
+                    .capabilities()
+                    .supports_set_expression
+                    .unwrap_or_default(),
+                session
+                    .capabilities()
+                    .supports_data_breakpoints
+                    .unwrap_or_default(),
+                session
+                    .capabilities()
+                    .supports_read_memory_request
+                    .unwrap_or_default(),
+            )
+        });
         let can_toggle_data_breakpoint = entry
             .as_variable()
             .filter(|_| supports_data_breakpoints)
@@ -720,7 +746,7 @@ impl VariableList {
                     .when(entry.as_watcher().is_some(), |menu| {
                         menu.action("Copy Name", CopyVariableName.boxed_clone())
                             .action("Copy Value", CopyVariableValue.boxed_clone())
-                            .when(supports_set_variable, |menu| {
+                            .when(supports_set_expression, |menu| {
                                 menu.action("Edit Value", EditVariable.boxed_clone())
                             })
                             .action("Remove Watch", RemoveWatch.boxed_clone())
@@ -1095,13 +1121,15 @@ impl VariableList {
                     } else {
                         this.text_color(cx.theme().colors().text_muted)
                             .when(
-                                !self.disabled
-                                    && self
-                                        .session
-                                        .read(cx)
-                                        .capabilities()
-                                        .supports_set_variable
-                                        .unwrap_or_default(),
+                                !self.disabled && {
+                                    let capabilities = self.session.read(cx).capabilities();
+                                    if entry.as_watcher().is_some() {
+                                        capabilities.supports_set_expression
+                                    } else {
+                                        capabilities.supports_set_variable
+                                    }
+                                    .unwrap_or_default()
+                                },
                                 |this| {
                                     let path = entry.path.clone();
                                     let variable_value = value.clone();
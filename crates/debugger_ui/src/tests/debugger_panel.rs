@@ -9,8 +9,8 @@ use dap::{
     adapters::DebugTaskDefinition,
     client::SessionId,
     requests::{
-        Continue, Disconnect, Launch, Next, RunInTerminal, SetBreakpoints, StackTrace,
-        StartDebugging, StepBack, StepIn, StepOut, Threads,
+        Continue, Disconnect, Goto, GotoTargets, Launch, Next, RunInTerminal, SetBreakpoints,
+        StackTrace, StartDebugging, StepBack, StepIn, StepOut, Threads,
     },
 };
 use editor::{
@@ -20,13 +20,16 @@ use editor::{
 use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
 use project::{
     FakeFs, Project,
-    debugger::session::{ThreadId, ThreadStatus},
+    debugger::{
+        breakpoint_store::BreakpointEditAction,
+        session::{ThreadId, ThreadStatus},
+    },
 };
 use serde_json::json;
 use std::{
     path::Path,
     sync::{
-        Arc,
+        Arc, Mutex,
         atomic::{AtomicBool, Ordering},
     },
 };
@@ -1360,6 +1363,226 @@ async fn test_unsetting_breakpoints_on_clear_breakpoint_action(
     cx.run_until_parked();
 }
 
+#[gpui::test]
+async fn test_send_function_breakpoints(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let breakpoint_store = project.read_with(cx, |project, _| project.breakpoint_store());
+
+    breakpoint_store.update(cx, |breakpoint_store, cx| {
+        breakpoint_store.add_function_breakpoint("main".into(), cx);
+    });
+
+    let sent_function_breakpoints = Arc::new(Mutex::new(Vec::<Vec<String>>::new()));
+
+    start_debug_session(&workspace, cx, {
+        let sent_function_breakpoints = sent_function_breakpoints.clone();
+        move |client| {
+            client.on_request::<dap::requests::Initialize, _>(move |_, _| {
+                Ok(dap::Capabilities {
+                    supports_function_breakpoints: Some(true),
+                    ..Default::default()
+                })
+            });
+
+            let sent_function_breakpoints = sent_function_breakpoints.clone();
+            client.on_request::<dap::requests::SetFunctionBreakpoints, _>(move |_, args| {
+                sent_function_breakpoints.lock().unwrap().push(
+                    args.breakpoints
+                        .into_iter()
+                        .map(|breakpoint| breakpoint.name)
+                        .collect(),
+                );
+
+                Ok(dap::SetFunctionBreakpointsResponse {
+                    breakpoints: Vec::default(),
+                })
+            });
+        }
+    })
+    .unwrap();
+
+    cx.run_until_parked();
+
+    assert_eq!(
+        sent_function_breakpoints
+            .lock()
+            .unwrap()
+            .drain(..)
+            .collect::<Vec<_>>(),
+        vec![vec!["main".to_string()]],
+        "Function breakpoints should be sent when the session starts"
+    );
+
+    breakpoint_store.update(cx, |breakpoint_store, cx| {
+        breakpoint_store.add_function_breakpoint("helper".into(), cx);
+    });
+    cx.run_until_parked();
+
+    assert_eq!(
+        sent_function_breakpoints
+            .lock()
+            .unwrap()
+            .drain(..)
+            .collect::<Vec<_>>(),
+        vec![vec!["main".to_string(), "helper".to_string()]],
+    );
+
+    breakpoint_store.update(cx, |breakpoint_store, cx| {
+        breakpoint_store.edit_function_breakpoint("main", BreakpointEditAction::InvertState, cx);
+    });
+    cx.run_until_parked();
+
+    assert_eq!(
+        sent_function_breakpoints
+            .lock()
+            .unwrap()
+            .drain(..)
+            .collect::<Vec<_>>(),
+        vec![vec!["helper".to_string()]],
+        "Disabled function breakpoints should not be sent"
+    );
+
+    breakpoint_store.update(cx, |breakpoint_store, cx| {
+        breakpoint_store.edit_function_breakpoint("helper", BreakpointEditAction::Toggle, cx);
+    });
+    cx.run_until_parked();
+
+    assert_eq!(
+        sent_function_breakpoints
+            .lock()
+            .unwrap()
+            .drain(..)
+            .collect::<Vec<_>>(),
+        vec![Vec::<String>::new()],
+    );
+}
+
+#[gpui::test]
+async fn test_set_next_statement_sends_goto_request(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<dap::requests::Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_goto_targets_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: Vec::default(),
+            total_frames: None,
+        })
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    client.on_request::<GotoTargets, _>(move |_, args| {
+        assert_eq!(
+            Some(path!("/project/main.rs").to_string()),
+            args.source.path
+        );
+        assert_eq!(3, args.line, "Goto targets are requested for 1-based lines");
+
+        Ok(dap::GotoTargetsResponse {
+            targets: vec![
+                dap::GotoTarget {
+                    id: 7,
+                    label: "Third line".into(),
+                    line: 3,
+                    column: None,
+                    end_line: None,
+                    end_column: None,
+                    instruction_pointer_reference: None,
+                },
+                dap::GotoTarget {
+                    id: 8,
+                    label: "Third line (inlined)".into(),
+                    line: 3,
+                    column: None,
+                    end_line: None,
+                    end_column: None,
+                    instruction_pointer_reference: None,
+                },
+            ],
+        })
+    });
+
+    let called_goto = Arc::new(AtomicBool::new(false));
+    client.on_request::<Goto, _>({
+        let called_goto = called_goto.clone();
+        move |_, args| {
+            assert_eq!(1, args.thread_id);
+            assert_eq!(7, args.target_id, "The first goto target should be used");
+            called_goto.store(true, Ordering::SeqCst);
+
+            Ok(())
+        }
+    });
+
+    session.update(cx, |session, cx| {
+        session.goto_position(
+            Arc::from(Path::new(path!("/project/main.rs"))),
+            2,
+            ThreadId(1),
+            cx,
+        );
+    });
+    cx.run_until_parked();
+
+    assert!(
+        called_goto.load(Ordering::SeqCst),
+        "Goto request handler was not called"
+    );
+}
+
 #[gpui::test]
 async fn test_debug_session_is_shutdown_when_attach_and_launch_request_fails(
     executor: BackgroundExecutor,
@@ -0,0 +1,367 @@
+use crate::{
+    StepIntoInstruction, StepOverInstruction,
+    persistence::DebuggerPaneItem,
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    DisassembledInstruction, StackFrame, SteppingGranularity,
+    requests::{Disassemble, Next, Scopes, SetInstructionBreakpoints, StackTrace, StepIn, Threads},
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{FakeFs, Project};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use util::path;
+
+/// Disassembles the four instructions starting at `base`, the first two compiled from line
+/// `first_line` of main.rs and the others from the line after it.
+fn instructions(base: u64, first_line: u64) -> Vec<DisassembledInstruction> {
+    ["push rbp", "mov rbp, rsp", "xor eax, eax", "ret"]
+        .into_iter()
+        .enumerate()
+        .map(|(ix, instruction)| {
+            let mut instruction = json!({
+                "address": format!("{:#x}", base + 4 * ix as u64),
+                "instruction": instruction,
+                "line": first_line + ix as u64 / 2,
+            });
+            // Adapters only send the location when it changes.
+            if ix == 0 {
+                instruction["location"] = json!({
+                    "name": "main.rs",
+                    "path": path!("/project/main.rs"),
+                });
+            }
+            serde_json::from_value(instruction).unwrap()
+        })
+        .collect()
+}
+
+fn stopped_event() -> dap::messages::Events {
+    dap::messages::Events::Stopped(dap::StoppedEvent {
+        reason: dap::StoppedEventReason::Step,
+        description: None,
+        thread_id: Some(1),
+        preserve_focus_hint: None,
+        text: None,
+        all_threads_stopped: None,
+        hit_breakpoint_ids: None,
+    })
+}
+
+#[gpui::test]
+async fn test_disassembly_view(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "fn main() {\n    let a = 1;\n    let b = 2;\n}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<dap::requests::Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_disassemble_request: Some(true),
+                supports_instruction_breakpoints: Some(true),
+                supports_stepping_granularity: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+
+    let instruction_pointer = Arc::new(Mutex::new("0x1004".to_string()));
+    client.on_request::<StackTrace, _>({
+        let instruction_pointer = instruction_pointer.clone();
+        move |_, _| {
+            Ok(dap::StackTraceResponse {
+                stack_frames: vec![StackFrame {
+                    id: 1,
+                    name: "main".into(),
+                    source: None,
+                    line: 1,
+                    column: 1,
+                    end_line: None,
+                    end_column: None,
+                    can_restart: None,
+                    instruction_pointer_reference: Some(
+                        instruction_pointer.lock().unwrap().clone(),
+                    ),
+                    module_id: None,
+                    presentation_hint: None,
+                }],
+                total_frames: None,
+            })
+        }
+    });
+
+    let disassembled = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<Disassemble, _>({
+        let disassembled = disassembled.clone();
+        move |_, args| {
+            disassembled.lock().unwrap().push((
+                args.memory_reference.clone(),
+                args.instruction_offset,
+                args.instruction_count,
+            ));
+            let instructions = match args.memory_reference.as_str() {
+                "0x1004" => instructions(0x1000, 1),
+                "0x2000" => instructions(0x2000, 3),
+                reference => unreachable!("unexpected memory reference {reference}"),
+            };
+            Ok(dap::DisassembleResponse { instructions })
+        }
+    });
+
+    client.fake_event(stopped_event()).await;
+    cx.run_until_parked();
+
+    // The instructions around the instruction pointer are disassembled, below the source lines
+    // they were compiled from.
+    let running_state = active_debug_session_panel(workspace, cx)
+        .update(cx, |item, _| item.running_state().clone());
+    let disassembly_view = running_state.update(cx, |state, _| state.disassembly_view().clone());
+    assert_eq!(
+        disassembled.lock().unwrap().drain(..).collect::<Vec<_>>(),
+        vec![("0x1004".to_string(), Some(-32), 128)]
+    );
+    disassembly_view.update(cx, |view, cx| {
+        view.assert_visual_rows(
+            vec![
+                "main.rs:1 fn main() {",
+                "    0x1000 push rbp",
+                "    0x1004 mov rbp, rsp <=== instruction pointer",
+                "main.rs:2 let a = 1;",
+                "    0x1008 xor eax, eax",
+                "    0x100c ret",
+            ],
+            cx,
+        );
+    });
+
+    // Stopping at an instruction that's already shown only moves the instruction pointer.
+    *instruction_pointer.lock().unwrap() = "0x100c".to_string();
+    client.fake_event(stopped_event()).await;
+    cx.run_until_parked();
+
+    assert!(disassembled.lock().unwrap().is_empty());
+    disassembly_view.update(cx, |view, cx| {
+        view.assert_visual_rows(
+            vec![
+                "main.rs:1 fn main() {",
+                "    0x1000 push rbp",
+                "    0x1004 mov rbp, rsp",
+                "main.rs:2 let a = 1;",
+                "    0x1008 xor eax, eax",
+                "    0x100c ret <=== instruction pointer",
+            ],
+            cx,
+        );
+    });
+
+    // Stopping anywhere else disassembles the memory there instead.
+    *instruction_pointer.lock().unwrap() = "0x2000".to_string();
+    client.fake_event(stopped_event()).await;
+    cx.run_until_parked();
+
+    assert_eq!(
+        disassembled.lock().unwrap().drain(..).collect::<Vec<_>>(),
+        vec![("0x2000".to_string(), Some(-32), 128)]
+    );
+    disassembly_view.update(cx, |view, cx| {
+        view.assert_visual_rows(
+            vec![
+                "main.rs:3 let b = 2;",
+                "    0x2000 push rbp <=== instruction pointer",
+                "    0x2004 mov rbp, rsp",
+                "main.rs:4 }",
+                "    0x2008 xor eax, eax",
+                "    0x200c ret",
+            ],
+            cx,
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_instruction_breakpoints_and_stepping(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(path!("/project"), json!({ "main.rs": "fn main() {}" }))
+        .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let sent_instruction_breakpoints = Arc::new(Mutex::new(Vec::<Vec<String>>::new()));
+    let session = start_debug_session(&workspace, cx, {
+        let sent_instruction_breakpoints = sent_instruction_breakpoints.clone();
+        move |client| {
+            client.on_request::<dap::requests::Initialize, _>(move |_, _| {
+                Ok(dap::Capabilities {
+                    supports_disassemble_request: Some(true),
+                    supports_instruction_breakpoints: Some(true),
+                    supports_stepping_granularity: Some(true),
+                    ..Default::default()
+                })
+            });
+
+            let sent_instruction_breakpoints = sent_instruction_breakpoints.clone();
+            client.on_request::<SetInstructionBreakpoints, _>(move |_, args| {
+                sent_instruction_breakpoints.lock().unwrap().push(
+                    args.breakpoints
+                        .into_iter()
+                        .map(|breakpoint| breakpoint.instruction_reference)
+                        .collect(),
+                );
+                Ok(dap::SetInstructionBreakpointsResponse {
+                    breakpoints: Vec::default(),
+                })
+            });
+        }
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: Vec::default(),
+            total_frames: None,
+        })
+    });
+
+    let granularities = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<Next, _>({
+        let granularities = granularities.clone();
+        move |_, args| {
+            granularities
+                .lock()
+                .unwrap()
+                .push(("next", args.granularity));
+            Ok(())
+        }
+    });
+    client.on_request::<StepIn, _>({
+        let granularities = granularities.clone();
+        move |_, args| {
+            granularities
+                .lock()
+                .unwrap()
+                .push(("stepIn", args.granularity));
+            Ok(())
+        }
+    });
+
+    client.fake_event(stopped_event()).await;
+    cx.run_until_parked();
+    sent_instruction_breakpoints.lock().unwrap().clear();
+
+    // Setting a breakpoint on an instruction sends all of them, and setting it again removes it.
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x1004".into(), cx);
+    });
+    cx.run_until_parked();
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x1008".into(), cx);
+    });
+    cx.run_until_parked();
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x1004".into(), cx);
+    });
+    cx.run_until_parked();
+
+    // Disabled breakpoints aren't sent, but they're kept so they can be enabled again.
+    session.update(cx, |session, cx| {
+        session.set_instruction_breakpoint_enabled("0x1008", false, cx);
+    });
+    cx.run_until_parked();
+    session.update(cx, |session, cx| {
+        assert_eq!(
+            vec![("0x1008", false)],
+            session
+                .instruction_breakpoints()
+                .map(|state| (state.dap.instruction_reference.as_str(), state.is_enabled))
+                .collect::<Vec<_>>()
+        );
+        session.set_instruction_breakpoint_enabled("0x1008", true, cx);
+    });
+    cx.run_until_parked();
+
+    assert_eq!(
+        sent_instruction_breakpoints
+            .lock()
+            .unwrap()
+            .drain(..)
+            .map(|mut breakpoints| {
+                breakpoints.sort();
+                breakpoints
+            })
+            .collect::<Vec<_>>(),
+        vec![
+            vec!["0x1004".to_string()],
+            vec!["0x1004".to_string(), "0x1008".to_string()],
+            vec!["0x1008".to_string()],
+            vec![],
+            vec!["0x1008".to_string()],
+        ]
+    );
+
+    // The view's stepping actions step a single instruction at a time.
+    active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+        item.running_state().update(cx, |state, cx| {
+            state.activate_item(DebuggerPaneItem::Disassembly, window, cx);
+            state
+                .disassembly_view()
+                .update(cx, |_, cx| cx.focus_self(window));
+        });
+    });
+    cx.run_until_parked();
+
+    cx.dispatch_action(StepOverInstruction);
+    cx.run_until_parked();
+    client.fake_event(stopped_event()).await;
+    cx.run_until_parked();
+    cx.dispatch_action(StepIntoInstruction);
+    cx.run_until_parked();
+
+    assert_eq!(
+        granularities.lock().unwrap().drain(..).collect::<Vec<_>>(),
+        vec![
+            ("next", Some(SteppingGranularity::Instruction)),
+            ("stepIn", Some(SteppingGranularity::Instruction)),
+        ]
+    );
+}
@@ -0,0 +1,458 @@
+use crate::{
+    recording::{SessionReplay, load_recording},
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    ErrorResponse, Scope, StackFrame, StoppedEvent, Thread, Variable,
+    requests::{Scopes, StackTrace, Threads, Variables},
+};
+use gpui::{AppContext as _, BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{
+    FakeFs, Project,
+    debugger::recording::{MAX_RECORDED_STACK_FRAMES, MAX_RECORDED_VARIABLES, VariableSnapshot},
+};
+use serde_json::json;
+use std::{
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+use util::path;
+
+fn variable(name: &str, value: &str, variables_reference: u64) -> Variable {
+    Variable {
+        name: name.into(),
+        value: value.into(),
+        type_: None,
+        presentation_hint: None,
+        evaluate_name: None,
+        variables_reference,
+        named_variables: None,
+        indexed_variables: None,
+        memory_reference: None,
+        declaration_location_reference: None,
+        value_location_reference: None,
+    }
+}
+
+fn stack_frame(id: u64) -> StackFrame {
+    StackFrame {
+        id,
+        name: format!("frame {id}"),
+        source: None,
+        line: 1,
+        column: 1,
+        end_line: None,
+        end_column: None,
+        can_restart: None,
+        instruction_pointer_reference: None,
+        module_id: None,
+        presentation_hint: None,
+    }
+}
+
+fn scope(name: &str, variables_reference: u64, expensive: bool) -> Scope {
+    Scope {
+        name: name.into(),
+        presentation_hint: None,
+        variables_reference,
+        named_variables: None,
+        indexed_variables: None,
+        expensive,
+        source: None,
+        line: None,
+        column: None,
+        end_line: None,
+        end_column: None,
+    }
+}
+
+async fn fake_stop(client: &dap::client::DebugAdapterClient) {
+    client
+        .fake_event(dap::messages::Events::Stopped(StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+}
+
+#[gpui::test]
+async fn test_record_and_replay_session(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![
+                Thread {
+                    id: 1,
+                    name: "main".into(),
+                },
+                Thread {
+                    id: 2,
+                    name: "worker".into(),
+                },
+            ],
+        })
+    });
+
+    client.on_request::<StackTrace, _>(move |_, args| {
+        // Only the thread that hit the stop can report its stack.
+        if args.thread_id != 1 {
+            return Err(ErrorResponse { error: None });
+        }
+
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: Some(dap::Source {
+                    name: Some("main.rs".into()),
+                    path: Some(path!("/project/main.rs").into()),
+                    source_reference: None,
+                    presentation_hint: None,
+                    origin: None,
+                    sources: None,
+                    adapter_data: None,
+                    checksums: None,
+                }),
+                line: 2,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: None,
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    client.on_request::<Scopes, _>(move |_, _| {
+        Ok(dap::ScopesResponse {
+            scopes: vec![scope("Locals", 2, false), scope("Globals", 3, true)],
+        })
+    });
+
+    client.on_request::<Variables, _>(move |_, args| {
+        let variables = match args.variables_reference {
+            2 => vec![variable("point", "Point", 4)],
+            4 => vec![variable("x", "1", 0), variable("y", "2", 0)],
+            3 => vec![variable("global", "0", 0)],
+            _ => vec![],
+        };
+        Ok(dap::VariablesResponse { variables })
+    });
+
+    fake_stop(&client).await;
+    cx.run_until_parked();
+
+    let running_state = active_debug_session_panel(workspace, cx)
+        .read_with(cx, |session, _| session.running_state().clone());
+    let recording_path = PathBuf::from(path!("/project/session.debug-recording.jsonl"));
+
+    running_state.update_in(cx, |running_state, window, cx| {
+        running_state.toggle_recording(window, cx);
+    });
+    cx.simulate_new_path_selection({
+        let recording_path = recording_path.clone();
+        move |_| Some(recording_path)
+    });
+    cx.run_until_parked();
+
+    assert!(running_state.read_with(cx, |running_state, _| running_state.is_recording()));
+
+    let recording = load_recording(&recording_path, fs.clone()).await.unwrap();
+    assert_eq!(
+        1,
+        recording.stops.len(),
+        "The stop the session was in when recording started should be captured"
+    );
+
+    let stop = &recording.stops[0];
+    assert_eq!(Some(1), stop.thread_id);
+    assert_eq!(2, stop.threads.len());
+    assert!(
+        stop.threads[1].stack_frames.is_empty(),
+        "Threads without a stack should still be recorded"
+    );
+
+    let frame = &stop.threads[0].stack_frames[0];
+    assert_eq!("main", frame.stack_frame.name);
+    assert_eq!(2, frame.scopes.len());
+    assert!(
+        frame.scopes[1].variables.is_empty(),
+        "Expensive scopes should not be recorded"
+    );
+
+    let point = &frame.scopes[0].variables[0];
+    assert_eq!("point", point.variable.name);
+    assert_eq!(
+        vec!["x", "y"],
+        point
+            .children
+            .iter()
+            .map(|child| child.variable.name.as_str())
+            .collect::<Vec<_>>()
+    );
+
+    fake_stop(&client).await;
+    cx.run_until_parked();
+
+    let recording = load_recording(&recording_path, fs.clone()).await.unwrap();
+    assert_eq!(2, recording.stops.len(), "Each stop should be recorded");
+    assert_eq!(
+        3,
+        fs.load(&recording_path).await.unwrap().lines().count(),
+        "Each stop should be appended to the file on a line of its own, after the header"
+    );
+
+    running_state.update_in(cx, |running_state, window, cx| {
+        running_state.toggle_recording(window, cx);
+    });
+    cx.run_until_parked();
+
+    fake_stop(&client).await;
+    cx.run_until_parked();
+
+    let recording = load_recording(&recording_path, fs.clone()).await.unwrap();
+    assert_eq!(
+        2,
+        recording.stops.len(),
+        "Stops after the recording ended should not be recorded"
+    );
+
+    let replay = cx.new(|cx| SessionReplay::new(recording, recording_path.clone(), cx));
+    replay.read_with(cx, |replay, _| {
+        assert_eq!(
+            vec![
+                "main stopped".to_string(),
+                format!(" main {}:2", path!("/project/main.rs")),
+                "  Locals".to_string(),
+                "   point Point".to_string(),
+                "  Globals not recorded, expensive to fetch".to_string(),
+                "worker".to_string(),
+            ],
+            replay.visible_entries(),
+            "The stopped thread should be expanded down to the variables of its topmost frame"
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_recording_is_bounded(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(path!("/project"), json!({ "main.rs": "" }))
+        .await;
+
+    let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![Thread {
+                id: 1,
+                name: "main".into(),
+            }],
+        })
+    });
+
+    // A deep stack, whose adapter ignores how many frames were asked for.
+    client.on_request::<StackTrace, _>(move |_, args| {
+        if args.levels.is_some() {
+            assert_eq!(Some(MAX_RECORDED_STACK_FRAMES), args.levels);
+        }
+        Ok(dap::StackTraceResponse {
+            stack_frames: (1..=MAX_RECORDED_STACK_FRAMES * 2)
+                .map(stack_frame)
+                .collect(),
+            total_frames: None,
+        })
+    });
+
+    client.on_request::<Scopes, _>(move |_, _| {
+        Ok(dap::ScopesResponse {
+            scopes: vec![scope("Locals", 1, false)],
+        })
+    });
+
+    // Every variable has a hundred children, far more than can be recorded.
+    let variable_requests = Arc::new(AtomicUsize::new(0));
+    client.on_request::<Variables, _>({
+        let variable_requests = variable_requests.clone();
+        move |_, args| {
+            variable_requests.fetch_add(1, Ordering::SeqCst);
+            let variables = (0..100)
+                .map(|ix| {
+                    let reference = args.variables_reference * 100 + ix + 1;
+                    variable(&format!("v{reference}"), "", reference)
+                })
+                .collect();
+            Ok(dap::VariablesResponse { variables })
+        }
+    });
+
+    fake_stop(&client).await;
+    cx.run_until_parked();
+
+    let running_state = active_debug_session_panel(workspace, cx)
+        .read_with(cx, |session, _| session.running_state().clone());
+    let recording_path = PathBuf::from(path!("/project/session.debug-recording.jsonl"));
+    let variable_requests_before_recording = variable_requests.load(Ordering::SeqCst);
+
+    running_state.update_in(cx, |running_state, window, cx| {
+        running_state.toggle_recording(window, cx);
+    });
+    cx.simulate_new_path_selection({
+        let recording_path = recording_path.clone();
+        move |_| Some(recording_path)
+    });
+    cx.run_until_parked();
+
+    fn count_variables(variables: &[VariableSnapshot]) -> usize {
+        variables
+            .iter()
+            .map(|variable| 1 + count_variables(&variable.children))
+            .sum()
+    }
+
+    let recording = load_recording(&recording_path, fs.clone()).await.unwrap();
+    let frames = &recording.stops[0].threads[0].stack_frames;
+    assert_eq!(MAX_RECORDED_STACK_FRAMES as usize, frames.len());
+    assert_eq!(
+        MAX_RECORDED_VARIABLES,
+        frames
+            .iter()
+            .flat_map(|frame| &frame.scopes)
+            .map(|scope| count_variables(&scope.variables))
+            .sum::<usize>()
+    );
+    // The Locals of every frame are captured first, then the children of the variables in them
+    // until the budget runs out.
+    assert!(
+        frames
+            .iter()
+            .all(|frame| frame.scopes[0].variables.len() == 100)
+    );
+    assert_eq!(
+        MAX_RECORDED_VARIABLES / 100,
+        variable_requests.load(Ordering::SeqCst) - variable_requests_before_recording,
+        "No more variables should be requested once the stop's budget is used up"
+    );
+}
+
+#[gpui::test]
+async fn test_recording_shares_budget_between_variables(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(path!("/project"), json!({ "main.rs": "" }))
+        .await;
+
+    let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![Thread {
+                id: 1,
+                name: "main".into(),
+            }],
+        })
+    });
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![stack_frame(1)],
+            total_frames: None,
+        })
+    });
+    client.on_request::<Scopes, _>(move |_, _| {
+        Ok(dap::ScopesResponse {
+            scopes: vec![scope("Locals", 1, false)],
+        })
+    });
+
+    // The first variable has more nested variables than can be recorded, while its sibling
+    // only has one.
+    client.on_request::<Variables, _>(move |_, args| {
+        let variables = match args.variables_reference {
+            1 => vec![variable("wide", "", 2), variable("sibling", "", 3)],
+            3 => vec![variable("field", "1", 0)],
+            reference => (0..100)
+                .map(|ix| {
+                    let reference = reference * 100 + ix;
+                    variable(&format!("v{reference}"), "", reference)
+                })
+                .collect(),
+        };
+        Ok(dap::VariablesResponse { variables })
+    });
+
+    fake_stop(&client).await;
+    cx.run_until_parked();
+
+    let running_state = active_debug_session_panel(workspace, cx)
+        .read_with(cx, |session, _| session.running_state().clone());
+    let recording_path = PathBuf::from(path!("/project/session.debug-recording.jsonl"));
+
+    running_state.update_in(cx, |running_state, window, cx| {
+        running_state.toggle_recording(window, cx);
+    });
+    cx.simulate_new_path_selection({
+        let recording_path = recording_path.clone();
+        move |_| Some(recording_path)
+    });
+    cx.run_until_parked();
+
+    let recording = load_recording(&recording_path, fs.clone()).await.unwrap();
+    let variables = &recording.stops[0].threads[0].stack_frames[0].scopes[0].variables;
+    assert_eq!("wide", variables[0].variable.name);
+    assert_eq!(100, variables[0].children.len());
+    assert_eq!("sibling", variables[1].variable.name);
+    assert_eq!(
+        vec!["field"],
+        variables[1]
+            .children
+            .iter()
+            .map(|child| child.variable.name.as_str())
+            .collect::<Vec<_>>(),
+        "A variable with many nested variables shouldn't use up the budget of its siblings"
+    );
+}
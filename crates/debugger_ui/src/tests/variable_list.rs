@@ -1,5 +1,5 @@
 use std::sync::{
-    Arc,
+    Arc, Mutex,
     atomic::{AtomicBool, Ordering},
 };
 
@@ -14,7 +14,7 @@ use crate::{
 use collections::HashMap;
 use dap::{
     Scope, StackFrame, Variable,
-    requests::{Evaluate, Initialize, Launch, Scopes, StackTrace, Variables},
+    requests::{Evaluate, Initialize, Launch, Scopes, SetExpression, StackTrace, Variables},
 };
 use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
 use menu::{SelectFirst, SelectNext, SelectPrevious};
@@ -2340,3 +2340,90 @@ async fn test_refresh_watchers(executor: BackgroundExecutor, cx: &mut TestAppCon
         assert_eq!(3, watcher.variables_reference);
     });
 }
+
+#[gpui::test]
+async fn test_set_watcher_value(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(path!("/project"), json!({ "main.rs": "" }))
+        .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_set_expression: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    let value = Arc::new(Mutex::new("1".to_string()));
+    client.on_request::<Evaluate, _>({
+        let value = value.clone();
+        move |_, args| {
+            assert_eq!("variable1", args.expression);
+            assert_eq!(Some(1), args.frame_id);
+
+            Ok(dap::EvaluateResponse {
+                result: value.lock().unwrap().clone(),
+                type_: None,
+                presentation_hint: None,
+                variables_reference: 0,
+                named_variables: None,
+                indexed_variables: None,
+                memory_reference: None,
+                value_location_reference: None,
+            })
+        }
+    });
+
+    let set_expressions = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<SetExpression, _>({
+        let value = value.clone();
+        let set_expressions = set_expressions.clone();
+        move |_, args| {
+            set_expressions.lock().unwrap().push((
+                args.expression.clone(),
+                args.value.clone(),
+                args.frame_id,
+            ));
+            *value.lock().unwrap() = args.value.clone();
+            Ok(serde_json::from_value(json!({ "value": args.value })).unwrap())
+        }
+    });
+
+    session
+        .update(cx, |session, cx| {
+            session.add_watcher("variable1".into(), 1, cx)
+        })
+        .await
+        .unwrap();
+
+    session.update(cx, |session, cx| {
+        session.set_expression(1, "variable1".into(), "42".into(), cx);
+    });
+    cx.run_until_parked();
+
+    assert_eq!(
+        vec![("variable1".to_string(), "42".to_string(), Some(1))],
+        *set_expressions.lock().unwrap()
+    );
+    session.update(cx, |session, _| {
+        let watcher = session
+            .watchers()
+            .get(&SharedString::from("variable1"))
+            .unwrap();
+
+        assert_eq!(
+            "42",
+            watcher.value.to_string(),
+            "Watchers should be refreshed after an expression is set"
+        );
+    });
+}
@@ -379,8 +379,13 @@ impl ProjectDiagnosticsEditor {
 
     /// Enqueue an update of all excerpts. Updates all paths that either
     /// currently have diagnostics or are currently present in this view.
+    /// In local projects, also asks the language servers for fresh workspace
+    /// diagnostics, which stream in for the files that were never opened.
     fn update_all_excerpts(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.project.update(cx, |project, cx| {
+            project.lsp_store().update(cx, |lsp_store, _| {
+                lsp_store.pull_workspace_diagnostics_for_all_servers();
+            });
             let mut paths = project
                 .diagnostic_summaries(false, cx)
                 .map(|(path, _, _)| path)
@@ -291,6 +291,34 @@ pub struct SplitSelectionIntoLines {
     pub keep_selections: bool,
 }
 
+/// Aligns the selected lines on a delimiter.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct AlignOnDelimiter {
+    /// The delimiter to align on. Prompts for one when omitted.
+    #[serde(default)]
+    pub delimiter: Option<String>,
+    /// Whether the delimiter is a regular expression.
+    #[serde(default)]
+    pub regex: bool,
+    /// How to justify the text between delimiters.
+    #[serde(default)]
+    pub justify: Justification,
+    /// Align on the first delimiter of each line only, instead of on every delimiter.
+    #[serde(default)]
+    pub first_only: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Justification {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
 /// Goes to the next diagnostic in the file.
 #[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
 #[action(namespace = editor)]
@@ -314,6 +342,8 @@ actions!(
     [
         /// Runs program execution to the current cursor position.
         RunToCursor,
+        /// Moves program execution to the current cursor position, without running the code in between.
+        SetNextStatement,
         /// Evaluates the selected text in the debugger context.
         EvaluateSelectedText
     ]
@@ -328,6 +358,15 @@ actions!(
     ]
 );
 
+actions!(
+    bookmarks,
+    [
+        /// Edits the label of the bookmark at the current line, adding a bookmark if needed.
+        #[action(name = "EditLabel")]
+        EditBookmarkLabel
+    ]
+);
+
 actions!(
     editor,
     [
@@ -340,6 +379,8 @@ actions!(
         AddSelectionAbove,
         /// Adds a cursor below the current selection.
         AddSelectionBelow,
+        /// Aligns the columns of the markdown table at the cursor.
+        AlignMarkdownTable,
         /// Applies all diff hunks in the editor.
         ApplyAllDiffHunks,
         /// Applies the diff hunk at the current position.
@@ -483,10 +524,14 @@ actions!(
         GoToImplementation,
         /// Goes to implementation in a split pane.
         GoToImplementationSplit,
+        /// Goes to the next bookmark in the project.
+        GoToNextBookmark,
         /// Goes to the next change in the file.
         GoToNextChange,
         /// Goes to the parent module of the current file.
         GoToParentModule,
+        /// Goes to the previous bookmark in the project.
+        GoToPreviousBookmark,
         /// Goes to the previous change in the file.
         GoToPreviousChange,
         /// Goes to the type definition of the symbol at cursor.
@@ -698,6 +743,8 @@ actions!(
         Tab,
         /// Removes a tab character or outdents.
         Backtab,
+        /// Toggles a bookmark at the current line.
+        ToggleBookmark,
         /// Toggles a breakpoint at the current line.
         ToggleBreakpoint,
         /// Toggles the case of selected text.
@@ -0,0 +1,577 @@
+use crate::{
+    Editor, EditorEvent, MultiBufferSnapshot,
+    actions::{AlignMarkdownTable, AlignOnDelimiter, Justification},
+};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Styled, Subscription,
+    WeakEntity, div, prelude::*,
+};
+use multi_buffer::MultiBufferRow;
+use regex::Regex;
+use std::{collections::BTreeSet, ops::Range};
+use text::Point;
+use ui::prelude::*;
+use util::ResultExt as _;
+use workspace::ModalView;
+
+impl Editor {
+    /// Aligns the selected lines on a delimiter, prompting for the delimiter if the action
+    /// doesn't specify one. With a single cursor, the lines around it that contain the delimiter
+    /// are aligned.
+    pub fn align_on_delimiter(
+        &mut self,
+        action: &AlignOnDelimiter,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(delimiter) = action
+            .delimiter
+            .as_ref()
+            .filter(|delimiter| !delimiter.is_empty())
+        else {
+            let Some(workspace) = self.workspace() else {
+                return;
+            };
+            let editor = cx.entity().downgrade();
+            let action = action.clone();
+            workspace.update(cx, |workspace, cx| {
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    AlignDelimiterPrompt::new(editor, action, window, cx)
+                });
+            });
+            return;
+        };
+
+        let pattern = if action.regex {
+            Regex::new(delimiter)
+        } else {
+            Regex::new(&regex::escape(delimiter))
+        };
+        let Some(pattern) = pattern.log_err() else {
+            return;
+        };
+        let tab_size = self.buffer.read(cx).language_settings(cx).tab_size.get() as usize;
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut rows = self.selected_rows(&snapshot, cx);
+        if rows.len() == 1
+            && let Some(&row) = rows.first()
+        {
+            rows = expand_rows(&snapshot, row, |line| pattern.is_match(line)).collect();
+        }
+
+        let lines = rows
+            .iter()
+            .map(|&row| line_text(&snapshot, row))
+            .collect::<Vec<_>>();
+        let aligned = align_lines(
+            &lines,
+            &pattern,
+            action.justify,
+            action.first_only,
+            tab_size,
+        );
+        self.replace_lines(&snapshot, &rows, &lines, &aligned, window, cx);
+    }
+
+    /// Aligns the columns of the markdown tables that contain a cursor, and normalizes their
+    /// delimiter rows to match.
+    pub fn align_markdown_table(
+        &mut self,
+        _: &AlignMarkdownTable,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let tab_size = self.buffer.read(cx).language_settings(cx).tab_size.get() as usize;
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut table_rows = BTreeSet::new();
+        for row in self.selected_rows(&snapshot, cx) {
+            if table_rows.contains(&row) {
+                continue;
+            }
+            table_rows.extend(expand_rows(&snapshot, row, is_table_row));
+        }
+
+        let mut rows = Vec::new();
+        let mut lines = Vec::new();
+        let mut aligned = Vec::new();
+        let table_rows = table_rows.into_iter().collect::<Vec<_>>();
+        for table in table_rows.chunk_by(|previous, next| previous + 1 == *next) {
+            let table_lines = table
+                .iter()
+                .map(|&row| line_text(&snapshot, row))
+                .collect::<Vec<_>>();
+            aligned.extend(align_markdown_table(&table_lines, tab_size));
+            lines.extend(table_lines);
+            rows.extend_from_slice(table);
+        }
+        self.replace_lines(&snapshot, &rows, &lines, &aligned, window, cx);
+    }
+
+    /// The rows touched by any selection, in ascending order. A selection ending at the start of
+    /// a line doesn't include that line.
+    fn selected_rows(&self, snapshot: &MultiBufferSnapshot, cx: &mut App) -> Vec<u32> {
+        let mut rows = BTreeSet::new();
+        for selection in self.selections.all::<Point>(cx) {
+            let mut end_row = selection.end.row;
+            if selection.end.column == 0 && selection.end.row > selection.start.row {
+                end_row -= 1;
+            }
+            rows.extend(selection.start.row..=end_row.min(snapshot.max_point().row));
+        }
+        rows.into_iter().collect()
+    }
+
+    fn replace_lines(
+        &mut self,
+        snapshot: &MultiBufferSnapshot,
+        rows: &[u32],
+        lines: &[String],
+        new_lines: &[String],
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mut edits = Vec::new();
+        for ((&row, line), new_line) in rows.iter().zip(lines).zip(new_lines) {
+            let line_start = snapshot.point_to_offset(Point::new(row, 0));
+            for (range, new_text) in language::text_diff(line, new_line) {
+                edits.push((line_start + range.start..line_start + range.end, new_text));
+            }
+        }
+        if edits.is_empty() {
+            return;
+        }
+        self.transact(window, cx, |this, _, cx| {
+            this.edit(edits, cx);
+        });
+    }
+}
+
+fn line_text(snapshot: &MultiBufferSnapshot, row: u32) -> String {
+    let line_end = Point::new(row, snapshot.line_len(MultiBufferRow(row)));
+    snapshot
+        .text_for_range(Point::new(row, 0)..line_end)
+        .collect()
+}
+
+/// The contiguous rows around `row` that satisfy `predicate`, including `row` itself.
+fn expand_rows(
+    snapshot: &MultiBufferSnapshot,
+    row: u32,
+    predicate: impl Fn(&str) -> bool,
+) -> Range<u32> {
+    let matches = |row| predicate(&line_text(snapshot, row));
+    if !matches(row) {
+        return row..row + 1;
+    }
+    let mut start = row;
+    while start > 0 && matches(start - 1) {
+        start -= 1;
+    }
+    let mut end = row + 1;
+    while end <= snapshot.max_point().row && matches(end) {
+        end += 1;
+    }
+    start..end
+}
+
+fn is_table_row(line: &str) -> bool {
+    line.contains('|')
+}
+
+/// The number of columns `text` occupies when it starts at `start_column`, expanding tabs to the
+/// next multiple of `tab_size`.
+fn display_width(text: &str, start_column: usize, tab_size: usize) -> usize {
+    let mut column = start_column;
+    for c in text.chars() {
+        if c == '\t' {
+            column += tab_size - column % tab_size;
+        } else {
+            column += 1;
+        }
+    }
+    column - start_column
+}
+
+fn justify(text: &str, padding: usize, justification: Justification) -> String {
+    let left_padding = match justification {
+        Justification::Left => 0,
+        Justification::Right => padding,
+        Justification::Center => padding / 2,
+    };
+    let right_padding = padding - left_padding;
+    format!(
+        "{}{text}{}",
+        " ".repeat(left_padding),
+        " ".repeat(right_padding)
+    )
+}
+
+struct ColumnLayout {
+    start: usize,
+    width: usize,
+    delimiter_width: usize,
+    is_attached: bool,
+    has_content: bool,
+}
+
+struct DelimitedLine<'a> {
+    indent: &'a str,
+    cells: Vec<&'a str>,
+    delimiters: Vec<&'a str>,
+    /// Whether each delimiter is separated by whitespace from the text before it.
+    spaced_delimiters: Vec<bool>,
+}
+
+/// Aligns `lines` into columns separated by the matches of `delimiter`, leaving lines without a
+/// delimiter unchanged.
+///
+/// The text between delimiters is trimmed and padded with spaces to the width of its column. A
+/// delimiter stays attached to the text before it if it is not preceded by whitespace on any
+/// line, as in `key: value`; otherwise it is surrounded by single spaces, as in `name = value`.
+pub(crate) fn align_lines(
+    lines: &[String],
+    delimiter: &Regex,
+    justification: Justification,
+    first_only: bool,
+    tab_size: usize,
+) -> Vec<String> {
+    let parsed = lines
+        .iter()
+        .map(|line| {
+            let indent_len = line.len() - line.trim_start().len();
+            let mut cells = Vec::new();
+            let mut delimiters = Vec::new();
+            let mut spaced_delimiters = Vec::new();
+            let mut cell_start = indent_len;
+            let matches = delimiter
+                .find_iter(&line[indent_len..])
+                .filter(|mat| !mat.is_empty())
+                .take(if first_only { 1 } else { usize::MAX });
+            for mat in matches {
+                let range = indent_len + mat.start()..indent_len + mat.end();
+                let cell = line[cell_start..range.start].trim();
+                cells.push(cell);
+                delimiters.push(&line[range.clone()]);
+                spaced_delimiters
+                    .push(!cell.is_empty() && line[..range.start].ends_with(char::is_whitespace));
+                cell_start = range.end;
+            }
+            cells.push(line[cell_start..].trim());
+            DelimitedLine {
+                indent: &line[..indent_len],
+                cells,
+                delimiters,
+                spaced_delimiters,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let column_count = parsed
+        .iter()
+        .filter(|line| !line.delimiters.is_empty())
+        .map(|line| line.cells.len())
+        .max()
+        .unwrap_or(0);
+    let mut column_start = 0;
+    let mut column_layouts = Vec::new();
+    for column in 0..column_count {
+        let is_attached = parsed
+            .iter()
+            .all(|line| line.spaced_delimiters.get(column) != Some(&true));
+
+        let mut width = 0;
+        let mut delimiter_width = 0;
+        let mut has_content = false;
+        for line in parsed.iter().filter(|line| !line.delimiters.is_empty()) {
+            let Some(cell) = line.cells.get(column) else {
+                continue;
+            };
+            let is_last = column + 1 == line.cells.len();
+            has_content |= !cell.is_empty();
+            if is_last && justification == Justification::Left {
+                continue;
+            }
+            let prefix = if column == 0 { line.indent } else { "" };
+            let mut unit = format!("{prefix}{cell}");
+            if let Some(delimiter) = line.delimiters.get(column) {
+                if is_attached {
+                    unit.push_str(delimiter);
+                } else {
+                    delimiter_width = delimiter_width.max(display_width(delimiter, 0, tab_size));
+                }
+            }
+            width = width.max(display_width(&unit, column_start, tab_size));
+        }
+
+        let separator_width = match (is_attached, has_content) {
+            (true, _) => 0,
+            (false, true) => 1 + delimiter_width,
+            (false, false) => delimiter_width,
+        };
+        column_layouts.push(ColumnLayout {
+            start: column_start,
+            width,
+            delimiter_width,
+            is_attached,
+            has_content,
+        });
+        column_start += width + separator_width + 1;
+    }
+
+    lines
+        .iter()
+        .zip(&parsed)
+        .map(|(line, parsed_line)| {
+            if parsed_line.delimiters.is_empty() {
+                return line.clone();
+            }
+            let mut aligned = String::new();
+            for (column, cell) in parsed_line.cells.iter().enumerate() {
+                let layout = &column_layouts[column];
+                let delimiter = parsed_line.delimiters.get(column);
+                if column > 0 {
+                    aligned.push(' ');
+                }
+                let prefix = if column == 0 { parsed_line.indent } else { "" };
+                aligned.push_str(prefix);
+                let mut unit = cell.to_string();
+                if layout.is_attached
+                    && let Some(delimiter) = delimiter
+                {
+                    unit.push_str(delimiter);
+                }
+                let unit_width = display_width(&format!("{prefix}{unit}"), layout.start, tab_size);
+                let padding = layout.width.saturating_sub(unit_width);
+                aligned.push_str(&justify(&unit, padding, justification));
+                if !layout.is_attached
+                    && let Some(delimiter) = delimiter
+                {
+                    if layout.has_content {
+                        aligned.push(' ');
+                    }
+                    let padding = layout
+                        .delimiter_width
+                        .saturating_sub(display_width(delimiter, 0, tab_size));
+                    aligned.push_str(&justify(delimiter, padding, Justification::Left));
+                }
+            }
+            aligned.truncate(aligned.trim_end().len());
+            aligned
+        })
+        .collect()
+}
+
+/// Aligns the columns of a markdown table, justifying each column as its delimiter row
+/// specifies, and adding the outer pipes if they are missing.
+pub(crate) fn align_markdown_table(lines: &[String], tab_size: usize) -> Vec<String> {
+    let indent = lines
+        .first()
+        .map_or("", |line| &line[..line.len() - line.trim_start().len()]);
+    let rows = lines
+        .iter()
+        .map(|line| split_table_row(line))
+        .collect::<Vec<_>>();
+    let is_delimiter_row = |cells: &[&str]| {
+        !cells.is_empty()
+            && cells.iter().all(|cell| {
+                let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+                !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+            })
+    };
+    let justifications = rows
+        .iter()
+        .find(|cells| is_delimiter_row(cells))
+        .map(|cells| {
+            cells
+                .iter()
+                .map(|cell| match (cell.starts_with(':'), cell.ends_with(':')) {
+                    (true, true) => Some(Justification::Center),
+                    (false, true) => Some(Justification::Right),
+                    (true, false) => Some(Justification::Left),
+                    (false, false) => None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let column_count = rows.iter().map(|cells| cells.len()).max().unwrap_or(0);
+    let widths = (0..column_count)
+        .map(|column| {
+            rows.iter()
+                .filter(|cells| !is_delimiter_row(cells))
+                .filter_map(|cells| cells.get(column))
+                .map(|cell| display_width(cell, 0, tab_size))
+                .max()
+                .unwrap_or(0)
+                .max(3)
+        })
+        .collect::<Vec<_>>();
+
+    rows.iter()
+        .map(|cells| {
+            let is_delimiter = is_delimiter_row(cells);
+            let mut aligned = format!("{indent}|");
+            for (column, &width) in widths.iter().enumerate() {
+                let justification = justifications.get(column).copied().flatten();
+                let cell = if is_delimiter {
+                    match justification {
+                        None => "-".repeat(width),
+                        Some(Justification::Left) => format!(":{}", "-".repeat(width - 1)),
+                        Some(Justification::Right) => format!("{}:", "-".repeat(width - 1)),
+                        Some(Justification::Center) => format!(":{}:", "-".repeat(width - 2)),
+                    }
+                } else {
+                    let cell = cells.get(column).copied().unwrap_or("");
+                    let padding = width.saturating_sub(display_width(cell, 0, tab_size));
+                    justify(cell, padding, justification.unwrap_or_default())
+                };
+                aligned.push(' ');
+                aligned.push_str(&cell);
+                aligned.push_str(" |");
+            }
+            aligned
+        })
+        .collect()
+}
+
+/// Splits a markdown table row on the pipes that aren't escaped, dropping the optional outer
+/// pipes.
+fn split_table_row(line: &str) -> Vec<&str> {
+    let mut row = line.trim();
+    row = row.strip_prefix('|').unwrap_or(row);
+    if row.ends_with('|') && !row.ends_with("\\|") {
+        row = &row[..row.len() - 1];
+    }
+
+    let mut cells = Vec::new();
+    let mut cell_start = 0;
+    let mut escaped = false;
+    for (ix, c) in row.char_indices() {
+        match c {
+            '\\' => escaped = !escaped,
+            '|' if !escaped => {
+                cells.push(row[cell_start..ix].trim());
+                cell_start = ix + 1;
+            }
+            _ => escaped = false,
+        }
+    }
+    cells.push(row[cell_start..].trim());
+    cells
+}
+
+/// A prompt for the delimiter to align on, for when [`AlignOnDelimiter`] is dispatched without
+/// one.
+struct AlignDelimiterPrompt {
+    editor: WeakEntity<Editor>,
+    action: AlignOnDelimiter,
+    delimiter_editor: Entity<Editor>,
+    _delimiter_editor_subscription: Subscription,
+}
+
+impl ModalView for AlignDelimiterPrompt {}
+
+impl Focusable for AlignDelimiterPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.delimiter_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for AlignDelimiterPrompt {}
+
+impl AlignDelimiterPrompt {
+    fn new(
+        editor: WeakEntity<Editor>,
+        action: AlignOnDelimiter,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delimiter_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(
+                if action.regex {
+                    "Regular expression to align on"
+                } else {
+                    "Delimiter to align on"
+                },
+                cx,
+            );
+            editor
+        });
+        let delimiter_editor_subscription =
+            cx.subscribe(&delimiter_editor, |_, _, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    cx.notify();
+                }
+            });
+        Self {
+            editor,
+            action,
+            delimiter_editor,
+            _delimiter_editor_subscription: delimiter_editor_subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let delimiter = self.delimiter_editor.read(cx).text(cx);
+        if delimiter.is_empty() || self.error(cx).is_some() {
+            return;
+        }
+        let action = AlignOnDelimiter {
+            delimiter: Some(delimiter),
+            ..self.action.clone()
+        };
+        self.editor
+            .update(cx, |editor, cx| {
+                editor.align_on_delimiter(&action, window, cx);
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+
+    fn error(&self, cx: &App) -> Option<String> {
+        if !self.action.regex {
+            return None;
+        }
+        let delimiter = self.delimiter_editor.read(cx).text(cx);
+        Regex::new(&delimiter).err().map(|error| error.to_string())
+    }
+}
+
+impl Render for AlignDelimiterPrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (help_text, color) = match self.error(cx) {
+            Some(error) => (error, Color::Error),
+            None => (
+                "Align the selected lines on this delimiter".to_string(),
+                Color::Muted,
+            ),
+        };
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("AlignDelimiterPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.delimiter_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .child(Label::new(help_text).color(color)),
+            )
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{
+    Anchor, Direction, DisplayPoint, DisplayRow, Editor, EditorSnapshot, SelectionEffects, ToPoint,
+    actions::{GoToNextBookmark, GoToPreviousBookmark, ToggleBookmark},
+    display_map::ToDisplayPoint,
+    scroll::Autoscroll,
+};
+use collections::{HashMap, HashSet};
+use gpui::{App, ClickEvent, Context, Entity, Window};
+use language::Buffer;
+use project::bookmark_store::BookmarkStore;
+use std::{cmp::Ordering, ops::Range, path::Path, sync::Arc};
+use text::{Bias, Point};
+use ui::{IconButton, IconName, IconSize, Tooltip, prelude::*};
+
+impl Editor {
+    pub fn bookmark_store(&self) -> Option<Entity<BookmarkStore>> {
+        self.bookmark_store.clone()
+    }
+
+    /// The buffer and the start of the line containing the newest cursor, which is where a
+    /// bookmark for that line is placed.
+    pub fn bookmark_position_at_cursor(&self, cx: &App) -> Option<(Entity<Buffer>, text::Anchor)> {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let row = self
+            .selections
+            .newest_anchor()
+            .head()
+            .to_point(&snapshot)
+            .row;
+        let anchor = snapshot.anchor_before(Point::new(row, 0));
+        let buffer = self.buffer.read(cx).buffer_for_anchor(anchor, cx)?;
+        Some((buffer, anchor.text_anchor))
+    }
+
+    pub fn toggle_bookmark(&mut self, _: &ToggleBookmark, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut rows = HashSet::default();
+        for selection in self.selections.disjoint_anchors().iter() {
+            let row = selection.head().to_point(&snapshot).row;
+            if !rows.insert(row) {
+                continue;
+            }
+            let anchor = snapshot.anchor_before(Point::new(row, 0));
+            let Some(buffer) = self.buffer.read(cx).buffer_for_anchor(anchor, cx) else {
+                continue;
+            };
+            bookmark_store.update(cx, |bookmark_store, cx| {
+                bookmark_store.toggle_bookmark(buffer, anchor.text_anchor, cx)
+            });
+        }
+    }
+
+    pub fn go_to_next_bookmark(
+        &mut self,
+        _: &GoToNextBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_bookmark(Direction::Next, window, cx);
+    }
+
+    pub fn go_to_previous_bookmark(
+        &mut self,
+        _: &GoToPreviousBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_bookmark(Direction::Prev, window, cx);
+    }
+
+    /// Moves to the bookmark after (or before) the newest cursor, in path order across the whole
+    /// project, wrapping around at either end.
+    fn go_to_bookmark(
+        &mut self,
+        direction: Direction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+
+        let current = self
+            .bookmark_position_at_cursor(cx)
+            .and_then(|(buffer, anchor)| {
+                let path = BookmarkStore::abs_path_from_buffer(&buffer, cx)?;
+                let row = anchor.summary::<Point>(&buffer.read(cx).snapshot()).row;
+                Some((path, row))
+            });
+        let bookmarks = bookmark_store
+            .read(cx)
+            .all_bookmarks()
+            .map(|(path, buffer, bookmark)| {
+                let row = bookmark
+                    .position
+                    .summary::<Point>(&buffer.read(cx).snapshot())
+                    .row;
+                (path.clone(), row, buffer.clone())
+            })
+            .collect::<Vec<_>>();
+        let is_past_current = |path: &Arc<Path>, row: &u32, ordering: Ordering| {
+            current.as_ref().is_none_or(|(current_path, current_row)| {
+                (path, row).cmp(&(current_path, current_row)) == ordering
+            })
+        };
+        let target = match direction {
+            Direction::Next => bookmarks
+                .iter()
+                .find(|(path, row, _)| is_past_current(path, row, Ordering::Greater))
+                .or_else(|| bookmarks.first()),
+            Direction::Prev => bookmarks
+                .iter()
+                .rev()
+                .find(|(path, row, _)| is_past_current(path, row, Ordering::Less))
+                .or_else(|| bookmarks.last()),
+        };
+        let Some((_, row, buffer)) = target.cloned() else {
+            return;
+        };
+        let point = Point::new(row, 0);
+
+        if self.buffer.read(cx).as_singleton().as_ref() == Some(&buffer) {
+            let multibuffer = self.buffer.read(cx);
+            let Some(anchor) = multibuffer.buffer_point_to_anchor(&buffer, point, cx) else {
+                return;
+            };
+            self.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()).nav_history(true),
+                window,
+                cx,
+                |s| s.select_anchor_ranges([anchor..anchor]),
+            );
+            return;
+        }
+
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        window.defer(cx, move |window, cx| {
+            workspace.update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().clone();
+                let editor =
+                    workspace.open_project_item::<Editor>(pane, buffer, true, true, window, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.go_to_singleton_buffer_point(point, window, cx);
+                });
+            });
+        });
+    }
+
+    /// The bookmarks on visible rows, along with their labels.
+    pub(crate) fn active_bookmarks(
+        &self,
+        range: Range<DisplayRow>,
+        snapshot: &EditorSnapshot,
+        cx: &App,
+    ) -> HashMap<DisplayRow, (Anchor, Option<Arc<str>>)> {
+        let mut bookmark_display_points = HashMap::default();
+
+        let Some(bookmark_store) = self.bookmark_store.as_ref() else {
+            return bookmark_display_points;
+        };
+        let Some(project) = self.project() else {
+            return bookmark_display_points;
+        };
+
+        let multi_buffer_snapshot = &snapshot.display_snapshot.buffer_snapshot;
+        let range = snapshot.display_point_to_point(DisplayPoint::new(range.start, 0), Bias::Left)
+            ..snapshot.display_point_to_point(DisplayPoint::new(range.end, 0), Bias::Right);
+
+        for (buffer_snapshot, range, excerpt_id) in
+            multi_buffer_snapshot.range_to_buffer_ranges(range)
+        {
+            let Some(buffer) = project
+                .read(cx)
+                .buffer_for_id(buffer_snapshot.remote_id(), cx)
+            else {
+                continue;
+            };
+            for bookmark in bookmark_store.read(cx).bookmarks_for_buffer(&buffer, cx) {
+                let offset = bookmark.position.summary::<usize>(buffer_snapshot);
+                if offset < range.start || offset > range.end {
+                    continue;
+                }
+                let multi_buffer_anchor =
+                    Anchor::in_buffer(excerpt_id, buffer_snapshot.remote_id(), bookmark.position);
+                let position = multi_buffer_anchor
+                    .to_point(multi_buffer_snapshot)
+                    .to_display_point(snapshot);
+
+                bookmark_display_points.insert(
+                    position.row(),
+                    (multi_buffer_anchor, bookmark.label.clone()),
+                );
+            }
+        }
+
+        bookmark_display_points
+    }
+
+    pub(crate) fn render_bookmark(
+        &self,
+        position: Anchor,
+        row: DisplayRow,
+        label: Option<Arc<str>>,
+        cx: &mut Context<Self>,
+    ) -> IconButton {
+        let focus_handle = self.focus_handle.clone();
+        IconButton::new(("bookmark_indicator", row.0 as usize), IconName::Bookmark)
+            .icon_size(IconSize::XSmall)
+            .size(ui::ButtonSize::None)
+            .icon_color(Color::Accent)
+            .style(ButtonStyle::Transparent)
+            .on_click(cx.listener(move |editor, _: &ClickEvent, window, cx| {
+                window.focus(&editor.focus_handle(cx));
+                let Some(bookmark_store) = editor.bookmark_store.clone() else {
+                    return;
+                };
+                let Some(buffer) = editor.buffer.read(cx).buffer_for_anchor(position, cx) else {
+                    return;
+                };
+                bookmark_store.update(cx, |bookmark_store, cx| {
+                    bookmark_store.remove_bookmark(&buffer, position.text_anchor, cx)
+                });
+            }))
+            .tooltip(move |window, cx| match &label {
+                Some(label) => Tooltip::with_meta_in(
+                    "Remove Bookmark",
+                    Some(&ToggleBookmark),
+                    label.to_string(),
+                    &focus_handle,
+                    window,
+                    cx,
+                ),
+                None => Tooltip::for_action_in(
+                    "Remove Bookmark",
+                    &ToggleBookmark,
+                    &focus_handle,
+                    window,
+                    cx,
+                ),
+            })
+    }
+}
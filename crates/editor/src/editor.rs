@@ -13,7 +13,9 @@
 //!
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides its behavior.
 pub mod actions;
+mod alignment;
 mod blink_manager;
+mod bookmarks;
 mod clangd_ext;
 pub mod code_context_menus;
 pub mod display_map;
@@ -47,6 +49,7 @@ mod edit_prediction_tests;
 #[cfg(test)]
 mod editor_tests;
 mod signature_help;
+mod sticky_scroll;
 #[cfg(any(test, feature = "test-support"))]
 pub mod test;
 
@@ -150,7 +153,8 @@ use project::{
     BreakpointWithPosition, CodeAction, Completion, CompletionDisplayOptions, CompletionIntent,
     CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight, InlayHint,
     Location, LocationLink, PrepareRenameResponse, Project, ProjectItem, ProjectPath,
-    ProjectTransaction, TaskSourceKind,
+    ProjectTransaction, ShowDocumentRequest, ShowDocumentTarget, TaskSourceKind,
+    bookmark_store::BookmarkStore,
     debugger::{
         breakpoint_store::{
             Breakpoint, BreakpointEditAction, BreakpointSessionState, BreakpointState,
@@ -360,12 +364,23 @@ pub fn init(cx: &mut App) {
     workspace::register_serializable_item::<Editor>(cx);
 
     cx.observe_new(
-        |workspace: &mut Workspace, _: Option<&mut Window>, _cx: &mut Context<Workspace>| {
+        |workspace: &mut Workspace, window: Option<&mut Window>, cx: &mut Context<Workspace>| {
             workspace.register_action(Editor::new_file);
             workspace.register_action(Editor::new_file_vertical);
             workspace.register_action(Editor::new_file_horizontal);
             workspace.register_action(Editor::cancel_language_server_work);
             workspace.register_action(Editor::toggle_focus);
+
+            let Some(window) = window else {
+                return;
+            };
+            let project = workspace.project().clone();
+            cx.subscribe_in(&project, window, |workspace, _, event, window, cx| {
+                if let project::Event::ShowDocument(request) = event {
+                    Editor::show_language_server_document(workspace, request, window, cx);
+                }
+            })
+            .detach();
         },
     )
     .detach();
@@ -1150,6 +1165,7 @@ pub struct Editor {
     tasks: BTreeMap<(BufferId, BufferRow), RunnableTasks>,
     tasks_update_task: Option<Task<()>>,
     breakpoint_store: Option<Entity<BreakpointStore>>,
+    bookmark_store: Option<Entity<BookmarkStore>>,
     gutter_breakpoint_indicator: (Option<PhantomBreakpointIndicator>, Option<Task<()>>),
     hovered_diff_hunk_row: Option<DisplayRow>,
     pull_diagnostics_task: Task<()>,
@@ -1970,6 +1986,9 @@ impl Editor {
                 ));
             };
 
+            project_subscriptions
+                .push(cx.observe(&project.read(cx).bookmark_store(), |_, _, cx| cx.notify()));
+
             project_subscriptions.push(cx.subscribe_in(
                 &project.read(cx).breakpoint_store(),
                 window,
@@ -2042,6 +2061,10 @@ impl Editor {
             (EditorMode::Full { .. }, Some(project)) => Some(project.read(cx).breakpoint_store()),
             _ => None,
         };
+        let bookmark_store = match (&mode, project.as_ref()) {
+            (EditorMode::Full { .. }, Some(project)) => Some(project.read(cx).bookmark_store()),
+            _ => None,
+        };
 
         let mut code_action_providers = Vec::new();
         let mut load_uncommitted_diff = None;
@@ -2199,6 +2222,7 @@ impl Editor {
             tasks: BTreeMap::default(),
 
             breakpoint_store,
+            bookmark_store,
             gutter_breakpoint_indicator: (None, None),
             hovered_diff_hunk_row: None,
             _subscriptions: (!is_minimap)
@@ -2591,6 +2615,35 @@ impl Editor {
         }))
     }
 
+    fn show_language_server_document(
+        workspace: &mut Workspace,
+        request: &ShowDocumentRequest,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        match &request.target {
+            ShowDocumentTarget::Url(url) => cx.open_url(url),
+            ShowDocumentTarget::Buffer { buffer, selection } => {
+                let pane = workspace.active_pane().clone();
+                let editor = workspace.open_project_item::<Self>(
+                    pane,
+                    buffer.clone(),
+                    request.take_focus,
+                    request.take_focus,
+                    window,
+                    cx,
+                );
+                if let Some(selection) = selection.clone() {
+                    let range = selection.to_point(buffer.read(cx));
+                    editor.update(cx, |editor, cx| {
+                        editor.go_to_singleton_buffer_range(range, window, cx);
+                    });
+                }
+            }
+        }
+        request.respond(true);
+    }
+
     pub fn new_file(
         workspace: &mut Workspace,
         _: &workspace::NewFile,
@@ -8074,6 +8127,7 @@ impl Editor {
         };
 
         let run_to_cursor = window.is_action_available(&RunToCursor, cx);
+        let set_next_statement = window.is_action_available(&SetNextStatement, cx);
 
         let toggle_state_msg = breakpoint.as_ref().map_or(None, |bp| match bp.1.state {
             BreakpointState::Enabled => Some("Disable"),
@@ -8102,8 +8156,25 @@ impl Editor {
 
                         window.dispatch_action(Box::new(RunToCursor), cx);
                     })
-                    .separator()
                 })
+                .when(set_next_statement, |this| {
+                    let weak_editor = weak_editor.clone();
+                    this.entry("Set next statement", None, move |window, cx| {
+                        weak_editor
+                            .update(cx, |editor, cx| {
+                                editor.change_selections(
+                                    SelectionEffects::no_scroll(),
+                                    window,
+                                    cx,
+                                    |s| s.select_ranges([Point::new(row, 0)..Point::new(row, 0)]),
+                                );
+                            })
+                            .ok();
+
+                        window.dispatch_action(Box::new(SetNextStatement), cx);
+                    })
+                })
+                .when(run_to_cursor || set_next_statement, |this| this.separator())
                 .when_some(toggle_state_msg, |this, msg| {
                     this.entry(msg, None, {
                         let weak_editor = weak_editor.clone();
@@ -18618,7 +18689,18 @@ impl Editor {
 
     pub fn soft_wrap_mode(&self, cx: &App) -> SoftWrap {
         let settings = self.buffer.read(cx).language_settings(cx);
-        let mode = self.soft_wrap_mode_override.unwrap_or(settings.soft_wrap);
+        let mode = self.soft_wrap_mode_override.unwrap_or_else(|| {
+            let large_file_mode = self
+                .buffer
+                .read(cx)
+                .as_singleton()
+                .is_some_and(|buffer| buffer.read(cx).large_file_mode());
+            if large_file_mode {
+                language_settings::SoftWrap::None
+            } else {
+                settings.soft_wrap
+            }
+        });
         match mode {
             language_settings::SoftWrap::PreferLine | language_settings::SoftWrap::None => {
                 SoftWrap::None
@@ -20321,6 +20403,23 @@ impl Editor {
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
             }
+            multi_buffer::Event::LargeFileModeChanged(buffer_id) => {
+                if let Some(project) = self.project.clone()
+                    && let Some(buffer) = multibuffer.read(cx).buffer(*buffer_id)
+                {
+                    self.load_diff_task = Some(
+                        update_uncommitted_diff_for_buffer(
+                            cx.entity(),
+                            &project,
+                            [buffer],
+                            self.buffer.clone(),
+                            cx,
+                        )
+                        .shared(),
+                    );
+                }
+                cx.notify();
+            }
             multi_buffer::Event::DirtyChanged => cx.emit(EditorEvent::DirtyChanged),
             multi_buffer::Event::Saved => cx.emit(EditorEvent::Saved),
             multi_buffer::Event::FileHandleChanged
@@ -21607,7 +21706,9 @@ fn update_uncommitted_diff_for_buffer(
     let mut tasks = Vec::new();
     project.update(cx, |project, cx| {
         for buffer in buffers {
-            if project::File::from_dyn(buffer.read(cx).file()).is_some() {
+            if project::File::from_dyn(buffer.read(cx).file()).is_some()
+                && !buffer.read(cx).large_file_mode()
+            {
                 tasks.push(project.open_uncommitted_diff(buffer.clone(), cx))
             }
         }
@@ -22069,6 +22170,19 @@ pub trait SemanticsProvider {
         cx: &mut App,
     ) -> Option<Task<Result<Option<Vec<LocationLink>>>>>;
 
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<project::DocumentLink>>>>;
+
+    fn resolve_document_link(
+        &self,
+        link: project::DocumentLink,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<project::DocumentLink>>>;
+
     fn range_for_rename(
         &self,
         buffer: &Entity<Buffer>,
@@ -22514,6 +22628,25 @@ impl SemanticsProvider for Entity<Project> {
         }))
     }
 
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<project::DocumentLink>>>> {
+        Some(self.update(cx, |project, cx| project.document_links(buffer, cx)))
+    }
+
+    fn resolve_document_link(
+        &self,
+        link: project::DocumentLink,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<project::DocumentLink>>> {
+        Some(self.update(cx, |project, cx| {
+            project.resolve_document_link(link, buffer.clone(), cx)
+        }))
+    }
+
     fn supports_inlay_hints(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool {
         self.update(cx, |project, cx| {
             if project
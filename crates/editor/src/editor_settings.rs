@@ -56,6 +56,7 @@ pub struct EditorSettings {
     pub diagnostics_max_severity: Option<DiagnosticSeverity>,
     pub inline_code_actions: bool,
     pub drag_and_drop_selection: DragAndDropSelection,
+    pub sticky_scroll: StickyScroll,
     pub lsp_document_colors: DocumentColorsRenderMode,
     pub minimum_contrast_for_highlights: f32,
 }
@@ -141,6 +142,10 @@ pub struct StatusBar {
     ///
     /// Default: true
     pub active_language_button: bool,
+    /// Whether to display the active buffer's file encoding button in the status bar.
+    ///
+    /// Default: true
+    pub active_encoding_button: bool,
     /// Whether to show the cursor position button in the status bar.
     ///
     /// Default: true
@@ -319,6 +324,36 @@ fn default_drag_and_drop_selection_delay_ms() -> u64 {
     300
 }
 
+/// Whether to pin the lines of the scopes enclosing the top of the viewport while scrolling.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq, SettingsUi)]
+pub struct StickyScroll {
+    /// When true, pins the first lines of the enclosing functions, impls, classes and other
+    /// outline items to the top of the editor.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// The maximum number of scope lines pinned at once.
+    ///
+    /// Default: 5
+    #[serde(default = "default_sticky_scroll_max_depth")]
+    pub max_depth: u32,
+}
+
+impl Default for StickyScroll {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_depth: default_sticky_scroll_max_depth(),
+        }
+    }
+}
+
+fn default_sticky_scroll_max_depth() -> u32 {
+    5
+}
+
 /// Which diagnostic indicators to show in the scrollbar.
 ///
 /// Default: all
@@ -611,6 +646,9 @@ pub struct EditorSettingsContent {
     /// Drag and drop related settings
     pub drag_and_drop_selection: Option<DragAndDropSelection>,
 
+    /// Sticky scroll related settings
+    pub sticky_scroll: Option<StickyScroll>,
+
     /// How to render LSP `textDocument/documentColor` colors in the editor.
     ///
     /// Default: [`DocumentColorsRenderMode::Inlay`]
@@ -624,6 +662,10 @@ pub struct StatusBarContent {
     ///
     /// Default: true
     pub active_language_button: Option<bool>,
+    /// Whether to display the active buffer's file encoding button in the status bar.
+    ///
+    /// Default: true
+    pub active_encoding_button: Option<bool>,
     /// Whether to show the cursor position button in the status bar.
     ///
     /// Default: true
@@ -947,6 +989,22 @@ impl Settings for EditorSettings {
             search.include_ignored = use_ignored;
         }
 
+        if let Some(enabled) = vscode.read_bool("editor.stickyScroll.enabled") {
+            let mut sticky_scroll = current.sticky_scroll.unwrap_or_default();
+            sticky_scroll.enabled = enabled;
+            current.sticky_scroll = Some(sticky_scroll);
+        }
+        let mut sticky_scroll_max_depth = None;
+        vscode.u32_setting(
+            "editor.stickyScroll.maxLineCount",
+            &mut sticky_scroll_max_depth,
+        );
+        if let Some(max_depth) = sticky_scroll_max_depth {
+            let mut sticky_scroll = current.sticky_scroll.unwrap_or_default();
+            sticky_scroll.max_depth = max_depth;
+            current.sticky_scroll = Some(sticky_scroll);
+        }
+
         let mut minimap = MinimapContent::default();
         let minimap_enabled = vscode.read_bool("editor.minimap.enabled").unwrap_or(true);
         let autohide = vscode.read_bool("editor.minimap.autohide");
@@ -4473,6 +4473,108 @@ async fn test_custom_newlines_cause_no_false_positive_diffs(
     });
 }
 
+#[gpui::test]
+async fn test_align_on_delimiter(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // With a single cursor, the surrounding lines containing the delimiter are aligned.
+    cx.set_state(indoc! {"
+        let a = 1;
+        let bbb = 22;ˇ
+        let cc = 333;
+
+        let unrelated = 4;
+    "});
+    cx.update_editor(|editor, window, cx| {
+        editor.align_on_delimiter(
+            &AlignOnDelimiter {
+                delimiter: Some("=".into()),
+                ..Default::default()
+            },
+            window,
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        let a   = 1;
+        let bbb = 22;ˇ
+        let cc  = 333;
+
+        let unrelated = 4;
+    "});
+
+    // Delimiters without whitespace before them stay attached to the preceding text.
+    cx.set_state(indoc! {"
+        a: 1, b: 22ˇ
+        ccc: 333, d: 4ˇ
+    "});
+    cx.update_editor(|editor, window, cx| {
+        editor.align_on_delimiter(
+            &AlignOnDelimiter {
+                delimiter: Some(":".into()),
+                justify: Justification::Right,
+                ..Default::default()
+            },
+            window,
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+          a:   1, b: 22ˇ
+        ccc: 333, d:  4ˇ
+    "});
+
+    cx.set_state(indoc! {"
+        «x => 1,
+        long_name  =>  2,
+        y := 3,ˇ»
+    "});
+    cx.update_editor(|editor, window, cx| {
+        editor.align_on_delimiter(
+            &AlignOnDelimiter {
+                delimiter: Some("=>|:=".into()),
+                regex: true,
+                ..Default::default()
+            },
+            window,
+            cx,
+        )
+    });
+    cx.assert_editor_state(indoc! {"
+        «x         => 1,
+        long_name => 2,
+        y         := 3,ˇ»
+    "});
+}
+
+#[gpui::test]
+async fn test_align_markdown_table(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        Fruit:
+
+        | Name | Qty |
+        |:-|-:|
+        | apple | 3 |ˇ
+        kiwi | 12
+    "});
+    cx.update_editor(|editor, window, cx| {
+        editor.align_markdown_table(&AlignMarkdownTable, window, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        Fruit:
+
+        | Name  | Qty |
+        | :---- | --: |
+        | apple |   3 |ˇ
+        | kiwi  |  12 |
+    "});
+}
+
 #[gpui::test]
 async fn test_manipulate_immutable_lines_with_single_selection(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -16476,6 +16578,66 @@ fn test_split_words() {
     assert_eq!(split(":do_the_thing"), &[":", "do_", "the_", "thing"]);
 }
 
+#[gpui::test]
+async fn test_sticky_scopes(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_outline_query(
+            r#"
+            (impl_item
+                "impl" @context
+                type: (_) @name) @item
+            (function_item
+                "fn" @context
+                name: (_) @name) @item
+            "#,
+        )
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+    cx.set_state(indoc! {"
+        ˇimpl Foo {
+            fn one() {
+                let a = 1;
+                let b = 2;
+                let c = 3;
+            }
+
+            fn two() {
+                let d = 4;
+            }
+        }
+    "});
+    cx.run_until_parked();
+
+    let mut sticky_rows = |top_row: u32, max_depth: usize| {
+        cx.update_editor(|editor, window, cx| {
+            editor
+                .snapshot(window, cx)
+                .sticky_scopes(DisplayRow(top_row), max_depth)
+                .into_iter()
+                .map(|scope| scope.row.0)
+                .collect::<Vec<_>>()
+        })
+    };
+
+    assert_eq!(sticky_rows(0, 5), Vec::<u32>::new());
+    assert_eq!(sticky_rows(1, 5), vec![0]);
+    assert_eq!(sticky_rows(2, 5), vec![0, 1]);
+    assert_eq!(sticky_rows(2, 1), vec![0]);
+    // The function's pinned line would cover its closing brace, so only the impl is pinned.
+    assert_eq!(sticky_rows(4, 5), vec![0]);
+    assert_eq!(sticky_rows(8, 5), vec![0]);
+    assert_eq!(sticky_rows(10, 5), Vec::<u32>::new());
+}
+
 #[gpui::test]
 async fn test_move_to_enclosing_bracket(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -22449,6 +22611,437 @@ async fn test_breakpoint_enabling_and_disabling(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_bookmark_toggling(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let sample_text = "First line\nSecond line\nThird line\nFourth line".to_string();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/a"),
+        json!({
+            "main.rs": sample_text,
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/a").as_ref()], cx).await;
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace.deref(), cx);
+    let worktree_id = workspace
+        .update(cx, |workspace, _window, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        })
+        .unwrap();
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, "main.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        Editor::new(
+            EditorMode::full(),
+            MultiBuffer::build_from_buffer(buffer, cx),
+            Some(project.clone()),
+            window,
+            cx,
+        )
+    });
+
+    // Each cursor bookmarks the start of its row.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([
+                Point::new(0, 3)..Point::new(0, 3),
+                Point::new(2, 1)..Point::new(2, 1),
+            ])
+        });
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmark_rows(&editor, cx), vec![(0, None), (2, None)]);
+
+    // Toggling a bookmarked row removes its bookmark.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(0, 5)..Point::new(0, 5)])
+        });
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmark_rows(&editor, cx), vec![(2, None)]);
+
+    // Several cursors on the same row toggle it once.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([
+                Point::new(1, 0)..Point::new(1, 0),
+                Point::new(1, 4)..Point::new(1, 4),
+            ])
+        });
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmark_rows(&editor, cx), vec![(1, None), (2, None)]);
+
+    // Removing every bookmark in a file forgets the file.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([
+                Point::new(1, 0)..Point::new(1, 0),
+                Point::new(2, 0)..Point::new(2, 0),
+            ])
+        });
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmark_rows(&editor, cx), vec![]);
+    editor.update(cx, |editor, cx| {
+        assert_eq!(
+            editor
+                .bookmark_store()
+                .unwrap()
+                .read(cx)
+                .all_bookmarks()
+                .count(),
+            0
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_bookmarks_follow_edits(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let sample_text = "First line\nSecond line\nThird line\nFourth line".to_string();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/a"),
+        json!({
+            "main.rs": sample_text,
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/a").as_ref()], cx).await;
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace.deref(), cx);
+    let worktree_id = workspace
+        .update(cx, |workspace, _window, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        })
+        .unwrap();
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, "main.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        Editor::new(
+            EditorMode::full(),
+            MultiBuffer::build_from_buffer(buffer.clone(), cx),
+            Some(project.clone()),
+            window,
+            cx,
+        )
+    });
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([
+                Point::new(1, 0)..Point::new(1, 0),
+                Point::new(3, 0)..Point::new(3, 0),
+            ])
+        });
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmark_rows(&editor, cx), vec![(1, None), (3, None)]);
+
+    // Lines inserted above a bookmark push it down.
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "Zeroth line\nHalf line\n")], None, cx);
+    });
+    assert_eq!(bookmark_rows(&editor, cx), vec![(3, None), (5, None)]);
+
+    // Text typed at the start of a bookmarked line stays on that line.
+    buffer.update(cx, |buffer, cx| {
+        let offset = buffer.point_to_offset(Point::new(3, 0));
+        buffer.edit([(offset..offset, "// ")], None, cx);
+    });
+    assert_eq!(bookmark_rows(&editor, cx), vec![(3, None), (5, None)]);
+
+    // Lines removed above a bookmark pull it up.
+    buffer.update(cx, |buffer, cx| {
+        let end = buffer.point_to_offset(Point::new(2, 0));
+        buffer.edit([(0..end, "")], None, cx);
+    });
+    assert_eq!(bookmark_rows(&editor, cx), vec![(1, None), (3, None)]);
+    assert_eq!(
+        buffer.read_with(cx, |buffer, _| buffer.text()),
+        "First line\n// Second line\nThird line\nFourth line"
+    );
+
+    // The bookmark is still found at its new row.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(1, 2)..Point::new(1, 2)])
+        });
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmark_rows(&editor, cx), vec![(3, None)]);
+}
+
+#[gpui::test]
+async fn test_bookmark_labels(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let sample_text = "First line\nSecond line\nThird line\nFourth line".to_string();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/a"),
+        json!({
+            "main.rs": sample_text,
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/a").as_ref()], cx).await;
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace.deref(), cx);
+    let worktree_id = workspace
+        .update(cx, |workspace, _window, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        })
+        .unwrap();
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, "main.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        Editor::new(
+            EditorMode::full(),
+            MultiBuffer::build_from_buffer(buffer.clone(), cx),
+            Some(project.clone()),
+            window,
+            cx,
+        )
+    });
+    let bookmark_store = editor.update(cx, |editor, _| editor.bookmark_store().unwrap());
+    let anchor_at = |row: u32, column: u32, cx: &mut VisualTestContext| {
+        buffer.read_with(cx, |buffer, _| {
+            buffer.anchor_before(Point::new(row, column))
+        })
+    };
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(2, 0)..Point::new(2, 0)])
+        });
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+
+    // Labeling a row without a bookmark adds one.
+    let position = anchor_at(1, 4, cx);
+    bookmark_store.update(cx, |bookmark_store, cx| {
+        bookmark_store.set_bookmark_label(buffer.clone(), position, Some("setup".into()), cx)
+    });
+    // Labeling a bookmarked row keeps its position.
+    let position = anchor_at(2, 7, cx);
+    bookmark_store.update(cx, |bookmark_store, cx| {
+        bookmark_store.set_bookmark_label(buffer.clone(), position, Some("main loop".into()), cx)
+    });
+    assert_eq!(
+        bookmark_rows(&editor, cx),
+        vec![(1, Some("setup".into())), (2, Some("main loop".into()))]
+    );
+
+    let position = anchor_at(1, 9, cx);
+    bookmark_store.read_with(cx, |bookmark_store, cx| {
+        let bookmark = bookmark_store.bookmark_at(&buffer, position, cx).unwrap();
+        assert_eq!(bookmark.label.as_deref(), Some("setup"));
+    });
+
+    // Blank labels clear the label but keep the bookmark.
+    let position = anchor_at(2, 0, cx);
+    bookmark_store.update(cx, |bookmark_store, cx| {
+        bookmark_store.set_bookmark_label(buffer.clone(), position, Some("  ".into()), cx)
+    });
+    assert_eq!(
+        bookmark_rows(&editor, cx),
+        vec![(1, Some("setup".into())), (2, None)]
+    );
+
+    // Toggling a labeled bookmark removes it along with its label.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(1, 0)..Point::new(1, 0)])
+        });
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmark_rows(&editor, cx), vec![(2, None)]);
+    let position = anchor_at(1, 0, cx);
+    bookmark_store.read_with(cx, |bookmark_store, cx| {
+        assert!(bookmark_store.bookmark_at(&buffer, position, cx).is_none());
+    });
+}
+
+#[gpui::test]
+async fn test_bookmark_navigation_across_files(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/a"),
+        json!({
+            "one.rs": "one a\none b\none c\none d",
+            "two.rs": "two a\ntwo b\ntwo c\ntwo d",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/a").as_ref()], cx).await;
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace.deref(), cx);
+    let worktree_id = workspace
+        .update(cx, |workspace, _window, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        })
+        .unwrap();
+
+    let editor_one = workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.open_path((worktree_id, "one.rs"), None, true, window, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    let buffer_two = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, "two.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    editor_one.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([
+                Point::new(1, 0)..Point::new(1, 0),
+                Point::new(3, 0)..Point::new(3, 0),
+            ])
+        });
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(0, 2)..Point::new(0, 2)])
+        });
+    });
+    let bookmark_store = editor_one.update(cx, |editor, _| editor.bookmark_store().unwrap());
+    bookmark_store.update(cx, |bookmark_store, cx| {
+        for row in [0, 2] {
+            let position = buffer_two.read(cx).anchor_before(Point::new(row, 0));
+            bookmark_store.toggle_bookmark(buffer_two.clone(), position, cx);
+        }
+    });
+
+    let active_position = |cx: &mut VisualTestContext| {
+        let editor = workspace
+            .update(cx, |workspace, _, cx| {
+                workspace.active_item_as::<Editor>(cx).unwrap()
+            })
+            .unwrap();
+        editor.update(cx, |editor, cx| {
+            let path = editor.project_path(cx).unwrap().path;
+            let row = editor.selections.newest::<Point>(cx).head().row;
+            (path.to_string_lossy().into_owned(), row)
+        })
+    };
+    let go_to_next = |cx: &mut VisualTestContext| {
+        let editor = workspace
+            .update(cx, |workspace, _, cx| {
+                workspace.active_item_as::<Editor>(cx).unwrap()
+            })
+            .unwrap();
+        editor.update_in(cx, |editor, window, cx| {
+            editor.go_to_next_bookmark(&actions::GoToNextBookmark, window, cx)
+        });
+        cx.run_until_parked();
+    };
+    let go_to_previous = |cx: &mut VisualTestContext| {
+        let editor = workspace
+            .update(cx, |workspace, _, cx| {
+                workspace.active_item_as::<Editor>(cx).unwrap()
+            })
+            .unwrap();
+        editor.update_in(cx, |editor, window, cx| {
+            editor.go_to_previous_bookmark(&actions::GoToPreviousBookmark, window, cx)
+        });
+        cx.run_until_parked();
+    };
+
+    go_to_next(cx);
+    assert_eq!(active_position(cx), ("one.rs".to_string(), 1));
+    go_to_next(cx);
+    assert_eq!(active_position(cx), ("one.rs".to_string(), 3));
+
+    // Moving past the last bookmark in a file opens the next file with bookmarks.
+    go_to_next(cx);
+    assert_eq!(active_position(cx), ("two.rs".to_string(), 0));
+    go_to_next(cx);
+    assert_eq!(active_position(cx), ("two.rs".to_string(), 2));
+
+    // Navigation wraps around at the end of the project.
+    go_to_next(cx);
+    assert_eq!(active_position(cx), ("one.rs".to_string(), 1));
+    let active_editor = workspace
+        .update(cx, |workspace, _, cx| {
+            workspace.active_item_as::<Editor>(cx).unwrap()
+        })
+        .unwrap();
+    assert_eq!(active_editor, editor_one, "should reuse the open editor");
+
+    // ...and at the start.
+    go_to_previous(cx);
+    assert_eq!(active_position(cx), ("two.rs".to_string(), 2));
+    go_to_previous(cx);
+    assert_eq!(active_position(cx), ("two.rs".to_string(), 0));
+    go_to_previous(cx);
+    assert_eq!(active_position(cx), ("one.rs".to_string(), 3));
+}
+
+fn bookmark_rows(
+    editor: &Entity<Editor>,
+    cx: &mut VisualTestContext,
+) -> Vec<(u32, Option<Arc<str>>)> {
+    editor.update(cx, |editor, cx| {
+        let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+        let snapshot = buffer.read(cx).snapshot();
+        editor
+            .bookmark_store()
+            .unwrap()
+            .read(cx)
+            .bookmarks_for_buffer(&buffer, cx)
+            .iter()
+            .map(|bookmark| {
+                (
+                    bookmark.position.summary::<Point>(&snapshot).row,
+                    bookmark.label.clone(),
+                )
+            })
+            .collect()
+    })
+}
+
 #[gpui::test]
 async fn test_rename_with_duplicate_edits(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -8,8 +8,8 @@ use crate::{
     HandleInput, HoveredCursor, InlayHintRefreshReason, JumpData, LineDown, LineHighlight, LineUp,
     MAX_LINE_LEN, MINIMAP_FONT_SIZE, MULTI_BUFFER_EXCERPT_HEADER_HEIGHT, OpenExcerpts, PageDown,
     PageUp, PhantomBreakpointIndicator, Point, RowExt, RowRangeExt, SelectPhase,
-    SelectedTextHighlight, Selection, SelectionDragState, SoftWrap, StickyHeaderExcerpt, ToPoint,
-    ToggleFold, ToggleFoldAll,
+    SelectedTextHighlight, Selection, SelectionDragState, SelectionEffects, SoftWrap,
+    StickyHeaderExcerpt, ToPoint, ToggleFold, ToggleFoldAll,
     code_context_menus::{CodeActionsMenu, MENU_ASIDE_MAX_WIDTH, MENU_ASIDE_MIN_WIDTH, MENU_GAP},
     display_map::{
         Block, BlockContext, BlockStyle, ChunkRendererId, DisplaySnapshot, EditorMargins,
@@ -28,7 +28,7 @@ use crate::{
     inlay_hint_settings,
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MenuPosition},
-    scroll::{ActiveScrollbarState, ScrollbarThumbState, scroll_amount::ScrollAmount},
+    scroll::{ActiveScrollbarState, Autoscroll, ScrollbarThumbState, scroll_amount::ScrollAmount},
 };
 use buffer_diff::{DiffHunkStatus, DiffHunkStatusKind};
 use collections::{BTreeMap, HashMap};
@@ -235,6 +235,8 @@ impl EditorElement {
         register_action(editor, window, Editor::join_lines);
         register_action(editor, window, Editor::sort_lines_by_length);
         register_action(editor, window, Editor::sort_lines_case_sensitive);
+        register_action(editor, window, Editor::align_on_delimiter);
+        register_action(editor, window, Editor::align_markdown_table);
         register_action(editor, window, Editor::sort_lines_case_insensitive);
         register_action(editor, window, Editor::reverse_lines);
         register_action(editor, window, Editor::shuffle_lines);
@@ -583,6 +585,9 @@ impl EditorElement {
         register_action(editor, window, Editor::insert_uuid_v4);
         register_action(editor, window, Editor::insert_uuid_v7);
         register_action(editor, window, Editor::open_selections_in_multibuffer);
+        register_action(editor, window, Editor::toggle_bookmark);
+        register_action(editor, window, Editor::go_to_next_bookmark);
+        register_action(editor, window, Editor::go_to_previous_bookmark);
         register_action(editor, window, Editor::toggle_breakpoint);
         register_action(editor, window, Editor::edit_log_breakpoint);
         register_action(editor, window, Editor::enable_breakpoint);
@@ -2862,6 +2867,64 @@ impl EditorElement {
         })
     }
 
+    fn layout_bookmarks(
+        &self,
+        line_height: Pixels,
+        range: Range<DisplayRow>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        display_hunks: &[(DisplayDiffHunk, Option<Hitbox>)],
+        snapshot: &EditorSnapshot,
+        bookmarks: HashMap<DisplayRow, (Anchor, Option<Arc<str>>)>,
+        row_infos: &[RowInfo],
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            bookmarks
+                .into_iter()
+                .filter_map(|(display_row, (anchor, label))| {
+                    if row_infos
+                        .get((display_row.0.saturating_sub(range.start.0)) as usize)
+                        .is_some_and(|row_info| {
+                            row_info.expand_info.is_some()
+                                || row_info
+                                    .diff_status
+                                    .is_some_and(|status| status.is_deleted())
+                        })
+                    {
+                        return None;
+                    }
+
+                    if range.start > display_row || range.end < display_row {
+                        return None;
+                    }
+
+                    let row =
+                        MultiBufferRow(DisplayPoint::new(display_row, 0).to_point(snapshot).row);
+                    if snapshot.is_line_folded(row) {
+                        return None;
+                    }
+
+                    let button = editor.render_bookmark(anchor, display_row, label, cx);
+                    let button = prepaint_gutter_button(
+                        button,
+                        display_row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_pixel_position,
+                        gutter_hitbox,
+                        display_hunks,
+                        window,
+                        cx,
+                    );
+                    Some(button)
+                })
+                .collect_vec()
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_run_indicators(
         &self,
@@ -2874,6 +2937,7 @@ impl EditorElement {
         display_hunks: &[(DisplayDiffHunk, Option<Hitbox>)],
         snapshot: &EditorSnapshot,
         breakpoints: &mut HashMap<DisplayRow, (Anchor, Breakpoint, Option<BreakpointSessionState>)>,
+        bookmarks: &mut HashMap<DisplayRow, (Anchor, Option<Arc<str>>)>,
         window: &mut Window,
         cx: &mut App,
     ) -> Vec<AnyElement> {
@@ -2948,6 +3012,7 @@ impl EditorElement {
                         return None;
                     }
 
+                    bookmarks.remove(&display_row);
                     let button = editor.render_run_indicator(
                         &self.style,
                         Some(display_row) == active_task_indicator_row,
@@ -4292,6 +4357,88 @@ impl EditorElement {
         }
     }
 
+    fn layout_sticky_scopes(
+        &self,
+        snapshot: &EditorSnapshot,
+        scroll_position: gpui::Point<f32>,
+        has_sticky_buffer_header: bool,
+        line_height: Pixels,
+        editor_width: Pixels,
+        right_margin: Pixels,
+        gutter_dimensions: GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        text_hitbox: &Hitbox,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<StickyScopeLayout> {
+        let settings = EditorSettings::get_global(cx);
+        if !settings.sticky_scroll.enabled || !snapshot.mode.is_full() {
+            return Vec::new();
+        }
+
+        // Scopes are pinned below the sticky buffer header, if there is one.
+        let row_offset = if has_sticky_buffer_header {
+            FILE_HEADER_HEIGHT as usize
+        } else {
+            0
+        };
+        let top_row = DisplayRow(scroll_position.y as u32 + row_offset as u32);
+        let include_line_numbers = snapshot
+            .show_line_numbers
+            .unwrap_or(settings.gutter.line_numbers);
+
+        snapshot
+            .sticky_scopes(top_row, settings.sticky_scroll.max_depth as usize)
+            .into_iter()
+            .enumerate()
+            .map(|(ix, scope)| {
+                let line = layout_line(
+                    scope.row,
+                    snapshot,
+                    &self.style,
+                    editor_width,
+                    |_| false,
+                    window,
+                    cx,
+                );
+                let origin = gutter_hitbox.origin
+                    + point(Pixels::ZERO, (row_offset + ix) as f32 * line_height);
+                let hitbox = window.insert_hitbox(
+                    Bounds::new(
+                        origin,
+                        size(
+                            gutter_hitbox.size.width + text_hitbox.size.width - right_margin,
+                            line_height,
+                        ),
+                    ),
+                    HitboxBehavior::BlockMouseExceptScroll,
+                );
+                let line_number = include_line_numbers
+                    .then(|| snapshot.row_infos(scope.row).next()?.buffer_row)
+                    .flatten()
+                    .map(|buffer_row| {
+                        let shaped_line = self.shape_line_number(
+                            SharedString::from((buffer_row + 1).to_string()),
+                            cx.theme().colors().editor_line_number,
+                            window,
+                        );
+                        let x = gutter_hitbox.size.width
+                            - shaped_line.width
+                            - gutter_dimensions.right_padding;
+                        (shaped_line, origin + point(x, Pixels::ZERO))
+                    });
+
+                StickyScopeLayout {
+                    line,
+                    line_number,
+                    start: scope.start,
+                    autoscroll_row: row_offset + ix,
+                    hitbox,
+                }
+            })
+            .collect()
+    }
+
     fn layout_sticky_buffer_header(
         &self,
         StickyHeaderExcerpt { excerpt }: StickyHeaderExcerpt<'_>,
@@ -5716,6 +5863,83 @@ impl EditorElement {
         }
     }
 
+    fn paint_sticky_scopes(
+        &mut self,
+        layout: &mut EditorLayout,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some(last_scope) = layout.sticky_scopes.last() else {
+            return;
+        };
+
+        let line_height = layout.position_map.line_height;
+        let text_bounds = layout.position_map.text_hitbox.bounds;
+        let text_x = layout.content_origin.x - layout.position_map.scroll_pixel_position.x;
+        let background = cx.theme().colors().editor_background;
+        let separator_color = cx.theme().colors().border_variant;
+        let separator_bounds = Bounds::new(
+            last_scope.hitbox.bottom_left() - point(Pixels::ZERO, px(1.)),
+            size(last_scope.hitbox.size.width, px(1.)),
+        );
+
+        window.paint_layer(layout.hitbox.bounds, |window| {
+            for scope in &layout.sticky_scopes {
+                window.paint_quad(fill(scope.hitbox.bounds, background));
+                if let Some((line_number, origin)) = &scope.line_number {
+                    line_number
+                        .paint(*origin, line_height, window, cx)
+                        .log_err();
+                }
+                window.with_content_mask(
+                    Some(ContentMask {
+                        bounds: text_bounds,
+                        ..Default::default()
+                    }),
+                    |window| {
+                        scope.line.draw_text_at(
+                            point(text_x, scope.hitbox.origin.y),
+                            line_height,
+                            window,
+                            cx,
+                        )
+                    },
+                );
+                window.set_cursor_style(CursorStyle::PointingHand, &scope.hitbox);
+            }
+            window.paint_quad(fill(separator_bounds, separator_color));
+        });
+
+        let scopes = layout
+            .sticky_scopes
+            .iter()
+            .map(|scope| (scope.hitbox.clone(), scope.start, scope.autoscroll_row))
+            .collect::<Vec<_>>();
+        window.on_mouse_event({
+            let editor = self.editor.clone();
+            move |event: &MouseDownEvent, phase, window, cx| {
+                if phase == DispatchPhase::Capture || event.button != MouseButton::Left {
+                    return;
+                }
+                let Some((_, start, autoscroll_row)) =
+                    scopes.iter().find(|(hitbox, ..)| hitbox.is_hovered(window))
+                else {
+                    return;
+                };
+
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(
+                        SelectionEffects::scroll(Autoscroll::top_relative(*autoscroll_row)),
+                        window,
+                        cx,
+                        |selections| selections.select_anchor_ranges([*start..*start]),
+                    );
+                });
+                cx.stop_propagation();
+            }
+        });
+    }
+
     fn paint_line_numbers(&mut self, layout: &mut EditorLayout, window: &mut Window, cx: &mut App) {
         let is_singleton = self.editor.read(cx).is_singleton(cx);
 
@@ -5945,6 +6169,10 @@ impl EditorElement {
                 }
             });
 
+            for bookmark in layout.bookmarks.iter_mut() {
+                bookmark.paint(window, cx);
+            }
+
             for breakpoint in layout.breakpoints.iter_mut() {
                 breakpoint.paint(window, cx);
             }
@@ -7855,9 +8083,34 @@ impl LineWithInvisibles {
         let line_y = line_height
             * (row.as_f32() - layout.position_map.scroll_pixel_position.y / line_height);
 
-        let mut fragment_origin =
-            content_origin + gpui::point(-layout.position_map.scroll_pixel_position.x, line_y);
+        self.draw_text_at(
+            content_origin + gpui::point(-layout.position_map.scroll_pixel_position.x, line_y),
+            line_height,
+            window,
+            cx,
+        );
+
+        self.draw_invisibles(
+            selection_ranges,
+            layout,
+            content_origin,
+            line_y,
+            row,
+            line_height,
+            whitespace_setting,
+            window,
+            cx,
+        );
+    }
 
+    fn draw_text_at(
+        &self,
+        origin: gpui::Point<Pixels>,
+        line_height: Pixels,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let mut fragment_origin = origin;
         for fragment in &self.fragments {
             match fragment {
                 LineFragment::Text(line) => {
@@ -7870,18 +8123,6 @@ impl LineWithInvisibles {
                 }
             }
         }
-
-        self.draw_invisibles(
-            selection_ranges,
-            layout,
-            content_origin,
-            line_y,
-            row,
-            line_height,
-            whitespace_setting,
-            window,
-            cx,
-        );
     }
 
     fn draw_background(
@@ -8635,6 +8876,11 @@ impl Element for EditorElement {
                     let mut breakpoint_rows = self.editor.update(cx, |editor, cx| {
                         editor.active_breakpoints(start_row..end_row, window, cx)
                     });
+                    // Breakpoints and run indicators take precedence over bookmarks in the gutter.
+                    let mut bookmark_rows =
+                        self.editor
+                            .read(cx)
+                            .active_bookmarks(start_row..end_row, &snapshot, cx);
                     for (display_row, (_, bp, state)) in &breakpoint_rows {
                         if bp.is_enabled() && state.is_none_or(|s| s.verified) {
                             active_rows.entry(*display_row).or_default().breakpoint = true;
@@ -8880,6 +9126,24 @@ impl Element for EditorElement {
                         })
                     });
 
+                    let sticky_scopes = if is_minimap {
+                        Vec::new()
+                    } else {
+                        self.layout_sticky_scopes(
+                            &snapshot,
+                            scroll_position,
+                            sticky_buffer_header.is_some(),
+                            line_height,
+                            editor_width,
+                            right_margin,
+                            gutter_dimensions,
+                            &gutter_hitbox,
+                            &text_hitbox,
+                            window,
+                            cx,
+                        )
+                    };
+
                     let start_buffer_row =
                         MultiBufferRow(start_anchor.to_point(&snapshot.buffer_snapshot).row);
                     let end_buffer_row =
@@ -9152,6 +9416,7 @@ impl Element for EditorElement {
                             &display_hunks,
                             &snapshot,
                             &mut breakpoint_rows,
+                            &mut bookmark_rows,
                             window,
                             cx,
                         )
@@ -9162,6 +9427,22 @@ impl Element for EditorElement {
                     let show_breakpoints = snapshot
                         .show_breakpoints
                         .unwrap_or(gutter_settings.breakpoints);
+                    if show_breakpoints {
+                        bookmark_rows.retain(|row, _| !breakpoint_rows.contains_key(row));
+                    }
+                    let bookmarks = self.layout_bookmarks(
+                        line_height,
+                        start_row..end_row,
+                        scroll_pixel_position,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        &display_hunks,
+                        &snapshot,
+                        bookmark_rows,
+                        &row_infos,
+                        window,
+                        cx,
+                    );
                     let breakpoints = if show_breakpoints {
                         self.layout_breakpoints(
                             line_height,
@@ -9363,11 +9644,13 @@ impl Element for EditorElement {
                         mouse_context_menu,
                         test_indicators,
                         breakpoints,
+                        bookmarks,
                         crease_toggles,
                         crease_trailers,
                         tab_invisible,
                         space_invisible,
                         sticky_buffer_header,
+                        sticky_scopes,
                         expand_toggles,
                     }
                 })
@@ -9436,6 +9719,8 @@ impl Element for EditorElement {
                         });
                     }
 
+                    self.paint_sticky_scopes(layout, window, cx);
+
                     window.with_element_namespace("blocks", |window| {
                         if let Some(mut sticky_header) = layout.sticky_buffer_header.take() {
                             sticky_header.paint(window, cx)
@@ -9541,6 +9826,7 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     test_indicators: Vec<AnyElement>,
     breakpoints: Vec<AnyElement>,
+    bookmarks: Vec<AnyElement>,
     crease_toggles: Vec<Option<AnyElement>>,
     expand_toggles: Vec<Option<(AnyElement, gpui::Point<Pixels>)>>,
     diff_hunk_controls: Vec<AnyElement>,
@@ -9550,6 +9836,7 @@ pub struct EditorLayout {
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
     sticky_buffer_header: Option<AnyElement>,
+    sticky_scopes: Vec<StickyScopeLayout>,
     document_colors: Option<(DocumentColorsRenderMode, Vec<(Range<DisplayPoint>, Hsla)>)>,
 }
 
@@ -9564,6 +9851,14 @@ struct LineNumberLayout {
     hitbox: Option<Hitbox>,
 }
 
+struct StickyScopeLayout {
+    line: LineWithInvisibles,
+    line_number: Option<(ShapedLine, gpui::Point<Pixels>)>,
+    start: Anchor,
+    autoscroll_row: usize,
+    hitbox: Hitbox,
+}
+
 struct ColoredRange<T> {
     start: T,
     end: T,
@@ -1,6 +1,7 @@
 use crate::{
     Anchor, Editor, EditorSettings, EditorSnapshot, FindAllReferences, GoToDefinition,
     GoToTypeDefinition, GotoDefinitionKind, InlayId, Navigated, PointForPosition, SelectPhase,
+    SemanticsProvider,
     editor_settings::GoToDefinitionFallback,
     hover_popover::{self, InlayHover},
     scroll::ScrollAmount,
@@ -14,7 +15,7 @@ use project::{
     ResolveState, ResolvedPath,
 };
 use settings::Settings;
-use std::ops::Range;
+use std::{ops::Range, rc::Rc, str::FromStr as _};
 use theme::ActiveTheme as _;
 use util::{ResultExt, TryFutureExt as _, maybe};
 
@@ -550,6 +551,22 @@ pub fn show_link_definition(
                             (range, vec![HoverLink::Url(url)])
                         })
                         .ok()
+                    } else if let Some((link_range, link)) = find_document_link(
+                        &buffer,
+                        project.clone(),
+                        provider.clone(),
+                        buffer_position,
+                        cx,
+                    )
+                    .await
+                    {
+                        let range = maybe!({
+                            let start = snapshot.anchor_in_excerpt(excerpt_id, link_range.start)?;
+                            let end = snapshot.anchor_in_excerpt(excerpt_id, link_range.end)?;
+                            Some(RangeInEditor::Text(start..end))
+                        });
+
+                        Some((range, vec![link]))
                     } else if let Some((filename_range, filename)) =
                         find_file(&buffer, project.clone(), buffer_position, cx).await
                     {
@@ -781,6 +798,55 @@ pub(crate) fn find_url_from_range(
     None
 }
 
+/// Finds a language server provided document link under the position,
+/// resolving its target lazily if the server did not send one upfront.
+pub(crate) async fn find_document_link(
+    buffer: &Entity<language::Buffer>,
+    project: Option<Entity<Project>>,
+    provider: Option<Rc<dyn SemanticsProvider>>,
+    position: text::Anchor,
+    cx: &mut AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, HoverLink)> {
+    let provider = provider?;
+    let links = cx
+        .update(|_, cx| provider.document_links(buffer, cx))
+        .ok()??
+        .await
+        .log_err()?;
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot()).ok()?;
+    let link = links.into_iter().find(|link| {
+        link.range.start.cmp(&position, &snapshot).is_le()
+            && link.range.end.cmp(&position, &snapshot).is_ge()
+    })?;
+    let range = link.range.clone();
+    let link = if link.is_resolved() {
+        link
+    } else {
+        cx.update(|_, cx| provider.resolve_document_link(link, buffer, cx))
+            .ok()??
+            .await
+            .log_err()?
+    };
+
+    let target = link.target?;
+    let abs_path = lsp::Uri::from_str(&target)
+        .ok()
+        .and_then(|uri| uri.to_file_path().ok());
+    let hover_link = match abs_path {
+        Some(abs_path) => {
+            let resolved_path = project?
+                .update(cx, |project, cx| {
+                    project.resolve_abs_path(&abs_path.to_string_lossy(), cx)
+                })
+                .ok()?
+                .await?;
+            HoverLink::File(resolved_path)
+        }
+        None => HoverLink::Url(target),
+    };
+    Some((range, hover_link))
+}
+
 pub(crate) async fn find_file(
     buffer: &Entity<language::Buffer>,
     project: Option<Entity<Project>>,
@@ -1459,6 +1525,82 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_document_links(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            extern crate serdeˇ;
+        "});
+        let link_range = cx.lsp_range(indoc! {"
+            extern crate «serde»;
+        "});
+        let screen_coord = cx.pixel_position(indoc! {"
+            extern crate seˇrde;
+        "});
+
+        let mut link_requests = cx.set_request_handler::<lsp::request::DocumentLinkRequest, _, _>(
+            move |_, _, _| async move {
+                Ok(Some(vec![lsp::DocumentLink {
+                    range: link_range,
+                    target: None,
+                    tooltip: None,
+                    data: Some(serde_json::json!({ "crate": "serde" })),
+                }]))
+            },
+        );
+        let mut resolve_requests = cx
+            .set_request_handler::<lsp::request::DocumentLinkResolve, _, _>(
+                move |_, link, _| async move {
+                    Ok(lsp::DocumentLink {
+                        target: Some("https://docs.rs/serde".parse().unwrap()),
+                        ..link
+                    })
+                },
+            );
+
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        link_requests.next().await;
+        resolve_requests.next().await;
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            extern crate «serde»;
+        "});
+
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        assert_eq!(cx.opened_url(), Some("https://docs.rs/serde".into()));
+
+        let other_coord = cx.pixel_position(indoc! {"
+            extˇern crate serde;
+        "});
+        cx.simulate_mouse_move(other_coord, None, Modifiers::secondary_key());
+        cx.run_until_parked();
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            extern crate «serde»;
+        "});
+        assert!(
+            link_requests.try_next().is_err(),
+            "Links of an unchanged buffer should be reused"
+        );
+        assert!(
+            resolve_requests.try_next().is_err(),
+            "Resolved links should be reused"
+        );
+    }
+
     #[gpui::test]
     async fn test_urls_at_beginning_of_buffer(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
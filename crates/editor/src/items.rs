@@ -1624,19 +1624,10 @@ impl SearchableItem for Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let text = self.buffer.read(cx);
-        let text = text.snapshot(cx);
-        let text = text.text_for_range(identifier.clone()).collect::<Vec<_>>();
-        let text: Cow<_> = if text.len() == 1 {
-            text.first().cloned().unwrap().into()
-        } else {
-            let joined_chunks = text.join("");
-            joined_chunks.into()
-        };
-
-        if let Some(replacement) = query.replacement_for(&text) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        if let Some(replacement) = replacement_for_match(&snapshot, identifier, query) {
             self.transact(window, cx, |this, _, cx| {
-                this.edit([(identifier.clone(), Arc::from(&*replacement))], cx);
+                this.edit([(identifier.clone(), replacement)], cx);
             });
         }
     }
@@ -1647,22 +1638,12 @@ impl SearchableItem for Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let text = self.buffer.read(cx);
-        let text = text.snapshot(cx);
+        let snapshot = self.buffer.read(cx).snapshot(cx);
         let mut edits = vec![];
 
         for m in matches {
-            let text = text.text_for_range(m.clone()).collect::<Vec<_>>();
-
-            let text: Cow<_> = if text.len() == 1 {
-                text.first().cloned().unwrap().into()
-            } else {
-                let joined_chunks = text.join("");
-                joined_chunks.into()
-            };
-
-            if let Some(replacement) = query.replacement_for(&text) {
-                edits.push((m.clone(), Arc::from(&*replacement)));
+            if let Some(replacement) = replacement_for_match(&snapshot, m, query) {
+                edits.push((m.clone(), replacement));
             }
         }
 
@@ -1807,6 +1788,34 @@ impl SearchableItem for Editor {
     }
 }
 
+fn replacement_for_match(
+    snapshot: &MultiBufferSnapshot,
+    range: &Range<Anchor>,
+    query: &SearchQuery,
+) -> Option<Arc<str>> {
+    if query.is_structural() {
+        // Structural replacements are rendered from the syntax tree of the buffer holding the match.
+        let buffer_ranges = snapshot.range_to_buffer_ranges(range.clone());
+        let [(buffer, buffer_range, _)] = buffer_ranges.as_slice() else {
+            return None;
+        };
+        return query
+            .structural_replacement_for(buffer, buffer_range.clone())
+            .map(Arc::from);
+    }
+
+    let text = snapshot.text_for_range(range.clone()).collect::<Vec<_>>();
+    let text: Cow<_> = if text.len() == 1 {
+        text.first().cloned().unwrap().into()
+    } else {
+        let joined_chunks = text.join("");
+        joined_chunks.into()
+    };
+    query
+        .replacement_for(&text)
+        .map(|replacement| Arc::from(&*replacement))
+}
+
 pub fn active_match_index(
     direction: Direction,
     ranges: &[Range<Anchor>],
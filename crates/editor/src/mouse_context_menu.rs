@@ -2,7 +2,7 @@ use crate::{
     Copy, CopyAndTrim, CopyPermalinkToLine, Cut, DisplayPoint, DisplaySnapshot, Editor,
     EvaluateSelectedText, FindAllReferences, GoToDeclaration, GoToDefinition, GoToImplementation,
     GoToTypeDefinition, Paste, Rename, RevealInFileManager, RunToCursor, SelectMode,
-    SelectionEffects, SelectionExt, ToDisplayPoint, ToggleCodeActions,
+    SelectionEffects, SelectionExt, SetNextStatement, ToDisplayPoint, ToggleCodeActions,
     actions::{Format, FormatSelections},
     selections_collection::SelectionsCollection,
 };
@@ -203,6 +203,7 @@ pub fn deploy_context_menu(
 
         let evaluate_selection = window.is_action_available(&EvaluateSelectedText, cx);
         let run_to_cursor = window.is_action_available(&RunToCursor, cx);
+        let set_next_statement = window.is_action_available(&SetNextStatement, cx);
 
         ui::ContextMenu::build(window, cx, |menu, _window, _cx| {
             let builder = menu
@@ -210,11 +211,14 @@ pub fn deploy_context_menu(
                 .when(run_to_cursor, |builder| {
                     builder.action("Run to Cursor", Box::new(RunToCursor))
                 })
+                .when(set_next_statement, |builder| {
+                    builder.action("Set Next Statement", Box::new(SetNextStatement))
+                })
                 .when(evaluate_selection && has_selections, |builder| {
                     builder.action("Evaluate Selection", Box::new(EvaluateSelectedText))
                 })
                 .when(
-                    run_to_cursor || (evaluate_selection && has_selections),
+                    run_to_cursor || set_next_statement || (evaluate_selection && has_selections),
                     |builder| builder.separator(),
                 )
                 .action("Go to Definition", Box::new(GoToDefinition))
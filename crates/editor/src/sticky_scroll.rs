@@ -0,0 +1,61 @@
+use crate::{
+    DisplayPoint, EditorSnapshot,
+    display_map::{DisplayRow, ToDisplayPoint},
+};
+use multi_buffer::Anchor;
+
+/// The first line of a scope, such as a function or an impl, that encloses the top of the
+/// viewport and is pinned there while scrolling.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StickyScope {
+    /// The display row of the scope's first line.
+    pub row: DisplayRow,
+    /// Where the scope starts.
+    pub start: Anchor,
+}
+
+impl EditorSnapshot {
+    /// Returns the outline items enclosing `top_row`, outermost first, whose first lines are
+    /// scrolled out of view and should be pinned to the top of the editor.
+    ///
+    /// Each pinned line hides the row underneath it, so a scope is only included while it
+    /// starts above the row its pinned line covers and continues past it.
+    pub fn sticky_scopes(&self, top_row: DisplayRow, max_depth: usize) -> Vec<StickyScope> {
+        let mut scopes = Vec::new();
+        if max_depth == 0 || top_row.0 == 0 {
+            return scopes;
+        }
+
+        let top = DisplayPoint::new(top_row, 0).to_point(&self.display_snapshot);
+        let Some((_, items)) = self.buffer_snapshot.symbols_containing(top, None) else {
+            return scopes;
+        };
+
+        for item in items {
+            let covered_row = DisplayRow(top_row.0 + scopes.len() as u32);
+            let start_row = item
+                .range
+                .start
+                .to_display_point(&self.display_snapshot)
+                .row();
+            let end_row = item
+                .range
+                .end
+                .to_display_point(&self.display_snapshot)
+                .row();
+            if start_row >= covered_row || end_row <= covered_row {
+                continue;
+            }
+
+            scopes.push(StickyScope {
+                row: start_row,
+                start: item.range.start,
+            });
+            if scopes.len() == max_depth {
+                break;
+            }
+        }
+
+        scopes
+    }
+}
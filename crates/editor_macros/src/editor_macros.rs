@@ -0,0 +1,572 @@
+//! Keyboard macros for the editor: records the actions dispatched in an editor along with the
+//! text typed into it, and replays them later.
+//!
+//! Named macros are stored in `macros.json` next to the user's settings, and can be bound to keys
+//! with the [`PlayMacro`] action.
+
+mod macro_name_prompt;
+mod macros_picker;
+mod recording_indicator;
+mod replay;
+
+use anyhow::Result;
+use collections::BTreeMap;
+use editor::{Editor, EditorEvent};
+use fs::Fs;
+use futures::StreamExt as _;
+use gpui::{Action, App, AppContext as _, Global, WeakEntity, Window, actions};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{cell::Cell, ops::Range, rc::Rc, sync::Arc};
+use util::ResultExt as _;
+use workspace::Workspace;
+
+pub use recording_indicator::RecordingIndicator;
+
+actions!(
+    editor_macros,
+    [
+        /// Starts recording a macro in the active editor, or stops the recording in progress.
+        ToggleRecording,
+        /// Saves the last recorded macro under a name.
+        SaveLastMacro,
+        /// Opens a picker to play one of the saved macros.
+        OpenMacros
+    ]
+);
+
+/// Replays the last recorded macro.
+#[derive(PartialEq, Clone, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor_macros)]
+#[serde(deny_unknown_fields)]
+pub struct ReplayLastMacro {
+    /// How many times to replay the macro.
+    #[serde(default = "default_times")]
+    pub times: usize,
+    /// Whether to replay the macro once at each selection, instead of once for all of them.
+    #[serde(default)]
+    pub for_each_selection: bool,
+}
+
+impl Default for ReplayLastMacro {
+    fn default() -> Self {
+        Self {
+            times: default_times(),
+            for_each_selection: false,
+        }
+    }
+}
+
+/// Plays a saved macro by name.
+#[derive(PartialEq, Clone, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor_macros)]
+#[serde(deny_unknown_fields)]
+pub struct PlayMacro {
+    /// The name the macro was saved under.
+    pub name: String,
+    /// How many times to play the macro.
+    #[serde(default = "default_times")]
+    pub times: usize,
+    /// Whether to play the macro once at each selection, instead of once for all of them.
+    #[serde(default)]
+    pub for_each_selection: bool,
+}
+
+fn default_times() -> usize {
+    1
+}
+
+/// A single recorded step of a macro.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MacroStep {
+    /// An action dispatched in the editor, with the input that builds it, if any.
+    Action {
+        action: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        input: Option<serde_json::Value>,
+    },
+    /// Text typed into an editor.
+    Insertion {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+}
+
+/// The recording in progress, the last recorded macro and the saved macros.
+pub struct EditorMacros {
+    fs: Arc<dyn Fs>,
+    recording: Option<Recording>,
+    last_macro: Vec<MacroStep>,
+    saved: BTreeMap<String, Vec<MacroStep>>,
+    replaying: Rc<Cell<bool>>,
+}
+
+/// A macro being recorded from the actions and input of a single editor.
+struct Recording {
+    editor: WeakEntity<Editor>,
+    steps: Vec<MacroStep>,
+}
+
+impl Global for EditorMacros {}
+
+impl EditorMacros {
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Stops the recording in progress, or starts recording in `editor` if there's none.
+    fn toggle_recording(&mut self, editor: Option<WeakEntity<Editor>>) {
+        if let Some(recording) = self.recording.take() {
+            self.last_macro = recording.steps;
+        } else if let Some(editor) = editor {
+            self.recording = Some(Recording {
+                editor,
+                steps: Vec::new(),
+            });
+        }
+    }
+
+    /// The editor being recorded, unless a macro is being replayed into it.
+    fn recording_editor(&self) -> Option<&WeakEntity<Editor>> {
+        if self.replaying.get() {
+            return None;
+        }
+        Some(&self.recording.as_ref()?.editor)
+    }
+
+    fn observe_action(&mut self, action: &dyn Action, window: &Window, cx: &App) {
+        if action.name().starts_with("editor_macros::") {
+            return;
+        }
+        let Some(editor) = self.recording_editor().and_then(|editor| editor.upgrade()) else {
+            return;
+        };
+        if !editor.focus_handle(cx).contains_focused(window, cx) {
+            return;
+        }
+        let Some(input) = action_input(action, cx) else {
+            log::warn!(
+                "not recording {} in the macro, as it can't be built again",
+                action.name()
+            );
+            return;
+        };
+        if let Some(recording) = self.recording.as_mut() {
+            recording.steps.push(MacroStep::Action {
+                action: action.name().to_string(),
+                input,
+            });
+        }
+    }
+
+    fn observe_insertion(&mut self, text: &str, utf16_range_to_replace: Option<Range<isize>>) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording.steps.push(MacroStep::Insertion {
+                text: text.to_string(),
+                utf16_range_to_replace,
+            });
+        }
+    }
+
+    fn save_last_macro(&mut self, name: String, cx: &mut App) {
+        self.saved.insert(name, self.last_macro.clone());
+        let fs = self.fs.clone();
+        let macros = self.saved.clone();
+        cx.background_spawn(async move {
+            let contents = serde_json::to_string_pretty(&macros)?;
+            fs.atomic_write(paths::macros_file().clone(), contents)
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+/// The input that builds `action` again, or `None` if there isn't one.
+///
+/// Actions don't serialize themselves, so this is either no input at all, when that builds an
+/// equal action, or the input of a key binding for an equal action.
+fn action_input(action: &dyn Action, cx: &App) -> Option<Option<serde_json::Value>> {
+    if cx
+        .build_action(action.name(), None)
+        .is_ok_and(|built| built.partial_eq(action))
+    {
+        return Some(None);
+    }
+    cx.key_bindings()
+        .borrow()
+        .bindings_for_action(action)
+        .find_map(|binding| binding.action_input())
+        .and_then(|input| serde_json::from_str(&input).log_err())
+        .map(Some)
+}
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut App) {
+    cx.set_global(EditorMacros {
+        fs: fs.clone(),
+        recording: None,
+        last_macro: Vec::new(),
+        saved: BTreeMap::default(),
+        replaying: Rc::new(Cell::new(false)),
+    });
+    load_saved_macros(fs, cx);
+
+    cx.observe_actions(|action, window, cx| {
+        if cx.global::<EditorMacros>().recording_editor().is_some() {
+            cx.update_global(|macros: &mut EditorMacros, cx| {
+                macros.observe_action(action, window, cx)
+            });
+        }
+    })
+    .detach();
+
+    cx.observe_new(|editor: &mut Editor, _, cx| {
+        if !editor.mode().is_full() {
+            return;
+        }
+        cx.subscribe_self(|_, event: &EditorEvent, cx| {
+            if let EditorEvent::InputHandled {
+                text,
+                utf16_range_to_replace,
+            } = event
+                && cx
+                    .global::<EditorMacros>()
+                    .recording_editor()
+                    .is_some_and(|editor| editor.entity_id() == cx.entity_id())
+            {
+                cx.global_mut::<EditorMacros>()
+                    .observe_insertion(text, utf16_range_to_replace.clone());
+            }
+        })
+        .detach();
+        replay::register(editor, cx);
+    })
+    .detach();
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleRecording, _, cx| {
+            let editor = workspace
+                .active_item_as::<Editor>(cx)
+                .map(|editor| editor.downgrade());
+            cx.global_mut::<EditorMacros>().toggle_recording(editor);
+        });
+        macro_name_prompt::MacroNamePrompt::register(workspace);
+        macros_picker::MacrosPicker::register(workspace);
+    })
+    .detach();
+}
+
+fn load_saved_macros(fs: Arc<dyn Fs>, cx: &mut App) {
+    let mut macros_file_rx =
+        settings::watch_config_file(cx.background_executor(), fs, paths::macros_file().clone());
+    cx.spawn(async move |cx| {
+        while let Some(contents) = macros_file_rx.next().await {
+            let saved: Result<BTreeMap<String, Vec<MacroStep>>> = if contents.trim().is_empty() {
+                Ok(BTreeMap::default())
+            } else {
+                settings::parse_json_with_comments(&contents)
+            };
+            let Some(saved) = saved.log_err() else {
+                continue;
+            };
+            cx.update_global(|macros: &mut EditorMacros, _| macros.saved = saved)
+                .log_err();
+        }
+    })
+    .detach();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::{
+        actions::{MoveDown, MoveToBeginningOfLine, MoveToEndOfLine},
+        test::editor_lsp_test_context::EditorLspTestContext,
+    };
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use indoc::indoc;
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    async fn init_test(cx: &mut TestAppContext) -> (EditorLspTestContext, Arc<FakeFs>) {
+        let fs = FakeFs::new(cx.executor());
+        fs.create_dir(paths::config_dir()).await.unwrap();
+        cx.update(|cx| {
+            let settings = SettingsStore::test(cx);
+            cx.set_global(settings);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init_settings(cx);
+            workspace::init_settings(cx);
+            project::Project::init_settings(cx);
+            init(fs.clone(), cx);
+        });
+        let cx = EditorLspTestContext::new_rust(Default::default(), cx).await;
+        (cx, fs)
+    }
+
+    fn record_bullet_macro(cx: &mut EditorLspTestContext) {
+        cx.dispatch_action(ToggleRecording);
+        cx.simulate_input("- ");
+        cx.dispatch_action(MoveDown);
+        cx.dispatch_action(MoveToBeginningOfLine {
+            stop_at_soft_wraps: true,
+            stop_at_indent: false,
+        });
+        cx.dispatch_action(ToggleRecording);
+        cx.run_until_parked();
+    }
+
+    #[gpui::test]
+    async fn test_record_and_replay(cx: &mut TestAppContext) {
+        let (mut cx, _) = init_test(cx).await;
+        cx.set_state(indoc! {"
+            ˇone
+            two
+            three
+            four
+            five
+        "});
+
+        record_bullet_macro(&mut cx);
+        cx.assert_editor_state(indoc! {"
+            - one
+            ˇtwo
+            three
+            four
+            five
+        "});
+        cx.update(|_, cx| {
+            assert_eq!(
+                cx.global::<EditorMacros>().last_macro,
+                [
+                    MacroStep::Insertion {
+                        text: "-".into(),
+                        utf16_range_to_replace: None,
+                    },
+                    MacroStep::Insertion {
+                        text: " ".into(),
+                        utf16_range_to_replace: None,
+                    },
+                    MacroStep::Action {
+                        action: "editor::MoveDown".into(),
+                        input: None,
+                    },
+                    MacroStep::Action {
+                        action: "editor::MoveToBeginningOfLine".into(),
+                        input: None,
+                    },
+                ]
+            );
+        });
+
+        cx.dispatch_action(ReplayLastMacro::default());
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            - one
+            - two
+            ˇthree
+            four
+            five
+        "});
+
+        cx.dispatch_action(ReplayLastMacro {
+            times: 2,
+            for_each_selection: false,
+        });
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            - one
+            - two
+            - three
+            - four
+            ˇfive
+        "});
+        cx.update(|_, cx| assert!(!cx.global::<EditorMacros>().replaying.get()));
+
+        // Replayed steps aren't recorded into a new macro.
+        cx.dispatch_action(ToggleRecording);
+        cx.dispatch_action(ReplayLastMacro::default());
+        cx.run_until_parked();
+        cx.dispatch_action(ToggleRecording);
+        cx.update(|_, cx| assert!(cx.global::<EditorMacros>().last_macro.is_empty()));
+    }
+
+    #[gpui::test]
+    async fn test_recording_only_the_focused_editor(cx: &mut TestAppContext) {
+        let (mut cx, _) = init_test(cx).await;
+        cx.set_state("ˇone\ntwo\n");
+
+        cx.dispatch_action(ToggleRecording);
+        cx.dispatch_action(MoveDown);
+        cx.update(|window, _| window.blur());
+        // Dispatched while the recording editor isn't focused.
+        cx.dispatch_action(MoveToEndOfLine {
+            stop_at_soft_wraps: true,
+        });
+        cx.update(|window, cx| {
+            let editor = cx.global::<EditorMacros>().recording_editor().cloned();
+            window.focus(&editor.unwrap().upgrade().unwrap().focus_handle(cx));
+        });
+        cx.dispatch_action(ToggleRecording);
+        cx.run_until_parked();
+
+        cx.update(|_, cx| {
+            assert_eq!(
+                cx.global::<EditorMacros>().last_macro,
+                [MacroStep::Action {
+                    action: "editor::MoveDown".into(),
+                    input: None,
+                }]
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_replay_for_each_selection(cx: &mut TestAppContext) {
+        let (mut cx, _) = init_test(cx).await;
+        cx.set_state("ˇa\n");
+
+        cx.dispatch_action(ToggleRecording);
+        cx.simulate_input("<");
+        cx.dispatch_action(MoveToEndOfLine {
+            stop_at_soft_wraps: true,
+        });
+        cx.simulate_input(">");
+        cx.dispatch_action(ToggleRecording);
+        cx.run_until_parked();
+        assert_eq!(cx.buffer_text(), "<a>\n");
+
+        cx.set_state(indoc! {"
+            ˇone
+            two
+            ˇthree
+            ˇfour
+        "});
+        cx.dispatch_action(ReplayLastMacro {
+            times: 1,
+            for_each_selection: true,
+        });
+        cx.run_until_parked();
+        assert_eq!(cx.buffer_text(), "<one>\ntwo\n<three>\n<four>\n");
+        cx.update_editor(|editor, _, cx| {
+            assert_eq!(editor.selections.count(), 3);
+        });
+
+        cx.set_state("ˇx\nˇy\n");
+        cx.dispatch_action(ReplayLastMacro {
+            times: 2,
+            for_each_selection: true,
+        });
+        cx.run_until_parked();
+        assert_eq!(cx.buffer_text(), "<x><>\n<y><>\n");
+    }
+
+    #[gpui::test]
+    async fn test_saved_macros(cx: &mut TestAppContext) {
+        let (mut cx, fs) = init_test(cx).await;
+        cx.set_state(indoc! {"
+            ˇone
+            two
+            three
+        "});
+
+        record_bullet_macro(&mut cx);
+        cx.update(|_, cx| {
+            cx.update_global(|macros: &mut EditorMacros, cx| {
+                macros.save_last_macro("bullet".into(), cx)
+            })
+        });
+        cx.run_until_parked();
+        let saved: BTreeMap<String, Vec<MacroStep>> =
+            serde_json::from_str(&fs.load(paths::macros_file()).await.unwrap()).unwrap();
+        assert_eq!(saved.keys().collect::<Vec<_>>(), ["bullet"]);
+
+        cx.dispatch_action(PlayMacro {
+            name: "bullet".into(),
+            times: 1,
+            for_each_selection: false,
+        });
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            - one
+            - two
+            ˇthree
+        "});
+
+        // Macros edited in the macros file can be played too.
+        fs.insert_file(
+            paths::macros_file(),
+            json!({
+                "shout": [
+                    { "text": "!" },
+                    { "action": "editor::MoveDown" },
+                ],
+            })
+            .to_string()
+            .into_bytes(),
+        )
+        .await;
+        cx.run_until_parked();
+        cx.dispatch_action(PlayMacro {
+            name: "shout".into(),
+            times: 1,
+            for_each_selection: false,
+        });
+        cx.run_until_parked();
+        cx.assert_editor_state("- one\n- two\n!three\nˇ");
+    }
+
+    #[gpui::test]
+    async fn test_closing_window_during_replay(cx: &mut TestAppContext) {
+        let (mut cx, _) = init_test(cx).await;
+        cx.set_state("ˇone\ntwo\n");
+        record_bullet_macro(&mut cx);
+
+        let editor = cx.editor.clone();
+        cx.update(|window, cx| {
+            let steps = cx.global::<EditorMacros>().last_macro.clone();
+            replay::play(&steps, 5, false, editor, window, cx).unwrap();
+            assert!(cx.global::<EditorMacros>().replaying.get());
+            window.remove_window();
+        });
+
+        let cx: &mut TestAppContext = &mut cx;
+        cx.run_until_parked();
+        cx.update(|cx| assert!(!cx.global::<EditorMacros>().replaying.get()));
+    }
+
+    #[test]
+    fn test_macro_steps_serialization() {
+        let steps = vec![
+            MacroStep::Action {
+                action: "editor::SelectNext".into(),
+                input: Some(json!({ "replace_newest": true })),
+            },
+            MacroStep::Action {
+                action: "editor::Newline".into(),
+                input: None,
+            },
+            MacroStep::Insertion {
+                text: "hello".into(),
+                utf16_range_to_replace: None,
+            },
+        ];
+
+        let serialized = serde_json::to_value(&steps).unwrap();
+        assert_eq!(
+            serialized,
+            json!([
+                { "action": "editor::SelectNext", "input": { "replace_newest": true } },
+                { "action": "editor::Newline" },
+                { "text": "hello" },
+            ])
+        );
+        assert_eq!(
+            serde_json::from_value::<Vec<MacroStep>>(serialized).unwrap(),
+            steps
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{EditorMacros, SaveLastMacro};
+use editor::{Editor, EditorEvent};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Styled, Subscription,
+    div, prelude::*,
+};
+use ui::prelude::*;
+use workspace::{ModalView, Workspace};
+
+/// A prompt for the name to save the last recorded macro under.
+pub(crate) struct MacroNamePrompt {
+    name_editor: Entity<Editor>,
+    _name_editor_subscription: Subscription,
+}
+
+impl ModalView for MacroNamePrompt {}
+
+impl Focusable for MacroNamePrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+impl EventEmitter<DismissEvent> for MacroNamePrompt {}
+
+impl MacroNamePrompt {
+    pub(crate) fn register(workspace: &mut Workspace) {
+        workspace.register_action(|workspace, _: &SaveLastMacro, window, cx| {
+            if cx.global::<EditorMacros>().last_macro.is_empty() {
+                return;
+            }
+            workspace.toggle_modal(window, cx, MacroNamePrompt::new);
+        });
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Macro name", cx);
+            editor
+        });
+        let name_editor_subscription =
+            cx.subscribe(&name_editor, |_, _, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    cx.notify();
+                }
+            });
+        Self {
+            name_editor,
+            _name_editor_subscription: name_editor_subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        cx.update_global(|macros: &mut EditorMacros, cx| macros.save_last_macro(name, cx));
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for MacroNamePrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let name = self.name_editor.read(cx).text(cx);
+        let help_text = if cx.global::<EditorMacros>().saved.contains_key(name.trim()) {
+            format!("Replace the saved macro {:?}", name.trim())
+        } else {
+            "Save the last recorded macro".to_string()
+        };
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("MacroNamePrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.name_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .child(Label::new(help_text).color(Color::Muted)),
+            )
+    }
+}
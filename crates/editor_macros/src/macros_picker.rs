@@ -0,0 +1,221 @@
+use crate::{EditorMacros, MacroStep, OpenMacros, replay};
+use editor::Editor;
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ParentElement,
+    Render, Styled, Task, WeakEntity, Window,
+};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+/// Lists the saved macros and plays the selected one in the active editor.
+pub(crate) struct MacrosPicker {
+    picker: Entity<Picker<MacrosPickerDelegate>>,
+}
+
+impl MacrosPicker {
+    pub(crate) fn register(workspace: &mut Workspace) {
+        workspace.register_action(|workspace, _: &OpenMacros, window, cx| {
+            let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+                return;
+            };
+            let macros = cx
+                .global::<EditorMacros>()
+                .saved
+                .iter()
+                .map(|(name, steps)| (name.clone(), steps.clone()))
+                .collect::<Vec<_>>();
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                MacrosPicker::new(editor.downgrade(), macros, window, cx)
+            });
+        });
+    }
+
+    fn new(
+        editor: WeakEntity<Editor>,
+        macros: Vec<(String, Vec<MacroStep>)>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = MacrosPickerDelegate::new(cx.entity().downgrade(), editor, macros);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+impl Render for MacrosPicker {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("MacrosPicker")
+            .w(rems(28.))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for MacrosPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for MacrosPicker {}
+impl ModalView for MacrosPicker {}
+
+pub(crate) struct MacrosPickerDelegate {
+    macros_picker: WeakEntity<MacrosPicker>,
+    editor: WeakEntity<Editor>,
+    macros: Vec<(String, Vec<MacroStep>)>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl MacrosPickerDelegate {
+    fn new(
+        macros_picker: WeakEntity<MacrosPicker>,
+        editor: WeakEntity<Editor>,
+        macros: Vec<(String, Vec<MacroStep>)>,
+    ) -> Self {
+        let candidates = macros
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, (name, _))| StringMatchCandidate::new(candidate_id, name))
+            .collect();
+        Self {
+            macros_picker,
+            editor,
+            macros,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for MacrosPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Play a macro…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No saved macros".into())
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let selected = self
+            .matches
+            .get(self.selected_index)
+            .map(|mat| self.macros[mat.candidate_id].1.clone());
+        self.dismissed(window, cx);
+        if let Some((steps, editor)) = selected.zip(self.editor.upgrade()) {
+            replay::play(&steps, 1, false, editor, window, cx).log_err();
+        }
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.macros_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let step_count = self.macros[mat.candidate_id].1.len();
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            mat.string.clone(),
+                            mat.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(if step_count == 1 {
+                                "1 step".to_string()
+                            } else {
+                                format!("{step_count} steps")
+                            })
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{EditorMacros, ToggleRecording};
+use gpui::{Context, Render, Subscription, Window, div};
+use ui::{Tooltip, prelude::*};
+use workspace::{StatusItemView, item::ItemHandle};
+
+/// Shows in the status bar while a macro is being recorded.
+pub struct RecordingIndicator {
+    _observe_macros: Subscription,
+}
+
+impl RecordingIndicator {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        Self {
+            _observe_macros: cx.observe_global::<EditorMacros>(|_, cx| cx.notify()),
+        }
+    }
+}
+
+impl Render for RecordingIndicator {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if !cx.global::<EditorMacros>().is_recording() {
+            return div().into_any_element();
+        }
+
+        Button::new("macro-recording-indicator", "Recording Macro")
+            .label_size(LabelSize::Small)
+            .icon(IconName::Circle)
+            .icon_size(IconSize::XSmall)
+            .icon_color(Color::Error)
+            .icon_position(IconPosition::Start)
+            .tooltip(Tooltip::for_action_title(
+                "Stop Recording",
+                &ToggleRecording,
+            ))
+            .on_click(|_, window, cx| window.dispatch_action(Box::new(ToggleRecording), cx))
+            .into_any_element()
+    }
+}
+
+impl StatusItemView for RecordingIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        _active_pane_item: Option<&dyn ItemHandle>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) {
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{EditorMacros, MacroStep, PlayMacro, ReplayLastMacro};
+use anyhow::{Context as _, Result};
+use editor::{Anchor, Editor, SelectionEffects};
+use gpui::{Action, App, Context, Entity, Window};
+use std::{cell::Cell, collections::VecDeque, ops::Range, rc::Rc};
+use util::ResultExt as _;
+
+/// Replaying stops after this many steps, in case a macro ends up replaying itself.
+const MAX_REPLAYED_STEPS: usize = 10000;
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let handle = cx.entity().downgrade();
+    editor
+        .register_action(move |action: &ReplayLastMacro, window, cx| {
+            let Some(editor) = handle.upgrade() else {
+                return;
+            };
+            let steps = cx.global::<EditorMacros>().last_macro.clone();
+            play(
+                &steps,
+                action.times,
+                action.for_each_selection,
+                editor,
+                window,
+                cx,
+            )
+            .log_err();
+        })
+        .detach();
+
+    let handle = cx.entity().downgrade();
+    editor
+        .register_action(move |action: &PlayMacro, window, cx| {
+            let Some(editor) = handle.upgrade() else {
+                return;
+            };
+            let Some(steps) = cx.global::<EditorMacros>().saved.get(&action.name).cloned() else {
+                log::error!("no macro named {:?}", action.name);
+                return;
+            };
+            play(
+                &steps,
+                action.times,
+                action.for_each_selection,
+                editor,
+                window,
+                cx,
+            )
+            .log_err();
+        })
+        .detach();
+}
+
+enum ReplayStep {
+    Select(Vec<Range<Anchor>>),
+    Action(Box<dyn Action>),
+    Insertion {
+        text: String,
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+}
+
+/// Plays `steps` in `editor`, `times` times over.
+///
+/// When `for_each_selection` is set, the steps are played once with each of the editor's
+/// selections on its own, after which the original selections are restored.
+pub(crate) fn play(
+    steps: &[MacroStep],
+    times: usize,
+    for_each_selection: bool,
+    editor: Entity<Editor>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Result<()> {
+    if steps.is_empty() || times == 0 || cx.global::<EditorMacros>().replaying.get() {
+        return Ok(());
+    }
+
+    let mut iteration = Vec::with_capacity(steps.len() * times);
+    for _ in 0..times {
+        for step in steps {
+            iteration.push(match step {
+                MacroStep::Action { action, input } => ReplayStep::Action(
+                    cx.build_action(action, input.clone())
+                        .with_context(|| format!("building macro action {action:?}"))?,
+                ),
+                MacroStep::Insertion {
+                    text,
+                    utf16_range_to_replace,
+                } => ReplayStep::Insertion {
+                    text: text.clone(),
+                    utf16_range_to_replace: utf16_range_to_replace.clone(),
+                },
+            });
+        }
+    }
+
+    let mut replay_steps = VecDeque::new();
+    if for_each_selection {
+        let selections = editor
+            .read(cx)
+            .selections
+            .disjoint_anchor_ranges()
+            .collect::<Vec<_>>();
+        for selection in &selections {
+            replay_steps.push_back(ReplayStep::Select(vec![selection.clone()]));
+            replay_steps.extend(iteration.iter().map(ReplayStep::clone_step));
+        }
+        replay_steps.push_back(ReplayStep::Select(selections));
+    } else {
+        replay_steps.extend(iteration);
+    }
+    if replay_steps.len() > MAX_REPLAYED_STEPS {
+        anyhow::bail!("refusing to replay more than {MAX_REPLAYED_STEPS} macro steps");
+    }
+
+    window.focus(&editor.focus_handle(cx));
+    let replayer = Replayer {
+        steps: replay_steps,
+        editor,
+        _replaying: ReplayingGuard::new(cx.global::<EditorMacros>().replaying.clone()),
+    };
+    window.defer(cx, move |window, cx| replayer.next(window, cx));
+    Ok(())
+}
+
+impl ReplayStep {
+    fn clone_step(&self) -> Self {
+        match self {
+            ReplayStep::Select(ranges) => ReplayStep::Select(ranges.clone()),
+            ReplayStep::Action(action) => ReplayStep::Action(action.boxed_clone()),
+            ReplayStep::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => ReplayStep::Insertion {
+                text: text.clone(),
+                utf16_range_to_replace: utf16_range_to_replace.clone(),
+            },
+        }
+    }
+}
+
+/// Plays steps one at a time, letting each action take effect before dispatching the next one.
+struct Replayer {
+    steps: VecDeque<ReplayStep>,
+    editor: Entity<Editor>,
+    _replaying: ReplayingGuard,
+}
+
+/// Marks a replay as in progress until it's dropped, which happens once the last step has been
+/// played, or earlier if the replay is cut short because its window was closed.
+struct ReplayingGuard(Rc<Cell<bool>>);
+
+impl ReplayingGuard {
+    fn new(replaying: Rc<Cell<bool>>) -> Self {
+        replaying.set(true);
+        Self(replaying)
+    }
+}
+
+impl Drop for ReplayingGuard {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+impl Replayer {
+    fn next(mut self, window: &mut Window, cx: &mut App) {
+        let Some(step) = self.steps.pop_front() else {
+            return;
+        };
+        match step {
+            ReplayStep::Select(ranges) => self.editor.update(cx, |editor, cx| {
+                editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+                    s.select_anchor_ranges(ranges)
+                })
+            }),
+            ReplayStep::Action(action) => window.dispatch_action(action, cx),
+            ReplayStep::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => self.editor.update(cx, |editor, cx| {
+                editor.replay_insert_event(&text, utf16_range_to_replace, window, cx)
+            }),
+        }
+        window.defer(cx, move |window, cx| self.next(window, cx));
+    }
+}
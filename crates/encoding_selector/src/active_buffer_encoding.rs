@@ -0,0 +1,100 @@
+use editor::{Editor, EditorSettings};
+use gpui::{Context, Entity, IntoElement, ParentElement, Render, Subscription, Window, div};
+use project::Encoding;
+use settings::Settings as _;
+use ui::{Button, ButtonCommon, ContextMenu, FluentBuilder, LabelSize, PopoverMenu, Tooltip};
+use workspace::{StatusItemView, item::ItemHandle};
+
+use crate::{ReopenWithEncoding, SaveWithEncoding};
+
+pub struct ActiveBufferEncoding {
+    active_encoding: Option<Encoding>,
+    _observe_active_editor: Option<Subscription>,
+    _observe_active_buffer: Option<Subscription>,
+}
+
+impl ActiveBufferEncoding {
+    pub fn new() -> Self {
+        Self {
+            active_encoding: None,
+            _observe_active_editor: None,
+            _observe_active_buffer: None,
+        }
+    }
+
+    fn update_encoding(&mut self, editor: Entity<Editor>, _: &mut Window, cx: &mut Context<Self>) {
+        self.active_encoding = None;
+        self._observe_active_buffer = None;
+
+        if let Some((_, buffer, _)) = editor.read(cx).active_excerpt(cx)
+            && buffer.read(cx).file().is_some()
+        {
+            self.active_encoding = Some(buffer.read(cx).encoding());
+            self._observe_active_buffer = Some(cx.observe(&buffer, |this, buffer, cx| {
+                let encoding = buffer.read(cx).encoding();
+                if this.active_encoding != Some(encoding) {
+                    this.active_encoding = Some(encoding);
+                    cx.notify();
+                }
+            }));
+        }
+
+        cx.notify();
+    }
+}
+
+impl Default for ActiveBufferEncoding {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Render for ActiveBufferEncoding {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if !EditorSettings::get_global(cx)
+            .status_bar
+            .active_encoding_button
+        {
+            return div();
+        }
+
+        div().when_some(self.active_encoding, |el, active_encoding| {
+            el.child(
+                PopoverMenu::new("active-buffer-encoding")
+                    .trigger_with_tooltip(
+                        Button::new("change-encoding", active_encoding.to_string())
+                            .label_size(LabelSize::Small),
+                        Tooltip::text("Change File Encoding"),
+                    )
+                    .anchor(gpui::Corner::BottomRight)
+                    .menu(|window, cx| {
+                        Some(ContextMenu::build(window, cx, |menu, _, _| {
+                            menu.action("Reopen with Encoding…", Box::new(ReopenWithEncoding))
+                                .action("Save with Encoding…", Box::new(SaveWithEncoding))
+                        }))
+                    }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for ActiveBufferEncoding {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.downcast::<Editor>()) {
+            self._observe_active_editor =
+                Some(cx.observe_in(&editor, window, Self::update_encoding));
+            self.update_encoding(editor, window, cx);
+        } else {
+            self.active_encoding = None;
+            self._observe_active_editor = None;
+            self._observe_active_buffer = None;
+        }
+
+        cx.notify();
+    }
+}
@@ -0,0 +1,448 @@
+use anyhow::{Result, anyhow};
+use std::{borrow::Cow, fmt};
+
+/// The character encoding of a file on disk.
+///
+/// Buffers are always UTF-8 in memory; the encoding is only used to decode a file's
+/// contents when loading it and to encode them again when saving.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Encoding {
+    encoding: &'static encoding_rs::Encoding,
+    /// Whether a byte order mark precedes the contents. UTF-8 byte order marks are kept
+    /// in the text itself, so this is only set for UTF-16.
+    bom: bool,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self::UTF_8
+    }
+}
+
+impl fmt::Debug for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+/// How many bytes are inspected when guessing the encoding of a file.
+const DETECTION_SAMPLE_LEN: usize = 8 * 1024;
+
+const UTF_8_BOM: &[u8] = b"\xEF\xBB\xBF";
+const UTF_16LE_BOM: &[u8] = b"\xFF\xFE";
+const UTF_16BE_BOM: &[u8] = b"\xFE\xFF";
+
+impl Encoding {
+    pub const UTF_8: Self = Self::new(&encoding_rs::UTF_8_INIT);
+    pub const UTF_16LE: Self = Self::new(&encoding_rs::UTF_16LE_INIT).with_bom();
+    pub const UTF_16BE: Self = Self::new(&encoding_rs::UTF_16BE_INIT).with_bom();
+    pub const WINDOWS_1252: Self = Self::new(&encoding_rs::WINDOWS_1252_INIT);
+    pub const SHIFT_JIS: Self = Self::new(&encoding_rs::SHIFT_JIS_INIT);
+
+    const fn new(encoding: &'static encoding_rs::Encoding) -> Self {
+        Self {
+            encoding,
+            bom: false,
+        }
+    }
+
+    const fn with_bom(mut self) -> Self {
+        self.bom = true;
+        self
+    }
+
+    /// The encodings that can be picked explicitly when reopening or saving a file.
+    pub fn all() -> &'static [Encoding] {
+        const ALL: &[Encoding] = &[
+            Encoding::UTF_8,
+            Encoding::UTF_16LE,
+            Encoding::UTF_16BE,
+            Encoding::new(&encoding_rs::UTF_16LE_INIT),
+            Encoding::new(&encoding_rs::UTF_16BE_INIT),
+            Encoding::WINDOWS_1252,
+            Encoding::new(&encoding_rs::WINDOWS_1250_INIT),
+            Encoding::new(&encoding_rs::WINDOWS_1251_INIT),
+            Encoding::new(&encoding_rs::WINDOWS_1253_INIT),
+            Encoding::new(&encoding_rs::WINDOWS_1254_INIT),
+            Encoding::new(&encoding_rs::WINDOWS_1255_INIT),
+            Encoding::new(&encoding_rs::WINDOWS_1256_INIT),
+            Encoding::new(&encoding_rs::WINDOWS_1257_INIT),
+            Encoding::new(&encoding_rs::WINDOWS_1258_INIT),
+            Encoding::new(&encoding_rs::WINDOWS_874_INIT),
+            Encoding::new(&encoding_rs::ISO_8859_2_INIT),
+            Encoding::new(&encoding_rs::ISO_8859_5_INIT),
+            Encoding::new(&encoding_rs::ISO_8859_7_INIT),
+            Encoding::new(&encoding_rs::ISO_8859_15_INIT),
+            Encoding::new(&encoding_rs::KOI8_R_INIT),
+            Encoding::new(&encoding_rs::KOI8_U_INIT),
+            Encoding::new(&encoding_rs::IBM866_INIT),
+            Encoding::new(&encoding_rs::MACINTOSH_INIT),
+            Encoding::SHIFT_JIS,
+            Encoding::new(&encoding_rs::EUC_JP_INIT),
+            Encoding::new(&encoding_rs::ISO_2022_JP_INIT),
+            Encoding::new(&encoding_rs::GBK_INIT),
+            Encoding::new(&encoding_rs::GB18030_INIT),
+            Encoding::new(&encoding_rs::BIG5_INIT),
+            Encoding::new(&encoding_rs::EUC_KR_INIT),
+        ];
+        ALL
+    }
+
+    /// A name that identifies the encoding, e.g. `Shift_JIS` or `UTF-16LE BOM`.
+    pub fn name(&self) -> Cow<'static, str> {
+        if self.bom {
+            format!("{} BOM", self.encoding.name()).into()
+        } else {
+            self.encoding.name().into()
+        }
+    }
+
+    /// Parses a name produced by [`Encoding::name`]. Any label recognized by the
+    /// WHATWG Encoding Standard is accepted as well.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let (label, bom) = match name.strip_suffix(" BOM") {
+            Some(label) => (label, true),
+            None => (name, false),
+        };
+        let encoding = encoding_rs::Encoding::for_label(label.trim().as_bytes())?;
+        let is_utf_16 = encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE;
+        Some(Self {
+            encoding,
+            bom: bom && is_utf_16,
+        })
+    }
+
+    pub fn is_utf_8(&self) -> bool {
+        self.encoding == encoding_rs::UTF_8
+    }
+
+    /// Guesses the encoding of the given file contents, based on the byte order mark and,
+    /// in its absence, on which encoding decodes the contents most plausibly.
+    ///
+    /// Returns `None` when the contents look like binary data rather than text. Valid UTF-8 is
+    /// always considered text, even if it contains NUL bytes.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(UTF_8_BOM) {
+            return Some(Self::UTF_8);
+        } else if bytes.starts_with(UTF_16LE_BOM) {
+            return Some(Self::UTF_16LE);
+        } else if bytes.starts_with(UTF_16BE_BOM) {
+            return Some(Self::UTF_16BE);
+        }
+
+        if let Some(encoding) = detect_utf_16_without_bom(bytes) {
+            return Some(encoding);
+        }
+        if std::str::from_utf8(bytes).is_ok() {
+            return Some(Self::UTF_8);
+        }
+        // Text in the remaining encodings doesn't contain NUL bytes in practice, so this is
+        // most likely binary data.
+        if bytes[..bytes.len().min(DETECTION_SAMPLE_LEN)].contains(&0) {
+            return None;
+        }
+        if looks_like_shift_jis(bytes) {
+            return Some(Self::SHIFT_JIS);
+        }
+        Some(Self::WINDOWS_1252)
+    }
+
+    /// Like [`Encoding::detect`], but for the beginning of a file that's read incrementally,
+    /// which may end in the middle of a character.
+    pub fn detect_prefix(bytes: &[u8]) -> Option<Self> {
+        let len = match std::str::from_utf8(bytes) {
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            _ => bytes.len(),
+        };
+        Self::detect(&bytes[..len])
+    }
+
+    /// Decodes file contents, failing if they aren't valid in this encoding.
+    pub fn decode(&self, bytes: Vec<u8>) -> Result<String> {
+        if self.is_utf_8() {
+            return Ok(String::from_utf8(bytes)?);
+        }
+
+        let bytes = match (self.bom, self.bom_bytes()) {
+            (true, Some(bom)) => bytes.strip_prefix(bom).unwrap_or(&bytes),
+            _ => &bytes[..],
+        };
+        self.encoding
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .map(Cow::into_owned)
+            .ok_or_else(|| anyhow!("file contents are not valid {}", self.encoding.name()))
+    }
+
+    /// Encodes text to be written to disk, failing if it contains characters that can't be
+    /// represented in this encoding.
+    pub fn encode<'a>(&self, text: &'a str) -> Result<Cow<'a, [u8]>> {
+        if self.is_utf_8() {
+            return Ok(Cow::Borrowed(text.as_bytes()));
+        }
+
+        let bom = self.bom_bytes().filter(|_| self.bom).unwrap_or_default();
+        if self.encoding == encoding_rs::UTF_16LE || self.encoding == encoding_rs::UTF_16BE {
+            // encoding_rs only decodes UTF-16, so encode it by hand.
+            let little_endian = self.encoding == encoding_rs::UTF_16LE;
+            let mut bytes = Vec::with_capacity(bom.len() + text.len() * 2);
+            bytes.extend_from_slice(bom);
+            for unit in text.encode_utf16() {
+                if little_endian {
+                    bytes.extend_from_slice(&unit.to_le_bytes());
+                } else {
+                    bytes.extend_from_slice(&unit.to_be_bytes());
+                }
+            }
+            return Ok(Cow::Owned(bytes));
+        }
+
+        let (bytes, _, had_unmappable_characters) = self.encoding.encode(text);
+        if had_unmappable_characters {
+            let character = text
+                .chars()
+                .find(|c| {
+                    let mut buffer = [0; 4];
+                    self.encoding.encode(c.encode_utf8(&mut buffer)).2
+                })
+                .unwrap_or(char::REPLACEMENT_CHARACTER);
+            anyhow::bail!(
+                "{character:?} cannot be represented in {}",
+                self.encoding.name()
+            );
+        }
+        Ok(bytes)
+    }
+
+    /// Returns a decoder for file contents that are read in chunks, such as large files that
+    /// are streamed from disk rather than loaded all at once.
+    pub fn streaming_decoder(&self) -> StreamingDecoder {
+        StreamingDecoder {
+            encoding: *self,
+            decoder: self.encoding.new_decoder_without_bom_handling(),
+            bom: self.bom_bytes().filter(|_| self.bom),
+            start: Vec::new(),
+        }
+    }
+
+    fn bom_bytes(&self) -> Option<&'static [u8]> {
+        if self.encoding == encoding_rs::UTF_16LE {
+            Some(UTF_16LE_BOM)
+        } else if self.encoding == encoding_rs::UTF_16BE {
+            Some(UTF_16BE_BOM)
+        } else {
+            None
+        }
+    }
+}
+
+/// Incrementally decodes file contents in a given [`Encoding`].
+pub struct StreamingDecoder {
+    encoding: Encoding,
+    decoder: encoding_rs::Decoder,
+    /// The byte order mark that's yet to be stripped from the start of the contents.
+    bom: Option<&'static [u8]>,
+    /// The first bytes of the contents, held back until it's known whether they're a BOM.
+    start: Vec<u8>,
+}
+
+impl StreamingDecoder {
+    /// Decodes the next chunk of the contents and appends it to `output`. Characters split
+    /// across chunks are decoded once the rest of their bytes arrive, so `last` must be set
+    /// for the final chunk.
+    pub fn decode_chunk(&mut self, bytes: &[u8], last: bool, output: &mut String) -> Result<()> {
+        let Some(bom) = self.bom else {
+            return self.decode(bytes, last, output);
+        };
+
+        self.start.extend_from_slice(bytes);
+        if !last && self.start.len() < bom.len() && bom.starts_with(&self.start) {
+            return Ok(());
+        }
+        self.bom = None;
+        let start = std::mem::take(&mut self.start);
+        self.decode(start.strip_prefix(bom).unwrap_or(&start), last, output)
+    }
+
+    fn decode(&mut self, mut bytes: &[u8], last: bool, output: &mut String) -> Result<()> {
+        loop {
+            let additional = self
+                .decoder
+                .max_utf8_buffer_length_without_replacement(bytes.len())
+                .unwrap_or(bytes.len());
+            output.reserve(additional);
+            let (result, read) = self
+                .decoder
+                .decode_to_string_without_replacement(bytes, output, last);
+            bytes = &bytes[read..];
+            match result {
+                encoding_rs::DecoderResult::InputEmpty => return Ok(()),
+                encoding_rs::DecoderResult::OutputFull => continue,
+                encoding_rs::DecoderResult::Malformed(..) => {
+                    anyhow::bail!(
+                        "file contents are not valid {}",
+                        self.encoding.encoding.name()
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// UTF-16 text without a byte order mark is recognized by mostly-ASCII contents having a
+/// zero byte in every other position.
+fn detect_utf_16_without_bom(bytes: &[u8]) -> Option<Encoding> {
+    if bytes.len() < 4 || bytes.len() % 2 != 0 {
+        return None;
+    }
+
+    let sample = &bytes[..bytes.len().min(DETECTION_SAMPLE_LEN)];
+    let pairs = sample.len() / 2;
+
+    let (mut even_zeros, mut odd_zeros) = (0, 0);
+    for pair in sample.chunks_exact(2) {
+        even_zeros += (pair[0] == 0) as usize;
+        odd_zeros += (pair[1] == 0) as usize;
+    }
+
+    let is_mostly = |zeros: usize| zeros * 10 >= pairs * 7;
+    let is_rare = |zeros: usize| zeros * 20 <= pairs;
+    if is_mostly(odd_zeros) && is_rare(even_zeros) {
+        Some(Encoding::new(&encoding_rs::UTF_16LE_INIT))
+    } else if is_mostly(even_zeros) && is_rare(odd_zeros) {
+        Some(Encoding::new(&encoding_rs::UTF_16BE_INIT))
+    } else {
+        None
+    }
+}
+
+/// Single-byte encodings such as Windows-1252 accept almost any input, so Shift_JIS is only
+/// preferred when the contents decode cleanly and every non-ASCII character is Japanese.
+fn looks_like_shift_jis(bytes: &[u8]) -> bool {
+    let Some(text) =
+        encoding_rs::SHIFT_JIS.decode_without_bom_handling_and_without_replacement(bytes)
+    else {
+        return false;
+    };
+
+    let mut non_ascii = text.chars().filter(|c| !c.is_ascii()).peekable();
+    non_ascii.peek().is_some()
+        && non_ascii.all(|c| {
+            matches!(c,
+                '\u{3000}'..='\u{30FF}' // CJK punctuation, hiragana and katakana
+                | '\u{4E00}'..='\u{9FFF}' // CJK unified ideographs
+                | '\u{FF00}'..='\u{FFEF}' // Halfwidth and fullwidth forms
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_encoding() {
+        assert_eq!(Encoding::detect(b"plain ascii"), Some(Encoding::UTF_8));
+        assert_eq!(Encoding::detect("héllo".as_bytes()), Some(Encoding::UTF_8));
+        assert_eq!(
+            Encoding::detect(b"\xEF\xBB\xBFwith bom"),
+            Some(Encoding::UTF_8)
+        );
+        assert_eq!(
+            Encoding::detect(b"\xFF\xFEh\0i\0"),
+            Some(Encoding::UTF_16LE)
+        );
+        assert_eq!(
+            Encoding::detect(b"\xFE\xFF\0h\0i"),
+            Some(Encoding::UTF_16BE)
+        );
+        assert_eq!(
+            Encoding::detect(b"h\0e\0l\0l\0o\0"),
+            Some(Encoding::new(&encoding_rs::UTF_16LE_INIT))
+        );
+        assert_eq!(
+            Encoding::detect(b"caf\xE9 cr\xE8me"),
+            Some(Encoding::WINDOWS_1252)
+        );
+        assert_eq!(
+            Encoding::detect(b"// \x82\xB1\x82\xF1\x82\xC9\x82\xBF\x82\xCD"),
+            Some(Encoding::SHIFT_JIS)
+        );
+        assert_eq!(Encoding::detect(b"\x7FELF\x02\x01\x01\0\0\0\xE9"), None);
+        assert_eq!(
+            Encoding::detect(b"valid utf-8\0with a nul"),
+            Some(Encoding::UTF_8)
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for (encoding, text) in [
+            (Encoding::UTF_8, "\u{FEFF}fn main() {}\n"),
+            (Encoding::UTF_16LE, "let x = \"é\";\n"),
+            (Encoding::UTF_16BE, "let x = \"é\";\n"),
+            (Encoding::new(&encoding_rs::UTF_16LE_INIT), "no bom\n"),
+            (Encoding::WINDOWS_1252, "café crème\n"),
+            (Encoding::SHIFT_JIS, "// こんにちは\n"),
+        ] {
+            let bytes = encoding.encode(text).unwrap().into_owned();
+            assert_eq!(Encoding::detect(&bytes), Some(encoding), "{encoding}");
+            assert_eq!(encoding.decode(bytes).unwrap(), text, "{encoding}");
+        }
+    }
+
+    #[test]
+    fn test_streaming_decoder() {
+        for (encoding, text) in [
+            (Encoding::UTF_8, "fn main() { \"héllo\" }\n"),
+            (Encoding::UTF_16LE, "let x = \"é\";\n"),
+            (Encoding::UTF_16BE, "let x = \"é\";\n"),
+            (Encoding::SHIFT_JIS, "// こんにちは\n"),
+        ] {
+            let bytes = encoding.encode(text).unwrap().into_owned();
+            assert_eq!(
+                Encoding::detect_prefix(&bytes[..bytes.len() - 3]),
+                Some(encoding),
+                "{encoding}"
+            );
+
+            // Split every character across chunks.
+            let mut decoder = encoding.streaming_decoder();
+            let mut decoded = String::new();
+            for (ix, byte) in bytes.iter().enumerate() {
+                decoder
+                    .decode_chunk(&[*byte], ix == bytes.len() - 1, &mut decoded)
+                    .unwrap();
+            }
+            assert_eq!(decoded, text, "{encoding}");
+        }
+
+        let mut decoder = Encoding::UTF_8.streaming_decoder();
+        let mut decoded = String::new();
+        decoder
+            .decode_chunk(b"ok \xC3", false, &mut decoded)
+            .unwrap();
+        assert!(decoder.decode_chunk(b"", true, &mut decoded).is_err());
+    }
+
+    #[test]
+    fn test_unrepresentable_characters() {
+        assert!(Encoding::WINDOWS_1252.encode("ok").is_ok());
+        assert!(Encoding::WINDOWS_1252.encode("日本").is_err());
+        assert!(Encoding::SHIFT_JIS.decode(b"\x82".to_vec()).is_err());
+    }
+
+    #[test]
+    fn test_names() {
+        for encoding in Encoding::all() {
+            assert_eq!(Encoding::from_name(&encoding.name()), Some(*encoding));
+        }
+        assert_eq!(Encoding::from_name("latin1"), Some(Encoding::WINDOWS_1252));
+        assert_eq!(Encoding::from_name("nonsense"), None);
+    }
+}
@@ -1,3 +1,4 @@
+mod encoding;
 #[cfg(target_os = "macos")]
 mod mac_watcher;
 
@@ -36,6 +37,8 @@ use std::{
 use tempfile::TempDir;
 use text::LineEnding;
 
+pub use encoding::{Encoding, StreamingDecoder};
+
 #[cfg(any(test, feature = "test-support"))]
 mod fake_git_repo;
 #[cfg(any(test, feature = "test-support"))]
@@ -108,10 +111,40 @@ pub trait Fs: Send + Sync {
     async fn load(&self, path: &Path) -> Result<String> {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
+    /// Loads a text file, decoding it with the given encoding or, if none is given,
+    /// with the one detected from its contents.
+    async fn load_with_encoding(
+        &self,
+        path: &Path,
+        encoding: Option<Encoding>,
+    ) -> Result<(String, Encoding)> {
+        let bytes = self.load_bytes(path).await?;
+        let encoding = match encoding {
+            Some(encoding) => encoding,
+            None => Encoding::detect(&bytes)
+                .with_context(|| format!("{path:?} does not appear to be a text file"))?,
+        };
+        let text = encoding
+            .decode(bytes)
+            .with_context(|| format!("decoding {path:?} as {encoding}"))?;
+        Ok((text, encoding))
+    }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
+    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+        self.save_with_encoding(path, text, line_ending, Encoding::UTF_8)
+            .await
+    }
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()>;
     async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
+    /// Appends `content` to the file at `path`, creating the file if it doesn't exist.
+    async fn append(&self, path: &Path, content: &[u8]) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
     async fn is_dir(&self, path: &Path) -> bool;
@@ -596,11 +629,23 @@ impl Fs for RealFs {
         Ok(())
     }
 
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
         let buffer_size = text.summary().len.min(10 * 1024);
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
+        if !encoding.is_utf_8() {
+            let content = chunks(text, line_ending).collect::<String>();
+            let content = encoding.encode(&content)?;
+            smol::fs::write(path, content).await?;
+            return Ok(());
+        }
         let file = smol::fs::File::create(path).await?;
         let mut writer = smol::io::BufWriter::with_capacity(buffer_size, file);
         for chunk in chunks(text, line_ending) {
@@ -618,6 +663,20 @@ impl Fs for RealFs {
         Ok(())
     }
 
+    async fn append(&self, path: &Path, content: &[u8]) -> Result<()> {
+        let path = path.to_path_buf();
+        let content = content.to_vec();
+        smol::unblock(move || {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?
+                .write_all(&content)
+        })
+        .await?;
+        Ok(())
+    }
+
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
         Ok(smol::fs::canonicalize(path)
             .await
@@ -2275,14 +2334,21 @@ impl Fs for FakeFs {
         Ok(())
     }
 
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path);
         let content = chunks(text, line_ending).collect::<String>();
+        let content = encoding.encode(&content)?.into_owned();
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
-        self.write_file_internal(path, content.into_bytes(), false)?;
+        self.write_file_internal(path, content, false)?;
         Ok(())
     }
 
@@ -2296,6 +2362,19 @@ impl Fs for FakeFs {
         Ok(())
     }
 
+    async fn append(&self, path: &Path, content: &[u8]) -> Result<()> {
+        self.simulate_random_delay().await;
+        let path = normalize_path(path);
+        let mut new_content = if self.is_file(&path).await {
+            self.load_internal(&path).await?
+        } else {
+            Vec::new()
+        };
+        new_content.extend_from_slice(content);
+        self.write_file_internal(path, new_content, false)?;
+        Ok(())
+    }
+
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
         let path = normalize_path(path);
         self.simulate_random_delay().await;
@@ -2730,6 +2809,19 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_append(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor.clone());
+        fs.insert_tree(path!("/root"), json!({ "a": "A" })).await;
+
+        fs.append(path!("/root/a").as_ref(), b"B").await.unwrap();
+        assert_eq!(fs.load(path!("/root/a").as_ref()).await.unwrap(), "AB");
+
+        fs.append(path!("/root/b").as_ref(), b"B").await.unwrap();
+        fs.append(path!("/root/b").as_ref(), b"C").await.unwrap();
+        assert_eq!(fs.load(path!("/root/b").as_ref()).await.unwrap(), "BC");
+    }
+
     #[gpui::test]
     async fn test_copy_recursive_with_single_file(executor: BackgroundExecutor) {
         let fs = FakeFs::new(executor.clone());
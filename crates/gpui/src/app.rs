@@ -233,6 +233,8 @@ type Handler = Box<dyn FnMut(&mut App) -> bool + 'static>;
 type Listener = Box<dyn FnMut(&dyn Any, &mut App) -> bool + 'static>;
 pub(crate) type KeystrokeObserver =
     Box<dyn FnMut(&KeystrokeEvent, &mut Window, &mut App) -> bool + 'static>;
+pub(crate) type ActionObserver =
+    Box<dyn FnMut(&dyn Action, &mut Window, &mut App) -> bool + 'static>;
 type QuitHandler = Box<dyn FnOnce(&mut App) -> LocalBoxFuture<'static, ()> + 'static>;
 type WindowClosedHandler = Box<dyn FnMut(&mut App)>;
 type ReleaseListener = Box<dyn FnOnce(&mut dyn Any, &mut App) + 'static>;
@@ -572,6 +574,7 @@ pub struct App {
     pub(crate) event_listeners: SubscriberSet<EntityId, (TypeId, Listener)>,
     pub(crate) keystroke_observers: SubscriberSet<(), KeystrokeObserver>,
     pub(crate) keystroke_interceptors: SubscriberSet<(), KeystrokeObserver>,
+    pub(crate) action_observers: SubscriberSet<(), ActionObserver>,
     pub(crate) keyboard_layout_observers: SubscriberSet<(), Handler>,
     pub(crate) release_listeners: SubscriberSet<EntityId, ReleaseListener>,
     pub(crate) global_observers: SubscriberSet<TypeId, Handler>,
@@ -649,6 +652,7 @@ impl App {
                 release_listeners: SubscriberSet::new(),
                 keystroke_observers: SubscriberSet::new(),
                 keystroke_interceptors: SubscriberSet::new(),
+                action_observers: SubscriberSet::new(),
                 keyboard_layout_observers: SubscriberSet::new(),
                 global_observers: SubscriberSet::new(),
                 quit_observers: SubscriberSet::new(),
@@ -1674,6 +1678,24 @@ impl App {
         )
     }
 
+    /// Register a callback to be invoked after an action is dispatched in any window, whether it
+    /// was bound to a keystroke or dispatched with [`Window::dispatch_action`], as the command
+    /// palette and menus do.
+    pub fn observe_actions(
+        &mut self,
+        mut f: impl FnMut(&dyn Action, &mut Window, &mut App) + 'static,
+    ) -> Subscription {
+        let (subscription, activate) = self.action_observers.insert(
+            (),
+            Box::new(move |action, window, cx| {
+                f(action, window, cx);
+                true
+            }),
+        );
+        activate();
+        subscription
+    }
+
     /// Register key bindings.
     pub fn bind_keys(&mut self, bindings: impl IntoIterator<Item = KeyBinding>) {
         self.keymap.borrow_mut().add_bindings(bindings);
@@ -3983,6 +3983,18 @@ impl Window {
         node_id: DispatchNodeId,
         action: &dyn Action,
         cx: &mut App,
+    ) {
+        self.dispatch_action_listeners(node_id, action, cx);
+        cx.action_observers
+            .clone()
+            .retain(&(), |callback| callback(action, self, cx));
+    }
+
+    fn dispatch_action_listeners(
+        &mut self,
+        node_id: DispatchNodeId,
+        action: &dyn Action,
+        cx: &mut App,
     ) {
         let dispatch_path = self.rendered_frame.dispatch_tree.dispatch_path(node_id);
 
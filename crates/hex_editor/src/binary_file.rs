@@ -0,0 +1,429 @@
+use std::{ffi::OsStr, ops::Range, path::Path, sync::Arc};
+
+use anyhow::{Context as _, Result, anyhow};
+use fs::{Fs, MTime};
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Subscription, Task};
+use project::{Project, ProjectEntryId, ProjectItem, ProjectPath};
+use util::ResultExt as _;
+
+/// Extensions of files that are opened in the hex editor instead of as text.
+const BINARY_EXTENSIONS: &[&str] = &[
+    "a", "bin", "class", "dat", "dll", "dylib", "elf", "exe", "ko", "lib", "o", "obj", "pyc", "so",
+    "wasm",
+];
+
+pub fn is_binary_file(path: &ProjectPath) -> bool {
+    path.path
+        .extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|extension| {
+            BINARY_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+        })
+}
+
+pub enum BinaryFileEvent {
+    Edited,
+    Saved,
+    Reloaded,
+    Conflicted,
+}
+
+/// The contents of a file on disk, edited in place by overwriting bytes.
+pub struct BinaryFile {
+    project_path: ProjectPath,
+    entry_id: Option<ProjectEntryId>,
+    abs_path: Arc<Path>,
+    fs: Arc<dyn Fs>,
+    bytes: Vec<u8>,
+    undo_stack: Vec<ByteEdit>,
+    redo_stack: Vec<ByteEdit>,
+    /// The depth of the undo stack when the file was last loaded or saved, or `None` if that
+    /// state can no longer be reached by undoing or redoing.
+    saved_depth: Option<usize>,
+    /// The modification time of the file when it was last loaded or saved.
+    mtime: Option<MTime>,
+    /// Whether the file changed on disk while it had unsaved edits.
+    has_conflict: bool,
+    reload_task: Option<Task<()>>,
+    _project_subscription: Subscription,
+}
+
+struct ByteEdit {
+    offset: usize,
+    old_bytes: Vec<u8>,
+    new_bytes: Vec<u8>,
+}
+
+impl EventEmitter<BinaryFileEvent> for BinaryFile {}
+
+impl BinaryFile {
+    pub fn open(
+        project: &Entity<Project>,
+        project_path: ProjectPath,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let project = project.read(cx);
+        let fs = project.fs().clone();
+        let entry_id = project
+            .entry_for_path(&project_path, cx)
+            .map(|entry| entry.id);
+        let Some(abs_path) = project.absolute_path(&project_path, cx) else {
+            return Task::ready(Err(anyhow!("no absolute path for {project_path:?}")));
+        };
+        let project = project.clone();
+        cx.spawn(async move |cx| {
+            let (bytes, mtime) = load(fs.as_ref(), &abs_path).await?;
+            cx.new(|cx| Self {
+                _project_subscription: cx.subscribe(&project, Self::handle_project_event),
+                project_path,
+                entry_id,
+                abs_path: abs_path.into(),
+                fs,
+                bytes,
+                undo_stack: Vec::new(),
+                redo_stack: Vec::new(),
+                saved_depth: Some(0),
+                mtime,
+                has_conflict: false,
+                reload_task: None,
+            })
+        })
+    }
+
+    fn handle_project_event(
+        &mut self,
+        _: Entity<Project>,
+        event: &project::Event,
+        cx: &mut Context<Self>,
+    ) {
+        if let project::Event::WorktreeUpdatedEntries(worktree_id, changes) = event
+            && *worktree_id == self.project_path.worktree_id
+            && changes
+                .iter()
+                .any(|(path, _, _)| *path == self.project_path.path)
+        {
+            self.file_changed_on_disk(cx);
+        }
+    }
+
+    /// Reloads the file if it has no unsaved edits, and marks it as conflicted otherwise.
+    fn file_changed_on_disk(&mut self, cx: &mut Context<Self>) {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        self.reload_task = Some(cx.spawn(async move |this, cx| {
+            let Some(metadata) = fs.metadata(&abs_path).await.log_err().flatten() else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                if this.mtime == Some(metadata.mtime) {
+                    return;
+                }
+                if this.is_dirty() {
+                    this.has_conflict = true;
+                    cx.emit(BinaryFileEvent::Conflicted);
+                    cx.notify();
+                } else {
+                    this.reload(cx).detach_and_log_err(cx);
+                }
+            })
+            .ok();
+        }));
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn abs_path(&self) -> &Arc<Path> {
+        &self.abs_path
+    }
+
+    pub fn file_name(&self) -> &OsStr {
+        self.abs_path
+            .file_name()
+            .unwrap_or(self.abs_path.as_os_str())
+    }
+
+    /// Overwrites the bytes starting at `offset` with `new_bytes`, which are truncated so that
+    /// the file never grows.
+    pub fn overwrite(&mut self, offset: usize, new_bytes: &[u8], cx: &mut Context<Self>) {
+        let end = offset.saturating_add(new_bytes.len()).min(self.bytes.len());
+        if offset >= end {
+            return;
+        }
+        let new_bytes = &new_bytes[..end - offset];
+        if self.bytes[offset..end] == *new_bytes {
+            return;
+        }
+
+        let old_bytes = self.bytes[offset..end].to_vec();
+        self.bytes[offset..end].copy_from_slice(new_bytes);
+        if self
+            .saved_depth
+            .is_some_and(|depth| depth > self.undo_stack.len())
+        {
+            self.saved_depth = None;
+        }
+        self.undo_stack.push(ByteEdit {
+            offset,
+            old_bytes,
+            new_bytes: new_bytes.to_vec(),
+        });
+        self.redo_stack.clear();
+        cx.emit(BinaryFileEvent::Edited);
+        cx.notify();
+    }
+
+    /// Reverts the most recent edit, returning the range of bytes it restored.
+    pub fn undo(&mut self, cx: &mut Context<Self>) -> Option<Range<usize>> {
+        let edit = self.undo_stack.pop()?;
+        let range = edit.offset..edit.offset + edit.old_bytes.len();
+        self.bytes[range.clone()].copy_from_slice(&edit.old_bytes);
+        self.redo_stack.push(edit);
+        cx.emit(BinaryFileEvent::Edited);
+        cx.notify();
+        Some(range)
+    }
+
+    /// Reapplies the most recently undone edit, returning the range of bytes it overwrote.
+    pub fn redo(&mut self, cx: &mut Context<Self>) -> Option<Range<usize>> {
+        let edit = self.redo_stack.pop()?;
+        let range = edit.offset..edit.offset + edit.new_bytes.len();
+        self.bytes[range.clone()].copy_from_slice(&edit.new_bytes);
+        self.undo_stack.push(edit);
+        cx.emit(BinaryFileEvent::Edited);
+        cx.notify();
+        Some(range)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.saved_depth != Some(self.undo_stack.len())
+    }
+
+    pub fn has_conflict(&self) -> bool {
+        self.has_conflict
+    }
+
+    pub fn save(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        let bytes = self.bytes.clone();
+        let depth = self.undo_stack.len();
+        cx.spawn(async move |this, cx| {
+            fs.write(&abs_path, &bytes)
+                .await
+                .with_context(|| format!("saving {abs_path:?}"))?;
+            let mtime = fs.metadata(&abs_path).await?.map(|metadata| metadata.mtime);
+            this.update(cx, |this, cx| {
+                this.saved_depth = Some(depth);
+                this.mtime = mtime;
+                this.has_conflict = false;
+                cx.emit(BinaryFileEvent::Saved);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Replaces the contents with those on disk, discarding any edits and their history.
+    pub fn reload(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        cx.spawn(async move |this, cx| {
+            let (bytes, mtime) = load(fs.as_ref(), &abs_path).await?;
+            this.update(cx, |this, cx| {
+                this.bytes = bytes;
+                this.mtime = mtime;
+                this.undo_stack.clear();
+                this.redo_stack.clear();
+                this.saved_depth = Some(0);
+                this.has_conflict = false;
+                cx.emit(BinaryFileEvent::Reloaded);
+                cx.notify();
+            })
+        })
+    }
+}
+
+async fn load(fs: &dyn Fs, abs_path: &Path) -> Result<(Vec<u8>, Option<MTime>)> {
+    let bytes = fs
+        .load_bytes(abs_path)
+        .await
+        .with_context(|| format!("loading {abs_path:?}"))?;
+    let mtime = fs.metadata(abs_path).await?.map(|metadata| metadata.mtime);
+    Ok((bytes, mtime))
+}
+
+impl ProjectItem for BinaryFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        if project.read(cx).is_local() && is_binary_file(path) {
+            Some(Self::open(project, path.clone(), cx))
+        } else {
+            None
+        }
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        BinaryFile::is_dirty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use settings::SettingsStore;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            Project::init_settings(cx);
+        });
+    }
+
+    async fn open_file(
+        contents: &[u8],
+        cx: &mut TestAppContext,
+    ) -> (Arc<FakeFs>, Entity<BinaryFile>) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), serde_json::json!({})).await;
+        fs.insert_file(path!("/dir/data.bin"), contents.to_vec())
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let project_path = ProjectPath {
+            worktree_id,
+            path: Path::new("data.bin").into(),
+        };
+        let file = cx
+            .update(|cx| BinaryFile::open(&project, project_path, cx))
+            .await
+            .unwrap();
+        (fs, file)
+    }
+
+    #[gpui::test]
+    async fn test_overwrite_undo_and_redo(cx: &mut TestAppContext) {
+        let (_, file) = open_file(&[0, 1, 2, 3], cx).await;
+
+        file.update(cx, |file, cx| {
+            file.overwrite(1, &[9, 9], cx);
+            assert_eq!(file.bytes(), [0, 9, 9, 3]);
+            assert!(file.is_dirty());
+
+            // Edits never grow the file, and no-op edits aren't recorded.
+            file.overwrite(3, &[7, 7, 7], cx);
+            assert_eq!(file.bytes(), [0, 9, 9, 7]);
+            file.overwrite(1, &[9], cx);
+            file.overwrite(4, &[5], cx);
+            assert_eq!(file.bytes(), [0, 9, 9, 7]);
+
+            assert_eq!(file.undo(cx), Some(3..4));
+            assert_eq!(file.bytes(), [0, 9, 9, 3]);
+            assert_eq!(file.undo(cx), Some(1..3));
+            assert_eq!(file.bytes(), [0, 1, 2, 3]);
+            assert!(!file.is_dirty());
+            assert_eq!(file.undo(cx), None);
+
+            assert_eq!(file.redo(cx), Some(1..3));
+            assert_eq!(file.redo(cx), Some(3..4));
+            assert_eq!(file.bytes(), [0, 9, 9, 7]);
+            assert_eq!(file.redo(cx), None);
+
+            // A new edit discards the edits that could be redone.
+            file.undo(cx);
+            file.overwrite(0, &[8], cx);
+            assert_eq!(file.bytes(), [8, 9, 9, 3]);
+            assert_eq!(file.redo(cx), None);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_save(cx: &mut TestAppContext) {
+        let (fs, file) = open_file(&[0, 1, 2, 3], cx).await;
+
+        file.update(cx, |file, cx| file.overwrite(0, &[4, 5], cx));
+        file.update(cx, |file, cx| file.save(cx)).await.unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            fs.load_bytes(path!("/dir/data.bin").as_ref())
+                .await
+                .unwrap(),
+            [4, 5, 2, 3]
+        );
+
+        file.update(cx, |file, cx| {
+            assert!(!file.is_dirty());
+            assert!(!file.has_conflict());
+
+            // Undoing and redoing back to the saved state makes the file clean again.
+            file.undo(cx);
+            assert!(file.is_dirty());
+            file.redo(cx);
+            assert!(!file.is_dirty());
+
+            // Once a different edit replaces the saved one, the saved state can't be reached.
+            file.undo(cx);
+            file.overwrite(0, &[6, 7], cx);
+            assert!(file.is_dirty());
+            file.undo(cx);
+            assert!(file.is_dirty());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_changes_on_disk(cx: &mut TestAppContext) {
+        let (fs, file) = open_file(&[0, 1, 2, 3], cx).await;
+        let abs_path = Path::new(path!("/dir/data.bin"));
+
+        // Files without edits are reloaded.
+        fs.insert_file(abs_path, vec![4, 5, 6]).await;
+        cx.run_until_parked();
+        file.read_with(cx, |file, _| {
+            assert_eq!(file.bytes(), [4, 5, 6]);
+            assert!(!file.is_dirty());
+            assert!(!file.has_conflict());
+        });
+
+        // Files with edits are marked as conflicted instead.
+        file.update(cx, |file, cx| file.overwrite(0, &[7], cx));
+        fs.insert_file(abs_path, vec![8, 9]).await;
+        cx.run_until_parked();
+        file.read_with(cx, |file, _| {
+            assert_eq!(file.bytes(), [7, 5, 6]);
+            assert!(file.has_conflict());
+        });
+
+        file.update(cx, |file, cx| file.reload(cx)).await.unwrap();
+        file.read_with(cx, |file, _| {
+            assert_eq!(file.bytes(), [8, 9]);
+            assert!(!file.is_dirty());
+            assert!(!file.has_conflict());
+        });
+    }
+}
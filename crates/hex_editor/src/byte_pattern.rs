@@ -0,0 +1,137 @@
+use anyhow::{Context as _, Result};
+
+/// A sequence of bytes to search for, in which some bytes may be wildcards.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BytePattern {
+    bytes: Vec<Option<u8>>,
+}
+
+impl BytePattern {
+    /// Parses a pattern written either as hex bytes, such as `7f 45 4c 46` or `cafe??be`, where
+    /// `??` matches any byte, or as quoted text, such as `"PNG"`, which matches its UTF-8 bytes.
+    pub fn parse(query: &str) -> Result<Self> {
+        let query = query.trim();
+        let bytes = if let Some(text) = query.strip_prefix('"') {
+            let text = text.strip_suffix('"').unwrap_or(text);
+            text.bytes().map(Some).collect()
+        } else {
+            let mut bytes = Vec::new();
+            for word in query.split_whitespace() {
+                let digits = word.as_bytes();
+                anyhow::ensure!(
+                    digits.len() % 2 == 0,
+                    "{word:?} does not consist of two-digit hex bytes"
+                );
+                for pair in digits.chunks(2) {
+                    bytes.push(parse_hex_byte(pair)?);
+                }
+            }
+            bytes
+        };
+        anyhow::ensure!(!bytes.is_empty(), "pattern is empty");
+        Ok(Self { bytes })
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn matches_at(&self, haystack: &[u8], offset: usize) -> bool {
+        haystack
+            .get(offset..offset + self.len())
+            .is_some_and(|window| {
+                window
+                    .iter()
+                    .zip(&self.bytes)
+                    .all(|(byte, expected)| expected.is_none_or(|expected| expected == *byte))
+            })
+    }
+
+    /// Returns the offset of the first match at or after `offset`, wrapping around to the start
+    /// of `haystack` if there is none.
+    pub fn find_forward(&self, haystack: &[u8], offset: usize) -> Option<usize> {
+        let last_start = haystack.len().checked_sub(self.len())?;
+        let offset = offset.min(last_start + 1);
+        (offset..=last_start)
+            .chain(0..offset)
+            .find(|&start| self.matches_at(haystack, start))
+    }
+
+    /// Returns the offset of the last match before `offset`, wrapping around to the end of
+    /// `haystack` if there is none.
+    pub fn find_backward(&self, haystack: &[u8], offset: usize) -> Option<usize> {
+        let last_start = haystack.len().checked_sub(self.len())?;
+        let offset = offset.min(last_start + 1);
+        (0..offset)
+            .rev()
+            .chain((offset..=last_start).rev())
+            .find(|&start| self.matches_at(haystack, start))
+    }
+}
+
+fn parse_hex_byte(digits: &[u8]) -> Result<Option<u8>> {
+    if digits == b"??" {
+        return Ok(None);
+    }
+    let text = std::str::from_utf8(digits).context("invalid hex byte")?;
+    u8::from_str_radix(text, 16)
+        .map(Some)
+        .with_context(|| format!("{text:?} is not a hex byte"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            BytePattern::parse("7f 45 4C46").unwrap().bytes,
+            [Some(0x7f), Some(0x45), Some(0x4c), Some(0x46)]
+        );
+        assert_eq!(
+            BytePattern::parse("ca ?? be").unwrap().bytes,
+            [Some(0xca), None, Some(0xbe)]
+        );
+        assert_eq!(
+            BytePattern::parse("\"PK\"").unwrap().bytes,
+            [Some(b'P'), Some(b'K')]
+        );
+        assert_eq!(
+            BytePattern::parse("\"a b").unwrap().bytes,
+            [Some(b'a'), Some(b' '), Some(b'b')]
+        );
+        assert!(BytePattern::parse("").is_err());
+        assert!(BytePattern::parse("\"\"").is_err());
+        assert!(BytePattern::parse("7f 4").is_err());
+        assert!(BytePattern::parse("zz").is_err());
+        assert!(BytePattern::parse("é1").is_err());
+    }
+
+    #[test]
+    fn test_find() {
+        let haystack = b"\x00\x01\x02\x00\x01\x03";
+        let pattern = BytePattern::parse("00 01").unwrap();
+        assert_eq!(pattern.find_forward(haystack, 0), Some(0));
+        assert_eq!(pattern.find_forward(haystack, 1), Some(3));
+        assert_eq!(pattern.find_forward(haystack, 4), Some(0));
+        assert_eq!(pattern.find_backward(haystack, 3), Some(0));
+        assert_eq!(pattern.find_backward(haystack, 0), Some(3));
+        assert_eq!(pattern.find_backward(haystack, 100), Some(3));
+
+        let pattern = BytePattern::parse("01 ??").unwrap();
+        assert_eq!(pattern.find_forward(haystack, 2), Some(4));
+        assert_eq!(pattern.find_forward(haystack, 5), Some(1));
+
+        let pattern = BytePattern::parse("04").unwrap();
+        assert_eq!(pattern.find_forward(haystack, 0), None);
+        assert_eq!(pattern.find_backward(haystack, 0), None);
+
+        let pattern = BytePattern::parse("00 01 02 00 01 03 04").unwrap();
+        assert_eq!(pattern.find_forward(haystack, 0), None);
+    }
+}
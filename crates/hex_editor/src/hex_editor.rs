@@ -0,0 +1,752 @@
+mod binary_file;
+mod byte_pattern;
+mod hex_editor_prompt;
+
+use std::ops::Range;
+
+use anyhow::Result;
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, Hsla, KeyDownEvent,
+    MouseButton, MouseDownEvent, Pixels, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, Window, actions, uniform_list,
+};
+use project::{Project, ProjectItem as _, ProjectPath};
+use settings::Settings as _;
+use theme::ThemeSettings;
+use ui::prelude::*;
+use util::paths::PathExt as _;
+use workspace::{
+    ItemSettings, Pane, Workspace,
+    invalid_buffer_view::InvalidBufferView,
+    item::{Item, ItemEvent, ProjectItem, SaveOptions},
+};
+use zed_actions::hex_editor::OpenActiveFile;
+
+pub use crate::binary_file::*;
+pub use crate::byte_pattern::*;
+use crate::hex_editor_prompt::{HexEditorPrompt, PromptKind};
+
+actions!(
+    hex_editor,
+    [
+        /// Moves the cursor to the previous byte.
+        MoveLeft,
+        /// Moves the cursor to the next byte.
+        MoveRight,
+        /// Moves the cursor up one row.
+        MoveUp,
+        /// Moves the cursor down one row.
+        MoveDown,
+        /// Moves the cursor up one page.
+        PageUp,
+        /// Moves the cursor down one page.
+        PageDown,
+        /// Moves the cursor to the first byte of its row.
+        MoveToBeginningOfLine,
+        /// Moves the cursor to the last byte of its row.
+        MoveToEndOfLine,
+        /// Moves the cursor to the first byte of the file.
+        MoveToBeginning,
+        /// Moves the cursor to the last byte of the file.
+        MoveToEnd,
+        /// Switches the cursor between the hex and text columns.
+        SwitchColumn,
+        /// Prompts for an offset and moves the cursor to it.
+        GoToOffset,
+        /// Prompts for a byte pattern and moves the cursor to its next occurrence.
+        Find,
+        /// Moves the cursor to the next occurrence of the last byte pattern searched for.
+        FindNext,
+        /// Moves the cursor to the previous occurrence of the last byte pattern searched for.
+        FindPrevious,
+        /// Undoes the last edit.
+        Undo,
+        /// Redoes the last undone edit.
+        Redo,
+    ]
+);
+
+const BYTES_PER_ROW: usize = 16;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Hex,
+    Text,
+}
+
+struct Search {
+    pattern: BytePattern,
+    query: SharedString,
+}
+
+/// Shows the bytes of a [`BinaryFile`] as offset, hex and text columns, and edits them by
+/// overwriting.
+pub struct HexEditor {
+    file: Entity<BinaryFile>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    cursor: usize,
+    column: Column,
+    /// Whether the next hex digit typed replaces the low nibble of the byte under the cursor,
+    /// rather than the high one.
+    low_nibble: bool,
+    search: Option<Search>,
+    search_match: Option<Range<usize>>,
+    status: Option<SharedString>,
+    _file_subscription: Subscription,
+}
+
+pub enum HexEditorEvent {
+    Edited,
+    Saved,
+    Reloaded,
+    Conflicted,
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+
+impl HexEditor {
+    pub fn new(
+        file: Entity<BinaryFile>,
+        project: Entity<Project>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let file_subscription = cx.subscribe(&file, |this, _, event: &BinaryFileEvent, cx| {
+            match event {
+                BinaryFileEvent::Edited => cx.emit(HexEditorEvent::Edited),
+                BinaryFileEvent::Saved => cx.emit(HexEditorEvent::Saved),
+                BinaryFileEvent::Reloaded => {
+                    this.search_match = None;
+                    this.set_cursor(this.cursor, cx);
+                    cx.emit(HexEditorEvent::Reloaded);
+                }
+                BinaryFileEvent::Conflicted => cx.emit(HexEditorEvent::Conflicted),
+            }
+            cx.notify();
+        });
+        Self {
+            file,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            cursor: 0,
+            column: Column::Hex,
+            low_nibble: false,
+            search: None,
+            search_match: None,
+            status: None,
+            _file_subscription: file_subscription,
+        }
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub(crate) fn go_to_offset(&mut self, offset: usize, cx: &mut Context<Self>) {
+        self.set_cursor(offset, cx);
+    }
+
+    pub(crate) fn search(
+        &mut self,
+        pattern: BytePattern,
+        query: SharedString,
+        cx: &mut Context<Self>,
+    ) {
+        self.search = Some(Search { pattern, query });
+        self.select_match(self.cursor, true, cx);
+    }
+
+    fn set_cursor(&mut self, offset: usize, cx: &mut Context<Self>) {
+        let len = self.file.read(cx).len();
+        self.cursor = offset.min(len.saturating_sub(1));
+        self.low_nibble = false;
+        self.status = None;
+        self.scroll_handle
+            .scroll_to_item(self.cursor / BYTES_PER_ROW, ScrollStrategy::Top);
+        cx.notify();
+    }
+
+    fn rows_per_page(&self) -> usize {
+        let state = self.scroll_handle.0.borrow();
+        state
+            .last_item_size
+            .filter(|size| size.item.height > Pixels::ZERO)
+            .map_or(1, |size| {
+                (state.base_handle.bounds().size.height / size.item.height) as usize
+            })
+            .max(1)
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(self.cursor.saturating_sub(1), cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(self.cursor + 1, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(offset) = self.cursor.checked_sub(BYTES_PER_ROW) {
+            self.set_cursor(offset, cx);
+        }
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.cursor + BYTES_PER_ROW;
+        if offset < self.file.read(cx).len() {
+            self.set_cursor(offset, cx);
+        }
+    }
+
+    fn page_up(&mut self, _: &PageUp, _: &mut Window, cx: &mut Context<Self>) {
+        let distance = self.rows_per_page() * BYTES_PER_ROW;
+        let offset = self
+            .cursor
+            .checked_sub(distance)
+            .unwrap_or(self.cursor % BYTES_PER_ROW);
+        self.set_cursor(offset, cx);
+    }
+
+    fn page_down(&mut self, _: &PageDown, _: &mut Window, cx: &mut Context<Self>) {
+        let distance = self.rows_per_page() * BYTES_PER_ROW;
+        self.set_cursor(self.cursor + distance, cx);
+    }
+
+    fn move_to_beginning_of_line(
+        &mut self,
+        _: &MoveToBeginningOfLine,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_cursor(self.cursor - self.cursor % BYTES_PER_ROW, cx);
+    }
+
+    fn move_to_end_of_line(&mut self, _: &MoveToEndOfLine, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(
+            self.cursor - self.cursor % BYTES_PER_ROW + BYTES_PER_ROW - 1,
+            cx,
+        );
+    }
+
+    fn move_to_beginning(&mut self, _: &MoveToBeginning, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(0, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(usize::MAX, cx);
+    }
+
+    fn switch_column(&mut self, _: &SwitchColumn, _: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Text,
+            Column::Text => Column::Hex,
+        };
+        self.low_nibble = false;
+        cx.notify();
+    }
+
+    fn go_to_offset_prompt(&mut self, _: &GoToOffset, window: &mut Window, cx: &mut Context<Self>) {
+        self.toggle_prompt(PromptKind::GoToOffset, None, window, cx);
+    }
+
+    fn find(&mut self, _: &Find, window: &mut Window, cx: &mut Context<Self>) {
+        let query = self.search.as_ref().map(|search| search.query.clone());
+        self.toggle_prompt(PromptKind::Find, query.as_deref(), window, cx);
+    }
+
+    fn find_next(&mut self, _: &FindNext, window: &mut Window, cx: &mut Context<Self>) {
+        if self.search.is_some() {
+            self.select_match(self.cursor + 1, true, cx);
+        } else {
+            self.toggle_prompt(PromptKind::Find, None, window, cx);
+        }
+    }
+
+    fn find_previous(&mut self, _: &FindPrevious, window: &mut Window, cx: &mut Context<Self>) {
+        if self.search.is_some() {
+            self.select_match(self.cursor, false, cx);
+        } else {
+            self.toggle_prompt(PromptKind::Find, None, window, cx);
+        }
+    }
+
+    fn toggle_prompt(
+        &mut self,
+        kind: PromptKind,
+        initial_query: Option<&str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = window.root::<Workspace>().flatten() else {
+            return;
+        };
+        let hex_editor = cx.entity().downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                HexEditorPrompt::new(hex_editor, kind, initial_query, window, cx)
+            });
+        });
+    }
+
+    /// Moves the cursor to the nearest match of the current search, starting at `offset`.
+    fn select_match(&mut self, offset: usize, forward: bool, cx: &mut Context<Self>) {
+        let Some(search) = self.search.as_ref() else {
+            return;
+        };
+        let bytes = self.file.read(cx).bytes();
+        let start = if forward {
+            search.pattern.find_forward(bytes, offset)
+        } else {
+            search.pattern.find_backward(bytes, offset)
+        };
+        let len = search.pattern.len();
+        match start {
+            Some(start) => {
+                self.set_cursor(start, cx);
+                self.search_match = Some(start..start + len);
+            }
+            None => {
+                self.search_match = None;
+                self.status = Some(format!("No matches for {}", search.query).into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn undo(&mut self, _: &Undo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(range) = self.file.update(cx, |file, cx| file.undo(cx)) {
+            self.set_cursor(range.start, cx);
+        }
+    }
+
+    fn redo(&mut self, _: &Redo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(range) = self.file.update(cx, |file, cx| file.redo(cx)) {
+            self.set_cursor(range.start, cx);
+        }
+    }
+
+    fn handle_key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let modifiers = &event.keystroke.modifiers;
+        if modifiers.control || modifiers.alt || modifiers.platform || modifiers.function {
+            return;
+        }
+        let Some(key_char) = event.keystroke.key_char.as_deref() else {
+            return;
+        };
+        let mut chars = key_char.chars();
+        let (Some(character), None) = (chars.next(), chars.next()) else {
+            return;
+        };
+        let Some(&byte) = self.file.read(cx).bytes().get(self.cursor) else {
+            return;
+        };
+
+        let (new_byte, advance) = match self.column {
+            Column::Hex => {
+                let Some(digit) = character.to_digit(16).map(|digit| digit as u8) else {
+                    return;
+                };
+                if self.low_nibble {
+                    ((byte & 0xf0) | digit, true)
+                } else {
+                    ((byte & 0x0f) | (digit << 4), false)
+                }
+            }
+            Column::Text => {
+                if !(character.is_ascii_graphic() || character == ' ') {
+                    return;
+                }
+                (character as u8, true)
+            }
+        };
+        cx.stop_propagation();
+
+        let cursor = self.cursor;
+        self.file
+            .update(cx, |file, cx| file.overwrite(cursor, &[new_byte], cx));
+        if advance && cursor + 1 < self.file.read(cx).len() {
+            self.set_cursor(cursor + 1, cx);
+        } else {
+            self.low_nibble = !advance;
+            cx.notify();
+        }
+    }
+
+    fn click_byte(
+        &mut self,
+        offset: usize,
+        column: Column,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.column = column;
+        self.set_cursor(offset, cx);
+        window.focus(&self.focus_handle);
+    }
+
+    fn byte_background(&self, offset: usize, column: Column, cx: &App) -> Option<Hsla> {
+        if offset == self.cursor {
+            Some(if column == self.column {
+                cx.theme().players().local().selection
+            } else {
+                cx.theme().colors().element_selected
+            })
+        } else if self
+            .search_match
+            .as_ref()
+            .is_some_and(|range| range.contains(&offset))
+        {
+            Some(cx.theme().colors().search_match_background)
+        } else {
+            None
+        }
+    }
+
+    fn render_row(&self, row: usize, cx: &mut Context<Self>) -> AnyElement {
+        let colors = cx.theme().colors();
+        let cursor_color = cx.theme().players().local().cursor;
+        let bytes = self.file.read(cx).bytes();
+        let row_start = row * BYTES_PER_ROW;
+        let offset_color = if self.cursor / BYTES_PER_ROW == row {
+            colors.editor_active_line_number
+        } else {
+            colors.editor_line_number
+        };
+
+        let mut hex_column = h_flex().gap_1();
+        let mut text_column = h_flex();
+        for column_ix in 0..BYTES_PER_ROW {
+            let offset = row_start + column_ix;
+            let hex_byte = div().when(column_ix == BYTES_PER_ROW / 2, |this| this.ml_2());
+            let Some(&byte) = bytes.get(offset) else {
+                hex_column = hex_column.child(hex_byte.child("  "));
+                continue;
+            };
+
+            let hex_digits = format!("{byte:02x}");
+            let hex_byte = if offset == self.cursor && self.column == Column::Hex {
+                let (high, low) = hex_digits.split_at(1);
+                hex_byte.child(
+                    h_flex()
+                        .child(
+                            div()
+                                .when(!self.low_nibble, |this| {
+                                    this.border_b_2().border_color(cursor_color)
+                                })
+                                .child(high.to_string()),
+                        )
+                        .child(
+                            div()
+                                .when(self.low_nibble, |this| {
+                                    this.border_b_2().border_color(cursor_color)
+                                })
+                                .child(low.to_string()),
+                        ),
+                )
+            } else {
+                hex_byte.child(hex_digits)
+            };
+            hex_column = hex_column.child(
+                hex_byte
+                    .when_some(self.byte_background(offset, Column::Hex, cx), |this, bg| {
+                        this.bg(bg)
+                    })
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _: &MouseDownEvent, window, cx| {
+                            this.click_byte(offset, Column::Hex, window, cx)
+                        }),
+                    ),
+            );
+
+            let (character, color) = if byte.is_ascii_graphic() || byte == b' ' {
+                (byte as char, colors.editor_foreground)
+            } else {
+                ('.', colors.text_muted)
+            };
+            text_column = text_column.child(
+                div()
+                    .text_color(color)
+                    .when_some(
+                        self.byte_background(offset, Column::Text, cx),
+                        |this, bg| this.bg(bg),
+                    )
+                    .child(character.to_string())
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _: &MouseDownEvent, window, cx| {
+                            this.click_byte(offset, Column::Text, window, cx)
+                        }),
+                    ),
+            );
+        }
+
+        h_flex()
+            .gap_4()
+            .child(
+                div()
+                    .text_color(offset_color)
+                    .child(format!("{row_start:08x}")),
+            )
+            .child(hex_column)
+            .child(text_column)
+            .into_any_element()
+    }
+
+    fn render_status(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let file = self.file.read(cx);
+        let cursor = self.cursor;
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_4()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(format!("Offset 0x{cursor:08x} ({cursor})"))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .when_some(file.bytes().get(cursor), |this, byte| {
+                this.child(
+                    Label::new(format!("Value 0x{byte:02x} ({byte})"))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .child(
+                Label::new(format!("{} bytes", file.len()))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .when_some(self.status.clone(), |this, status| {
+                this.child(
+                    Label::new(status)
+                        .size(LabelSize::Small)
+                        .color(Color::Warning),
+                )
+            })
+    }
+}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::Edited => {
+                f(ItemEvent::Edit);
+                f(ItemEvent::UpdateTab);
+            }
+            HexEditorEvent::Saved | HexEditorEvent::Reloaded | HexEditorEvent::Conflicted => {
+                f(ItemEvent::UpdateTab)
+            }
+        }
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.file.entity_id(), self.file.read(cx))
+    }
+
+    fn is_singleton(&self, _cx: &App) -> bool {
+        true
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.file
+            .read(cx)
+            .file_name()
+            .to_string_lossy()
+            .to_string()
+            .into()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.file.read(cx).abs_path();
+        Some(abs_path.compact().to_string_lossy().to_string().into())
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.file.read(cx).abs_path();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<workspace::WorkspaceId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<Self>>
+    where
+        Self: Sized,
+    {
+        Some(cx.new(|cx| Self::new(self.file.clone(), self.project.clone(), window, cx)))
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.file.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.file.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _: SaveOptions,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.file.update(cx, |file, cx| file.save(cx))
+    }
+
+    fn reload(
+        &mut self,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.file.update(cx, |file, cx| file.reload(cx))
+    }
+}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme_settings = ThemeSettings::get_global(cx);
+        let font = theme_settings.buffer_font.clone();
+        let font_size = theme_settings.buffer_font_size(cx);
+        let row_count = self.file.read(cx).len().div_ceil(BYTES_PER_ROW).max(1);
+
+        v_flex()
+            .key_context("HexEditor")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::page_up))
+            .on_action(cx.listener(Self::page_down))
+            .on_action(cx.listener(Self::move_to_beginning_of_line))
+            .on_action(cx.listener(Self::move_to_end_of_line))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::switch_column))
+            .on_action(cx.listener(Self::go_to_offset_prompt))
+            .on_action(cx.listener(Self::find))
+            .on_action(cx.listener(Self::find_next))
+            .on_action(cx.listener(Self::find_previous))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
+            .on_key_down(cx.listener(Self::handle_key_down))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .text_color(cx.theme().colors().editor_foreground)
+            .child(
+                div().flex_1().px_2().font(font).text_size(font_size).child(
+                    uniform_list(
+                        "hex-editor-rows",
+                        row_count,
+                        cx.processor(|this, range: Range<usize>, _, cx| {
+                            range.map(|row| this.render_row(row, cx)).collect()
+                        }),
+                    )
+                    .track_scroll(self.scroll_handle.clone())
+                    .size_full(),
+                ),
+            )
+            .child(self.render_status(cx))
+    }
+}
+
+impl ProjectItem for HexEditor {
+    type Item = BinaryFile;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, project, window, cx)
+    }
+}
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<HexEditor>(cx);
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(open_active_file);
+    })
+    .detach();
+}
+
+/// Opens the file of the active item in a hex editor, including files that failed to open as text.
+fn open_active_file(
+    workspace: &mut Workspace,
+    _: &OpenActiveFile,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().clone();
+    if !project.read(cx).is_local() {
+        return;
+    }
+    let Some(project_path) = active_project_path(workspace, cx) else {
+        return;
+    };
+
+    let existing_hex_editor = workspace.items_of_type::<HexEditor>(cx).find(|hex_editor| {
+        hex_editor.read(cx).file.read(cx).project_path(cx).as_ref() == Some(&project_path)
+    });
+    if let Some(hex_editor) = existing_hex_editor {
+        workspace.activate_item(&hex_editor, true, true, window, cx);
+        return;
+    }
+
+    let open_file = BinaryFile::open(&project, project_path, cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let file = open_file.await?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            let hex_editor = cx.new(|cx| HexEditor::new(file, project, window, cx));
+            workspace.add_item_to_active_pane(Box::new(hex_editor), None, true, window, cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn active_project_path(workspace: &Workspace, cx: &App) -> Option<ProjectPath> {
+    let item = workspace.active_item(cx)?;
+    match item.downcast::<InvalidBufferView>() {
+        Some(invalid_buffer_view) => workspace
+            .project()
+            .read(cx)
+            .find_project_path(&invalid_buffer_view.read(cx).abs_path, cx),
+        None => item.project_path(cx),
+    }
+}
@@ -0,0 +1,174 @@
+use anyhow::{Context as _, Result};
+use editor::{Editor, EditorEvent, actions::SelectAll};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription, WeakEntity,
+};
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::{BytePattern, HexEditor};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PromptKind {
+    GoToOffset,
+    Find,
+}
+
+/// Asks for an offset to jump to, or a byte pattern to search for, in a [`HexEditor`].
+pub(crate) struct HexEditorPrompt {
+    hex_editor: WeakEntity<HexEditor>,
+    kind: PromptKind,
+    query_editor: Entity<Editor>,
+    _query_editor_subscription: Subscription,
+}
+
+impl ModalView for HexEditorPrompt {}
+
+impl Focusable for HexEditorPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.query_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for HexEditorPrompt {}
+
+impl HexEditorPrompt {
+    pub(crate) fn new(
+        hex_editor: WeakEntity<HexEditor>,
+        kind: PromptKind,
+        initial_query: Option<&str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let query_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(
+                match kind {
+                    PromptKind::GoToOffset => "Offset, in decimal or 0x-prefixed hex",
+                    PromptKind::Find => "Hex bytes, such as 7f 45 ?? 46, or \"quoted text\"",
+                },
+                cx,
+            );
+            if let Some(query) = initial_query {
+                editor.set_text(query, window, cx);
+                editor.select_all(&SelectAll, window, cx);
+            }
+            editor
+        });
+        let query_editor_subscription =
+            cx.subscribe(&query_editor, |_, _, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    cx.notify();
+                }
+            });
+        Self {
+            hex_editor,
+            kind,
+            query_editor,
+            _query_editor_subscription: query_editor_subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let query = self.query_editor.read(cx).text(cx);
+        let result = match self.kind {
+            PromptKind::GoToOffset => parse_offset(&query).map(|offset| {
+                self.hex_editor
+                    .update(cx, |hex_editor, cx| hex_editor.go_to_offset(offset, cx))
+                    .ok();
+            }),
+            PromptKind::Find => BytePattern::parse(&query).map(|pattern| {
+                self.hex_editor
+                    .update(cx, |hex_editor, cx| {
+                        hex_editor.search(pattern, query.trim().to_string().into(), cx)
+                    })
+                    .ok();
+            }),
+        };
+        if result.is_ok() {
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn error(&self, cx: &App) -> Option<String> {
+        let query = self.query_editor.read(cx).text(cx);
+        if query.trim().is_empty() {
+            return None;
+        }
+        let result = match self.kind {
+            PromptKind::GoToOffset => parse_offset(&query).map(drop),
+            PromptKind::Find => BytePattern::parse(&query).map(drop),
+        };
+        result.err().map(|error| error.to_string())
+    }
+}
+
+/// Parses a byte offset, written in decimal or as hex with a `0x` prefix.
+fn parse_offset(text: &str) -> Result<usize> {
+    let text = text.trim();
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => {
+            usize::from_str_radix(hex, 16).with_context(|| format!("{text:?} is not a hex offset"))
+        }
+        None => text
+            .parse()
+            .with_context(|| format!("{text:?} is not a decimal offset")),
+    }
+}
+
+impl Render for HexEditorPrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (help_text, color) = match self.error(cx) {
+            Some(error) => (error, Color::Error),
+            None => (
+                match self.kind {
+                    PromptKind::GoToOffset => "Move the cursor to this offset",
+                    PromptKind::Find => "Find the next occurrence of these bytes",
+                }
+                .to_string(),
+                Color::Muted,
+            ),
+        };
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("HexEditorPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.query_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .child(Label::new(help_text).color(color)),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("1024").unwrap(), 1024);
+        assert_eq!(parse_offset(" 0x1f ").unwrap(), 0x1f);
+        assert_eq!(parse_offset("0XFF").unwrap(), 0xff);
+        assert!(parse_offset("1f").is_err());
+        assert!(parse_offset("0x").is_err());
+        assert!(parse_offset("-1").is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     DebuggerTextObject, LanguageScope, Outline, OutlineConfig, RunnableCapture, RunnableTag,
-    TextObject, TreeSitterOptions,
+    StructuralPattern, TextObject, TreeSitterOptions,
     diagnostic_set::{DiagnosticEntry, DiagnosticGroup},
     language_settings::{LanguageSettings, language_settings},
     outline::OutlineItem,
@@ -21,7 +21,7 @@ use anyhow::{Context as _, Result};
 pub use clock::ReplicaId;
 use clock::{AGENT_REPLICA_ID, Lamport};
 use collections::HashMap;
-use fs::MTime;
+use fs::{Encoding, MTime};
 use futures::channel::oneshot;
 use gpui::{
     App, AppContext as _, Context, Entity, EventEmitter, HighlightStyle, SharedString, StyledText,
@@ -76,6 +76,9 @@ pub use lsp::DiagnosticSeverity;
 /// a diff against the contents of its file.
 pub static BUFFER_DIFF_TASK: LazyLock<TaskLabel> = LazyLock::new(TaskLabel::new);
 
+/// The size of the pieces a large file's contents are inserted in when it's reloaded.
+const LARGE_FILE_RELOAD_EDIT_LEN: usize = 1024 * 1024;
+
 /// Indicate whether a [`Buffer`] has permissions to edit.
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub enum Capability {
@@ -100,6 +103,11 @@ pub struct Buffer {
     /// The version vector when this buffer was last loaded from
     /// or saved to disk.
     saved_version: clock::Global,
+    /// The encoding of the file on disk, used when reloading or saving the buffer.
+    encoding: Encoding,
+    /// Whether the buffer was opened from a file large enough that syntax highlighting,
+    /// language servers and other expensive features are disabled for it.
+    large_file_mode: bool,
     preview_version: clock::Global,
     transaction_depth: usize,
     was_dirty_before_starting_transaction: Option<bool>,
@@ -315,6 +323,8 @@ pub enum BufferEvent {
     DiagnosticsUpdated,
     /// The buffer gained or lost editing capabilities.
     CapabilityChanged,
+    /// The buffer entered or left large-file mode.
+    LargeFileModeChanged,
     /// The buffer was explicitly requested to close.
     Closed,
     /// The buffer was discarded when closing.
@@ -400,8 +410,19 @@ pub trait LocalFile: File {
     /// Loads the file contents from disk and returns them as a UTF-8 encoded string.
     fn load(&self, cx: &App) -> Task<Result<String>>;
 
+    /// Loads the file contents from disk, decoding them with the given encoding.
+    fn load_with_encoding(&self, encoding: Encoding, cx: &App) -> Task<Result<String>>;
+
     /// Loads the file's contents from disk.
     fn load_bytes(&self, cx: &App) -> Task<Result<Vec<u8>>>;
+
+    /// Loads the file contents from disk in chunks, decoding them with the given encoding,
+    /// for files opened in large-file mode.
+    fn load_large_with_encoding(
+        &self,
+        encoding: Encoding,
+        cx: &App,
+    ) -> Task<Result<(Rope, LineEnding)>>;
 }
 
 /// The auto-indent behavior associated with an editing operation.
@@ -843,6 +864,8 @@ impl Buffer {
         ));
         this.saved_version = proto::deserialize_version(&message.saved_version);
         this.saved_mtime = message.saved_mtime.map(|time| time.into());
+        this.encoding = proto::deserialize_encoding(&message.encoding);
+        this.large_file_mode = message.large_file_mode;
         Ok(this)
     }
 
@@ -855,6 +878,8 @@ impl Buffer {
             line_ending: proto::serialize_line_ending(self.line_ending()) as i32,
             saved_version: proto::serialize_version(&self.saved_version),
             saved_mtime: self.saved_mtime.map(|time| time.into()),
+            encoding: proto::serialize_encoding(self.encoding),
+            large_file_mode: self.large_file_mode,
         }
     }
 
@@ -932,6 +957,8 @@ impl Buffer {
         Self {
             saved_mtime,
             saved_version: buffer.version(),
+            encoding: Encoding::default(),
+            large_file_mode: false,
             preview_version: buffer.version(),
             reload_task: None,
             transaction_depth: 0,
@@ -1222,6 +1249,34 @@ impl Buffer {
         self.saved_mtime
     }
 
+    /// The encoding used to decode the buffer's file when loading it and to encode it when saving.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Sets the encoding used the next time the buffer is reloaded from or saved to disk.
+    pub fn set_encoding(&mut self, encoding: Encoding, cx: &mut Context<Self>) {
+        if self.encoding != encoding {
+            self.encoding = encoding;
+            cx.notify();
+        }
+    }
+
+    /// Whether the buffer is in large-file mode, where syntax highlighting, language servers,
+    /// git diffs and soft wrap are disabled to keep very large files responsive.
+    pub fn large_file_mode(&self) -> bool {
+        self.large_file_mode
+    }
+
+    /// Enters or leaves large-file mode.
+    pub fn set_large_file_mode(&mut self, large_file_mode: bool, cx: &mut Context<Self>) {
+        if self.large_file_mode != large_file_mode {
+            self.large_file_mode = large_file_mode;
+            cx.emit(BufferEvent::LargeFileModeChanged);
+            cx.notify();
+        }
+    }
+
     /// Assign a language to the buffer.
     pub fn set_language(&mut self, language: Option<Arc<Language>>, cx: &mut Context<Self>) {
         self.non_text_state_update_count += 1;
@@ -1278,23 +1333,63 @@ impl Buffer {
         let (tx, rx) = futures::channel::oneshot::channel();
         let prev_version = self.text.version();
         self.reload_task = Some(cx.spawn(async move |this, cx| {
-            let Some((new_mtime, new_text)) = this.update(cx, |this, cx| {
+            let Some((new_mtime, load_diff)) = this.update(cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
+                let load_diff: Task<Result<Diff>> = if this.large_file_mode {
+                    // Large files are streamed from disk like when they were opened, and replaced
+                    // wholesale, since diffing them would take too long.
+                    let load = file.load_large_with_encoding(this.encoding, cx);
+                    let snapshot = this.text.snapshot();
+                    cx.background_spawn(async move {
+                        let (new_text, line_ending) = load.await?;
+                        let mut edits = Vec::new();
+                        if !new_text.chars().eq(snapshot.as_rope().chars()) {
+                            // The new text is inserted piece by piece rather than as a single
+                            // string, so each edit's range is relative to the buffer as left by
+                            // the previous one.
+                            let mut range = 0..snapshot.len();
+                            let mut piece = String::new();
+                            for chunk in new_text.chunks() {
+                                piece.push_str(chunk);
+                                if piece.len() >= LARGE_FILE_RELOAD_EDIT_LEN {
+                                    let end = range.start + piece.len();
+                                    edits.push((range, Arc::from(mem::take(&mut piece))));
+                                    range = end..end;
+                                }
+                            }
+                            if !piece.is_empty() || edits.is_empty() {
+                                edits.push((range, Arc::from(piece)));
+                            }
+                        }
+                        Ok(Diff {
+                            base_version: snapshot.version().clone(),
+                            line_ending,
+                            edits,
+                        })
+                    })
+                } else {
+                    let load = file.load_with_encoding(this.encoding, cx);
+                    cx.spawn(async move |this, cx| {
+                        let new_text = load.await?;
+                        Ok(this.update(cx, |this, cx| this.diff(new_text, cx))?.await)
+                    })
+                };
 
-                Some((file.disk_state().mtime(), file.load(cx)))
+                Some((file.disk_state().mtime(), load_diff))
             })?
             else {
                 return Ok(());
             };
 
-            let new_text = new_text.await?;
-            let diff = this
-                .update(cx, |this, cx| this.diff(new_text.clone(), cx))?
-                .await;
+            let diff = load_diff.await?;
             this.update(cx, |this, cx| {
                 if this.version() == diff.base_version {
                     this.finalize_last_transaction();
-                    this.apply_diff(diff, cx);
+                    if this.large_file_mode {
+                        this.apply_sequential_edits(diff, cx);
+                    } else {
+                        this.apply_diff(diff, cx);
+                    }
                     tx.send(this.finalize_last_transaction().cloned()).ok();
                     this.has_conflict = false;
                     this.did_reload(this.version(), this.line_ending(), new_mtime, cx);
@@ -1947,6 +2042,18 @@ impl Buffer {
         self.end_transaction(cx)
     }
 
+    /// Applies a diff whose edits are each relative to the buffer as left by the previous one,
+    /// as a single transaction. Unlike [`Buffer::apply_diff`], the edits aren't coalesced, so
+    /// large insertions don't have to be concatenated into one string.
+    fn apply_sequential_edits(&mut self, diff: Diff, cx: &mut Context<Self>) {
+        self.start_transaction();
+        self.text.set_line_ending(diff.line_ending);
+        for (range, new_text) in diff.edits {
+            self.edit([(range, new_text)], None, cx);
+        }
+        self.end_transaction(cx);
+    }
+
     fn has_unsaved_edits(&self) -> bool {
         let (last_version, has_unsaved_edits) = self.has_unsaved_edits.take();
 
@@ -3861,6 +3968,126 @@ impl BufferSnapshot {
         self.syntax.matches(range, self, query)
     }
 
+    /// Returns the sorted, non-overlapping ranges within `range` that hold natural language:
+    /// comments and strings in code, and everything except code and links in prose documents.
+    pub fn natural_language_ranges(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        let mut included = Vec::new();
+        let mut excluded = Vec::new();
+
+        if self
+            .language()
+            .is_none_or(|language| is_prose_language(language))
+        {
+            included.push(range.clone());
+            for layer in self
+                .syntax
+                .layers_for_range(range.clone(), &self.text, true)
+            {
+                if !is_prose_language(layer.language) {
+                    excluded.push(layer.node().byte_range());
+                }
+            }
+        }
+
+        let captures = self.syntax.captures(range.clone(), &self.text, |grammar| {
+            grammar.highlights_query.as_ref()
+        });
+        let capture_names = captures
+            .grammars()
+            .iter()
+            .map(|&grammar| {
+                grammar
+                    .highlights_query
+                    .as_ref()
+                    .map(|query| query.capture_names())
+            })
+            .collect::<Vec<_>>();
+        for capture in captures {
+            let Some(name) = capture_names[capture.grammar_index]
+                .and_then(|names| names.get(capture.index as usize))
+            else {
+                continue;
+            };
+            let node_range = capture.node.byte_range();
+            if *name == "text.literal"
+                || name.starts_with("link_uri")
+                || name.starts_with("string.escape")
+                || name.starts_with("string.regex")
+                || name.starts_with("string.special")
+            {
+                excluded.push(node_range);
+            } else if name.starts_with("comment") || name.starts_with("string") {
+                included.push(node_range);
+            }
+        }
+
+        included.sort_unstable_by_key(|range| range.start);
+        excluded.sort_unstable_by_key(|range| range.start);
+        let mut result: Vec<Range<usize>> = Vec::new();
+        for included_range in included {
+            let mut start = included_range.start.max(range.start);
+            let end = included_range.end.min(range.end);
+            if let Some(last) = result.last()
+                && last.end > start
+            {
+                start = last.end;
+            }
+            for excluded_range in &excluded {
+                if excluded_range.start >= end {
+                    break;
+                }
+                if excluded_range.end <= start {
+                    continue;
+                }
+                if excluded_range.start > start {
+                    result.push(start..excluded_range.start);
+                }
+                start = start.max(excluded_range.end);
+            }
+            if start < end {
+                result.push(start..end);
+            }
+        }
+        result
+    }
+
+    /// Returns the ranges of the outermost syntax nodes within `range` that match `pattern`,
+    /// in every syntax layer whose language the pattern can be parsed in.
+    pub fn structural_matches(
+        &self,
+        pattern: &StructuralPattern,
+        range: Range<usize>,
+    ) -> Vec<Range<usize>> {
+        let mut matches = Vec::new();
+        for layer in self
+            .syntax
+            .layers_for_range(range.clone(), &self.text, true)
+        {
+            pattern.find_matches(&layer, &self.text, range.clone(), &mut matches);
+        }
+        matches.sort_unstable_by_key(|range| (range.start, Reverse(range.end)));
+        matches.dedup_by(|next, previous| next.start < previous.end);
+        matches
+    }
+
+    /// Renders the replacement `template` for a match of `pattern` spanning exactly `range`,
+    /// substituting the text captured by its metavariables.
+    pub fn structural_replacement(
+        &self,
+        pattern: &StructuralPattern,
+        range: Range<usize>,
+        template: &str,
+    ) -> Option<String> {
+        self.syntax
+            .layers_for_range(range.clone(), &self.text, true)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .find_map(|layer| {
+                pattern.render_replacement(&layer, &self.text, range.clone(), template)
+            })
+    }
+
     pub fn all_bracket_ranges(
         &self,
         range: Range<usize>,
@@ -4964,9 +5191,17 @@ impl LocalFile for TestFile {
         unimplemented!()
     }
 
+    fn load_with_encoding(&self, _: Encoding, _cx: &App) -> Task<Result<String>> {
+        unimplemented!()
+    }
+
     fn load_bytes(&self, _cx: &App) -> Task<Result<Vec<u8>>> {
         unimplemented!()
     }
+
+    fn load_large_with_encoding(&self, _: Encoding, _: &App) -> Task<Result<(Rope, LineEnding)>> {
+        unimplemented!()
+    }
 }
 
 pub(crate) fn contiguous_ranges(
@@ -5074,6 +5309,14 @@ impl CharClassifier {
     }
 }
 
+/// Languages whose text is natural language, apart from the code and links embedded in it.
+fn is_prose_language(language: &Language) -> bool {
+    matches!(
+        language.name().as_ref(),
+        "Markdown" | "Markdown-Inline" | "Plain Text" | "Git Commit"
+    )
+}
+
 /// Find all of the ranges of whitespace that occur at the ends of lines
 /// in the given rope.
 ///
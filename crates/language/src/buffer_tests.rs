@@ -2523,6 +2523,105 @@ fn test_language_scope_at_with_rust(cx: &mut App) {
     });
 }
 
+#[gpui::test]
+fn test_natural_language_ranges(cx: &mut App) {
+    init_settings(cx, |_| {});
+
+    cx.new(|cx| {
+        let language = rust_lang()
+            .with_highlights_query(
+                r#"
+                (line_comment) @comment
+                (string_literal) @string
+                (escape_sequence) @string.escape
+                "#,
+            )
+            .unwrap();
+        let text = r#"
+            // Check this comment
+            fn main() {
+                let s = "and\nthis string";
+            }
+        "#
+        .unindent();
+
+        let buffer = Buffer::local(text.clone(), cx).with_language(Arc::new(language), cx);
+        let snapshot = buffer.snapshot();
+        let ranges = snapshot.natural_language_ranges(0..text.len());
+        assert_eq!(
+            ranges
+                .into_iter()
+                .map(|range| text[range].trim())
+                .collect::<Vec<_>>(),
+            &["// Check this comment", "\"and", "this string\""]
+        );
+
+        buffer
+    });
+
+    cx.new(|cx| {
+        let text = "Plain text is checked everywhere";
+        let buffer = Buffer::local(text, cx);
+        assert_eq!(
+            buffer.snapshot().natural_language_ranges(6..text.len()),
+            &[6..text.len()]
+        );
+        buffer
+    });
+}
+
+#[gpui::test]
+fn test_structural_matches(cx: &mut App) {
+    init_settings(cx, |_| {});
+
+    cx.new(|cx| {
+        let text = r#"
+            fn main() {
+                let a = foo.bar().unwrap();
+                let b = baz.unwrap_or(1);
+                // c.unwrap()
+                same(a, a);
+                same(a, b);
+                call(a.unwrap(), 1, 2);
+            }
+        "#
+        .unindent();
+
+        let buffer = Buffer::local(text.clone(), cx).with_language(Arc::new(rust_lang()), cx);
+        let snapshot = buffer.snapshot();
+        let matches_for = |pattern: &str| {
+            snapshot
+                .structural_matches(&StructuralPattern::new(pattern), 0..text.len())
+                .into_iter()
+                .map(|range| &text[range])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            matches_for("$X.unwrap()"),
+            &["foo.bar().unwrap()", "a.unwrap()"]
+        );
+        assert_eq!(matches_for("same($A, $A)"), &["same(a, a)"]);
+        assert_eq!(matches_for("call($$$ARGS)"), &["call(a.unwrap(), 1, 2)"]);
+        assert_eq!(matches_for("call($FIRST, $$$_, 3)"), Vec::<&str>::new());
+
+        let pattern = StructuralPattern::new("$X.unwrap()");
+        let range = text.find("foo").unwrap()..text.find(";").unwrap();
+        assert_eq!(
+            snapshot.structural_replacement(&pattern, range, "$X.expect(\"bar\")"),
+            Some("foo.bar().expect(\"bar\")".to_string())
+        );
+        let pattern = StructuralPattern::new("call($FIRST, $$$REST)");
+        let range = text.find("call").unwrap()..text.rfind(")").unwrap() + 1;
+        assert_eq!(
+            snapshot.structural_replacement(&pattern, range, "call($$$REST, $FIRST)"),
+            Some("call(1, 2, a.unwrap())".to_string())
+        );
+
+        buffer
+    });
+}
+
 #[gpui::test]
 fn test_language_scope_at_with_combined_injections(cx: &mut App) {
     init_settings(cx, |_| {});
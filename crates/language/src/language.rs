@@ -14,6 +14,7 @@ pub mod language_settings;
 mod manifest;
 mod outline;
 pub mod proto;
+mod structural_search;
 mod syntax_map;
 mod task_context;
 mod text_diff;
@@ -82,6 +83,7 @@ pub use language_registry::{
 };
 pub use lsp::{LanguageServerId, LanguageServerName};
 pub use outline::*;
+pub use structural_search::StructuralPattern;
 pub use syntax_map::{OwnedSyntaxLayer, SyntaxLayer, ToTreeSitterPoint, TreeSitterOptions};
 pub use text::{AnchorRangeExt, LineEnding};
 pub use tree_sitter::{Node, Parser, Tree, TreeCursor};
@@ -29,6 +29,16 @@ pub fn serialize_line_ending(message: text::LineEnding) -> proto::LineEnding {
     }
 }
 
+/// Deserializes an [`fs::Encoding`] from the RPC representation, defaulting to UTF-8.
+pub fn deserialize_encoding(name: &str) -> fs::Encoding {
+    fs::Encoding::from_name(name).unwrap_or_default()
+}
+
+/// Serializes an [`fs::Encoding`] to be sent over RPC.
+pub fn serialize_encoding(encoding: fs::Encoding) -> String {
+    encoding.name().into_owned()
+}
+
 /// Serializes a [`crate::Operation`] to be sent over RPC.
 pub fn serialize_operation(operation: &crate::Operation) -> proto::Operation {
     proto::Operation {
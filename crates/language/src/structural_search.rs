@@ -0,0 +1,401 @@
+use crate::{Language, LanguageId, SyntaxLayer, with_parser};
+use collections::HashMap;
+use parking_lot::Mutex;
+use std::{ops::Range, sync::Arc};
+use tree_sitter::{Node, Tree};
+
+const PLACEHOLDER_PREFIX: &str = "zed_metavariable_";
+const SEQUENCE_PLACEHOLDER_PREFIX: &str = "zed_metavariables_";
+
+/// A code pattern that is matched against syntax trees rather than text.
+///
+/// The pattern is written in the language being searched, and may contain metavariables:
+/// `$NAME` matches any single syntax node, and `$$$NAME` matches any sequence of sibling nodes,
+/// including an empty one. A metavariable that appears more than once must match the same text
+/// each time, except for `$_` and `$$$_`, which match anything without binding.
+///
+/// The pattern is parsed lazily with the grammar of each language it is matched against.
+pub struct StructuralPattern {
+    source: String,
+    parsed_source: String,
+    placeholders: HashMap<String, Metavariable>,
+    literal_hint: Option<String>,
+    trees: Mutex<HashMap<LanguageId, Option<Tree>>>,
+}
+
+#[derive(Clone, Debug)]
+struct Metavariable {
+    name: String,
+    is_sequence: bool,
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    Metavariable { name: &'a str, is_sequence: bool },
+}
+
+type Captures<'a> = Vec<(&'a str, Range<usize>)>;
+
+impl StructuralPattern {
+    pub fn new(source: &str) -> Self {
+        let source = source.trim();
+        let mut parsed_source = String::with_capacity(source.len());
+        let mut placeholders = HashMap::default();
+        let mut literal_hint: Option<&str> = None;
+        for segment in segments(source) {
+            match segment {
+                Segment::Text(text) => {
+                    parsed_source.push_str(text);
+                    for word in text.split(|c: char| !c.is_alphanumeric() && c != '_') {
+                        if literal_hint.is_none_or(|hint| word.len() > hint.len()) {
+                            literal_hint = Some(word);
+                        }
+                    }
+                }
+                Segment::Metavariable { name, is_sequence } => {
+                    let prefix = if is_sequence {
+                        SEQUENCE_PLACEHOLDER_PREFIX
+                    } else {
+                        PLACEHOLDER_PREFIX
+                    };
+                    let placeholder = format!("{prefix}{name}");
+                    parsed_source.push_str(&placeholder);
+                    placeholders.insert(
+                        placeholder,
+                        Metavariable {
+                            name: name.to_string(),
+                            is_sequence,
+                        },
+                    );
+                }
+            }
+        }
+
+        Self {
+            source: source.to_string(),
+            parsed_source,
+            placeholders,
+            literal_hint: literal_hint
+                .filter(|hint| !hint.is_empty())
+                .map(ToString::to_string),
+            trees: Mutex::default(),
+        }
+    }
+
+    /// The pattern, as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// The longest identifier-like word that every match must contain, if any. Useful for
+    /// skipping files that cannot contain a match without parsing them.
+    pub fn literal_hint(&self) -> Option<&str> {
+        self.literal_hint.as_deref()
+    }
+
+    /// Returns whether this pattern parses without errors in the given language.
+    pub fn is_valid_for(&self, language: &Arc<Language>) -> bool {
+        self.tree_for_language(language).is_some()
+    }
+
+    /// Appends the ranges of the outermost nodes within `range` that match this pattern.
+    pub(crate) fn find_matches(
+        &self,
+        layer: &SyntaxLayer,
+        text: &text::BufferSnapshot,
+        range: Range<usize>,
+        matches: &mut Vec<Range<usize>>,
+    ) {
+        let Some(tree) = self.tree_for_language(layer.language) else {
+            return;
+        };
+        let pattern = pattern_root(tree.root_node());
+        let mut cursor = layer.node().walk();
+        loop {
+            let node = cursor.node();
+            let mut descend = node.end_byte() > range.start && node.start_byte() < range.end;
+            if descend
+                && node.start_byte() >= range.start
+                && node.end_byte() <= range.end
+                && !node.byte_range().is_empty()
+                && self.match_node(pattern, node, text, &mut Vec::new())
+            {
+                matches.push(node.byte_range());
+                descend = false;
+            }
+            if descend && cursor.goto_first_child() {
+                continue;
+            }
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Renders `template` for the match spanning exactly `range`, substituting the text captured
+    /// by each metavariable. Returns `None` if no node at `range` matches this pattern.
+    pub(crate) fn render_replacement(
+        &self,
+        layer: &SyntaxLayer,
+        text: &text::BufferSnapshot,
+        range: Range<usize>,
+        template: &str,
+    ) -> Option<String> {
+        let tree = self.tree_for_language(layer.language)?;
+        let pattern = pattern_root(tree.root_node());
+        let mut candidates = Vec::new();
+        let mut node = layer
+            .node()
+            .descendant_for_byte_range(range.start, range.end);
+        while let Some(candidate) = node
+            && candidate.byte_range() == range
+        {
+            candidates.push(candidate);
+            node = candidate.parent();
+        }
+
+        let mut captures = Vec::new();
+        let is_match = candidates.into_iter().rev().any(|candidate| {
+            captures.clear();
+            self.match_node(pattern, candidate, text, &mut captures)
+        });
+        if !is_match {
+            return None;
+        }
+
+        let mut replacement = String::new();
+        for segment in segments(template) {
+            match segment {
+                Segment::Text(literal) => replacement.push_str(literal),
+                Segment::Metavariable { name, is_sequence } => {
+                    match captures.iter().find(|(captured, _)| *captured == name) {
+                        Some((_, range)) => replacement.extend(text.text_for_range(range.clone())),
+                        None => {
+                            replacement.push_str(if is_sequence { "$$$" } else { "$" });
+                            replacement.push_str(name);
+                        }
+                    }
+                }
+            }
+        }
+        Some(replacement)
+    }
+
+    fn tree_for_language(&self, language: &Arc<Language>) -> Option<Tree> {
+        self.trees
+            .lock()
+            .entry(language.id())
+            .or_insert_with(|| {
+                let grammar = language.grammar()?;
+                let tree = with_parser(|parser| {
+                    parser.set_language(&grammar.ts_language).ok()?;
+                    parser.parse(&self.parsed_source, None)
+                })?;
+                (!contains_error_node(tree.root_node())).then_some(tree)
+            })
+            .clone()
+    }
+
+    fn placeholder(&self, node: Node) -> Option<&Metavariable> {
+        self.placeholders
+            .get(self.parsed_source.get(node.byte_range())?)
+    }
+
+    fn match_node<'a>(
+        &'a self,
+        pattern: Node,
+        target: Node,
+        text: &text::BufferSnapshot,
+        captures: &mut Captures<'a>,
+    ) -> bool {
+        if let Some(metavariable) = self.placeholder(pattern) {
+            return bind(metavariable, target.byte_range(), text, captures);
+        }
+        if pattern.kind_id() != target.kind_id() {
+            return false;
+        }
+
+        let pattern_children = significant_children(pattern);
+        let target_children = significant_children(target);
+        if pattern_children.is_empty() && target_children.is_empty() {
+            return text_eq(
+                text,
+                target.byte_range(),
+                &self.parsed_source[pattern.byte_range()],
+            );
+        }
+        self.match_sequence(
+            &pattern_children,
+            &target_children,
+            target.end_byte(),
+            text,
+            captures,
+        )
+    }
+
+    fn match_sequence<'a>(
+        &'a self,
+        patterns: &[Node],
+        targets: &[Node],
+        end: usize,
+        text: &text::BufferSnapshot,
+        captures: &mut Captures<'a>,
+    ) -> bool {
+        let Some((pattern, remaining_patterns)) = patterns.split_first() else {
+            return targets.is_empty();
+        };
+
+        if let Some(metavariable) = self.placeholder(*pattern)
+            && metavariable.is_sequence
+        {
+            for len in 0..=targets.len() {
+                let range = match targets[..len] {
+                    [] => {
+                        let start = targets.first().map_or(end, |target| target.start_byte());
+                        start..start
+                    }
+                    [only] => only.byte_range(),
+                    [first, .., last] => first.start_byte()..last.end_byte(),
+                };
+                let captures_len = captures.len();
+                if bind(metavariable, range, text, captures)
+                    && self.match_sequence(remaining_patterns, &targets[len..], end, text, captures)
+                {
+                    return true;
+                }
+                captures.truncate(captures_len);
+            }
+            return false;
+        }
+
+        let Some((target, remaining_targets)) = targets.split_first() else {
+            return false;
+        };
+        let captures_len = captures.len();
+        if self.match_node(*pattern, *target, text, captures)
+            && self.match_sequence(remaining_patterns, remaining_targets, end, text, captures)
+        {
+            return true;
+        }
+        captures.truncate(captures_len);
+        false
+    }
+}
+
+impl std::fmt::Debug for StructuralPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("StructuralPattern")
+            .field(&self.source)
+            .finish()
+    }
+}
+
+fn bind<'a>(
+    metavariable: &'a Metavariable,
+    range: Range<usize>,
+    text: &text::BufferSnapshot,
+    captures: &mut Captures<'a>,
+) -> bool {
+    if metavariable.name == "_" {
+        return true;
+    }
+    if let Some((_, bound_range)) = captures.iter().find(|(name, _)| *name == metavariable.name) {
+        let bound_text = text.text_for_range(bound_range.clone()).collect::<String>();
+        return text_eq(text, range, &bound_text);
+    }
+    captures.push((metavariable.name.as_str(), range));
+    true
+}
+
+/// Skips the wrapper nodes that the grammar requires around a fragment, such as the source file
+/// and a statement around a lone expression, so that the pattern matches the fragment itself.
+fn pattern_root(mut node: Node) -> Node {
+    loop {
+        let children = significant_children(node);
+        match children.as_slice() {
+            [child] if child.is_named() => node = *child,
+            _ => return node,
+        }
+    }
+}
+
+/// The children that take part in matching: comments and other extras are ignored, as are the
+/// zero-width nodes inserted by error recovery, such as a missing trailing semicolon.
+fn significant_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|child| !child.is_extra() && !child.byte_range().is_empty())
+        .collect()
+}
+
+fn contains_error_node(node: Node) -> bool {
+    if !node.has_error() {
+        return false;
+    }
+    if node.is_error() {
+        return true;
+    }
+    let mut cursor = node.walk();
+    node.children(&mut cursor).any(contains_error_node)
+}
+
+fn text_eq(text: &text::BufferSnapshot, range: Range<usize>, expected: &str) -> bool {
+    if range.len() != expected.len() {
+        return false;
+    }
+    let mut expected = expected;
+    for chunk in text.text_for_range(range) {
+        match expected.strip_prefix(chunk) {
+            Some(rest) => expected = rest,
+            None => return false,
+        }
+    }
+    expected.is_empty()
+}
+
+/// Splits `source` into literal text and `$NAME` or `$$$NAME` metavariables, where names start
+/// with an uppercase letter or an underscore.
+fn segments(source: &str) -> impl Iterator<Item = Segment<'_>> {
+    let mut remaining = source;
+    std::iter::from_fn(move || {
+        if remaining.is_empty() {
+            return None;
+        }
+        let mut search_start = 0;
+        while let Some(dollar_ix) = remaining[search_start..].find('$') {
+            let dollar_ix = search_start + dollar_ix;
+            let (sigil_len, is_sequence) = if remaining[dollar_ix..].starts_with("$$$") {
+                (3, true)
+            } else {
+                (1, false)
+            };
+            let name_start = dollar_ix + sigil_len;
+            let name_len = metavariable_name_len(&remaining[name_start..]);
+            if name_len == 0 {
+                search_start = dollar_ix + 1;
+                continue;
+            }
+            if dollar_ix > 0 {
+                let text = &remaining[..dollar_ix];
+                remaining = &remaining[dollar_ix..];
+                return Some(Segment::Text(text));
+            }
+            let name = &remaining[name_start..name_start + name_len];
+            remaining = &remaining[name_start + name_len..];
+            return Some(Segment::Metavariable { name, is_sequence });
+        }
+        let text = remaining;
+        remaining = "";
+        Some(Segment::Text(text))
+    })
+}
+
+fn metavariable_name_len(text: &str) -> usize {
+    if !text.starts_with(|c: char| c.is_ascii_uppercase() || c == '_') {
+        return 0;
+    }
+    text.find(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+        .unwrap_or(text.len())
+}
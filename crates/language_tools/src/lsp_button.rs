@@ -16,6 +16,7 @@ use project::{
     project_settings::ProjectSettings,
 };
 use settings::{Settings as _, SettingsStore};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
 use ui::{
     Context, ContextMenu, ContextMenuEntry, ContextMenuItem, DocumentationAside, DocumentationSide,
     Indicator, PopoverMenu, PopoverMenuHandle, Tooltip, Window, prelude::*,
@@ -33,11 +34,15 @@ actions!(
     ]
 );
 
+const PROCESS_STATS_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
 pub struct LspButton {
     server_state: Entity<LanguageServerState>,
     popover_menu_handle: PopoverMenuHandle<ContextMenu>,
     lsp_menu: Option<Entity<ContextMenu>>,
     lsp_menu_refresh: Task<()>,
+    system: System,
+    _process_stats_refresh: Task<()>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -48,6 +53,14 @@ struct LanguageServerState {
     lsp_store: WeakEntity<LspStore>,
     active_editor: Option<ActiveEditor>,
     language_servers: LanguageServers,
+    process_stats: HashMap<LanguageServerId, ProcessStats>,
+}
+
+/// Resource usage of a language server process running on this machine.
+#[derive(Debug, Clone, Copy)]
+struct ProcessStats {
+    memory_bytes: u64,
+    cpu_usage: f32,
 }
 
 struct ActiveEditor {
@@ -253,8 +266,20 @@ impl LanguageServerState {
             };
 
             let server_name = server_info.name.clone();
+            let crash_count = self
+                .lsp_store
+                .read_with(cx, |lsp_store, _| {
+                    lsp_store.language_server_crash_count(&server_info.name)
+                })
+                .unwrap_or(0);
+            let state = cx.entity();
+            let server_id = server_info.id;
             menu = menu.item(ContextMenuItem::custom_entry(
-                move |_, _| {
+                move |_, cx| {
+                    let process_stats = server_id
+                        .and_then(|server_id| state.read(cx).process_stats.get(&server_id))
+                        .copied();
+                    let details = server_details(process_stats, crash_count);
                     h_flex()
                         .group("menu_item")
                         .w_full()
@@ -264,7 +289,14 @@ impl LanguageServerState {
                             h_flex()
                                 .gap_2()
                                 .child(Indicator::dot().color(status_color))
-                                .child(Label::new(server_name.0.clone())),
+                                .child(Label::new(server_name.0.clone()))
+                                .when_some(details, |div, details| {
+                                    div.child(
+                                        Label::new(details)
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                                }),
                         )
                         .when_some(hover_label, |div, hover_label| {
                             div.child(
@@ -366,6 +398,24 @@ impl LanguageServerState {
     }
 }
 
+fn server_details(process_stats: Option<ProcessStats>, crash_count: usize) -> Option<SharedString> {
+    let mut details = Vec::new();
+    if let Some(process_stats) = process_stats {
+        details.push(format!("{} MB", process_stats.memory_bytes / (1024 * 1024)));
+        details.push(format!("{:.0}% CPU", process_stats.cpu_usage));
+    }
+    match crash_count {
+        0 => {}
+        1 => details.push("crashed once".to_string()),
+        crash_count => details.push(format!("crashed {crash_count} times")),
+    }
+    if details.is_empty() {
+        None
+    } else {
+        Some(details.join(" · ").into())
+    }
+}
+
 impl LanguageServers {
     fn update_binary_status(
         &mut self,
@@ -553,6 +603,23 @@ impl LspButton {
             lsp_store: lsp_store.downgrade(),
             active_editor: None,
             language_servers,
+            process_stats: HashMap::default(),
+        });
+
+        let process_stats_refresh = cx.spawn(async move |lsp_button, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(PROCESS_STATS_REFRESH_INTERVAL)
+                    .await;
+                let updated = lsp_button.update(cx, |lsp_button, cx| {
+                    if lsp_button.popover_menu_handle.is_deployed() {
+                        lsp_button.refresh_process_stats(cx);
+                    }
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
         });
 
         let mut lsp_button = Self {
@@ -560,6 +627,8 @@ impl LspButton {
             popover_menu_handle,
             lsp_menu: None,
             lsp_menu_refresh: Task::ready(()),
+            system: System::new(),
+            _process_stats_refresh: process_stats_refresh,
             _subscriptions: vec![settings_subscription, lsp_store_subscription],
         };
         if !lsp_button
@@ -887,6 +956,50 @@ impl LspButton {
         });
     }
 
+    fn refresh_process_stats(&mut self, cx: &mut Context<Self>) {
+        let Ok(process_ids) = self
+            .server_state
+            .read(cx)
+            .lsp_store
+            .read_with(cx, |lsp_store, _| {
+                lsp_store
+                    .language_server_statuses()
+                    .filter_map(|(server_id, _)| {
+                        let process_id = lsp_store.language_server_process_id(server_id)?;
+                        Some((server_id, Pid::from_u32(process_id)))
+                    })
+                    .collect::<Vec<_>>()
+            })
+        else {
+            return;
+        };
+
+        let pids = process_ids.iter().map(|(_, pid)| *pid).collect::<Vec<_>>();
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&pids),
+            ProcessRefreshKind::new().with_cpu().with_memory(),
+        );
+        let process_stats = process_ids
+            .into_iter()
+            .filter_map(|(server_id, pid)| {
+                let process = self.system.process(pid)?;
+                Some((
+                    server_id,
+                    ProcessStats {
+                        memory_bytes: process.memory(),
+                        cpu_usage: process.cpu_usage(),
+                    },
+                ))
+            })
+            .collect();
+        self.server_state.update(cx, |state, _| {
+            state.process_stats = process_stats;
+        });
+        if let Some(lsp_menu) = &self.lsp_menu {
+            lsp_menu.update(cx, |_, cx| cx.notify());
+        }
+    }
+
     fn refresh_lsp_menu(
         &mut self,
         create_if_empty: bool,
@@ -901,6 +1014,7 @@ impl LspButton {
                     .await;
                 lsp_button
                     .update_in(cx, |lsp_button, window, cx| {
+                        lsp_button.refresh_process_stats(cx);
                         lsp_button.regenerate_items(cx);
                         let menu = ContextMenu::build(window, cx, |menu, _, cx| {
                             state.update(cx, |state, cx| state.fill_menu(menu, cx))
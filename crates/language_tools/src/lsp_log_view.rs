@@ -611,6 +611,31 @@ impl LspLogView {
         }
     }
 
+    fn export_rpc_trace(&mut self, cx: &mut Context<Self>) {
+        let Some(server_id) = self.current_server_id else {
+            return;
+        };
+        let log_store = self.log_store.read(cx);
+        let Some(rpc_trace) = log_store.rpc_trace_jsonl(server_id) else {
+            return;
+        };
+        let server_name = log_store
+            .language_servers
+            .get(&server_id)
+            .and_then(|state| state.name.clone())
+            .map_or_else(|| "language-server".to_string(), |name| name.0.to_string());
+        let suggested_name = format!("{server_name}-rpc-trace.jsonl");
+        let fs = self.project.read(cx).fs().clone();
+        let save_path = cx.prompt_for_new_path(util::paths::home_dir(), Some(&suggested_name));
+        cx.background_spawn(async move {
+            let Some(save_path) = save_path.await?? else {
+                return anyhow::Ok(());
+            };
+            fs.atomic_write(save_path, rpc_trace).await
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn show_server_info(
         &mut self,
         server_id: LanguageServerId,
@@ -1220,19 +1245,37 @@ impl Render for LspLogToolbarItemView {
                     ),
             )
             .child(
-                Button::new("clear_log_button", "Clear").on_click(cx.listener(
-                    |this, _, window, cx| {
-                        if let Some(log_view) = this.log_view.as_ref() {
-                            log_view.update(cx, |log_view, cx| {
-                                log_view.editor.update(cx, |editor, cx| {
-                                    editor.set_read_only(false);
-                                    editor.clear(window, cx);
-                                    editor.set_read_only(true);
-                                });
-                            })
-                        }
-                    },
-                )),
+                h_flex()
+                    .gap_0p5()
+                    .when(
+                        log_view.read(cx).active_entry_kind == LogKind::Rpc,
+                        |this| {
+                            this.child(Button::new("export_rpc_trace_button", "Export").on_click(
+                                cx.listener(|this, _, _, cx| {
+                                    if let Some(log_view) = this.log_view.as_ref() {
+                                        log_view.update(cx, |log_view, cx| {
+                                            log_view.export_rpc_trace(cx)
+                                        });
+                                    }
+                                }),
+                            ))
+                        },
+                    )
+                    .child(
+                        Button::new("clear_log_button", "Clear").on_click(cx.listener(
+                            |this, _, window, cx| {
+                                if let Some(log_view) = this.log_view.as_ref() {
+                                    log_view.update(cx, |log_view, cx| {
+                                        log_view.editor.update(cx, |editor, cx| {
+                                            editor.set_read_only(false);
+                                            editor.clear(window, cx);
+                                            editor.set_read_only(true);
+                                        });
+                                    })
+                                }
+                            },
+                        )),
+                    ),
             )
     }
 }
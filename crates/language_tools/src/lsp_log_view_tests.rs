@@ -105,6 +105,86 @@ async fn test_lsp_log_view(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_lsp_rpc_trace_export(cx: &mut TestAppContext) {
+    zlog::init_test();
+
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(path!("/the-root"), json!({ "test.rs": "" }))
+        .await;
+
+    let project = Project::test(fs.clone(), [path!("/the-root").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(Arc::new(Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::LANGUAGE.into()),
+    )));
+    let mut fake_rust_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            name: "the-rust-language-server",
+            ..Default::default()
+        },
+    );
+
+    let log_store = cx.new(|cx| LogStore::new(false, cx));
+    log_store.update(cx, |store, cx| store.add_project(&project, cx));
+
+    let _rust_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/the-root/test.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let mut language_server = fake_rust_servers.next().await.unwrap();
+    language_server
+        .receive_notification::<lsp::notification::DidOpenTextDocument>()
+        .await;
+    let server_id = language_server.server.server_id();
+
+    log_store.update(cx, |store, _| {
+        assert_eq!(
+            store.rpc_trace_jsonl(server_id),
+            None,
+            "No trace should be exported when RPC tracing is off"
+        );
+        store.enable_rpc_trace_for_language_server(server_id);
+    });
+
+    language_server.notify::<lsp::notification::LogMessage>(&lsp::LogMessageParams {
+        message: "hello from the server".into(),
+        typ: lsp::MessageType::INFO,
+    });
+    cx.executor().run_until_parked();
+
+    let exported = log_store
+        .read_with(cx, |store, _| store.rpc_trace_jsonl(server_id))
+        .unwrap();
+    let entries = exported
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(entries.len(), 1, "Unexpected RPC trace: {exported}");
+    assert_eq!(entries[0]["server_id"], json!(server_id.0));
+    assert_eq!(entries[0]["direction"], json!("receive"));
+    assert_eq!(entries[0]["message"]["method"], json!("window/logMessage"));
+    assert_eq!(
+        entries[0]["message"]["params"]["message"],
+        json!("hello from the server")
+    );
+}
+
 fn init_test(cx: &mut gpui::TestAppContext) {
     cx.update(|cx| {
         let settings_store = SettingsStore::test(cx);
@@ -0,0 +1,550 @@
+//! LocalHistoryDiffView shows how a file changed since one of its local history snapshots, and
+//! allows restoring hunks from the snapshot.
+
+use crate::{
+    LocalHistory,
+    history_store::{HistoryEntry, SnapshotSource},
+};
+use anyhow::Result;
+use buffer_diff::{BufferDiff, BufferDiffSnapshot, DiffHunkStatus};
+use editor::{Editor, EditorEvent, MultiBuffer, ToPoint as _};
+use futures::{FutureExt, select_biased};
+use gpui::{
+    AnyElement, AnyView, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter,
+    FocusHandle, Focusable, IntoElement, Render, Task, Window,
+};
+use language::{Buffer, Capability};
+use project::Project;
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    path::PathBuf,
+    pin::pin,
+    sync::Arc,
+    time::Duration,
+};
+use time::OffsetDateTime;
+use time_format::TimestampFormat;
+use ui::{Tooltip, prelude::*};
+use workspace::{
+    Item, ItemHandle as _, ItemNavHistory, ToolbarItemLocation, Workspace,
+    item::{BreadcrumbText, ItemEvent, SaveOptions, TabContentParams},
+    searchable::SearchableItemHandle,
+};
+
+pub struct LocalHistoryDiffView {
+    editor: Entity<Editor>,
+    snapshot_buffer: Entity<Buffer>,
+    buffer: Entity<Buffer>,
+    entry: HistoryEntry,
+    buffer_changes_tx: watch::Sender<()>,
+    _recalculate_diff_task: Task<Result<()>>,
+}
+
+const RECALCULATE_DIFF_DEBOUNCE: Duration = Duration::from_millis(250);
+
+impl LocalHistoryDiffView {
+    pub fn open(
+        buffer: Entity<Buffer>,
+        abs_path: PathBuf,
+        entry: HistoryEntry,
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let workspace = workspace.weak_handle();
+        let history = LocalHistory::global(cx);
+        window.spawn(cx, async move |cx| {
+            let project = workspace.update(cx, |workspace, _| workspace.project().clone())?;
+            let text = {
+                let entry = entry.clone();
+                cx.background_spawn(async move { history.load(&abs_path, &entry).await })
+                    .await?
+            };
+            let snapshot_buffer = cx.new(|cx| {
+                let language = buffer.read(cx).language().cloned();
+                let mut snapshot_buffer = Buffer::local(text, cx);
+                snapshot_buffer.set_language(language, cx);
+                snapshot_buffer.set_capability(Capability::ReadOnly, cx);
+                snapshot_buffer
+            })?;
+
+            let buffer_diff = build_buffer_diff(&snapshot_buffer, &buffer, cx).await?;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let diff_view = cx.new(|cx| {
+                    LocalHistoryDiffView::new(
+                        snapshot_buffer,
+                        buffer,
+                        entry,
+                        buffer_diff,
+                        project.clone(),
+                        window,
+                        cx,
+                    )
+                });
+
+                let pane = workspace.active_pane();
+                pane.update(cx, |pane, cx| {
+                    pane.add_item(Box::new(diff_view.clone()), true, true, None, window, cx);
+                });
+
+                diff_view
+            })
+        })
+    }
+
+    fn new(
+        snapshot_buffer: Entity<Buffer>,
+        buffer: Entity<Buffer>,
+        entry: HistoryEntry,
+        diff: Entity<BufferDiff>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::singleton(buffer.clone(), cx);
+            multibuffer.add_diff(diff.clone(), cx);
+            multibuffer
+        });
+        let editor = cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), window, cx);
+            editor.start_temporary_diff_override();
+            editor.disable_diagnostics(cx);
+            editor.set_expand_all_diff_hunks(cx);
+            editor.set_render_diff_hunk_controls(Arc::new(render_restore_hunk_controls), cx);
+            editor
+        });
+
+        let (buffer_changes_tx, mut buffer_changes_rx) = watch::channel(());
+
+        cx.subscribe(&buffer, move |this, _, event, _| match event {
+            language::BufferEvent::Edited
+            | language::BufferEvent::LanguageChanged
+            | language::BufferEvent::Reparsed => {
+                this.buffer_changes_tx.send(()).ok();
+            }
+            _ => {}
+        })
+        .detach();
+
+        Self {
+            editor,
+            buffer_changes_tx,
+            snapshot_buffer,
+            buffer,
+            entry,
+            _recalculate_diff_task: cx.spawn(async move |this, cx| {
+                while buffer_changes_rx.recv().await.is_ok() {
+                    loop {
+                        let mut timer = cx
+                            .background_executor()
+                            .timer(RECALCULATE_DIFF_DEBOUNCE)
+                            .fuse();
+                        let mut recv = pin!(buffer_changes_rx.recv().fuse());
+                        select_biased! {
+                            _ = timer => break,
+                            _ = recv => continue,
+                        }
+                    }
+
+                    let (snapshot, buffer_snapshot) = this.update(cx, |this, cx| {
+                        (
+                            this.snapshot_buffer.read(cx).snapshot(),
+                            this.buffer.read(cx).snapshot(),
+                        )
+                    })?;
+                    let diff_snapshot = cx
+                        .update(|cx| {
+                            BufferDiffSnapshot::new_with_base_buffer(
+                                buffer_snapshot.text.clone(),
+                                Some(snapshot.text().into()),
+                                snapshot,
+                                cx,
+                            )
+                        })?
+                        .await;
+                    diff.update(cx, |diff, cx| {
+                        diff.set_snapshot(diff_snapshot, &buffer_snapshot, cx)
+                    })?;
+                }
+                Ok(())
+            }),
+        }
+    }
+
+    fn snapshot_description(&self) -> String {
+        let timestamp = time_format::format_local_timestamp(
+            self.entry.timestamp(),
+            OffsetDateTime::now_utc(),
+            TimestampFormat::EnhancedAbsolute,
+        );
+        match self.entry.source {
+            SnapshotSource::Save => format!("saved {timestamp}"),
+            SnapshotSource::ExternalChange => format!("before external change {timestamp}"),
+        }
+    }
+}
+
+async fn build_buffer_diff(
+    snapshot_buffer: &Entity<Buffer>,
+    buffer: &Entity<Buffer>,
+    cx: &mut AsyncApp,
+) -> Result<Entity<BufferDiff>> {
+    let snapshot = snapshot_buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+    let buffer_snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+
+    let diff_snapshot = cx
+        .update(|cx| {
+            BufferDiffSnapshot::new_with_base_buffer(
+                buffer_snapshot.text.clone(),
+                Some(snapshot.text().into()),
+                snapshot,
+                cx,
+            )
+        })?
+        .await;
+
+    cx.new(|cx| {
+        let mut diff = BufferDiff::new(&buffer_snapshot.text, cx);
+        diff.set_snapshot(diff_snapshot, &buffer_snapshot.text, cx);
+        diff
+    })
+}
+
+/// Replaces the usual stage and restore controls with a single button that restores the hunk
+/// from the snapshot.
+fn render_restore_hunk_controls(
+    row: u32,
+    _status: &DiffHunkStatus,
+    hunk_range: Range<editor::Anchor>,
+    is_created_file: bool,
+    line_height: Pixels,
+    editor: &Entity<Editor>,
+    _window: &mut Window,
+    cx: &mut App,
+) -> AnyElement {
+    h_flex()
+        .h(line_height)
+        .mr_1()
+        .px_0p5()
+        .pb_1()
+        .border_x_1()
+        .border_b_1()
+        .border_color(cx.theme().colors().border_variant)
+        .rounded_b_lg()
+        .bg(cx.theme().colors().editor_background)
+        .block_mouse_except_scroll()
+        .shadow_md()
+        .child(
+            Button::new(("restore", row as u64), "Restore")
+                .tooltip({
+                    let focus_handle = editor.focus_handle(cx);
+                    move |window, cx| {
+                        Tooltip::for_action_in(
+                            "Restore Hunk From Snapshot",
+                            &git::Restore,
+                            &focus_handle,
+                            window,
+                            cx,
+                        )
+                    }
+                })
+                .on_click({
+                    let editor = editor.clone();
+                    move |_event, window, cx| {
+                        editor.update(cx, |editor, cx| {
+                            let snapshot = editor.snapshot(window, cx);
+                            let point = hunk_range.start.to_point(&snapshot.buffer_snapshot);
+                            editor.restore_hunks_in_ranges(vec![point..point], window, cx);
+                        });
+                    }
+                })
+                .disabled(is_created_file),
+        )
+        .into_any_element()
+}
+
+impl EventEmitter<EditorEvent> for LocalHistoryDiffView {}
+
+impl Focusable for LocalHistoryDiffView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Item for LocalHistoryDiffView {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        let filename = self
+            .buffer
+            .read(cx)
+            .file()
+            .and_then(|file| {
+                Some(
+                    file.full_path(cx)
+                        .file_name()?
+                        .to_string_lossy()
+                        .to_string(),
+                )
+            })
+            .unwrap_or_else(|| "untitled".into());
+
+        format!("{filename} (Local History)").into()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let path = self
+            .buffer
+            .read(cx)
+            .file()
+            .map(|file| file.full_path(cx).to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".into());
+
+        Some(
+            format!(
+                "{path}, compared with the version {}",
+                self.snapshot_description()
+            )
+            .into(),
+        )
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Local History Diff Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.editor
+            .update(cx, |editor, cx| editor.save(options, project, window, cx))
+    }
+}
+
+impl Render for LocalHistoryDiffView {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        self.editor.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use editor::test::editor_test_context::assert_state_with_diff;
+    use gpui::TestAppContext;
+    use language::Point;
+    use project::FakeFs;
+    use unindent::unindent;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_restoring_hunk_from_snapshot(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        init_test(fs.clone(), cx);
+        let old_text = unindent(
+            "
+            line 1
+            old line 2
+            line 3
+            ",
+        );
+        fs.insert_tree(
+            path!("/project"),
+            serde_json::json!({ "main.rs": old_text.clone() }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let abs_path = PathBuf::from(path!("/project/main.rs"));
+        let history = cx.update(|_, cx| LocalHistory::global(cx));
+        history
+            .record(
+                &abs_path,
+                old_text,
+                SnapshotSource::Save,
+                OffsetDateTime::now_utc(),
+            )
+            .await
+            .unwrap();
+        let entry = history.entries(&abs_path).await.unwrap().remove(0);
+
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(&abs_path, cx))
+            .await
+            .unwrap();
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_text(
+                unindent(
+                    "
+                    line 1
+                    new line 2
+                    line 3
+                    line 4
+                    ",
+                ),
+                cx,
+            )
+        });
+
+        let diff_view = workspace
+            .update_in(cx, |workspace, window, cx| {
+                LocalHistoryDiffView::open(buffer.clone(), abs_path, entry, workspace, window, cx)
+            })
+            .await
+            .unwrap();
+        let editor = diff_view.read_with(cx, |diff_view, _| diff_view.editor.clone());
+        cx.run_until_parked();
+        assert_state_with_diff(
+            &editor,
+            cx,
+            &unindent(
+                "
+                  ˇline 1
+                - old line 2
+                + new line 2
+                  line 3
+                + line 4
+                ",
+            ),
+        );
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.restore_hunks_in_ranges(vec![Point::new(1, 0)..Point::new(1, 0)], window, cx);
+        });
+        cx.executor().advance_clock(RECALCULATE_DIFF_DEBOUNCE);
+        cx.run_until_parked();
+
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            unindent(
+                "
+                line 1
+                old line 2
+                line 3
+                line 4
+                ",
+            )
+        );
+        assert_state_with_diff(
+            &editor,
+            cx,
+            &unindent(
+                "
+                  ˇline 1
+                  old line 2
+                  line 3
+                + line 4
+                ",
+            ),
+        );
+    }
+}
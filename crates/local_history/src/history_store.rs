@@ -0,0 +1,329 @@
+use anyhow::{Context as _, Result};
+use fs::{Fs, RemoveOptions};
+use futures::lock::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use time::OffsetDateTime;
+
+/// How many snapshots are kept for each file before the oldest ones are discarded.
+const MAX_ENTRIES_PER_FILE: usize = 50;
+
+/// Files larger than this are not snapshotted.
+pub(crate) const MAX_SNAPSHOT_LEN: usize = 4 * 1024 * 1024;
+
+const INDEX_FILE_NAME: &str = "index.json";
+
+/// Why a snapshot was taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotSource {
+    /// The buffer was saved.
+    Save,
+    /// The file changed on disk and the buffer was about to be reloaded.
+    ExternalChange,
+}
+
+impl SnapshotSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SnapshotSource::Save => "Saved",
+            SnapshotSource::ExternalChange => "Before external change",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// The hash of the snapshot's contents, which is also the name of the file they're stored in.
+    pub content_hash: String,
+    /// When the snapshot was taken, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    pub source: SnapshotSource,
+}
+
+impl HistoryEntry {
+    pub fn timestamp(&self) -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(self.timestamp).unwrap_or(OffsetDateTime::UNIX_EPOCH)
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct HistoryIndex {
+    path: PathBuf,
+    entries: Vec<HistoryEntry>,
+}
+
+/// Stores snapshots of local files on disk, independently of any version control.
+///
+/// Every file gets its own directory, named after a hash of its absolute path, that holds an
+/// index of its snapshots alongside their contents. Contents are stored under their own hash, so
+/// saving the same text repeatedly doesn't duplicate it.
+pub struct LocalHistory {
+    fs: Arc<dyn Fs>,
+    root: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl LocalHistory {
+    pub fn new(fs: Arc<dyn Fs>, root: PathBuf) -> Self {
+        Self {
+            fs,
+            root,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Records a snapshot of `text` for the file at `abs_path`, returning whether one was added.
+    ///
+    /// Nothing is recorded if the text matches the most recent snapshot or is too large.
+    pub async fn record(
+        &self,
+        abs_path: &Path,
+        text: String,
+        source: SnapshotSource,
+        timestamp: OffsetDateTime,
+    ) -> Result<bool> {
+        if text.len() > MAX_SNAPSHOT_LEN {
+            return Ok(false);
+        }
+
+        let _guard = self.write_lock.lock().await;
+        let dir = self.dir_for_path(abs_path);
+        let mut index = self.load_index(&dir).await?;
+        let content_hash = hash(text.as_bytes());
+        if index
+            .entries
+            .last()
+            .is_some_and(|entry| entry.content_hash == content_hash)
+        {
+            return Ok(false);
+        }
+
+        self.fs.create_dir(&dir).await?;
+        let content_path = dir.join(&content_hash);
+        if !self.fs.is_file(&content_path).await {
+            self.fs.atomic_write(content_path, text).await?;
+        }
+
+        index.path = abs_path.to_path_buf();
+        index.entries.push(HistoryEntry {
+            content_hash,
+            timestamp: timestamp.unix_timestamp(),
+            source,
+        });
+        if index.entries.len() > MAX_ENTRIES_PER_FILE {
+            let excess = index.entries.len() - MAX_ENTRIES_PER_FILE;
+            let removed = index.entries.drain(..excess).collect::<Vec<_>>();
+            for entry in removed {
+                if index
+                    .entries
+                    .iter()
+                    .any(|kept| kept.content_hash == entry.content_hash)
+                {
+                    continue;
+                }
+                self.fs
+                    .remove_file(
+                        &dir.join(&entry.content_hash),
+                        RemoveOptions {
+                            recursive: false,
+                            ignore_if_not_exists: true,
+                        },
+                    )
+                    .await?;
+            }
+        }
+
+        self.fs
+            .atomic_write(dir.join(INDEX_FILE_NAME), serde_json::to_string(&index)?)
+            .await?;
+        Ok(true)
+    }
+
+    /// Returns the snapshots of the file at `abs_path`, newest first.
+    pub async fn entries(&self, abs_path: &Path) -> Result<Vec<HistoryEntry>> {
+        let mut entries = self.load_index(&self.dir_for_path(abs_path)).await?.entries;
+        entries.reverse();
+        Ok(entries)
+    }
+
+    /// Loads the contents of a snapshot of the file at `abs_path`.
+    pub async fn load(&self, abs_path: &Path, entry: &HistoryEntry) -> Result<String> {
+        let content_path = self.dir_for_path(abs_path).join(&entry.content_hash);
+        self.fs
+            .load(&content_path)
+            .await
+            .with_context(|| format!("loading local history snapshot {content_path:?}"))
+    }
+
+    fn dir_for_path(&self, abs_path: &Path) -> PathBuf {
+        self.root.join(hash(abs_path.to_string_lossy().as_bytes()))
+    }
+
+    async fn load_index(&self, dir: &Path) -> Result<HistoryIndex> {
+        let index_path = dir.join(INDEX_FILE_NAME);
+        if !self.fs.is_file(&index_path).await {
+            return Ok(HistoryIndex::default());
+        }
+        let contents = self.fs.load(&index_path).await?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("parsing local history index {index_path:?}"))
+    }
+}
+
+fn hash(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_recording_snapshots(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let history = LocalHistory::new(fs.clone(), PathBuf::from(path!("/history")));
+        let path = Path::new(path!("/project/src/main.rs"));
+        let now = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+
+        assert!(
+            history
+                .record(path, "one".into(), SnapshotSource::Save, now)
+                .await
+                .unwrap()
+        );
+        // Saving the same contents again doesn't add another snapshot.
+        assert!(
+            !history
+                .record(path, "one".into(), SnapshotSource::Save, now)
+                .await
+                .unwrap()
+        );
+        assert!(
+            history
+                .record(path, "two".into(), SnapshotSource::ExternalChange, now)
+                .await
+                .unwrap()
+        );
+
+        let entries = history.entries(path).await.unwrap();
+        assert_eq!(
+            entries.iter().map(|entry| entry.source).collect::<Vec<_>>(),
+            [SnapshotSource::ExternalChange, SnapshotSource::Save]
+        );
+        assert_eq!(history.load(path, &entries[0]).await.unwrap(), "two");
+        assert_eq!(history.load(path, &entries[1]).await.unwrap(), "one");
+        assert!(
+            history
+                .entries(Path::new(path!("/project/src/lib.rs")))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_discarding_old_snapshots(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let history = LocalHistory::new(fs.clone(), PathBuf::from(path!("/history")));
+        let path = Path::new(path!("/project/notes.txt"));
+        let now = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+
+        for ix in 0..MAX_ENTRIES_PER_FILE + 5 {
+            history
+                .record(path, format!("version {ix}"), SnapshotSource::Save, now)
+                .await
+                .unwrap();
+        }
+
+        let entries = history.entries(path).await.unwrap();
+        assert_eq!(entries.len(), MAX_ENTRIES_PER_FILE);
+        assert_eq!(
+            history.load(path, &entries[0]).await.unwrap(),
+            format!("version {}", MAX_ENTRIES_PER_FILE + 4)
+        );
+        assert_eq!(
+            history.load(path, entries.last().unwrap()).await.unwrap(),
+            "version 5"
+        );
+
+        let discarded = HistoryEntry {
+            content_hash: hash(b"version 0"),
+            timestamp: now.unix_timestamp(),
+            source: SnapshotSource::Save,
+        };
+        assert!(history.load(path, &discarded).await.is_err());
+    }
+
+    #[gpui::test]
+    async fn test_keeping_contents_shared_with_kept_snapshots(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let history = LocalHistory::new(fs.clone(), PathBuf::from(path!("/history")));
+        let path = Path::new(path!("/project/notes.txt"));
+        let now = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+
+        // Alternate between two versions, so the discarded snapshots share their contents with
+        // ones that are kept.
+        for ix in 0..MAX_ENTRIES_PER_FILE + 10 {
+            let text = if ix % 2 == 0 { "even" } else { "odd" };
+            assert!(
+                history
+                    .record(path, text.into(), SnapshotSource::Save, now)
+                    .await
+                    .unwrap()
+            );
+        }
+
+        let entries = history.entries(path).await.unwrap();
+        assert_eq!(entries.len(), MAX_ENTRIES_PER_FILE);
+        assert_eq!(history.load(path, &entries[0]).await.unwrap(), "odd");
+        assert_eq!(
+            history.load(path, entries.last().unwrap()).await.unwrap(),
+            "even"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_skipping_large_snapshots(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let history = LocalHistory::new(fs.clone(), PathBuf::from(path!("/history")));
+        let path = Path::new(path!("/project/data.json"));
+        let now = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+
+        assert!(
+            !history
+                .record(
+                    path,
+                    "a".repeat(MAX_SNAPSHOT_LEN + 1),
+                    SnapshotSource::Save,
+                    now
+                )
+                .await
+                .unwrap()
+        );
+        assert!(history.entries(path).await.unwrap().is_empty());
+        assert!(!fs.is_dir(&history.dir_for_path(path)).await);
+
+        assert!(
+            history
+                .record(
+                    path,
+                    "a".repeat(MAX_SNAPSHOT_LEN),
+                    SnapshotSource::Save,
+                    now
+                )
+                .await
+                .unwrap()
+        );
+        assert_eq!(history.entries(path).await.unwrap().len(), 1);
+    }
+}
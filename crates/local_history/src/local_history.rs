@@ -0,0 +1,161 @@
+mod history_diff_view;
+mod history_store;
+mod timeline;
+
+use fs::Fs;
+use gpui::{App, AppContext as _, Context, Entity, Global, actions};
+use language::{Buffer, BufferEvent};
+use project::buffer_store::BufferStoreEvent;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use workspace::Workspace;
+
+pub use history_diff_view::LocalHistoryDiffView;
+pub use history_store::{HistoryEntry, LocalHistory, SnapshotSource};
+
+actions!(
+    local_history,
+    [
+        /// Shows the saved versions of the active file, to compare it with or restore it from.
+        ShowTimeline
+    ]
+);
+
+struct GlobalLocalHistory(Arc<LocalHistory>);
+
+impl Global for GlobalLocalHistory {}
+
+impl LocalHistory {
+    pub fn global(cx: &App) -> Arc<Self> {
+        cx.global::<GlobalLocalHistory>().0.clone()
+    }
+}
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut App) {
+    let history = LocalHistory::new(fs, paths::local_history_dir().clone());
+    cx.set_global(GlobalLocalHistory(Arc::new(history)));
+
+    cx.observe_new(|workspace: &mut Workspace, _, cx| {
+        timeline::TimelinePicker::register(workspace);
+        record_snapshots(workspace, cx);
+    })
+    .detach();
+}
+
+/// Snapshots the project's local files whenever they are saved, and before they are reloaded
+/// because they changed on disk.
+fn record_snapshots(workspace: &mut Workspace, cx: &mut Context<Workspace>) {
+    let project = workspace.project().read(cx);
+    if !project.is_local() {
+        return;
+    }
+
+    let buffer_store = project.buffer_store().clone();
+    cx.subscribe(&buffer_store, |_, _, event, cx| {
+        if let BufferStoreEvent::BufferAdded(buffer) = event {
+            cx.subscribe(buffer, |_, buffer, event, cx| {
+                let source = match event {
+                    BufferEvent::Saved => SnapshotSource::Save,
+                    // The buffer still holds the previous contents at this point.
+                    BufferEvent::ReloadNeeded => SnapshotSource::ExternalChange,
+                    _ => return,
+                };
+                record_snapshot(&buffer, source, cx);
+            })
+            .detach();
+        }
+    })
+    .detach();
+}
+
+fn record_snapshot(buffer: &Entity<Buffer>, source: SnapshotSource, cx: &mut App) {
+    let buffer = buffer.read(cx);
+    // Check the size up front, to avoid copying the text of a buffer that's too large to keep.
+    if buffer.large_file_mode() || buffer.len() > history_store::MAX_SNAPSHOT_LEN {
+        return;
+    }
+    let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+        return;
+    };
+
+    let abs_path = file.abs_path(cx);
+    let text = buffer.text();
+    let history = LocalHistory::global(cx);
+    let timestamp = OffsetDateTime::now_utc();
+    cx.background_spawn(async move { history.record(&abs_path, text, source, timestamp).await })
+        .detach_and_log_err(cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use settings::{Settings as _, SettingsStore};
+    use std::path::Path;
+    use util::path;
+
+    pub(crate) fn init_test(fs: Arc<dyn Fs>, cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init_settings(cx);
+            theme::ThemeSettings::register(cx);
+            init(fs, cx);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_snapshots_on_save_and_external_change(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        init_test(fs.clone(), cx);
+        fs.insert_tree(path!("/project"), serde_json::json!({ "main.rs": "one\n" }))
+            .await;
+
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (_workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let abs_path = Path::new(path!("/project/main.rs"));
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(abs_path, cx))
+            .await
+            .unwrap();
+        let history = cx.update(|_, cx| LocalHistory::global(cx));
+        assert!(history.entries(abs_path).await.unwrap().is_empty());
+
+        // Before an external change is reloaded, the previous contents are recorded.
+        fs.save(abs_path, &"two\n".into(), Default::default())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(buffer.read_with(cx, |buffer, _| buffer.text()), "two\n");
+
+        buffer.update(cx, |buffer, cx| buffer.set_text("three\n", cx));
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        // Saving unchanged contents again doesn't add a snapshot.
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        let entries = history.entries(abs_path).await.unwrap();
+        assert_eq!(
+            entries.iter().map(|entry| entry.source).collect::<Vec<_>>(),
+            [SnapshotSource::Save, SnapshotSource::ExternalChange]
+        );
+        assert_eq!(
+            history.load(abs_path, &entries[0]).await.unwrap(),
+            "three\n"
+        );
+        assert_eq!(history.load(abs_path, &entries[1]).await.unwrap(), "one\n");
+    }
+}
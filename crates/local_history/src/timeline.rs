@@ -0,0 +1,276 @@
+use crate::{HistoryEntry, LocalHistory, LocalHistoryDiffView, ShowTimeline};
+use editor::Editor;
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ParentElement,
+    Render, Styled, Task, WeakEntity, Window,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use std::{path::PathBuf, sync::Arc};
+use time::OffsetDateTime;
+use time_format::TimestampFormat;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+/// Lists the snapshots of a file, newest first, and opens a diff against the selected one.
+pub(crate) struct TimelinePicker {
+    picker: Entity<Picker<TimelinePickerDelegate>>,
+}
+
+impl TimelinePicker {
+    pub(crate) fn register(workspace: &mut Workspace) {
+        workspace.register_action(|workspace, _: &ShowTimeline, window, cx| {
+            Self::toggle(workspace, window, cx);
+        });
+    }
+
+    fn toggle(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let Some(abs_path) = buffer
+            .read(cx)
+            .file()
+            .and_then(|file| file.as_local())
+            .map(|file| file.abs_path(cx))
+        else {
+            return;
+        };
+
+        let history = LocalHistory::global(cx);
+        cx.spawn_in(window, async move |workspace, cx| {
+            let entries = {
+                let abs_path = abs_path.clone();
+                cx.background_spawn(async move { history.entries(&abs_path).await })
+                    .await?
+            };
+            workspace.update_in(cx, |workspace, window, cx| {
+                let weak_workspace = cx.entity().downgrade();
+                workspace.toggle_modal(window, cx, move |window, cx| {
+                    TimelinePicker::new(weak_workspace, buffer, abs_path, entries, window, cx)
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        buffer: Entity<Buffer>,
+        abs_path: PathBuf,
+        entries: Vec<HistoryEntry>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = TimelinePickerDelegate::new(
+            cx.entity().downgrade(),
+            workspace,
+            buffer,
+            abs_path,
+            entries,
+        );
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+impl Render for TimelinePicker {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("LocalHistoryTimeline")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for TimelinePicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for TimelinePicker {}
+impl ModalView for TimelinePicker {}
+
+pub(crate) struct TimelinePickerDelegate {
+    timeline_picker: WeakEntity<TimelinePicker>,
+    workspace: WeakEntity<Workspace>,
+    buffer: Entity<Buffer>,
+    abs_path: PathBuf,
+    entries: Vec<HistoryEntry>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl TimelinePickerDelegate {
+    fn new(
+        timeline_picker: WeakEntity<TimelinePicker>,
+        workspace: WeakEntity<Workspace>,
+        buffer: Entity<Buffer>,
+        abs_path: PathBuf,
+        entries: Vec<HistoryEntry>,
+    ) -> Self {
+        let now = OffsetDateTime::now_utc();
+        let candidates = entries
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, entry)| {
+                let timestamp = time_format::format_local_timestamp(
+                    entry.timestamp(),
+                    now,
+                    TimestampFormat::EnhancedAbsolute,
+                );
+                StringMatchCandidate::new(candidate_id, &timestamp)
+            })
+            .collect();
+        Self {
+            timeline_picker,
+            workspace,
+            buffer,
+            abs_path,
+            entries,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for TimelinePickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select a version to compare with…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No local history for this file".into())
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let entry = self.entries[mat.candidate_id].clone();
+            let buffer = self.buffer.clone();
+            let abs_path = self.abs_path.clone();
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    LocalHistoryDiffView::open(buffer, abs_path, entry, workspace, window, cx)
+                        .detach_and_log_err(cx);
+                })
+                .log_err();
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.timeline_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                let mut matches = match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await;
+                // Keep the timeline in chronological order.
+                matches.sort_unstable_by_key(|mat| mat.candidate_id);
+                matches
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let entry = &self.entries[mat.candidate_id];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(
+                    Icon::new(IconName::HistoryRerun)
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            mat.string.clone(),
+                            mat.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(entry.source.label())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
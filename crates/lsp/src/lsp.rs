@@ -99,6 +99,7 @@ pub struct LanguageServer {
     #[allow(clippy::type_complexity)]
     io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
     output_done_rx: Mutex<Option<barrier::Receiver>>,
+    input_done_rx: barrier::Receiver,
     server: Arc<Mutex<Option<Child>>>,
     workspace_folders: Option<Arc<Mutex<BTreeSet<Uri>>>>,
     root_uri: Uri,
@@ -397,6 +398,7 @@ impl LanguageServer {
     {
         let (outbound_tx, outbound_rx) = channel::unbounded::<String>();
         let (output_done_tx, output_done_rx) = barrier::channel();
+        let (input_done_tx, input_done_rx) = barrier::channel();
         let notification_handlers =
             Arc::new(Mutex::new(HashMap::<_, NotificationHandler>::default()));
         let response_handlers =
@@ -454,6 +456,7 @@ impl LanguageServer {
             .unwrap_or_else(|| Task::ready(None));
         let input_task = cx.background_spawn(async move {
             let (stdout, stderr) = futures::join!(stdout_input_task, stderr_input_task);
+            drop(input_done_tx);
             stdout.or(stderr)
         });
         let output_task = cx.background_spawn({
@@ -492,6 +495,7 @@ impl LanguageServer {
             executor: cx.background_executor().clone(),
             io_tasks: Mutex::new(Some((input_task, output_task))),
             output_done_rx: Mutex::new(Some(output_done_rx)),
+            input_done_rx,
             server: Arc::new(Mutex::new(server)),
             workspace_folders,
             root_uri,
@@ -793,6 +797,9 @@ impl LanguageServer {
                         }),
                         dynamic_registration: Some(true),
                     }),
+                    inline_value: Some(InlineValueClientCapabilities {
+                        dynamic_registration: Some(true),
+                    }),
                     publish_diagnostics: Some(PublishDiagnosticsClientCapabilities {
                         related_information: Some(true),
                         version_support: Some(true),
@@ -846,6 +853,10 @@ impl LanguageServer {
                     color_provider: Some(DocumentColorClientCapabilities {
                         dynamic_registration: Some(true),
                     }),
+                    document_link: Some(DocumentLinkClientCapabilities {
+                        dynamic_registration: Some(true),
+                        tooltip_support: Some(true),
+                    }),
                     ..TextDocumentClientCapabilities::default()
                 }),
                 experimental: Some(json!({
@@ -855,8 +866,11 @@ impl LanguageServer {
                 window: Some(WindowClientCapabilities {
                     work_done_progress: Some(true),
                     show_message: Some(ShowMessageRequestClientCapabilities {
-                        message_action_item: None,
+                        message_action_item: Some(MessageActionItemCapabilities {
+                            additional_properties_support: Some(true),
+                        }),
                     }),
+                    show_document: Some(ShowDocumentClientCapabilities { support: true }),
                     ..WindowClientCapabilities::default()
                 }),
             },
@@ -914,6 +928,7 @@ impl LanguageServer {
             let outbound_tx = self.outbound_tx.clone();
             let executor = self.executor.clone();
             let mut output_done = self.output_done_rx.lock().take().unwrap();
+            let mut input_done = self.input_done_rx.clone();
             let shutdown_request = Self::request_internal::<request::Shutdown>(
                 &next_id,
                 &response_handlers,
@@ -948,6 +963,11 @@ impl LanguageServer {
                     _ = timer => {
                         log::info!("timeout waiting for language server {name} (id {server_id}) to shutdown");
                     },
+
+                    // A server that has already exited cannot respond to the shutdown request.
+                    _ = input_done.recv().fuse() => {
+                        log::info!("language server {name} (id {server_id}) exited before shutdown");
+                    },
                 }
 
                 response_handlers.lock().take();
@@ -1128,6 +1148,20 @@ impl LanguageServer {
         &self.process_name
     }
 
+    /// Get the id of the language server process, if it is running as a separate process.
+    pub fn process_id(&self) -> Option<u32> {
+        self.server.lock().as_ref().map(|child| child.id())
+    }
+
+    /// Returns a future that resolves once the language server stops producing output,
+    /// which happens when its process exits, either after a shutdown or due to a crash.
+    pub fn exited(&self) -> impl 'static + Send + Future<Output = ()> + use<> {
+        let mut input_done = self.input_done_rx.clone();
+        async move {
+            input_done.recv().await;
+        }
+    }
+
     /// Get the reported capabilities of the running language server.
     pub fn capabilities(&self) -> ServerCapabilities {
         self.capabilities.read().clone()
@@ -1691,6 +1725,11 @@ impl FakeLanguageServer {
         self.server.notify::<T>(params).ok();
     }
 
+    /// Simulates the language server process exiting unexpectedly, closing its side of the connection.
+    pub fn crash(&self) {
+        self.server.io_tasks.lock().take();
+    }
+
     /// See [`LanguageServer::request`].
     pub async fn request<T>(&self, params: T::Params) -> ConnectionResult<T::Result>
     where
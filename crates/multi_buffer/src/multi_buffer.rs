@@ -117,6 +117,7 @@ pub enum Event {
 
     LanguageChanged(BufferId),
     CapabilityChanged,
+    LargeFileModeChanged(BufferId),
     Reparsed(BufferId),
     Saved,
     FileHandleChanged,
@@ -2454,6 +2455,9 @@ impl MultiBuffer {
                 self.capability = buffer.read(cx).capability();
                 Event::CapabilityChanged
             }
+            language::BufferEvent::LargeFileModeChanged => {
+                Event::LargeFileModeChanged(buffer.read(cx).remote_id())
+            }
             language::BufferEvent::Operation { .. } => return,
         });
     }
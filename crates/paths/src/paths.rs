@@ -186,6 +186,14 @@ pub fn database_dir() -> &'static PathBuf {
     DATABASE_DIR.get_or_init(|| data_dir().join("db"))
 }
 
+/// Returns the path to the local history directory.
+///
+/// This is where snapshots of files taken on save and before external changes are stored.
+pub fn local_history_dir() -> &'static PathBuf {
+    static LOCAL_HISTORY_DIR: OnceLock<PathBuf> = OnceLock::new();
+    LOCAL_HISTORY_DIR.get_or_init(|| data_dir().join("local_history"))
+}
+
 /// Returns the path to the crashes directory, if it exists for the current platform.
 pub fn crashes_dir() -> &'static Option<PathBuf> {
     static CRASHES_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
@@ -224,6 +232,24 @@ pub fn keymap_file() -> &'static PathBuf {
     KEYMAP_FILE.get_or_init(|| config_dir().join("keymap.json"))
 }
 
+/// Returns the path to the `macros.json` file, where named editor macros are saved.
+pub fn macros_file() -> &'static PathBuf {
+    static MACROS_FILE: OnceLock<PathBuf> = OnceLock::new();
+    MACROS_FILE.get_or_init(|| config_dir().join("macros.json"))
+}
+
+/// Returns the path to the `dictionary.txt` file, which lists the words added to the spell checker.
+pub fn user_dictionary_file() -> &'static PathBuf {
+    static USER_DICTIONARY_FILE: OnceLock<PathBuf> = OnceLock::new();
+    USER_DICTIONARY_FILE.get_or_init(|| config_dir().join("dictionary.txt"))
+}
+
+/// Returns the path to the directory where Hunspell dictionaries can be installed for spell checking.
+pub fn dictionaries_dir() -> &'static PathBuf {
+    static DICTIONARIES_DIR: OnceLock<PathBuf> = OnceLock::new();
+    DICTIONARIES_DIR.get_or_init(|| config_dir().join("dictionaries"))
+}
+
 /// Returns the path to the `keymap_backup.json` file.
 pub fn keymap_backup_file() -> &'static PathBuf {
     static KEYMAP_FILE: OnceLock<PathBuf> = OnceLock::new();
@@ -0,0 +1,350 @@
+//! Module for managing bookmarks in a project.
+//!
+//! Bookmarks are anchored to buffer positions, so they follow the text they were placed on as the
+//! buffer is edited. They are keyed by absolute path so they can be persisted with the workspace.
+use anyhow::Result;
+use collections::BTreeMap;
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Subscription, Task};
+use language::{Buffer, BufferEvent, BufferSnapshot, DiskState};
+use std::{path::Path, sync::Arc};
+use text::Point;
+
+use crate::{ProjectPath, buffer_store::BufferStore, worktree_store::WorktreeStore};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bookmark {
+    pub position: text::Anchor,
+    pub label: Option<Arc<str>>,
+}
+
+/// A bookmark as it is stored in the workspace database.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerializedBookmark {
+    pub path: Arc<Path>,
+    pub row: u32,
+    pub label: Option<Arc<str>>,
+}
+
+struct BookmarksInFile {
+    buffer: Entity<Buffer>,
+    /// Kept sorted by position.
+    bookmarks: Vec<Bookmark>,
+    _subscription: Subscription,
+}
+
+impl BookmarksInFile {
+    fn new(buffer: Entity<Buffer>, cx: &mut Context<BookmarkStore>) -> Self {
+        let subscription = cx.subscribe(&buffer, |bookmark_store, buffer, event, cx| {
+            if let BufferEvent::FileHandleChanged = event {
+                bookmark_store.on_file_handle_changed(buffer, cx);
+            }
+        });
+        Self {
+            buffer,
+            bookmarks: Vec::new(),
+            _subscription: subscription,
+        }
+    }
+
+    fn index_for_row(&self, row: u32, snapshot: &BufferSnapshot) -> Result<usize, usize> {
+        self.bookmarks
+            .binary_search_by(|bookmark| bookmark.position.summary::<Point>(snapshot).row.cmp(&row))
+    }
+}
+
+pub enum BookmarkStoreEvent {
+    BookmarksUpdated(Arc<Path>),
+    BookmarksCleared(Vec<Arc<Path>>),
+}
+
+impl EventEmitter<BookmarkStoreEvent> for BookmarkStore {}
+
+pub struct BookmarkStore {
+    bookmarks: BTreeMap<Arc<Path>, BookmarksInFile>,
+    worktree_store: Entity<WorktreeStore>,
+    buffer_store: Entity<BufferStore>,
+}
+
+impl BookmarkStore {
+    pub fn new(worktree_store: Entity<WorktreeStore>, buffer_store: Entity<BufferStore>) -> Self {
+        Self {
+            bookmarks: BTreeMap::default(),
+            worktree_store,
+            buffer_store,
+        }
+    }
+
+    pub fn abs_path_from_buffer(buffer: &Entity<Buffer>, cx: &App) -> Option<Arc<Path>> {
+        worktree::File::from_dyn(buffer.read(cx).file())
+            .and_then(|file| file.worktree.read(cx).absolutize(&file.path).ok())
+            .map(Arc::<Path>::from)
+    }
+
+    /// Adds a bookmark on the row containing `position`, or removes the one that is already there.
+    pub fn toggle_bookmark(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(abs_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.summary::<Point>(&snapshot).row;
+        let bookmarks_in_file = self
+            .bookmarks
+            .entry(abs_path.clone())
+            .or_insert_with(|| BookmarksInFile::new(buffer, cx));
+
+        match bookmarks_in_file.index_for_row(row, &snapshot) {
+            Ok(ix) => {
+                bookmarks_in_file.bookmarks.remove(ix);
+                if bookmarks_in_file.bookmarks.is_empty() {
+                    self.bookmarks.remove(&abs_path);
+                }
+            }
+            Err(ix) => bookmarks_in_file.bookmarks.insert(
+                ix,
+                Bookmark {
+                    position: snapshot.anchor_before(Point::new(row, 0)),
+                    label: None,
+                },
+            ),
+        }
+
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated(abs_path));
+        cx.notify();
+    }
+
+    /// Sets the label of the bookmark on the row containing `position`, adding a bookmark there
+    /// if there isn't one already.
+    pub fn set_bookmark_label(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        label: Option<Arc<str>>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(abs_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.summary::<Point>(&snapshot).row;
+        let label = label.filter(|label| !label.trim().is_empty());
+        let bookmarks_in_file = self
+            .bookmarks
+            .entry(abs_path.clone())
+            .or_insert_with(|| BookmarksInFile::new(buffer, cx));
+
+        match bookmarks_in_file.index_for_row(row, &snapshot) {
+            Ok(ix) => bookmarks_in_file.bookmarks[ix].label = label,
+            Err(ix) => bookmarks_in_file.bookmarks.insert(
+                ix,
+                Bookmark {
+                    position: snapshot.anchor_before(Point::new(row, 0)),
+                    label,
+                },
+            ),
+        }
+
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated(abs_path));
+        cx.notify();
+    }
+
+    pub fn remove_bookmark(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: text::Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(abs_path) = Self::abs_path_from_buffer(buffer, cx) else {
+            return;
+        };
+        let Some(bookmarks_in_file) = self.bookmarks.get_mut(&abs_path) else {
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.summary::<Point>(&snapshot).row;
+        if let Ok(ix) = bookmarks_in_file.index_for_row(row, &snapshot) {
+            bookmarks_in_file.bookmarks.remove(ix);
+            if bookmarks_in_file.bookmarks.is_empty() {
+                self.bookmarks.remove(&abs_path);
+            }
+            cx.emit(BookmarkStoreEvent::BookmarksUpdated(abs_path));
+            cx.notify();
+        }
+    }
+
+    pub fn clear_bookmarks(&mut self, cx: &mut Context<Self>) {
+        let paths = self.bookmarks.keys().cloned().collect();
+        self.bookmarks.clear();
+        cx.emit(BookmarkStoreEvent::BookmarksCleared(paths));
+        cx.notify();
+    }
+
+    /// The bookmark on the row containing `position`, if any.
+    pub fn bookmark_at(
+        &self,
+        buffer: &Entity<Buffer>,
+        position: text::Anchor,
+        cx: &App,
+    ) -> Option<&Bookmark> {
+        let bookmarks_in_file = self
+            .bookmarks
+            .get(&Self::abs_path_from_buffer(buffer, cx)?)?;
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.summary::<Point>(&snapshot).row;
+        let ix = bookmarks_in_file.index_for_row(row, &snapshot).ok()?;
+        bookmarks_in_file.bookmarks.get(ix)
+    }
+
+    /// The bookmarks in `buffer`, ordered by position.
+    pub fn bookmarks_for_buffer(&self, buffer: &Entity<Buffer>, cx: &App) -> &[Bookmark] {
+        Self::abs_path_from_buffer(buffer, cx)
+            .and_then(|path| self.bookmarks.get(&path))
+            .map_or(&[], |bookmarks_in_file| &bookmarks_in_file.bookmarks)
+    }
+
+    /// All bookmarks in the project, ordered by path and then by position.
+    pub fn all_bookmarks(&self) -> impl Iterator<Item = (&Arc<Path>, &Entity<Buffer>, &Bookmark)> {
+        self.bookmarks.iter().flat_map(|(path, bookmarks_in_file)| {
+            bookmarks_in_file
+                .bookmarks
+                .iter()
+                .map(move |bookmark| (path, &bookmarks_in_file.buffer, bookmark))
+        })
+    }
+
+    pub fn all_serialized_bookmarks(
+        &self,
+        cx: &App,
+    ) -> BTreeMap<Arc<Path>, Vec<SerializedBookmark>> {
+        self.bookmarks
+            .iter()
+            .map(|(path, bookmarks_in_file)| {
+                let snapshot = bookmarks_in_file.buffer.read(cx).snapshot();
+                (
+                    path.clone(),
+                    bookmarks_in_file
+                        .bookmarks
+                        .iter()
+                        .map(|bookmark| SerializedBookmark {
+                            path: path.clone(),
+                            row: bookmark.position.summary::<Point>(&snapshot).row,
+                            label: bookmark.label.clone(),
+                        })
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn with_serialized_bookmarks(
+        &self,
+        bookmarks: BTreeMap<Arc<Path>, Vec<SerializedBookmark>>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let worktree_store = self.worktree_store.clone();
+        let buffer_store = self.buffer_store.clone();
+        cx.spawn(async move |this, cx| {
+            let mut new_bookmarks = BTreeMap::default();
+            for (path, serialized_bookmarks) in bookmarks {
+                if serialized_bookmarks.is_empty() {
+                    continue;
+                }
+                let (worktree, relative_path) = worktree_store
+                    .update(cx, |worktree_store, cx| {
+                        worktree_store.find_or_create_worktree(&path, false, cx)
+                    })?
+                    .await?;
+                let buffer = buffer_store
+                    .update(cx, |buffer_store, cx| {
+                        let project_path = ProjectPath {
+                            worktree_id: worktree.read(cx).id(),
+                            path: relative_path.into(),
+                        };
+                        buffer_store.open_buffer(project_path, cx)
+                    })?
+                    .await;
+                let Ok(buffer) = buffer else {
+                    log::error!("Failed to open {path:?} to restore its bookmarks");
+                    continue;
+                };
+                let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+
+                let mut bookmarks_in_file =
+                    this.update(cx, |_, cx| BookmarksInFile::new(buffer, cx))?;
+                for serialized_bookmark in serialized_bookmarks {
+                    if serialized_bookmark.row > snapshot.max_point().row {
+                        log::error!("skipping a deserialized bookmark that's out of range");
+                        continue;
+                    }
+                    if let Err(ix) =
+                        bookmarks_in_file.index_for_row(serialized_bookmark.row, &snapshot)
+                    {
+                        bookmarks_in_file.bookmarks.insert(
+                            ix,
+                            Bookmark {
+                                position: snapshot
+                                    .anchor_before(Point::new(serialized_bookmark.row, 0)),
+                                label: serialized_bookmark.label,
+                            },
+                        );
+                    }
+                }
+                new_bookmarks.insert(path, bookmarks_in_file);
+            }
+
+            this.update(cx, |this, cx| {
+                log::debug!("Deserialized bookmarks in {} files", new_bookmarks.len());
+                this.bookmarks = new_bookmarks;
+                cx.notify();
+            })
+        })
+    }
+
+    fn on_file_handle_changed(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let entity_id = buffer.entity_id();
+        if buffer
+            .read(cx)
+            .file()
+            .is_none_or(|file| file.disk_state() == DiskState::Deleted)
+        {
+            let mut removed_paths = Vec::new();
+            self.bookmarks.retain(|path, bookmarks_in_file| {
+                let retain = bookmarks_in_file.buffer.entity_id() != entity_id;
+                if !retain {
+                    removed_paths.push(path.clone());
+                }
+                retain
+            });
+            if !removed_paths.is_empty() {
+                cx.emit(BookmarkStoreEvent::BookmarksCleared(removed_paths));
+                cx.notify();
+            }
+            return;
+        }
+
+        let Some(new_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+        if self.bookmarks.contains_key(&new_path) {
+            return;
+        }
+        let Some(old_path) = self
+            .bookmarks
+            .iter()
+            .find(|(_, bookmarks_in_file)| bookmarks_in_file.buffer.entity_id() == entity_id)
+            .map(|(path, _)| path.clone())
+        else {
+            return;
+        };
+        if let Some(bookmarks_in_file) = self.bookmarks.remove(&old_path) {
+            self.bookmarks.insert(new_path.clone(), bookmarks_in_file);
+            cx.emit(BookmarkStoreEvent::BookmarksCleared(vec![old_path]));
+            cx.emit(BookmarkStoreEvent::BookmarksUpdated(new_path));
+            cx.notify();
+        }
+    }
+}
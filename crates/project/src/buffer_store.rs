@@ -1,13 +1,14 @@
 use crate::{
     ProjectItem as _, ProjectPath,
     lsp_store::OpenLspBufferHandle,
+    project_settings::ProjectSettings,
     search::SearchQuery,
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
 use anyhow::{Context as _, Result, anyhow};
 use client::Client;
 use collections::{HashMap, HashSet, hash_map};
-use fs::Fs;
+use fs::{Encoding, Fs};
 use futures::{Future, FutureExt as _, StreamExt, channel::oneshot, future::Shared};
 use gpui::{
     App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity,
@@ -15,14 +16,15 @@ use gpui::{
 use language::{
     Buffer, BufferEvent, Capability, DiskState, File as _, Language, Operation,
     proto::{
-        deserialize_line_ending, deserialize_version, serialize_line_ending, serialize_version,
-        split_operations,
+        deserialize_encoding, deserialize_line_ending, deserialize_version, serialize_encoding,
+        serialize_line_ending, serialize_version, split_operations,
     },
 };
 use rpc::{
     AnyProtoClient, ErrorCode, ErrorExt as _, TypedEnvelope,
     proto::{self, ToProto},
 };
+use settings::Settings as _;
 use smol::channel::Receiver;
 use std::{io, path::Path, pin::pin, sync::Arc, time::Instant};
 use text::BufferId;
@@ -133,6 +135,7 @@ impl RemoteBufferStore {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id().into();
         let version = buffer.version();
+        let encoding = buffer.encoding();
         let rpc = self.upstream_client.clone();
         let project_id = self.project_id;
         cx.spawn(async move |_, cx| {
@@ -142,12 +145,15 @@ impl RemoteBufferStore {
                     buffer_id,
                     new_path,
                     version: serialize_version(&version),
+                    encoding: Some(serialize_encoding(encoding)),
                 })
                 .await?;
             let version = deserialize_version(&response.version);
             let mtime = response.mtime.map(|mtime| mtime.into());
+            let encoding = deserialize_encoding(&response.encoding);
 
             buffer_handle.update(cx, |buffer, cx| {
+                buffer.set_encoding(encoding, cx);
                 buffer.did_save(version.clone(), mtime, cx);
             })?;
 
@@ -335,6 +341,7 @@ impl RemoteBufferStore {
     fn reload_buffers(
         &self,
         buffers: HashSet<Entity<Buffer>>,
+        encoding: Option<Encoding>,
         push_to_history: bool,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<ProjectTransaction>> {
@@ -344,6 +351,7 @@ impl RemoteBufferStore {
                 .iter()
                 .map(|buffer| buffer.read(cx).remote_id().to_proto())
                 .collect(),
+            encoding: encoding.map(serialize_encoding),
         });
 
         cx.spawn(async move |this, cx| {
@@ -369,6 +377,7 @@ impl LocalBufferStore {
 
         let text = buffer.as_rope().clone();
         let line_ending = buffer.line_ending();
+        let encoding = buffer.encoding();
         let version = buffer.version();
         let buffer_id = buffer.remote_id();
         let file = buffer.file().cloned();
@@ -380,7 +389,7 @@ impl LocalBufferStore {
         }
 
         let save = worktree.update(cx, |worktree, cx| {
-            worktree.write_file(path.as_ref(), text, line_ending, cx)
+            worktree.write_file(path.as_ref(), text, line_ending, encoding, cx)
         });
 
         cx.spawn(async move |this, cx| {
@@ -403,6 +412,7 @@ impl LocalBufferStore {
                             buffer_id: buffer_id.to_proto(),
                             version: serialize_version(&version),
                             mtime: mtime.map(|time| time.into()),
+                            encoding: serialize_encoding(encoding),
                         })
                         .log_err();
                 }
@@ -609,22 +619,74 @@ impl LocalBufferStore {
         worktree: Entity<Worktree>,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<Entity<Buffer>>> {
-        let load_buffer = worktree.update(cx, |worktree, cx| {
-            let load_file = worktree.load_file(path.as_ref(), cx);
-            let reservation = cx.reserve_entity();
-            let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
-            cx.spawn(async move |_, cx| {
-                let loaded = load_file.await?;
-                let text_buffer = cx
-                    .background_spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
-                    .await;
-                cx.insert_entity(reservation, |_| {
-                    Buffer::build(text_buffer, Some(loaded.file), Capability::ReadWrite)
-                })
-            })
+        let large_file_threshold = Some(ProjectSettings::get_global(cx).large_file_threshold)
+            .filter(|threshold| *threshold > 0);
+        let file_size = large_file_threshold.and_then(|_| {
+            let worktree = worktree.read(cx).as_local()?;
+            let fs = worktree.fs().clone();
+            let abs_path = worktree.absolutize(&path).ok()?;
+            Some(async move { Some(fs.metadata(&abs_path).await.ok()??.len) })
         });
 
         cx.spawn(async move |this, cx| {
+            let file_size = match file_size {
+                Some(file_size) => file_size.await,
+                None => None,
+            };
+            let is_large_file = large_file_threshold
+                .zip(file_size)
+                .is_some_and(|(threshold, file_size)| file_size >= threshold);
+
+            let load_buffer = worktree.update(cx, |worktree, cx| {
+                let reservation = cx.reserve_entity();
+                let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
+                if is_large_file {
+                    let load_file = worktree.load_large_file(path.as_ref(), cx);
+                    cx.spawn(async move |_, cx| {
+                        let loaded = load_file.await?;
+                        let text_buffer = cx
+                            .background_spawn(async move {
+                                text::Buffer::new_normalized(
+                                    0,
+                                    buffer_id,
+                                    loaded.line_ending,
+                                    loaded.text,
+                                )
+                            })
+                            .await;
+                        cx.insert_entity(reservation, |cx| {
+                            let mut buffer = Buffer::build(
+                                text_buffer,
+                                Some(loaded.file),
+                                Capability::ReadWrite,
+                            );
+                            buffer.set_encoding(loaded.encoding, cx);
+                            buffer.set_large_file_mode(true, cx);
+                            buffer
+                        })
+                    })
+                } else {
+                    let load_file = worktree.load_file(path.as_ref(), cx);
+                    cx.spawn(async move |_, cx| {
+                        let loaded = load_file.await?;
+                        let text_buffer = cx
+                            .background_spawn(async move {
+                                text::Buffer::new(0, buffer_id, loaded.text)
+                            })
+                            .await;
+                        cx.insert_entity(reservation, |cx| {
+                            let mut buffer = Buffer::build(
+                                text_buffer,
+                                Some(loaded.file),
+                                Capability::ReadWrite,
+                            );
+                            buffer.set_encoding(loaded.encoding, cx);
+                            buffer
+                        })
+                    })
+                }
+            })?;
+
             let buffer = match load_buffer.await {
                 Ok(buffer) => Ok(buffer),
                 Err(error) if is_not_found_error(&error) => cx.new(|cx| {
@@ -684,13 +746,28 @@ impl LocalBufferStore {
     fn reload_buffers(
         &self,
         buffers: HashSet<Entity<Buffer>>,
+        encoding: Option<Encoding>,
         push_to_history: bool,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<ProjectTransaction>> {
         cx.spawn(async move |_, cx| {
             let mut project_transaction = ProjectTransaction::default();
             for buffer in buffers {
-                let transaction = buffer.update(cx, |buffer, cx| buffer.reload(cx))?.await?;
+                let previous_encoding = buffer.update(cx, |buffer, cx| {
+                    let previous_encoding = buffer.encoding();
+                    if let Some(encoding) = encoding {
+                        buffer.set_encoding(encoding, cx);
+                    }
+                    previous_encoding
+                })?;
+                let transaction = match buffer.update(cx, |buffer, cx| buffer.reload(cx))?.await {
+                    Ok(transaction) => transaction,
+                    Err(error) => {
+                        buffer
+                            .update(cx, |buffer, cx| buffer.set_encoding(previous_encoding, cx))?;
+                        return Err(error).context("reloading buffer");
+                    }
+                };
                 buffer.update(cx, |buffer, cx| {
                     if let Some(transaction) = transaction {
                         if !push_to_history {
@@ -713,6 +790,7 @@ impl BufferStore {
         client.add_entity_message_handler(Self::handle_update_buffer_file);
         client.add_entity_request_handler(Self::handle_save_buffer);
         client.add_entity_request_handler(Self::handle_reload_buffers);
+        client.add_entity_request_handler(Self::handle_set_buffer_large_file_mode);
     }
 
     /// Creates a buffer store, optionally retaining its buffers.
@@ -1150,9 +1228,26 @@ impl BufferStore {
                         version: serialize_version(&buffer.version()),
                         mtime: buffer.saved_mtime().map(|t| t.into()),
                         line_ending: serialize_line_ending(buffer.line_ending()) as i32,
+                        encoding: serialize_encoding(buffer.encoding()),
                     })
                     .log_err();
             }
+            BufferEvent::LargeFileModeChanged => {
+                // Language servers and git diffs run on the host, so it has to know when the
+                // user opts back into them.
+                let Some(remote) = self.as_remote() else {
+                    return;
+                };
+                let buffer = buffer.read(cx);
+                let request = remote
+                    .upstream_client
+                    .request(proto::SetBufferLargeFileMode {
+                        project_id: remote.project_id,
+                        buffer_id: buffer.remote_id().to_proto(),
+                        large_file_mode: buffer.large_file_mode(),
+                    });
+                cx.background_spawn(request).detach_and_log_err(cx);
+            }
             BufferEvent::LanguageChanged => {}
             _ => {}
         }
@@ -1266,6 +1361,7 @@ impl BufferStore {
                         mtime: buffer.saved_mtime().map(|time| time.into()),
                         line_ending: language::proto::serialize_line_ending(buffer.line_ending())
                             as i32,
+                        encoding: serialize_encoding(buffer.encoding()),
                     })
                     .log_err();
 
@@ -1378,6 +1474,11 @@ impl BufferStore {
             })?
             .await?;
         let buffer_id = buffer.read_with(&cx, |buffer, _| buffer.remote_id())?;
+        if let Some(encoding) = envelope.payload.encoding.as_deref() {
+            buffer.update(&mut cx, |buffer, cx| {
+                buffer.set_encoding(deserialize_encoding(encoding), cx)
+            })?;
+        }
 
         if let Some(new_path) = envelope.payload.new_path {
             let new_path = ProjectPath::from_proto(new_path);
@@ -1395,6 +1496,7 @@ impl BufferStore {
             buffer_id: buffer_id.into(),
             version: serialize_version(buffer.saved_version()),
             mtime: buffer.saved_mtime().map(|time| time.into()),
+            encoding: serialize_encoding(buffer.encoding()),
         })
     }
 
@@ -1431,9 +1533,11 @@ impl BufferStore {
         let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
         let version = deserialize_version(&envelope.payload.version);
         let mtime = envelope.payload.mtime.clone().map(|time| time.into());
+        let encoding = deserialize_encoding(&envelope.payload.encoding);
         this.update(&mut cx, move |this, cx| {
             if let Some(buffer) = this.get_possibly_incomplete(buffer_id) {
                 buffer.update(cx, |buffer, cx| {
+                    buffer.set_encoding(encoding, cx);
                     buffer.did_save(version, mtime, cx);
                 });
             }
@@ -1445,6 +1549,7 @@ impl BufferStore {
                         buffer_id: buffer_id.into(),
                         mtime: envelope.payload.mtime,
                         version: envelope.payload.version,
+                        encoding: envelope.payload.encoding,
                     })
                     .log_err();
             }
@@ -1463,9 +1568,11 @@ impl BufferStore {
             proto::LineEnding::from_i32(envelope.payload.line_ending)
                 .context("missing line ending")?,
         );
+        let encoding = deserialize_encoding(&envelope.payload.encoding);
         this.update(&mut cx, |this, cx| {
             if let Some(buffer) = this.get_possibly_incomplete(buffer_id) {
                 buffer.update(cx, |buffer, cx| {
+                    buffer.set_encoding(encoding, cx);
                     buffer.did_reload(version, line_ending, mtime, cx);
                 });
             }
@@ -1478,6 +1585,7 @@ impl BufferStore {
                         mtime: envelope.payload.mtime,
                         version: envelope.payload.version,
                         line_ending: envelope.payload.line_ending,
+                        encoding: envelope.payload.encoding,
                     })
                     .log_err();
             }
@@ -1489,29 +1597,63 @@ impl BufferStore {
         buffers: HashSet<Entity<Buffer>>,
         push_to_history: bool,
         cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        self.reload_buffers_with_encoding(buffers, None, push_to_history, cx)
+    }
+
+    /// Reloads the given buffers from disk. When an encoding is given, the buffers' files
+    /// are decoded with it and it's used for subsequent saves.
+    pub fn reload_buffers_with_encoding(
+        &self,
+        buffers: HashSet<Entity<Buffer>>,
+        encoding: Option<Encoding>,
+        push_to_history: bool,
+        cx: &mut Context<Self>,
     ) -> Task<Result<ProjectTransaction>> {
         if buffers.is_empty() {
             return Task::ready(Ok(ProjectTransaction::default()));
         }
         match &self.state {
-            BufferStoreState::Local(this) => this.reload_buffers(buffers, push_to_history, cx),
-            BufferStoreState::Remote(this) => this.reload_buffers(buffers, push_to_history, cx),
+            BufferStoreState::Local(this) => {
+                this.reload_buffers(buffers, encoding, push_to_history, cx)
+            }
+            BufferStoreState::Remote(this) => {
+                this.reload_buffers(buffers, encoding, push_to_history, cx)
+            }
         }
     }
 
+    async fn handle_set_buffer_large_file_mode(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::SetBufferLargeFileMode>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let buffer = this.read_with(&cx, |this, _| this.get_existing(buffer_id))??;
+        buffer.update(&mut cx, |buffer, cx| {
+            buffer.set_large_file_mode(envelope.payload.large_file_mode, cx)
+        })?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_reload_buffers(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::ReloadBuffers>,
         mut cx: AsyncApp,
     ) -> Result<proto::ReloadBuffersResponse> {
         let sender_id = envelope.original_sender_id().unwrap_or_default();
+        let encoding = envelope
+            .payload
+            .encoding
+            .as_deref()
+            .map(deserialize_encoding);
         let reload = this.update(&mut cx, |this, cx| {
             let mut buffers = HashSet::default();
             for buffer_id in &envelope.payload.buffer_ids {
                 let buffer_id = BufferId::new(*buffer_id)?;
                 buffers.insert(this.get_existing(buffer_id)?);
             }
-            anyhow::Ok(this.reload_buffers(buffers, false, cx))
+            anyhow::Ok(this.reload_buffers_with_encoding(buffers, encoding, false, cx))
         })??;
 
         let project_transaction = reload.await?;
@@ -156,6 +156,7 @@ pub struct ActiveStackFrame {
 
 pub struct BreakpointStore {
     breakpoints: BTreeMap<Arc<Path>, BreakpointsInFile>,
+    function_breakpoints: Vec<FunctionBreakpoint>,
     downstream_client: Option<(AnyProtoClient, u64)>,
     active_stack_frame: Option<ActiveStackFrame>,
     // E.g ssh
@@ -170,6 +171,7 @@ impl BreakpointStore {
     pub fn local(worktree_store: Entity<WorktreeStore>, buffer_store: Entity<BufferStore>) -> Self {
         BreakpointStore {
             breakpoints: BTreeMap::new(),
+            function_breakpoints: Vec::new(),
             mode: BreakpointStoreMode::Local(LocalBreakpointStore {
                 worktree_store,
                 buffer_store,
@@ -182,6 +184,7 @@ impl BreakpointStore {
     pub(crate) fn remote(upstream_project_id: u64, upstream_client: AnyProtoClient) -> Self {
         BreakpointStore {
             breakpoints: BTreeMap::new(),
+            function_breakpoints: Vec::new(),
             mode: BreakpointStoreMode::Remote(RemoteBreakpointStore {
                 upstream_client,
                 _upstream_project_id: upstream_project_id,
@@ -601,6 +604,70 @@ impl BreakpointStore {
         let breakpoint_paths = self.breakpoints.keys().cloned().collect();
         self.breakpoints.clear();
         cx.emit(BreakpointStoreEvent::BreakpointsCleared(breakpoint_paths));
+        if !self.function_breakpoints.is_empty() {
+            self.function_breakpoints.clear();
+            cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+        }
+    }
+
+    pub fn function_breakpoints(&self) -> &[FunctionBreakpoint] {
+        &self.function_breakpoints
+    }
+
+    pub fn add_function_breakpoint(&mut self, name: Arc<str>, cx: &mut Context<Self>) {
+        if name.is_empty()
+            || self
+                .function_breakpoints
+                .iter()
+                .any(|breakpoint| breakpoint.name == name)
+        {
+            return;
+        }
+        self.function_breakpoints.push(FunctionBreakpoint {
+            name,
+            condition: None,
+            hit_condition: None,
+            state: BreakpointState::Enabled,
+        });
+        cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+        cx.notify();
+    }
+
+    pub fn edit_function_breakpoint(
+        &mut self,
+        name: &str,
+        edit_action: BreakpointEditAction,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ix) = self
+            .function_breakpoints
+            .iter()
+            .position(|breakpoint| breakpoint.name.as_ref() == name)
+        else {
+            return;
+        };
+        let breakpoint = &mut self.function_breakpoints[ix];
+        match edit_action {
+            BreakpointEditAction::Toggle => {
+                self.function_breakpoints.remove(ix);
+            }
+            BreakpointEditAction::InvertState => {
+                breakpoint.state = match breakpoint.state {
+                    BreakpointState::Enabled => BreakpointState::Disabled,
+                    BreakpointState::Disabled => BreakpointState::Enabled,
+                };
+            }
+            BreakpointEditAction::EditCondition(condition) => {
+                breakpoint.condition = (!condition.is_empty()).then_some(condition);
+            }
+            BreakpointEditAction::EditHitCondition(hit_condition) => {
+                breakpoint.hit_condition = (!hit_condition.is_empty()).then_some(hit_condition);
+            }
+            // Function breakpoints can't log messages.
+            BreakpointEditAction::EditLogMessage(_) => return,
+        }
+        cx.emit(BreakpointStoreEvent::FunctionBreakpointsUpdated);
+        cx.notify();
     }
 
     pub fn breakpoints<'a>(
@@ -871,6 +938,7 @@ pub enum BreakpointStoreEvent {
     ClearDebugLines,
     BreakpointsUpdated(Arc<Path>, BreakpointUpdatedReason),
     BreakpointsCleared(Vec<Arc<Path>>),
+    FunctionBreakpointsUpdated,
 }
 
 impl EventEmitter<BreakpointStoreEvent> for BreakpointStore {}
@@ -1016,6 +1084,29 @@ pub struct SourceBreakpoint {
     pub state: BreakpointState,
 }
 
+/// Breakpoint on every call of a function, identified by its name.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct FunctionBreakpoint {
+    pub name: Arc<str>,
+    pub condition: Option<Arc<str>>,
+    pub hit_condition: Option<Arc<str>>,
+    pub state: BreakpointState,
+}
+
+impl From<FunctionBreakpoint> for dap::FunctionBreakpoint {
+    fn from(bp: FunctionBreakpoint) -> Self {
+        Self {
+            name: String::from(bp.name.as_ref()),
+            condition: bp
+                .condition
+                .map(|condition| String::from(condition.as_ref())),
+            hit_condition: bp
+                .hit_condition
+                .map(|hit_condition| String::from(hit_condition.as_ref())),
+        }
+    }
+}
+
 impl From<SourceBreakpoint> for dap::SourceBreakpoint {
     fn from(bp: SourceBreakpoint) -> Self {
         Self {
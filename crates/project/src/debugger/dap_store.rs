@@ -554,7 +554,7 @@ impl DapStore {
                 .read(cx)
                 .variables_by_stack_frame_id(stack_frame_id, true, false);
 
-        fn format_value(mut value: String) -> String {
+        fn truncate_value(mut value: String) -> String {
             const LIMIT: usize = 100;
 
             if let Some(index) = value.find("\n") {
@@ -572,7 +572,11 @@ impl DapStore {
                 value.push_str("…");
             }
 
-            format!(": {}", value)
+            value
+        }
+
+        fn format_value(value: String) -> String {
+            format!(": {}", truncate_value(value))
         }
 
         cx.spawn(async move |_, cx| {
@@ -584,7 +588,7 @@ impl DapStore {
                 ));
                 let position = snapshot.anchor_after(point);
 
-                match inline_value_location.lookup {
+                match &inline_value_location.lookup {
                     VariableLookupKind::Variable => {
                         let variable_search =
                             if inline_value_location.scope
@@ -637,6 +641,17 @@ impl DapStore {
                             });
                         };
                     }
+                    VariableLookupKind::Text(text) => {
+                        inlay_hints.push(InlayHint {
+                            position,
+                            label: InlayHintLabel::String(truncate_value(text.clone())),
+                            kind: Some(InlayHintKind::Type),
+                            padding_left: false,
+                            padding_right: false,
+                            tooltip: None,
+                            resolve_state: ResolveState::Resolved,
+                        });
+                    }
                 };
             }
 
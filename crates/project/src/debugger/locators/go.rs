@@ -266,6 +266,9 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
         };
 
         let scenario = locator
@@ -293,6 +296,9 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
         };
 
         let scenario = locator
@@ -431,6 +437,9 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
         };
 
         let scenario = locator
@@ -0,0 +1,233 @@
+//! Recordings of debug sessions, for inspecting a failure after the debuggee has exited.
+//!
+//! A recording holds a snapshot of every stop of a session: the threads, their stack frames, the scopes of those frames
+//! and the variables in them, up to a given depth. Deep stacks and large numbers of variables are truncated, so that
+//! each stop is captured quickly. Recordings are plain data, so they can be written to a file and opened on another
+//! machine.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use dap::client::DebugAdapterClient;
+use serde::{Deserialize, Serialize};
+use util::ResultExt as _;
+
+/// Containers with more children than this are truncated in a recording.
+const MAX_RECORDED_CHILDREN: usize = 100;
+
+/// How many of the innermost stack frames of each thread are recorded.
+pub const MAX_RECORDED_STACK_FRAMES: u64 = 32;
+
+/// How many variables are recorded at each stop, across all threads. Once they're used up, no more
+/// variables are requested, so that a stop is captured in a bounded number of requests.
+pub const MAX_RECORDED_VARIABLES: usize = 5_000;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SessionRecording {
+    pub label: String,
+    pub adapter: String,
+    pub stops: Vec<StopSnapshot>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StopSnapshot {
+    /// The thread that caused the stop, if the debug adapter reported one.
+    pub thread_id: Option<i64>,
+    pub threads: Vec<ThreadSnapshot>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ThreadSnapshot {
+    pub thread: dap::Thread,
+    /// Empty when the thread was still running at the time of the stop.
+    pub stack_frames: Vec<StackFrameSnapshot>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StackFrameSnapshot {
+    pub stack_frame: dap::StackFrame,
+    pub scopes: Vec<ScopeSnapshot>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScopeSnapshot {
+    pub scope: dap::Scope,
+    /// Empty for scopes that the debug adapter marked as expensive to fetch.
+    pub variables: Vec<VariableSnapshot>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VariableSnapshot {
+    pub variable: dap::Variable,
+    pub children: Vec<VariableSnapshot>,
+}
+
+pub(crate) async fn capture_stop(
+    client: Arc<DebugAdapterClient>,
+    thread_id: Option<i64>,
+    max_variable_depth: usize,
+) -> Result<StopSnapshot> {
+    let threads = client
+        .request::<dap::requests::Threads>(dap::ThreadsArgument {})
+        .await?
+        .threads;
+
+    // The variables are captured once the scopes of every thread are known, so that they can
+    // all share the stop's budget.
+    let mut scope_variables = Vec::new();
+    let mut thread_snapshots = Vec::with_capacity(threads.len());
+    for thread in threads {
+        // Debug adapters refuse to report the stack of running threads, so a failure here is expected.
+        let stack_frames = client
+            .request::<dap::requests::StackTrace>(dap::StackTraceArguments {
+                thread_id: thread.id,
+                start_frame: None,
+                levels: Some(MAX_RECORDED_STACK_FRAMES),
+                format: None,
+            })
+            .await
+            .map(|response| response.stack_frames)
+            .unwrap_or_default();
+        // Adapters may send more frames than were asked for.
+        let stack_frames = stack_frames
+            .into_iter()
+            .take(MAX_RECORDED_STACK_FRAMES as usize);
+
+        let mut stack_frame_snapshots = Vec::new();
+        for stack_frame in stack_frames {
+            let scopes = client
+                .request::<dap::requests::Scopes>(dap::ScopesArguments {
+                    frame_id: stack_frame.id,
+                })
+                .await
+                .log_err()
+                .map(|response| response.scopes)
+                .unwrap_or_default();
+
+            for (scope_ix, scope) in scopes.iter().enumerate() {
+                if !scope.expensive {
+                    scope_variables.push((
+                        (
+                            thread_snapshots.len(),
+                            stack_frame_snapshots.len(),
+                            scope_ix,
+                        ),
+                        scope.variables_reference,
+                    ));
+                }
+            }
+
+            stack_frame_snapshots.push(StackFrameSnapshot {
+                stack_frame,
+                scopes: scopes
+                    .into_iter()
+                    .map(|scope| ScopeSnapshot {
+                        scope,
+                        variables: Vec::new(),
+                    })
+                    .collect(),
+            });
+        }
+
+        thread_snapshots.push(ThreadSnapshot {
+            thread,
+            stack_frames: stack_frame_snapshots,
+        });
+    }
+
+    let (scopes, variables_references): (Vec<_>, Vec<_>) = scope_variables.into_iter().unzip();
+    let variables = capture_variables(&client, variables_references, max_variable_depth).await;
+    for ((thread_ix, stack_frame_ix, scope_ix), variables) in scopes.into_iter().zip(variables) {
+        thread_snapshots[thread_ix].stack_frames[stack_frame_ix].scopes[scope_ix].variables =
+            variables;
+    }
+
+    Ok(StopSnapshot {
+        thread_id,
+        threads: thread_snapshots,
+    })
+}
+
+/// Captures the variables in the given containers and their children, up to `max_depth` levels
+/// of nesting.
+///
+/// The variables are requested one level at a time, across all containers, so that a container
+/// with many nested variables can't use up the whole budget before its siblings are captured.
+async fn capture_variables(
+    client: &DebugAdapterClient,
+    variables_references: Vec<u64>,
+    max_depth: usize,
+) -> Vec<Vec<VariableSnapshot>> {
+    enum Parent {
+        Container(usize),
+        Variable(usize),
+    }
+
+    let mut containers = vec![Vec::new(); variables_references.len()];
+    // The captured variables, with the indices of their children in this list.
+    let mut variables = Vec::<(dap::Variable, Vec<usize>)>::new();
+    let mut budget = MAX_RECORDED_VARIABLES;
+
+    let mut level = variables_references
+        .into_iter()
+        .enumerate()
+        .map(|(ix, variables_reference)| (Parent::Container(ix), variables_reference))
+        .collect::<Vec<_>>();
+    for _ in 0..max_depth {
+        let mut next_level = Vec::new();
+        for (parent, variables_reference) in level {
+            if budget == 0 {
+                break;
+            }
+            if variables_reference == 0 {
+                continue;
+            }
+
+            let children = client
+                .request::<dap::requests::Variables>(dap::VariablesArguments {
+                    variables_reference,
+                    filter: None,
+                    start: None,
+                    count: None,
+                    format: None,
+                })
+                .await
+                .log_err()
+                .map(|response| response.variables)
+                .unwrap_or_default();
+
+            let len = children.len().min(MAX_RECORDED_CHILDREN).min(budget);
+            budget -= len;
+            for child in children.into_iter().take(len) {
+                let ix = variables.len();
+                match parent {
+                    Parent::Container(container_ix) => containers[container_ix].push(ix),
+                    Parent::Variable(variable_ix) => variables[variable_ix].1.push(ix),
+                }
+                next_level.push((Parent::Variable(ix), child.variables_reference));
+                variables.push((child, Vec::new()));
+            }
+        }
+        level = next_level;
+    }
+
+    // Children are always captured after their parents, so the tree can be assembled backwards.
+    let mut snapshots = Vec::<Option<VariableSnapshot>>::with_capacity(variables.len());
+    snapshots.resize_with(variables.len(), || None);
+    for (ix, (variable, children)) in variables.into_iter().enumerate().rev() {
+        let children = children
+            .into_iter()
+            .filter_map(|child_ix| snapshots[child_ix].take())
+            .collect();
+        snapshots[ix] = Some(VariableSnapshot { variable, children });
+    }
+    containers
+        .into_iter()
+        .map(|children| {
+            children
+                .into_iter()
+                .filter_map(|ix| snapshots[ix].take())
+                .collect()
+        })
+        .collect()
+}
@@ -6,14 +6,17 @@ use super::breakpoint_store::{
     BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason, SourceBreakpoint,
 };
 use super::dap_command::{
-    self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand, DisconnectCommand,
-    EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
-    ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetDataBreakpointsCommand, SetExceptionBreakpoints, SetVariableValueCommand,
+    self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand,
+    DisassembleCommand, DisconnectCommand, EvaluateCommand, GotoCommand, GotoTargetsCommand,
+    Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand, ModulesCommand,
+    NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand, ScopesCommand,
+    SetDataBreakpointsCommand, SetExceptionBreakpoints, SetExpressionCommand,
+    SetFunctionBreakpointsCommand, SetInstructionBreakpointsCommand, SetVariableValueCommand,
     StackTraceCommand, StepBackCommand, StepCommand, StepInCommand, StepOutCommand,
     TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
 };
 use super::dap_store::DapStore;
+use super::recording::{self, StopSnapshot};
 use anyhow::{Context as _, Result, anyhow};
 use base64::Engine;
 use collections::{HashMap, HashSet, IndexMap};
@@ -140,6 +143,12 @@ pub struct DataBreakpointState {
     pub context: Arc<DataBreakpointContext>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstructionBreakpointState {
+    pub dap: dap::InstructionBreakpoint,
+    pub is_enabled: bool,
+}
+
 pub enum SessionState {
     /// Represents a session that is building/initializing
     /// even if a session doesn't have a pre build task this state
@@ -166,7 +175,7 @@ pub struct SessionQuirks {
     pub prefer_thread_name: bool,
 }
 
-fn client_source(abs_path: &Path) -> dap::Source {
+pub(super) fn client_source(abs_path: &Path) -> dap::Source {
     dap::Source {
         name: abs_path
             .file_name()
@@ -331,6 +340,27 @@ impl RunningMode {
         self.request(arg)
     }
 
+    fn send_function_breakpoints(
+        &self,
+        ignore_breakpoints: bool,
+        breakpoint_store: &Entity<BreakpointStore>,
+        cx: &App,
+    ) -> Task<Result<Vec<dap::Breakpoint>>> {
+        let breakpoints = if ignore_breakpoints {
+            vec![]
+        } else {
+            breakpoint_store
+                .read(cx)
+                .function_breakpoints()
+                .iter()
+                .filter(|bp| bp.state.is_enabled())
+                .cloned()
+                .map(Into::into)
+                .collect()
+        };
+        self.request(SetFunctionBreakpointsCommand { breakpoints })
+    }
+
     fn send_source_breakpoints(
         &self,
         ignore_breakpoints: bool,
@@ -436,6 +466,8 @@ impl RunningMode {
         let supports_exception_filters = capabilities
             .supports_exception_filter_options
             .unwrap_or_default();
+        let supports_function_breakpoints =
+            SetFunctionBreakpointsCommand::is_supported(capabilities);
         let this = self.clone();
         let worktree = self.worktree().clone();
         let mut filters = capabilities
@@ -484,6 +516,12 @@ impl RunningMode {
                     }
                 })?;
 
+                if supports_function_breakpoints {
+                    cx.update(|cx| this.send_function_breakpoints(false, &breakpoint_store, cx))?
+                        .await
+                        .log_err();
+                }
+
                 if should_send_exception_breakpoints {
                     _ = session.update(cx, |this, _| {
                         filters.retain(|filter| {
@@ -691,6 +729,7 @@ pub struct Session {
     ignore_breakpoints: bool,
     exception_breakpoints: BTreeMap<String, (ExceptionBreakpointsFilter, IsEnabled)>,
     data_breakpoints: BTreeMap<String, DataBreakpointState>,
+    instruction_breakpoints: BTreeMap<String, InstructionBreakpointState>,
     background_tasks: Vec<Task<()>>,
     restart_task: Option<Task<()>>,
     task_context: TaskContext,
@@ -834,6 +873,9 @@ impl Session {
                         local.unset_breakpoints_from_paths(paths, cx).detach();
                     }
                 }
+                BreakpointStoreEvent::FunctionBreakpointsUpdated => {
+                    this.send_function_breakpoints(&store, cx);
+                }
                 BreakpointStoreEvent::SetDebugLine | BreakpointStoreEvent::ClearDebugLines => {}
             })
             .detach();
@@ -861,6 +903,7 @@ impl Session {
                 ignore_breakpoints: false,
                 breakpoint_store,
                 data_breakpoints: Default::default(),
+                instruction_breakpoints: Default::default(),
                 exception_breakpoints: Default::default(),
                 label,
                 adapter,
@@ -1339,6 +1382,83 @@ impl Session {
         }
     }
 
+    /// Moves execution of a stopped thread to the given row, without running the code in between.
+    ///
+    /// The first goto target that the debug adapter reports for the row is used.
+    pub fn goto_position(
+        &mut self,
+        path: Arc<Path>,
+        row: u32,
+        thread_id: ThreadId,
+        cx: &mut Context<Self>,
+    ) {
+        if !matches!(
+            self.thread_states.thread_state(thread_id),
+            Some(ThreadStatus::Stopped)
+        ) {
+            return;
+        }
+
+        let targets = self.goto_targets(path, row, cx);
+        cx.spawn(async move |this, cx| {
+            let Some(target) = targets.await.and_then(|targets| targets.into_iter().next()) else {
+                log::info!("Debug adapter reported no goto targets for row {row}");
+                return;
+            };
+            this.update(cx, |this, cx| this.goto(thread_id, target.id, cx))
+                .ok();
+        })
+        .detach();
+    }
+
+    pub fn goto_targets(
+        &mut self,
+        path: Arc<Path>,
+        row: u32,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Vec<dap::GotoTarget>>> {
+        self.request(
+            GotoTargetsCommand {
+                path,
+                line: row as u64 + 1,
+            },
+            |_, response, _| response.log_err(),
+            cx,
+        )
+    }
+
+    pub fn goto(&mut self, thread_id: ThreadId, target_id: u64, cx: &mut Context<Self>) {
+        self.thread_states.process_step(thread_id);
+        self.request(
+            GotoCommand {
+                thread_id: thread_id.0,
+                target_id,
+            },
+            Self::on_step_response::<GotoCommand>(thread_id),
+            cx,
+        )
+        .detach();
+    }
+
+    /// Captures the threads, stack frames, scopes and variables of this session, to be inspected after it has ended.
+    ///
+    /// Variables are captured up to `max_variable_depth` levels of nesting.
+    pub fn capture_stop(
+        &self,
+        thread_id: Option<ThreadId>,
+        max_variable_depth: usize,
+        cx: &App,
+    ) -> Task<Result<StopSnapshot>> {
+        let Some(client) = self.adapter_client() else {
+            return Task::ready(Err(anyhow!("no adapter running to capture a stop")));
+        };
+        cx.background_spawn(recording::capture_stop(
+            client,
+            thread_id.map(|thread_id| thread_id.0),
+            max_variable_depth,
+        ))
+    }
+
     pub fn has_new_output(&self, last_update: OutputToken) -> bool {
         self.output_token.0.checked_sub(last_update.0).unwrap_or(0) != 0
     }
@@ -1903,6 +2023,11 @@ impl Session {
         self.ignore_breakpoints = ignore;
 
         if let Some(local) = self.as_running() {
+            if SetFunctionBreakpointsCommand::is_supported(&self.capabilities) {
+                local
+                    .send_function_breakpoints(ignore, &self.breakpoint_store, cx)
+                    .detach_and_log_err(cx);
+            }
             local.send_source_breakpoints(ignore, &self.breakpoint_store, cx)
         } else {
             // todo(debugger): We need to propagate this change to downstream sessions and send a message to upstream sessions
@@ -1910,6 +2035,19 @@ impl Session {
         }
     }
 
+    fn send_function_breakpoints(&self, store: &Entity<BreakpointStore>, cx: &mut App) {
+        if self.ignore_breakpoints
+            || !SetFunctionBreakpointsCommand::is_supported(&self.capabilities)
+        {
+            return;
+        }
+        if let Some(local) = self.as_running() {
+            local
+                .send_function_breakpoints(false, store, cx)
+                .detach_and_log_err(cx);
+        }
+    }
+
     pub fn data_breakpoints(&self) -> impl Iterator<Item = &DataBreakpointState> {
         self.data_breakpoints.values()
     }
@@ -1986,6 +2124,84 @@ impl Session {
         self.send_data_breakpoints(cx);
     }
 
+    pub fn instruction_breakpoints(&self) -> impl Iterator<Item = &InstructionBreakpointState> {
+        self.instruction_breakpoints.values()
+    }
+
+    /// Enables or disables the breakpoint on the instruction at the given memory reference, keeping it in the list.
+    pub fn set_instruction_breakpoint_enabled(
+        &mut self,
+        instruction_reference: &str,
+        is_enabled: bool,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(state) = self.instruction_breakpoints.get_mut(instruction_reference)
+            && state.is_enabled != is_enabled
+        {
+            state.is_enabled = is_enabled;
+            self.send_instruction_breakpoints(cx);
+        }
+    }
+
+    fn send_instruction_breakpoints(&mut self, cx: &mut Context<Self>) {
+        if let Some(mode) = self.as_running() {
+            let breakpoints = self
+                .instruction_breakpoints
+                .values()
+                .filter_map(|state| state.is_enabled.then(|| state.dap.clone()))
+                .collect();
+            let command = SetInstructionBreakpointsCommand { breakpoints };
+            mode.request(command).detach_and_log_err(cx);
+        }
+    }
+
+    /// Sets a breakpoint on the instruction at the given memory reference, or removes the one that is already there.
+    pub fn toggle_instruction_breakpoint(
+        &mut self,
+        instruction_reference: String,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .instruction_breakpoints
+            .remove(&instruction_reference)
+            .is_none()
+        {
+            self.instruction_breakpoints.insert(
+                instruction_reference.clone(),
+                InstructionBreakpointState {
+                    dap: dap::InstructionBreakpoint {
+                        instruction_reference,
+                        offset: None,
+                        condition: None,
+                        hit_condition: None,
+                        mode: None,
+                    },
+                    is_enabled: true,
+                },
+            );
+        }
+        self.send_instruction_breakpoints(cx);
+    }
+
+    /// Disassembles `instruction_count` instructions, starting `instruction_offset` instructions away from the given memory reference.
+    pub fn disassemble(
+        &mut self,
+        memory_reference: String,
+        instruction_offset: i64,
+        instruction_count: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Vec<dap::DisassembledInstruction>>> {
+        self.request(
+            DisassembleCommand {
+                memory_reference,
+                instruction_offset,
+                instruction_count,
+            },
+            |_, response, _| response.log_err(),
+            cx,
+        )
+    }
+
     pub fn breakpoints_enabled(&self) -> bool {
         self.ignore_breakpoints
     }
@@ -2443,6 +2659,10 @@ impl Session {
             .unwrap_or_default()
     }
 
+    pub fn stack_frame(&self, stack_frame_id: StackFrameId) -> Option<&StackFrame> {
+        self.stack_frames.get(&stack_frame_id)
+    }
+
     pub fn variables_by_stack_frame_id(
         &self,
         stack_frame_id: StackFrameId,
@@ -2588,6 +2808,39 @@ impl Session {
         }
     }
 
+    pub fn set_expression(
+        &mut self,
+        stack_frame_id: u64,
+        expression: String,
+        value: String,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .capabilities
+            .supports_set_expression
+            .unwrap_or_default()
+        {
+            self.request(
+                SetExpressionCommand {
+                    expression,
+                    value,
+                    frame_id: Some(stack_frame_id),
+                },
+                move |this, response, cx| {
+                    let response = response.log_err()?;
+                    this.invalidate_command_type::<VariablesCommand>();
+                    this.invalidate_command_type::<ReadMemory>();
+                    this.memory.clear(cx.background_executor());
+                    this.refresh_watchers(stack_frame_id, cx);
+                    cx.emit(SessionEvent::Variables);
+                    Some(response)
+                },
+                cx,
+            )
+            .detach();
+        }
+    }
+
     pub fn evaluate(
         &mut self,
         expression: String,
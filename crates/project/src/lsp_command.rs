@@ -2,7 +2,7 @@ mod signature_help;
 
 use crate::{
     CodeAction, CompletionSource, CoreCompletion, CoreCompletionResponse, DocumentColor,
-    DocumentHighlight, DocumentSymbol, Hover, HoverBlock, HoverBlockKind, InlayHint,
+    DocumentHighlight, DocumentLink, DocumentSymbol, Hover, HoverBlock, HoverBlockKind, InlayHint,
     InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location,
     LocationLink, LspAction, LspPullDiagnostics, MarkupContent, PrepareRenameResponse,
     ProjectTransaction, PulledDiagnostics, ResolveState,
@@ -13,6 +13,10 @@ use async_trait::async_trait;
 use client::proto::{self, PeerId};
 use clock::Global;
 use collections::{HashMap, HashSet};
+use dap::{
+    StackFrameId,
+    inline_value::{InlineValueLocation, VariableLookupKind, VariableScope},
+};
 use futures::future;
 use gpui::{App, AsyncApp, Entity, Task};
 use language::{
@@ -245,6 +249,16 @@ pub(crate) struct GetCodeLens;
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct GetDocumentColor;
 
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetDocumentLinks;
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetInlineValues {
+    pub range: Range<Anchor>,
+    pub frame_id: StackFrameId,
+    pub stopped_location: Range<Anchor>,
+}
+
 impl GetCodeLens {
     pub(crate) fn can_resolve_lens(capabilities: &ServerCapabilities) -> bool {
         capabilities
@@ -4342,6 +4356,401 @@ impl LspCommand for GetDocumentColor {
     }
 }
 
+impl GetDocumentLinks {
+    pub(crate) fn can_resolve_links(capabilities: &ServerCapabilities) -> bool {
+        capabilities
+            .document_link_provider
+            .as_ref()
+            .and_then(|options| options.resolve_provider)
+            .unwrap_or(false)
+    }
+
+    pub(crate) fn lsp_to_project_link(
+        lsp_link: lsp::DocumentLink,
+        server_id: LanguageServerId,
+        snapshot: &BufferSnapshot,
+    ) -> DocumentLink {
+        let link_range = range_from_lsp(lsp_link.range);
+        let start = snapshot.clip_point_utf16(link_range.start, Bias::Left);
+        let end = snapshot.clip_point_utf16(link_range.end, Bias::Right);
+        DocumentLink {
+            range: snapshot.anchor_before(start)..snapshot.anchor_after(end),
+            target: lsp_link.target.as_ref().map(|target| target.to_string()),
+            tooltip: lsp_link.tooltip.clone(),
+            server_id,
+            lsp_link,
+        }
+    }
+
+    pub(crate) fn project_to_proto_link(link: &DocumentLink) -> proto::DocumentLink {
+        proto::DocumentLink {
+            start: Some(serialize_anchor(&link.range.start)),
+            end: Some(serialize_anchor(&link.range.end)),
+            target: link.target.clone(),
+            tooltip: link.tooltip.clone(),
+            server_id: link.server_id.to_proto(),
+            lsp_link: serde_json::to_vec(&link.lsp_link)
+                .expect("failed to serialize document link"),
+        }
+    }
+
+    pub(crate) fn proto_to_project_link(link: proto::DocumentLink) -> Result<DocumentLink> {
+        let start = link
+            .start
+            .and_then(deserialize_anchor)
+            .context("invalid document link start")?;
+        let end = link
+            .end
+            .and_then(deserialize_anchor)
+            .context("invalid document link end")?;
+        Ok(DocumentLink {
+            range: start..end,
+            target: link.target,
+            tooltip: link.tooltip,
+            server_id: LanguageServerId::from_proto(link.server_id),
+            lsp_link: serde_json::from_slice(&link.lsp_link)
+                .context("deserializing document link")?,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetDocumentLinks {
+    type Response = Vec<DocumentLink>;
+    type LspRequest = lsp::request::DocumentLinkRequest;
+    type ProtoRequest = proto::GetDocumentLinks;
+
+    fn display_name(&self) -> &str {
+        "Document links"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        capabilities
+            .server_capabilities
+            .document_link_provider
+            .is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::DocumentLinkParams> {
+        Ok(lsp::DocumentLinkParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::DocumentLink>>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<DocumentLink>> {
+        let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
+        Ok(message
+            .unwrap_or_default()
+            .into_iter()
+            .map(|lsp_link| Self::lsp_to_project_link(lsp_link, server_id, &snapshot))
+            .collect())
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentLinks {
+        proto::GetDocumentLinks {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetDocumentLinks,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Vec<DocumentLink>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetDocumentLinksResponse {
+        proto::GetDocumentLinksResponse {
+            links: response.iter().map(Self::project_to_proto_link).collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDocumentLinksResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<DocumentLink>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .links
+            .into_iter()
+            .map(Self::proto_to_project_link)
+            .collect::<Result<Vec<_>>>()
+            .context("deserializing proto document links response")
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetDocumentLinks) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+impl GetInlineValues {
+    fn lsp_to_inline_value_location(
+        inline_value: lsp::InlineValue,
+        snapshot: &BufferSnapshot,
+    ) -> InlineValueLocation {
+        let (range, lookup, name) = match inline_value {
+            lsp::InlineValue::Text(value) => {
+                (value.range, VariableLookupKind::Text(value.text), None)
+            }
+            lsp::InlineValue::VariableLookup(lookup) => (
+                lookup.range,
+                VariableLookupKind::Variable,
+                lookup.variable_name,
+            ),
+            lsp::InlineValue::EvaluatableExpression(expression) => (
+                expression.range,
+                VariableLookupKind::Expression,
+                expression.expression,
+            ),
+        };
+        let range = range_from_lsp(range);
+        let start = snapshot.clip_point_utf16(range.start, Bias::Left);
+        let end = snapshot.clip_point_utf16(range.end, Bias::Left);
+        // Servers may omit the name, in which case the text in the range is used instead.
+        let variable_name = name.unwrap_or_else(|| {
+            snapshot
+                .text_for_range(
+                    snapshot.point_utf16_to_point(start)..snapshot.point_utf16_to_point(end),
+                )
+                .collect()
+        });
+        let end = snapshot.point_utf16_to_point(end);
+        InlineValueLocation {
+            variable_name,
+            scope: VariableScope::Local,
+            lookup,
+            row: end.row as usize,
+            column: end.column as usize,
+        }
+    }
+
+    fn inline_value_location_to_proto(location: InlineValueLocation) -> proto::InlineValue {
+        let value = match location.lookup {
+            VariableLookupKind::Variable => {
+                proto::inline_value::Value::VariableName(location.variable_name)
+            }
+            VariableLookupKind::Expression => {
+                proto::inline_value::Value::Expression(location.variable_name)
+            }
+            VariableLookupKind::Text(text) => proto::inline_value::Value::Text(text),
+        };
+        proto::InlineValue {
+            row: location.row as u32,
+            column: location.column as u32,
+            value: Some(value),
+        }
+    }
+
+    fn inline_value_location_from_proto(value: proto::InlineValue) -> Option<InlineValueLocation> {
+        let (variable_name, lookup) = match value.value? {
+            proto::inline_value::Value::VariableName(name) => (name, VariableLookupKind::Variable),
+            proto::inline_value::Value::Expression(expression) => {
+                (expression, VariableLookupKind::Expression)
+            }
+            proto::inline_value::Value::Text(text) => {
+                (String::new(), VariableLookupKind::Text(text))
+            }
+        };
+        Some(InlineValueLocation {
+            variable_name,
+            scope: VariableScope::Local,
+            lookup,
+            row: value.row as usize,
+            column: value.column as usize,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetInlineValues {
+    /// `None` when no language server provides inline values for the buffer.
+    type Response = Option<Vec<InlineValueLocation>>;
+    type LspRequest = lsp::request::InlineValueRequest;
+    type ProtoRequest = proto::GetInlineValues;
+
+    fn display_name(&self) -> &str {
+        "Inline values"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        capabilities
+            .server_capabilities
+            .inline_value_provider
+            .as_ref()
+            .is_some_and(|capability| match capability {
+                OneOf::Left(supported) => *supported,
+                OneOf::Right(_) => true,
+            })
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        buffer: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::InlineValueParams> {
+        Ok(lsp::InlineValueParams {
+            work_done_progress_params: Default::default(),
+            text_document: make_text_document_identifier(path)?,
+            range: range_to_lsp(self.range.to_point_utf16(buffer))?,
+            context: lsp::InlineValueContext {
+                frame_id: i32::try_from(self.frame_id).context("frame id out of range")?,
+                stopped_location: range_to_lsp(self.stopped_location.to_point_utf16(buffer))?,
+            },
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::InlineValue>>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
+        Ok(Some(
+            message
+                .unwrap_or_default()
+                .into_iter()
+                .map(|inline_value| Self::lsp_to_inline_value_location(inline_value, &snapshot))
+                .collect(),
+        ))
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetInlineValues {
+        proto::GetInlineValues {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            start: Some(serialize_anchor(&self.range.start)),
+            end: Some(serialize_anchor(&self.range.end)),
+            frame_id: self.frame_id,
+            stopped_location_start: Some(serialize_anchor(&self.stopped_location.start)),
+            stopped_location_end: Some(serialize_anchor(&self.stopped_location.end)),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetInlineValues,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let start = message
+            .start
+            .and_then(deserialize_anchor)
+            .context("invalid start")?;
+        let end = message
+            .end
+            .and_then(deserialize_anchor)
+            .context("invalid end")?;
+        let stopped_location_start = message
+            .stopped_location_start
+            .and_then(deserialize_anchor)
+            .context("invalid stopped location start")?;
+        let stopped_location_end = message
+            .stopped_location_end
+            .and_then(deserialize_anchor)
+            .context("invalid stopped location end")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            range: start..end,
+            frame_id: message.frame_id,
+            stopped_location: stopped_location_start..stopped_location_end,
+        })
+    }
+
+    fn response_to_proto(
+        response: Self::Response,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetInlineValuesResponse {
+        proto::GetInlineValuesResponse {
+            provided: response.is_some(),
+            values: response
+                .unwrap_or_default()
+                .into_iter()
+                .map(Self::inline_value_location_to_proto)
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetInlineValuesResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        if !message.provided {
+            return Ok(None);
+        }
+        Ok(Some(
+            message
+                .values
+                .into_iter()
+                .filter_map(Self::inline_value_location_from_proto)
+                .collect(),
+        ))
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetInlineValues) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
 fn process_related_documents(
     diagnostics: &mut HashMap<lsp::Uri, LspPullDiagnostics>,
     server_id: LanguageServerId,
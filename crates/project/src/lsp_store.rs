@@ -17,8 +17,8 @@ pub mod rust_analyzer_ext;
 
 use crate::{
     CodeAction, ColorPresentation, Completion, CompletionDisplayOptions, CompletionResponse,
-    CompletionSource, CoreCompletion, DocumentColor, Hover, InlayHint, LocationLink, LspAction,
-    LspPullDiagnostics, ManifestProvidersStore, Project, ProjectItem, ProjectPath,
+    CompletionSource, CoreCompletion, DocumentColor, DocumentLink, Hover, InlayHint, LocationLink,
+    LspAction, LspPullDiagnostics, ManifestProvidersStore, Project, ProjectItem, ProjectPath,
     ProjectTransaction, PulledDiagnostics, ResolveState, Symbol,
     buffer_store::{BufferStore, BufferStoreEvent},
     environment::ProjectEnvironment,
@@ -65,7 +65,7 @@ use language::{
     language_settings::{
         FormatOnSave, Formatter, LanguageSettings, SelectedFormatter, language_settings,
     },
-    point_to_lsp,
+    point_from_lsp, point_to_lsp,
     proto::{
         deserialize_anchor, deserialize_lsp_edit, deserialize_version, serialize_anchor,
         serialize_lsp_edit, serialize_version,
@@ -131,6 +131,11 @@ pub use worktree::{
 };
 
 const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+const SERVER_RESTART_BACKOFF_BASE: Duration = Duration::from_secs(1);
+const SERVER_RESTART_BACKOFF_MAX: Duration = Duration::from_secs(60);
+/// How long a language server has to stay up for its previous crashes to stop counting towards the restart backoff.
+const SERVER_STABLE_UPTIME: Duration = Duration::from_secs(180);
+const MAX_CONSECUTIVE_SERVER_RESTARTS: u32 = 5;
 pub const SERVER_PROGRESS_THROTTLE_TIMEOUT: Duration = Duration::from_millis(100);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -223,6 +228,14 @@ pub struct LocalLspStore {
     registered_buffers: HashMap<BufferId, usize>,
     buffers_opened_in_servers: HashMap<BufferId, HashSet<LanguageServerId>>,
     buffer_pull_diagnostics_result_ids: HashMap<LanguageServerId, HashMap<PathBuf, Option<String>>>,
+    language_server_crashes: HashMap<LanguageServerName, LanguageServerCrashes>,
+}
+
+/// Crashes of a language server, tracked across its automatic restarts.
+#[derive(Debug, Default, Clone, Copy)]
+struct LanguageServerCrashes {
+    total: usize,
+    consecutive: u32,
 }
 
 impl LocalLspStore {
@@ -903,6 +916,26 @@ impl LocalLspStore {
                 }
             })
             .detach();
+        language_server
+            .on_request::<lsp::request::ShowDocument, _, _>({
+                let this = this.clone();
+                let name = name.to_string();
+                move |params, cx| {
+                    let this = this.clone();
+                    let name = name.to_string();
+                    let mut cx = cx.clone();
+                    async move {
+                        let success = LocalLspStore::on_lsp_show_document(
+                            this, params, server_id, name, &mut cx,
+                        )
+                        .await
+                        .log_err()
+                        .unwrap_or(false);
+                        Ok(lsp::ShowDocumentResult { success })
+                    }
+                }
+            })
+            .detach();
         language_server
             .on_notification::<lsp::notification::ShowMessage, _>({
                 let this = this.clone();
@@ -2330,6 +2363,9 @@ impl LocalLspStore {
     ) {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id();
+        if buffer.large_file_mode() {
+            return;
+        }
 
         let Some(file) = File::from_dyn(buffer.file()) else {
             return;
@@ -3067,6 +3103,49 @@ impl LocalLspStore {
         Ok(project_transaction)
     }
 
+    /// Asks the workspace to reveal the document, returning whether it was shown.
+    async fn on_lsp_show_document(
+        this: WeakEntity<LspStore>,
+        params: lsp::ShowDocumentParams,
+        server_id: LanguageServerId,
+        lsp_name: String,
+        cx: &mut AsyncApp,
+    ) -> Result<bool> {
+        let is_file = params.uri.to_file_path().is_ok();
+        let target = if params.external.unwrap_or(false) || !is_file {
+            ShowDocumentTarget::Url(params.uri.to_string())
+        } else {
+            let buffer = this
+                .update(cx, |this, cx| {
+                    this.open_local_buffer_via_lsp(params.uri.clone(), server_id, cx)
+                })?
+                .await
+                .context("opening document requested by the language server")?;
+            let selection = match params.selection {
+                Some(selection) => Some(buffer.read_with(cx, |buffer, _| {
+                    let start =
+                        buffer.clip_point_utf16(point_from_lsp(selection.start), Bias::Left);
+                    let end = buffer.clip_point_utf16(point_from_lsp(selection.end), Bias::Left);
+                    buffer.anchor_after(start)..buffer.anchor_before(end)
+                })?),
+                None => None,
+            };
+            ShowDocumentTarget::Buffer { buffer, selection }
+        };
+
+        let (tx, rx) = smol::channel::bounded(1);
+        this.update(cx, |_, cx| {
+            cx.emit(LspStoreEvent::ShowDocument(ShowDocumentRequest {
+                target,
+                take_focus: params.take_focus.unwrap_or(false),
+                lsp_name,
+                response_channel: tx,
+            }));
+        })?;
+        // Nobody showed the document if every copy of the request was dropped without a response.
+        Ok(rx.recv().await.unwrap_or(false))
+    }
+
     async fn on_lsp_workspace_edit(
         this: WeakEntity<LspStore>,
         params: lsp::ApplyWorkspaceEditParams,
@@ -3494,6 +3573,7 @@ pub struct LspStore {
     pub lsp_server_capabilities: HashMap<LanguageServerId, lsp::ServerCapabilities>,
     lsp_document_colors: HashMap<BufferId, DocumentColorData>,
     lsp_code_lens: HashMap<BufferId, CodeLensData>,
+    lsp_document_links: HashMap<BufferId, DocumentLinksData>,
     running_lsp_requests: HashMap<TypeId, (Global, HashMap<LspRequestId, Task<()>>)>,
 }
 
@@ -3505,6 +3585,7 @@ pub struct DocumentColors {
 
 type DocumentColorTask = Shared<Task<std::result::Result<DocumentColors, Arc<anyhow::Error>>>>;
 type CodeLensTask = Shared<Task<std::result::Result<Option<Vec<CodeAction>>, Arc<anyhow::Error>>>>;
+type DocumentLinksTask = Shared<Task<std::result::Result<Vec<DocumentLink>, Arc<anyhow::Error>>>>;
 
 #[derive(Debug, Default)]
 struct DocumentColorData {
@@ -3521,6 +3602,13 @@ struct CodeLensData {
     update: Option<(Global, CodeLensTask)>,
 }
 
+#[derive(Debug, Default)]
+struct DocumentLinksData {
+    links_for_version: Option<Global>,
+    links: Vec<DocumentLink>,
+    update: Option<(Global, DocumentLinksTask)>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum LspFetchStrategy {
     IgnoreCache,
@@ -3538,6 +3626,7 @@ pub enum LspStoreEvent {
     },
     LanguageServerLog(LanguageServerId, LanguageServerLogType, String),
     LanguageServerPrompt(LanguageServerPromptRequest),
+    ShowDocument(ShowDocumentRequest),
     LanguageDetected {
         buffer: Entity<Buffer>,
         new_language: Option<Arc<Language>>,
@@ -3602,6 +3691,7 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_inlay_hints);
         client.add_entity_request_handler(Self::handle_get_project_symbols);
         client.add_entity_request_handler(Self::handle_resolve_inlay_hint);
+        client.add_entity_request_handler(Self::handle_resolve_document_link);
         client.add_entity_request_handler(Self::handle_get_color_presentation);
         client.add_entity_request_handler(Self::handle_open_buffer_for_symbol);
         client.add_entity_request_handler(Self::handle_refresh_inlay_hints);
@@ -3616,6 +3706,8 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<GetHover>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentSymbols>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentLinks>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetInlineValues>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
@@ -3752,6 +3844,7 @@ impl LspStore {
                 registered_buffers: HashMap::default(),
                 buffers_opened_in_servers: HashMap::default(),
                 buffer_pull_diagnostics_result_ids: HashMap::default(),
+                language_server_crashes: HashMap::default(),
                 watched_manifest_filenames: ManifestProvidersStore::global(cx)
                     .manifest_file_names(),
             }),
@@ -3766,6 +3859,7 @@ impl LspStore {
             lsp_server_capabilities: HashMap::default(),
             lsp_document_colors: HashMap::default(),
             lsp_code_lens: HashMap::default(),
+            lsp_document_links: HashMap::default(),
             running_lsp_requests: HashMap::default(),
             active_entry: None,
             _maintain_workspace_config,
@@ -3828,6 +3922,7 @@ impl LspStore {
             lsp_server_capabilities: HashMap::default(),
             lsp_document_colors: HashMap::default(),
             lsp_code_lens: HashMap::default(),
+            lsp_document_links: HashMap::default(),
             running_lsp_requests: HashMap::default(),
             active_entry: None,
 
@@ -3961,6 +4056,12 @@ impl LspStore {
                 self.on_buffer_saved(buffer, cx);
             }
 
+            language::BufferEvent::LargeFileModeChanged => {
+                if !buffer.read(cx).large_file_mode() {
+                    self.detect_language_for_buffer(&buffer, cx);
+                }
+            }
+
             _ => {}
         }
     }
@@ -4129,6 +4230,7 @@ impl LspStore {
                     if refcount == 0 {
                         lsp_store.lsp_document_colors.remove(&buffer_id);
                         lsp_store.lsp_code_lens.remove(&buffer_id);
+                        lsp_store.lsp_document_links.remove(&buffer_id);
                         let local = lsp_store.as_local_mut().unwrap();
                         local.registered_buffers.remove(&buffer_id);
                         local.buffers_opened_in_servers.remove(&buffer_id);
@@ -4275,6 +4377,9 @@ impl LspStore {
     ) -> Option<language::AvailableLanguage> {
         // If the buffer has a language, set it and start the language server if we haven't already.
         let buffer = buffer_handle.read(cx);
+        if buffer.large_file_mode() {
+            return None;
+        }
         let file = buffer.file()?;
 
         let content = buffer.as_rope();
@@ -4964,6 +5069,103 @@ impl LspStore {
         }
     }
 
+    /// Resolves the target of a document link, and stores it in the buffer's cached links, so
+    /// that it's only resolved once.
+    pub fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<DocumentLink>> {
+        if link.is_resolved() {
+            return Task::ready(Ok(link));
+        }
+
+        let buffer_id = buffer.read(cx).remote_id();
+        let (range, server_id) = (link.range.clone(), link.server_id);
+        let resolve = self.request_document_link_resolve(link, buffer, cx);
+        cx.spawn(async move |lsp_store, cx| {
+            let resolved_link = resolve.await?;
+            if resolved_link.is_resolved() {
+                lsp_store.update(cx, |lsp_store, _| {
+                    let cached_link =
+                        lsp_store
+                            .lsp_document_links
+                            .get_mut(&buffer_id)
+                            .and_then(|lsp_data| {
+                                lsp_data.links.iter_mut().find(|cached_link| {
+                                    cached_link.server_id == server_id && cached_link.range == range
+                                })
+                            });
+                    if let Some(cached_link) = cached_link {
+                        *cached_link = DocumentLink {
+                            range,
+                            ..resolved_link.clone()
+                        };
+                    }
+                })?;
+            }
+            Ok(resolved_link)
+        })
+    }
+
+    fn request_document_link_resolve(
+        &self,
+        link: DocumentLink,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<DocumentLink>> {
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            if !self.check_if_capable_for_proto_request(
+                &buffer,
+                GetDocumentLinks::can_resolve_links,
+                cx,
+            ) {
+                return Task::ready(Ok(link));
+            }
+            let request = proto::ResolveDocumentLink {
+                project_id,
+                buffer_id: buffer.read(cx).remote_id().into(),
+                link: Some(GetDocumentLinks::project_to_proto_link(&link)),
+            };
+            cx.background_spawn(async move {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("document link proto request")?;
+                match response.link {
+                    Some(resolved_link) => GetDocumentLinks::proto_to_project_link(resolved_link)
+                        .context("document link proto resolve response conversion"),
+                    None => Ok(link),
+                }
+            })
+        } else {
+            let server_id = link.server_id;
+            let Some(lang_server) = buffer.update(cx, |buffer, cx| {
+                self.language_server_for_local_buffer(buffer, server_id, cx)
+                    .map(|(_, server)| server.clone())
+            }) else {
+                return Task::ready(Ok(link));
+            };
+            if !GetDocumentLinks::can_resolve_links(&lang_server.capabilities()) {
+                return Task::ready(Ok(link));
+            }
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            cx.background_spawn(async move {
+                let resolved_link = lang_server
+                    .request::<lsp::request::DocumentLinkResolve>(link.lsp_link)
+                    .await
+                    .into_response()
+                    .context("document link resolve LSP request")?;
+                Ok(GetDocumentLinks::lsp_to_project_link(
+                    resolved_link,
+                    server_id,
+                    &buffer_snapshot,
+                ))
+            })
+        }
+    }
+
     pub fn resolve_color_presentation(
         &mut self,
         mut color: DocumentColor,
@@ -5781,6 +5983,67 @@ impl LspStore {
         }
     }
 
+    /// Returns the document links of the buffer, reusing the links fetched for its current version.
+    pub fn document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> DocumentLinksTask {
+        let version_queried_for = buffer.read(cx).version();
+        let buffer_id = buffer.read(cx).remote_id();
+
+        let lsp_data = self.lsp_document_links.entry(buffer_id).or_default();
+        if let Some(links_for_version) = &lsp_data.links_for_version
+            && !version_queried_for.changed_since(links_for_version)
+        {
+            return Task::ready(Ok(lsp_data.links.clone())).shared();
+        }
+        if let Some((updating_for, running_update)) = &lsp_data.update
+            && !version_queried_for.changed_since(updating_for)
+        {
+            return running_update.clone();
+        }
+
+        let buffer = buffer.clone();
+        let query_version_queried_for = version_queried_for.clone();
+        let new_task = cx
+            .spawn(async move |lsp_store, cx| {
+                let fetched_links = lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.request_lsp(
+                            buffer,
+                            LanguageServerToQuery::FirstCapable,
+                            GetDocumentLinks,
+                            cx,
+                        )
+                    })
+                    .map_err(Arc::new)?
+                    .await
+                    .context("fetching document links")
+                    .map_err(Arc::new);
+
+                lsp_store
+                    .update(cx, |lsp_store, _| {
+                        let lsp_data = lsp_store.lsp_document_links.entry(buffer_id).or_default();
+                        lsp_data.update = None;
+                        let fetched_links = fetched_links?;
+                        let is_newer = match &lsp_data.links_for_version {
+                            Some(version) => !version.changed_since(&query_version_queried_for),
+                            None => true,
+                        };
+                        if is_newer {
+                            lsp_data.links_for_version = Some(query_version_queried_for);
+                            lsp_data.links = fetched_links.clone();
+                        }
+                        Ok(fetched_links)
+                    })
+                    .map_err(Arc::new)?
+            })
+            .shared();
+        lsp_data.update = Some((version_queried_for, new_task.clone()));
+        new_task
+    }
+
     #[inline(never)]
     pub fn completions(
         &self,
@@ -7566,7 +7829,8 @@ impl LspStore {
             .collect();
     }
 
-    #[cfg(test)]
+    /// Replaces the diagnostics of a file reported under the given server id, e.g. by a task's problem matchers
+    /// that have an id reserved for them, rather than by a language server.
     pub fn update_diagnostic_entries(
         &mut self,
         server_id: LanguageServerId,
@@ -9405,6 +9669,17 @@ impl LspStore {
                     .as_local_mut()
                     .and_then(|local| local.language_servers.get_mut(&language_server_id))
                 {
+                    let is_current_request = workspace_refresh_task
+                        .partial_result_token
+                        .lock()
+                        .as_deref()
+                        .is_some_and(|current_token| current_token == token);
+                    if !is_current_request {
+                        log::debug!(
+                            "skipping workspace diagnostics report for stale token {token} of server {language_server_id}"
+                        );
+                        return;
+                    }
                     workspace_refresh_task.progress_tx.try_send(()).ok();
                     self.apply_workspace_diagnostic_report(language_server_id, report, cx)
                 }
@@ -9860,6 +10135,33 @@ impl LspStore {
         })
     }
 
+    async fn handle_resolve_document_link(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::ResolveDocumentLink>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::ResolveDocumentLinkResponse> {
+        let link = GetDocumentLinks::proto_to_project_link(
+            envelope
+                .payload
+                .link
+                .context("incorrect protobuf resolve document link message: missing the link")?,
+        )
+        .context("resolved proto document link conversion")?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        let resolved_link = this
+            .update(&mut cx, |this, cx| {
+                this.resolve_document_link(link, buffer, cx)
+            })?
+            .await
+            .context("document link resolve")?;
+        Ok(proto::ResolveDocumentLinkResponse {
+            link: Some(GetDocumentLinks::project_to_proto_link(&resolved_link)),
+        })
+    }
+
     async fn handle_refresh_code_lens(
         this: Entity<Self>,
         _: TypedEnvelope<proto::RefreshCodeLens>,
@@ -10472,6 +10774,112 @@ impl LspStore {
         Task::ready(())
     }
 
+    /// Restarts the language server if its process exited while it was still in use,
+    /// backing off exponentially when it keeps crashing.
+    fn on_language_server_exited(
+        &mut self,
+        server_id: LanguageServerId,
+        started_at: Instant,
+        cx: &mut Context<Self>,
+    ) {
+        let buffers = self.buffer_store.read(cx).buffers().collect::<Vec<_>>();
+        let now = cx.background_executor().now();
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        // Servers stopped on purpose are removed from the store before their process exits.
+        let Some(LanguageServerState::Running { adapter, .. }) =
+            local.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let name = adapter.name();
+        let buffers = buffers
+            .into_iter()
+            .filter(|buffer| {
+                buffer.update(cx, |buffer, cx| {
+                    local
+                        .language_server_ids_for_buffer(buffer, cx)
+                        .contains(&server_id)
+                })
+            })
+            .collect::<Vec<_>>();
+        // Forget the crashed server, so that registering the buffers again starts a new one.
+        local
+            .lsp_tree
+            .remove_nodes(&BTreeSet::from_iter([server_id]));
+
+        let crashes = local
+            .language_server_crashes
+            .entry(name.clone())
+            .or_default();
+        if now.saturating_duration_since(started_at) >= SERVER_STABLE_UPTIME {
+            crashes.consecutive = 0;
+        }
+        crashes.total += 1;
+        crashes.consecutive += 1;
+        let consecutive_crashes = crashes.consecutive;
+
+        let stop_task = self.stop_local_language_server(server_id, cx);
+        if consecutive_crashes > MAX_CONSECUTIVE_SERVER_RESTARTS {
+            log::error!(
+                "language server {name} (id {server_id}) crashed {consecutive_crashes} times in a row, not restarting it"
+            );
+            cx.spawn(async move |lsp_store, cx| {
+                stop_task.await;
+                lsp_store
+                    .update(cx, |lsp_store, _| {
+                        lsp_store.languages.update_lsp_binary_status(
+                            name,
+                            BinaryStatus::Failed {
+                                error: format!(
+                                    "Crashed {consecutive_crashes} times in a row, restart it manually"
+                                ),
+                            },
+                        );
+                    })
+                    .ok();
+            })
+            .detach();
+            return;
+        }
+
+        let backoff = SERVER_RESTART_BACKOFF_BASE
+            .saturating_mul(1 << (consecutive_crashes - 1))
+            .min(SERVER_RESTART_BACKOFF_MAX);
+        log::warn!("language server {name} (id {server_id}) crashed, restarting it in {backoff:?}");
+        cx.spawn(async move |lsp_store, cx| {
+            stop_task.await;
+            cx.background_executor().timer(backoff).await;
+            lsp_store
+                .update(cx, |lsp_store, cx| {
+                    let only_servers = HashSet::from_iter([LanguageServerSelector::Name(name)]);
+                    for buffer in buffers {
+                        lsp_store.register_buffer_with_language_servers(
+                            &buffer,
+                            only_servers.clone(),
+                            true,
+                            cx,
+                        );
+                    }
+                })
+                .ok();
+        })
+        .detach();
+    }
+
+    /// Returns how many times the language server crashed during this session.
+    pub fn language_server_crash_count(&self, name: &LanguageServerName) -> usize {
+        self.as_local()
+            .and_then(|local| local.language_server_crashes.get(name))
+            .map_or(0, |crashes| crashes.total)
+    }
+
+    /// Returns the id of the language server process, if it runs locally.
+    pub fn language_server_process_id(&self, server_id: LanguageServerId) -> Option<u32> {
+        self.language_server_for_id(server_id)?.process_id()
+    }
+
     pub fn stop_all_language_servers(&mut self, cx: &mut Context<Self>) {
         if let Some((client, project_id)) = self.upstream_client() {
             let request = client.request(proto::StopLanguageServers {
@@ -10922,6 +11330,17 @@ impl LspStore {
         local
             .languages
             .update_lsp_binary_status(adapter.name(), BinaryStatus::None);
+        let started_at = cx.background_executor().now();
+        let server_exited = language_server.exited();
+        cx.spawn(async move |lsp_store, cx| {
+            server_exited.await;
+            lsp_store
+                .update(cx, |lsp_store, cx| {
+                    lsp_store.on_language_server_exited(server_id, started_at, cx)
+                })
+                .ok();
+        })
+        .detach();
         if let Some(file_ops_caps) = language_server
             .capabilities()
             .workspace
@@ -11554,6 +11973,11 @@ impl LspStore {
         for buffer_lens in self.lsp_code_lens.values_mut() {
             buffer_lens.lens.remove(&for_server);
         }
+        for buffer_links in self.lsp_document_links.values_mut() {
+            buffer_links
+                .links
+                .retain(|link| link.server_id != for_server);
+        }
         if let Some(local) = self.as_local_mut() {
             local.buffer_pull_diagnostics_result_ids.remove(&for_server);
             for buffer_servers in local.buffers_opened_in_servers.values_mut() {
@@ -11606,6 +12030,25 @@ impl LspStore {
         }
     }
 
+    /// Re-pulls workspace diagnostics from every running language server that supports them.
+    ///
+    /// This only affects local projects: in remote ones, the language servers run on the host,
+    /// which pulls their workspace diagnostics on its own schedule and forwards them.
+    pub fn pull_workspace_diagnostics_for_all_servers(&mut self) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        for state in local.language_servers.values_mut() {
+            if let LanguageServerState::Running {
+                workspace_refresh_task: Some(workspace_refresh_task),
+                ..
+            } = state
+            {
+                workspace_refresh_task.refresh_tx.try_send(()).ok();
+            }
+        }
+    }
+
     pub fn pull_workspace_diagnostics_for_buffer(&mut self, buffer_id: BufferId, cx: &mut App) {
         let Some(buffer) = self.buffer_store().read(cx).get_existing(buffer_id).ok() else {
             return;
@@ -11627,6 +12070,28 @@ impl LspStore {
         }
     }
 
+    /// Re-creates the workspace diagnostics pulling task of the server,
+    /// e.g. after its diagnostic capabilities were registered or unregistered dynamically.
+    fn restart_workspace_diagnostics_refresh(
+        &mut self,
+        server: &Arc<LanguageServer>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.as_local().is_none() {
+            return;
+        }
+        let new_refresh_task = lsp_workspace_diagnostics_refresh(server.clone(), cx);
+        if let Some(LanguageServerState::Running {
+            workspace_refresh_task,
+            ..
+        }) = self
+            .as_local_mut()
+            .and_then(|local| local.language_servers.get_mut(&server.server_id()))
+        {
+            *workspace_refresh_task = new_refresh_task;
+        }
+    }
+
     fn apply_workspace_diagnostic_report(
         &mut self,
         server_id: LanguageServerId,
@@ -11954,6 +12419,7 @@ impl LspStore {
                         server.update_capabilities(|capabilities| {
                             capabilities.diagnostic_provider = Some(caps);
                         });
+                        self.restart_workspace_diagnostics_refresh(&server, cx);
                         notify_server_capabilities_updated(&server, cx);
                     }
                 }
@@ -11968,6 +12434,25 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/documentLink" => {
+                    if let Some(caps) = reg
+                        .register_options
+                        .map(serde_json::from_value)
+                        .transpose()?
+                    {
+                        server.update_capabilities(|capabilities| {
+                            capabilities.document_link_provider = Some(caps);
+                        });
+                        notify_server_capabilities_updated(&server, cx);
+                    }
+                }
+                "textDocument/inlineValue" => {
+                    let options = parse_register_capabilities(reg)?;
+                    server.update_capabilities(|capabilities| {
+                        capabilities.inline_value_provider = Some(options);
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 _ => log::warn!("unhandled capability registration: {reg:?}"),
             }
         }
@@ -12117,6 +12602,7 @@ impl LspStore {
                     server.update_capabilities(|capabilities| {
                         capabilities.diagnostic_provider = None;
                     });
+                    self.restart_workspace_diagnostics_refresh(&server, cx);
                     notify_server_capabilities_updated(&server, cx);
                 }
                 "textDocument/documentColor" => {
@@ -12125,6 +12611,18 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/documentLink" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.document_link_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/inlineValue" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.inline_value_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 _ => log::warn!("unhandled capability unregistration: {unreg:?}"),
             }
         }
@@ -12324,6 +12822,8 @@ fn lsp_workspace_diagnostics_refresh(
     let (progress_tx, mut progress_rx) = mpsc::channel(1);
     let (mut refresh_tx, mut refresh_rx) = mpsc::channel(1);
     refresh_tx.try_send(()).ok();
+    let partial_result_token = Arc::new(Mutex::new(None));
+    let current_partial_result_token = partial_result_token.clone();
 
     let workspace_query_language_server = cx.spawn(async move |lsp_store, cx| {
         let mut attempts = 0;
@@ -12331,9 +12831,29 @@ fn lsp_workspace_diagnostics_refresh(
         let mut requests = 0;
 
         loop {
-            let Some(()) = refresh_rx.recv().await else {
+            let Ok(refresh_interval) = cx.update(|cx| {
+                ProjectSettings::get_global(cx)
+                    .diagnostics
+                    .lsp_pull_diagnostics
+                    .workspace_refresh_interval_ms
+            }) else {
                 return;
             };
+            let scheduled_refresh = if refresh_interval == 0 {
+                pending::<()>().left_future()
+            } else {
+                cx.background_executor()
+                    .timer(Duration::from_millis(refresh_interval))
+                    .right_future()
+            };
+            select_biased! {
+                refresh = refresh_rx.recv().fuse() => {
+                    if refresh.is_none() {
+                        return;
+                    }
+                }
+                () = scheduled_refresh.fuse() => {}
+            }
 
             'request: loop {
                 requests += 1;
@@ -12366,6 +12886,7 @@ fn lsp_workspace_diagnostics_refresh(
                 };
 
                 let token = format!("workspace/diagnostic-{}-{}", server.server_id(), requests);
+                *current_partial_result_token.lock() = Some(token.clone());
 
                 progress_rx.try_recv().ok();
                 let timer =
@@ -12387,6 +12908,7 @@ fn lsp_workspace_diagnostics_refresh(
                         }),
                     )
                     .await;
+                current_partial_result_token.lock().take();
 
                 // https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#diagnostic_refresh
                 // >  If a server closes a workspace diagnostic pull request the client should re-trigger the request.
@@ -12427,6 +12949,7 @@ fn lsp_workspace_diagnostics_refresh(
     Some(WorkspaceRefreshTask {
         refresh_tx,
         progress_tx,
+        partial_result_token,
         task: workspace_query_language_server,
     })
 }
@@ -12747,6 +13270,32 @@ impl PartialEq for LanguageServerPromptRequest {
     }
 }
 
+/// A document that a language server asked to reveal via `window/showDocument`.
+#[derive(Clone, Debug)]
+pub struct ShowDocumentRequest {
+    pub target: ShowDocumentTarget,
+    pub take_focus: bool,
+    pub lsp_name: String,
+    pub(crate) response_channel: Sender<bool>,
+}
+
+impl ShowDocumentRequest {
+    /// Reports back to the language server whether the document was shown.
+    pub fn respond(&self, success: bool) {
+        self.response_channel.try_send(success).ok();
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ShowDocumentTarget {
+    Buffer {
+        buffer: Entity<Buffer>,
+        selection: Option<Range<Anchor>>,
+    },
+    /// A document outside of the project, to be opened by the system.
+    Url(String),
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum LanguageServerLogType {
     Log(MessageType),
@@ -12820,6 +13369,9 @@ impl LanguageServerLogType {
 pub struct WorkspaceRefreshTask {
     refresh_tx: mpsc::Sender<()>,
     progress_tx: mpsc::Sender<()>,
+    /// The partial result token of the workspace diagnostics request in flight, if any.
+    /// `$/progress` reports for any other token belong to an abandoned request and are ignored.
+    partial_result_token: Arc<Mutex<Option<String>>>,
     #[allow(dead_code)]
     task: Task<()>,
 }
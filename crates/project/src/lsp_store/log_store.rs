@@ -611,6 +611,34 @@ impl LogStore {
         Some(&self.language_servers.get(&server_id)?.trace_messages)
     }
 
+    /// Serializes the captured RPC messages of the server into JSON lines, one object per message,
+    /// so that the exchange can be attached to bug reports.
+    /// Returns `None` if RPC tracing is not enabled for the server.
+    pub fn rpc_trace_jsonl(&self, server_id: LanguageServerId) -> Option<String> {
+        let rpc_state = self.language_servers.get(&server_id)?.rpc_state.as_ref()?;
+        let mut direction = None;
+        let mut jsonl = String::new();
+        for message in &rpc_state.rpc_messages {
+            let message = message.as_ref();
+            match message {
+                SEND_LINE => direction = Some("send"),
+                RECEIVE_LINE => direction = Some("receive"),
+                message => {
+                    let message = serde_json::from_str::<serde_json::Value>(message)
+                        .unwrap_or_else(|_| serde_json::Value::String(message.to_owned()));
+                    let entry = serde_json::json!({
+                        "server_id": server_id.0,
+                        "direction": direction,
+                        "message": message,
+                    });
+                    jsonl.push_str(&entry.to_string());
+                    jsonl.push('\n');
+                }
+            }
+        }
+        Some(jsonl)
+    }
+
     pub fn server_ids_for_project<'a>(
         &'a self,
         lookup_project: &'a WeakEntity<Project>,
@@ -1,3 +1,4 @@
+pub mod bookmark_store;
 pub mod buffer_store;
 mod color_extractor;
 pub mod connection_manager;
@@ -41,6 +42,7 @@ pub use git_store::{
 pub use manifest_tree::ManifestTree;
 
 use anyhow::{Context as _, Result, anyhow};
+use bookmark_store::BookmarkStore;
 use buffer_store::{BufferStore, BufferStoreEvent};
 use client::{Client, Collaborator, PendingEntitySubscription, TypedEnvelope, UserStore, proto};
 use clock::ReplicaId;
@@ -73,8 +75,8 @@ use gpui::{
 };
 use language::{
     Buffer, BufferEvent, Capability, CodeLabel, CursorShape, Language, LanguageName,
-    LanguageRegistry, PointUtf16, ToOffset, ToPointUtf16, Toolchain, ToolchainList, Transaction,
-    Unclipped, language_settings::InlayHintKind, proto::split_operations,
+    LanguageRegistry, ParseStatus, PointUtf16, ToOffset, ToPointUtf16, Toolchain, ToolchainList,
+    Transaction, Unclipped, language_settings::InlayHintKind, proto::split_operations,
 };
 use lsp::{
     CodeActionKind, CompletionContext, CompletionItemKind, DocumentHighlightKind, InsertTextMode,
@@ -129,14 +131,14 @@ pub use language::Location;
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
     BasicContextProvider, ContextProviderWithTasks, DebugScenarioContext, Inventory, TaskContexts,
-    TaskSourceKind,
+    TaskGraphNode, TaskSourceKind,
 };
 
 pub use buffer_store::ProjectTransaction;
 pub use lsp_store::{
     DiagnosticSummary, LanguageServerLogType, LanguageServerProgress, LanguageServerPromptRequest,
     LanguageServerStatus, LanguageServerToQuery, LspStore, LspStoreEvent,
-    SERVER_PROGRESS_THROTTLE_TIMEOUT,
+    SERVER_PROGRESS_THROTTLE_TIMEOUT, ShowDocumentRequest, ShowDocumentTarget,
 };
 pub use toolchain_store::ToolchainStore;
 const MAX_PROJECT_SEARCH_HISTORY_SIZE: usize = 500;
@@ -175,6 +177,7 @@ pub struct Project {
     dap_store: Entity<DapStore>,
 
     breakpoint_store: Entity<BreakpointStore>,
+    bookmark_store: Entity<BookmarkStore>,
     collab_client: Arc<client::Client>,
     join_project_response_message_id: u32,
     task_store: Entity<TaskStore>,
@@ -295,6 +298,7 @@ pub enum Event {
         notification_id: SharedString,
     },
     LanguageServerPrompt(LanguageServerPromptRequest),
+    ShowDocument(ShowDocumentRequest),
     LanguageNotFound(Entity<Buffer>),
     ActiveEntryChanged(Option<ProjectEntryId>),
     ActivateProjectPanel,
@@ -743,6 +747,24 @@ pub struct Symbol {
     pub signature: [u8; 32],
 }
 
+/// A link range in a buffer, as reported by `textDocument/documentLink`.
+///
+/// Servers may omit the [`DocumentLink::target`] and compute it lazily on `documentLink/resolve`.
+#[derive(Clone, Debug)]
+pub struct DocumentLink {
+    pub range: Range<language::Anchor>,
+    pub target: Option<String>,
+    pub tooltip: Option<String>,
+    pub server_id: LanguageServerId,
+    pub lsp_link: lsp::DocumentLink,
+}
+
+impl DocumentLink {
+    pub fn is_resolved(&self) -> bool {
+        self.target.is_some()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct DocumentSymbol {
     pub name: String,
@@ -1072,6 +1094,8 @@ impl Project {
 
             let breakpoint_store =
                 cx.new(|_| BreakpointStore::local(worktree_store.clone(), buffer_store.clone()));
+            let bookmark_store =
+                cx.new(|_| BookmarkStore::new(worktree_store.clone(), buffer_store.clone()));
 
             let dap_store = cx.new(|cx| {
                 DapStore::new_local(
@@ -1177,12 +1201,14 @@ impl Project {
                 fs,
                 remote_client: None,
                 breakpoint_store,
+                bookmark_store,
                 dap_store,
 
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
                 node: Some(node),
                 search_history: Self::new_search_history(),
@@ -1294,6 +1320,8 @@ impl Project {
 
             let breakpoint_store =
                 cx.new(|_| BreakpointStore::remote(REMOTE_SERVER_PROJECT_ID, remote_proto.clone()));
+            let bookmark_store =
+                cx.new(|_| BookmarkStore::new(worktree_store.clone(), buffer_store.clone()));
 
             let dap_store = cx.new(|cx| {
                 DapStore::new_remote(
@@ -1326,6 +1354,7 @@ impl Project {
                 lsp_store,
                 context_server_store,
                 breakpoint_store,
+                bookmark_store,
                 dap_store,
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
@@ -1363,6 +1392,7 @@ impl Project {
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
                 node: Some(node),
                 search_history: Self::new_search_history(),
@@ -1390,6 +1420,7 @@ impl Project {
             remote_proto.add_entity_message_handler(Self::handle_update_project);
             remote_proto.add_entity_message_handler(Self::handle_toast);
             remote_proto.add_entity_request_handler(Self::handle_language_server_prompt_request);
+            remote_proto.add_entity_request_handler(Self::handle_language_server_show_document);
             remote_proto.add_entity_message_handler(Self::handle_hide_toast);
             remote_proto.add_entity_request_handler(Self::handle_update_buffer_from_remote_server);
             BufferStore::init(&remote_proto);
@@ -1482,6 +1513,8 @@ impl Project {
 
         let breakpoint_store =
             cx.new(|_| BreakpointStore::remote(remote_id, client.clone().into()))?;
+        let bookmark_store =
+            cx.new(|_| BookmarkStore::new(worktree_store.clone(), buffer_store.clone()))?;
         let dap_store = cx.new(|cx| {
             DapStore::new_collab(
                 remote_id,
@@ -1593,12 +1626,14 @@ impl Project {
                     replica_id,
                 },
                 breakpoint_store,
+                bookmark_store,
                 dap_store: dap_store.clone(),
                 git_store: git_store.clone(),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
                 node: None,
                 search_history: Self::new_search_history(),
@@ -1799,6 +1834,10 @@ impl Project {
         self.breakpoint_store.clone()
     }
 
+    pub fn bookmark_store(&self) -> Entity<BookmarkStore> {
+        self.bookmark_store.clone()
+    }
+
     pub fn active_debug_session(&self, cx: &App) -> Option<(Entity<Session>, ActiveStackFrame)> {
         let active_position = self.breakpoint_store.read(cx).active_position()?;
         let session = self
@@ -2688,6 +2727,25 @@ impl Project {
             .update(cx, |buffer_store, cx| buffer_store.save_buffer(buffer, cx))
     }
 
+    /// Saves the buffer, encoding its file with the given encoding from now on.
+    pub fn save_buffer_with_encoding(
+        &self,
+        buffer: Entity<Buffer>,
+        encoding: fs::Encoding,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let previous_encoding = buffer.read(cx).encoding();
+        buffer.update(cx, |buffer, cx| buffer.set_encoding(encoding, cx));
+        let save = self.save_buffer(buffer.clone(), cx);
+        cx.spawn(async move |_, cx| {
+            let result = save.await;
+            if result.is_err() {
+                buffer.update(cx, |buffer, cx| buffer.set_encoding(previous_encoding, cx))?;
+            }
+            result
+        })
+    }
+
     pub fn save_buffer_as(
         &mut self,
         buffer: Entity<Buffer>,
@@ -2953,6 +3011,7 @@ impl Project {
             LspStoreEvent::LanguageServerPrompt(prompt) => {
                 cx.emit(Event::LanguageServerPrompt(prompt.clone()))
             }
+            LspStoreEvent::ShowDocument(request) => cx.emit(Event::ShowDocument(request.clone())),
             LspStoreEvent::DiskBasedDiagnosticsStarted { language_server_id } => {
                 cx.emit(Event::DiskBasedDiagnosticsStarted {
                     language_server_id: *language_server_id,
@@ -3422,6 +3481,23 @@ impl Project {
         })
     }
 
+    /// Reloads the buffer from disk, decoding its file with the given encoding.
+    pub fn reopen_buffer_with_encoding(
+        &self,
+        buffer: Entity<Buffer>,
+        encoding: fs::Encoding,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        self.buffer_store.update(cx, |buffer_store, cx| {
+            buffer_store.reload_buffers_with_encoding(
+                HashSet::from_iter([buffer]),
+                Some(encoding),
+                true,
+                cx,
+            )
+        })
+    }
+
     pub fn reload_images(
         &self,
         images: HashSet<Entity<ImageItem>>,
@@ -3562,6 +3638,32 @@ impl Project {
         )
     }
 
+    pub fn document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        let document_links = self
+            .lsp_store
+            .update(cx, |lsp_store, cx| lsp_store.document_links(buffer, cx));
+        cx.background_spawn(async move {
+            document_links
+                .await
+                .map_err(|e| anyhow!("document links fetch failed: {e:#}"))
+        })
+    }
+
+    pub fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DocumentLink>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.resolve_document_link(link, buffer, cx)
+        })
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
@@ -3796,10 +3898,30 @@ impl Project {
             .summary_for_anchor::<text::PointUtf16>(&range.end)
             .row as usize;
 
-        let inline_value_locations = provide_inline_values(captures, &snapshot, row);
+        let tree_sitter_locations = provide_inline_values(captures, &snapshot, row);
 
         let stack_frame_id = active_stack_frame.stack_frame_id;
+        let lsp_request = self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetInlineValues {
+                range,
+                frame_id: stack_frame_id,
+                stopped_location: active_stack_frame.position..active_stack_frame.position,
+            },
+            cx,
+        );
         cx.spawn(async move |this, cx| {
+            // Prefer the language server's inline values, falling back to tree-sitter
+            // when no server supports them or the request fails.
+            let inline_value_locations = match lsp_request.await {
+                Ok(Some(locations)) => locations,
+                Ok(None) => tree_sitter_locations,
+                Err(error) => {
+                    log::error!("failed to fetch inline values from language server: {error:#}");
+                    tree_sitter_locations
+                }
+            };
             this.update(cx, |project, cx| {
                 project.dap_store().update(cx, |dap_store, cx| {
                     dap_store.resolve_inline_value_locations(
@@ -3865,6 +3987,14 @@ impl Project {
                 let mut chunk_results = Vec::with_capacity(matching_buffer_chunk.len());
                 for buffer in matching_buffer_chunk {
                     let query = query.clone();
+                    if query.is_structural() {
+                        // Structural queries match syntax trees, so wait for the buffer to be parsed.
+                        let mut parse_status =
+                            buffer.read_with(cx, |buffer, _| buffer.parse_status())?;
+                        while *parse_status.borrow() != ParseStatus::Idle {
+                            parse_status.changed().await?;
+                        }
+                    }
                     let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
                     chunk_results.push(cx.background_spawn(async move {
                         let ranges = query
@@ -4626,6 +4756,51 @@ impl Project {
         })
     }
 
+    async fn handle_language_server_show_document(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::LanguageServerShowDocument>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LanguageServerShowDocumentResponse> {
+        let target = match (envelope.payload.url, envelope.payload.buffer_id) {
+            (Some(url), _) => ShowDocumentTarget::Url(url),
+            (None, Some(buffer_id)) => {
+                let buffer_id = BufferId::new(buffer_id)?;
+                let buffer = this
+                    .update(&mut cx, |this, cx| this.open_buffer_by_id(buffer_id, cx))?
+                    .await?;
+                let selection = match envelope.payload.selection {
+                    Some(selection) => {
+                        let selection = language::proto::deserialize_anchor_range(selection)?;
+                        buffer
+                            .update(&mut cx, |buffer, _| {
+                                buffer.wait_for_anchors([selection.start, selection.end])
+                            })?
+                            .await?;
+                        Some(selection)
+                    }
+                    None => None,
+                };
+                ShowDocumentTarget::Buffer { buffer, selection }
+            }
+            (None, None) => anyhow::bail!("no document to show"),
+        };
+
+        let (tx, rx) = smol::channel::bounded(1);
+        this.update(&mut cx, |_, cx| {
+            cx.emit(Event::ShowDocument(ShowDocumentRequest {
+                target,
+                take_focus: envelope.payload.take_focus,
+                lsp_name: envelope.payload.lsp_name,
+                response_channel: tx,
+            }));
+        })?;
+        drop(this);
+
+        Ok(proto::LanguageServerShowDocumentResponse {
+            success: rx.recv().await.unwrap_or(false),
+        })
+    }
+
     async fn handle_hide_toast(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::HideToast>,
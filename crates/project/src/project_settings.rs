@@ -80,6 +80,15 @@ pub struct ProjectSettings {
     /// Configuration for session-related features
     #[serde(default)]
     pub session: SessionSettings,
+
+    /// Files of at least this many bytes are opened in large-file mode, which
+    /// streams them from disk and disables syntax highlighting, language servers,
+    /// git diffs and soft wrap for them until re-enabled. Set to 0 to never use
+    /// large-file mode.
+    ///
+    /// Default: 104857600 (100 MiB)
+    #[serde(default)]
+    pub large_file_threshold: u64,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -197,12 +206,23 @@ pub struct LspPullDiagnosticsSettings {
     /// Default: 50
     #[serde(default = "default_lsp_diagnostics_pull_debounce_ms")]
     pub debounce_ms: u64,
+    /// How often to re-pull workspace diagnostics from the language server(s)
+    /// that support them, so that files which were never opened stay up to date.
+    /// 0 turns the periodic refresh off.
+    ///
+    /// Default: 30000
+    #[serde(default = "default_lsp_workspace_diagnostics_refresh_interval_ms")]
+    pub workspace_refresh_interval_ms: u64,
 }
 
 fn default_lsp_diagnostics_pull_debounce_ms() -> u64 {
     50
 }
 
+fn default_lsp_workspace_diagnostics_refresh_interval_ms() -> u64 {
+    30_000
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct InlineDiagnosticsSettings {
@@ -256,6 +276,7 @@ impl Default for LspPullDiagnosticsSettings {
         Self {
             enabled: true,
             debounce_ms: default_lsp_diagnostics_pull_debounce_ms(),
+            workspace_refresh_interval_ms: default_lsp_workspace_diagnostics_refresh_interval_ms(),
         }
     }
 }
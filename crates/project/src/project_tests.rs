@@ -9,7 +9,7 @@ use buffer_diff::{
     BufferDiffEvent, CALCULATE_DIFF_TASK, DiffHunkSecondaryStatus, DiffHunkStatus,
     DiffHunkStatusKind, assert_hunks,
 };
-use fs::FakeFs;
+use fs::{Encoding, FakeFs};
 use futures::{StreamExt, future};
 use git::{
     GitHostingProviderRegistry,
@@ -3266,6 +3266,477 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_language_server_show_document(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() { b() }",
+            "b.rs": "fn b() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+
+    let (_buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    let response = fake_server
+        .request::<lsp::request::ShowDocument>(lsp::ShowDocumentParams {
+            uri: lsp::Uri::from_file_path(path!("/dir/b.rs")).unwrap(),
+            external: None,
+            take_focus: None,
+            selection: None,
+        })
+        .await
+        .into_response()
+        .unwrap();
+    assert!(
+        !response.success,
+        "Documents that nobody showed should not be reported as shown"
+    );
+
+    let shown_documents = Arc::new(Mutex::new(Vec::new()));
+    let shown_urls = Arc::new(Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        let shown_documents = shown_documents.clone();
+        let shown_urls = shown_urls.clone();
+        cx.subscribe(&project, move |_, event, cx| {
+            if let Event::ShowDocument(request) = event {
+                match &request.target {
+                    ShowDocumentTarget::Buffer { buffer, selection } => {
+                        let buffer = buffer.read(cx);
+                        shown_documents.lock().push((
+                            buffer.file().unwrap().path().to_path_buf(),
+                            selection
+                                .as_ref()
+                                .map(|selection| selection.to_point(buffer)),
+                            request.take_focus,
+                        ));
+                    }
+                    ShowDocumentTarget::Url(url) => shown_urls.lock().push(url.clone()),
+                }
+                request.respond(true);
+            }
+        })
+        .detach();
+    });
+
+    let response = fake_server
+        .request::<lsp::request::ShowDocument>(lsp::ShowDocumentParams {
+            uri: lsp::Uri::from_file_path(path!("/dir/b.rs")).unwrap(),
+            external: None,
+            take_focus: Some(true),
+            selection: Some(lsp::Range::new(
+                lsp::Position::new(0, 3),
+                lsp::Position::new(0, 4),
+            )),
+        })
+        .await
+        .into_response()
+        .unwrap();
+    assert!(response.success);
+
+    cx.executor().run_until_parked();
+    assert_eq!(
+        &*shown_documents.lock(),
+        &[(
+            PathBuf::from("b.rs"),
+            Some(Point::new(0, 3)..Point::new(0, 4)),
+            true
+        )]
+    );
+
+    let response = fake_server
+        .request::<lsp::request::ShowDocument>(lsp::ShowDocumentParams {
+            uri: "https://zed.dev/docs".parse().unwrap(),
+            external: Some(true),
+            take_focus: None,
+            selection: None,
+        })
+        .await
+        .into_response()
+        .unwrap();
+    assert!(response.success);
+    assert_eq!(&*shown_urls.lock(), &["https://zed.dev/docs".to_string()]);
+}
+
+#[gpui::test]
+async fn test_crashed_language_server_restarts_with_backoff(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({ "a.rs": "fn a() {}" }))
+        .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+
+    let (_buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let mut fake_server = fake_servers.next().await.unwrap();
+
+    for backoff in [1, 2, 4, 8, 16].map(Duration::from_secs) {
+        fake_server.crash();
+        cx.executor().run_until_parked();
+        cx.executor()
+            .advance_clock(backoff - Duration::from_millis(1));
+        cx.executor().run_until_parked();
+        assert!(
+            fake_servers.try_next().is_err(),
+            "The server should not be restarted before {backoff:?} passed"
+        );
+
+        cx.executor().advance_clock(Duration::from_millis(1));
+        cx.executor().run_until_parked();
+        fake_server = fake_servers.next().await.unwrap();
+    }
+
+    fake_server.crash();
+    cx.executor().run_until_parked();
+    cx.executor().advance_clock(Duration::from_secs(120));
+    cx.executor().run_until_parked();
+    assert!(
+        fake_servers.try_next().is_err(),
+        "The server should not be restarted after crashing 6 times in a row"
+    );
+    let crash_count =
+        project.read_with(cx, |project, cx| {
+            project.lsp_store().read(cx).language_server_crash_count(
+                &LanguageServerName::new_static("the-fake-language-server"),
+            )
+        });
+    assert_eq!(crash_count, 6);
+}
+
+#[gpui::test]
+async fn test_language_server_restart_backoff_resets_after_stable_uptime(
+    cx: &mut gpui::TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({ "a.rs": "fn a() {}" }))
+        .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+
+    let (_buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    fake_server.crash();
+    cx.executor().run_until_parked();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    // Crashing again right after the restart doubles the delay.
+    fake_server.crash();
+    cx.executor().run_until_parked();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert!(fake_servers.try_next().is_err());
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    // A server that ran long enough before crashing is restarted without the accumulated delay.
+    cx.executor().advance_clock(Duration::from_secs(180));
+    cx.executor().run_until_parked();
+    fake_server.crash();
+    cx.executor().run_until_parked();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert!(
+        fake_servers.try_next().is_ok(),
+        "The server should be restarted after the base delay"
+    );
+}
+
+#[gpui::test]
+async fn test_workspace_diagnostics_pulled_on_schedule(cx: &mut gpui::TestAppContext) {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() {}",
+            "b.rs": "fn b() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let workspace_pulls = Arc::new(AtomicUsize::new(0));
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions {
+                        identifier: None,
+                        inter_file_dependencies: true,
+                        workspace_diagnostics: true,
+                        work_done_progress_options: Default::default(),
+                    },
+                )),
+                ..Default::default()
+            },
+            initializer: Some(Box::new({
+                let workspace_pulls = workspace_pulls.clone();
+                move |fake_server| {
+                    let workspace_pulls = workspace_pulls.clone();
+                    fake_server.set_request_handler::<lsp::WorkspaceDiagnosticRequest, _, _>(
+                        move |_, _| {
+                            workspace_pulls.fetch_add(1, Ordering::SeqCst);
+                            async move {
+                                Ok(lsp::WorkspaceDiagnosticReportResult::Report(
+                                    lsp::WorkspaceDiagnosticReport {
+                                        items: vec![lsp::WorkspaceDocumentDiagnosticReport::Full(
+                                            lsp::WorkspaceFullDocumentDiagnosticReport {
+                                                uri: Uri::from_file_path(path!("/dir/b.rs"))
+                                                    .unwrap(),
+                                                version: None,
+                                                full_document_diagnostic_report:
+                                                    lsp::FullDocumentDiagnosticReport {
+                                                        result_id: None,
+                                                        items: vec![lsp::Diagnostic {
+                                                            range: lsp::Range::new(
+                                                                lsp::Position::new(0, 3),
+                                                                lsp::Position::new(0, 4),
+                                                            ),
+                                                            severity: Some(
+                                                                DiagnosticSeverity::ERROR,
+                                                            ),
+                                                            message: "unused function".to_string(),
+                                                            ..Default::default()
+                                                        }],
+                                                    },
+                                            },
+                                        )],
+                                    },
+                                ))
+                            }
+                        },
+                    );
+                }
+            })),
+            ..Default::default()
+        },
+    );
+
+    let (_buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let _fake_server = fake_servers.next().await.unwrap();
+    cx.executor().advance_clock(Duration::from_millis(100));
+    cx.run_until_parked();
+
+    assert_eq!(
+        workspace_pulls.load(Ordering::SeqCst),
+        1,
+        "Workspace diagnostics should be pulled on server startup"
+    );
+    assert_eq!(
+        project
+            .read_with(cx, |project, cx| project.diagnostic_summary(false, cx))
+            .error_count,
+        1,
+        "Diagnostics of the file that was never opened should be reported"
+    );
+
+    let refresh_interval_ms = cx.update(|cx| {
+        ProjectSettings::get_global(cx)
+            .diagnostics
+            .lsp_pull_diagnostics
+            .workspace_refresh_interval_ms
+    });
+    cx.executor()
+        .advance_clock(Duration::from_millis(refresh_interval_ms + 100));
+    cx.run_until_parked();
+    assert_eq!(
+        workspace_pulls.load(Ordering::SeqCst),
+        2,
+        "Workspace diagnostics should be re-pulled on schedule"
+    );
+
+    project.update(cx, |project, cx| {
+        project.lsp_store().update(cx, |lsp_store, _| {
+            lsp_store.pull_workspace_diagnostics_for_all_servers()
+        })
+    });
+    cx.executor().advance_clock(Duration::from_millis(100));
+    cx.run_until_parked();
+    assert_eq!(
+        workspace_pulls.load(Ordering::SeqCst),
+        3,
+        "Workspace diagnostics should be re-pulled on demand"
+    );
+}
+
+#[gpui::test]
+async fn test_workspace_diagnostics_partial_results(cx: &mut gpui::TestAppContext) {
+    use futures::FutureExt as _;
+
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() {}",
+            "b.rs": "fn b() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    // The pull is held open until the test has streamed its partial results.
+    let (tokens_tx, mut tokens_rx) = futures::channel::mpsc::unbounded();
+    let (release_tx, release_rx) = futures::channel::oneshot::channel::<()>();
+    let release = release_rx.shared();
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions {
+                        identifier: None,
+                        inter_file_dependencies: true,
+                        workspace_diagnostics: true,
+                        work_done_progress_options: Default::default(),
+                    },
+                )),
+                ..Default::default()
+            },
+            initializer: Some(Box::new(move |fake_server| {
+                let tokens_tx = tokens_tx.clone();
+                let release = release.clone();
+                fake_server.set_request_handler::<lsp::WorkspaceDiagnosticRequest, _, _>(
+                    move |params, _| {
+                        tokens_tx
+                            .unbounded_send(params.partial_result_params.partial_result_token)
+                            .ok();
+                        let release = release.clone();
+                        async move {
+                            release.await.ok();
+                            Ok(lsp::WorkspaceDiagnosticReportResult::Report(
+                                lsp::WorkspaceDiagnosticReport { items: Vec::new() },
+                            ))
+                        }
+                    },
+                );
+            })),
+            ..Default::default()
+        },
+    );
+
+    let (_buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    cx.executor().advance_clock(Duration::from_millis(100));
+    cx.run_until_parked();
+    let token = tokens_rx.next().await.unwrap().unwrap();
+
+    let partial_result = |token: lsp::ProgressToken| lsp::ProgressParams {
+        token,
+        value: lsp::ProgressParamsValue::WorkspaceDiagnostic(
+            lsp::WorkspaceDiagnosticReportResult::Report(lsp::WorkspaceDiagnosticReport {
+                items: vec![lsp::WorkspaceDocumentDiagnosticReport::Full(
+                    lsp::WorkspaceFullDocumentDiagnosticReport {
+                        uri: Uri::from_file_path(path!("/dir/b.rs")).unwrap(),
+                        version: None,
+                        full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                            result_id: None,
+                            items: vec![lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 3),
+                                    lsp::Position::new(0, 4),
+                                ),
+                                severity: Some(DiagnosticSeverity::ERROR),
+                                message: "unused function".to_string(),
+                                ..Default::default()
+                            }],
+                        },
+                    },
+                )],
+            }),
+        ),
+    };
+    let error_count = |cx: &mut gpui::TestAppContext| {
+        project
+            .read_with(cx, |project, cx| project.diagnostic_summary(false, cx))
+            .error_count
+    };
+
+    fake_server.notify::<lsp::notification::Progress>(&partial_result(lsp::ProgressToken::String(
+        "some-other-request".to_string(),
+    )));
+    cx.run_until_parked();
+    assert_eq!(
+        error_count(cx),
+        0,
+        "Partial results of other requests should be ignored"
+    );
+
+    fake_server.notify::<lsp::notification::Progress>(&partial_result(token));
+    cx.run_until_parked();
+    assert_eq!(
+        error_count(cx),
+        1,
+        "Partial results of the current request should be applied"
+    );
+
+    release_tx.send(()).ok();
+    cx.run_until_parked();
+    assert_eq!(error_count(cx), 1);
+}
+
 #[gpui::test]
 async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3837,6 +4308,167 @@ async fn test_save_file(cx: &mut gpui::TestAppContext) {
     assert_eq!(new_text, buffer.update(cx, |buffer, _| buffer.text()));
 }
 
+#[gpui::test]
+async fn test_save_file_with_encoding(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({})).await;
+    // "café" in Windows-1252.
+    fs.insert_file(path!("/dir/file1"), b"caf\xe9".to_vec())
+        .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer(path!("/dir/file1"), cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "café");
+        assert_eq!(buffer.encoding(), Encoding::WINDOWS_1252);
+        buffer.edit([(buffer.len()..buffer.len(), " crème")], None, cx);
+    });
+
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes(Path::new(path!("/dir/file1"))).await.unwrap(),
+        b"caf\xe9 cr\xe8me"
+    );
+
+    project
+        .update(cx, |project, cx| {
+            project.save_buffer_with_encoding(buffer.clone(), Encoding::UTF_8, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes(Path::new(path!("/dir/file1"))).await.unwrap(),
+        "café crème".as_bytes()
+    );
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.encoding(), Encoding::UTF_8);
+    });
+
+    // Characters that can't be represented leave the buffer's encoding untouched.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "日本 ")], None, cx));
+    assert!(
+        project
+            .update(cx, |project, cx| {
+                project.save_buffer_with_encoding(buffer.clone(), Encoding::WINDOWS_1252, cx)
+            })
+            .await
+            .is_err()
+    );
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.encoding(), Encoding::UTF_8);
+    });
+}
+
+#[gpui::test]
+async fn test_large_file_mode(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.large_file_threshold = 1024;
+            });
+        });
+    });
+
+    // Large enough to be read in several chunks, with a CRLF split across the first two.
+    let contents = format!("xx{}", "a\r\n".repeat(400_000));
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "large.rs": contents,
+            "small.rs": "fn main() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_rust_servers =
+        language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+
+    let (small_buffer, _small_handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/small.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let (large_buffer, _large_handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/large.rs"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    small_buffer.read_with(cx, |buffer, _| {
+        assert!(!buffer.large_file_mode());
+        assert_eq!(buffer.language().map(|l| l.name()), Some("Rust".into()));
+    });
+    large_buffer.read_with(cx, |buffer, _| {
+        assert!(buffer.large_file_mode());
+        assert!(buffer.language().is_none());
+        assert_eq!(buffer.line_ending(), LineEnding::Windows);
+        assert_eq!(buffer.text(), format!("xx{}", "a\n".repeat(400_000)));
+    });
+
+    let fake_rust_server = fake_rust_servers.next().await.unwrap();
+    assert_eq!(
+        fake_rust_server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri,
+        lsp::Uri::from_file_path(path!("/dir/small.rs")).unwrap(),
+    );
+
+    // Changes on disk are streamed in again, staying in large-file mode.
+    fs.insert_file(
+        path!("/dir/large.rs"),
+        format!("yy{}", "b\r\n".repeat(400_000)).into_bytes(),
+    )
+    .await;
+    cx.executor().run_until_parked();
+    large_buffer.read_with(cx, |buffer, _| {
+        assert!(buffer.large_file_mode());
+        assert!(!buffer.is_dirty());
+        assert_eq!(buffer.line_ending(), LineEnding::Windows);
+        assert_eq!(buffer.text(), format!("yy{}", "b\n".repeat(400_000)));
+    });
+
+    // The contents are replaced in several edits, but they're undone together.
+    large_buffer.update(cx, |buffer, cx| {
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), format!("xx{}", "a\n".repeat(400_000)));
+        buffer.redo(cx);
+        assert_eq!(buffer.text(), format!("yy{}", "b\n".repeat(400_000)));
+    });
+
+    // Leaving large-file mode detects the language and opens the file in its language server.
+    large_buffer.update(cx, |buffer, cx| buffer.set_large_file_mode(false, cx));
+    cx.executor().run_until_parked();
+    large_buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.language().map(|l| l.name()), Some("Rust".into()));
+    });
+    assert_eq!(
+        fake_rust_server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri,
+        lsp::Uri::from_file_path(path!("/dir/large.rs")).unwrap(),
+    );
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_save_file_spawns_language_server(cx: &mut gpui::TestAppContext) {
     // Issue: #24349
@@ -5320,6 +5952,57 @@ async fn test_search(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "fn one() { let x = a.unwrap(); }",
+            "two.rs": "fn two() { b.unwrap_or(1); c.d().unwrap(); }",
+            "three.rs": "fn three() {}",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    let query = SearchQuery::structural(
+        "$X.unwrap()",
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+        &language_registry.to_vec(),
+    )
+    .unwrap();
+    assert_eq!(
+        search(&project, query, cx).await.unwrap(),
+        HashMap::from_iter([
+            (path!("dir/one.rs").to_string(), vec![19..29]),
+            (path!("dir/two.rs").to_string(), vec![27..41]),
+        ])
+    );
+
+    assert!(
+        SearchQuery::structural(
+            "$X.unwrap(",
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None,
+            &language_registry.to_vec(),
+        )
+        .is_err(),
+        "a pattern that doesn't parse in any language should be rejected"
+    );
+}
+
 #[gpui::test]
 async fn test_search_with_inclusions(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3,7 +3,7 @@ use anyhow::Result;
 use client::proto;
 use fancy_regex::{Captures, Regex, RegexBuilder};
 use gpui::Entity;
-use language::{Buffer, BufferSnapshot, CharKind};
+use language::{Buffer, BufferSnapshot, CharKind, Language, StructuralPattern};
 use smol::future::yield_now;
 use std::{
     borrow::Cow,
@@ -75,6 +75,13 @@ pub enum SearchQuery {
         one_match_per_line: bool,
         inner: SearchInputs,
     },
+
+    Structural {
+        pattern: Arc<StructuralPattern>,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 static WORD_MATCH_TEST: LazyLock<Regex> = LazyLock::new(|| {
@@ -200,6 +207,51 @@ impl SearchQuery {
         })
     }
 
+    /// Create a structural query
+    ///
+    /// The query is a code pattern that is matched against the syntax trees of the searched buffers,
+    /// where `$NAME` matches any single syntax node and `$$$NAME` matches any sequence of sibling nodes.
+    /// Include/exclude patterns are matched the same way as for [`SearchQuery::regex`].
+    ///
+    /// Fails if the pattern doesn't parse in any of the given `languages`. Pass no languages to
+    /// skip this check.
+    pub fn structural(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+        match_full_paths: bool,
+        buffers: Option<Vec<Entity<Buffer>>>,
+        languages: &[Arc<Language>],
+    ) -> Result<Self> {
+        let query = query.to_string();
+        let pattern = Arc::new(StructuralPattern::new(&query));
+        let candidates = languages
+            .iter()
+            .filter(|language| language.grammar().is_some())
+            .collect::<Vec<_>>();
+        if !candidates.is_empty()
+            && !candidates
+                .iter()
+                .any(|language| pattern.is_valid_for(language))
+        {
+            anyhow::bail!("pattern isn't valid code in any loaded language");
+        }
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_exclude,
+            files_to_include,
+            match_full_paths,
+            buffers,
+        };
+        Ok(Self::Structural {
+            pattern,
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
     /// Extracts case sensitivity settings from pattern items in the provided
     /// query and returns the same query, with the pattern items removed.
     ///
@@ -264,7 +316,17 @@ impl SearchQuery {
             message.files_to_exclude
         };
 
-        if message.regex {
+        if message.structural {
+            Self::structural(
+                message.query,
+                message.include_ignored,
+                PathMatcher::new(files_to_include)?,
+                PathMatcher::new(files_to_exclude)?,
+                message.match_full_paths,
+                None, // search opened only don't need search remote
+                &[],
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -299,6 +361,10 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
@@ -312,6 +378,7 @@ impl SearchQuery {
         proto::SearchQuery {
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            structural: self.is_structural(),
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
@@ -361,14 +428,25 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            Self::Structural { pattern, .. } => {
+                let Some(literal_hint) = pattern.literal_hint() else {
+                    return Ok(true);
+                };
+                let search = AhoCorasick::new([literal_hint])?;
+                Ok(search
+                    .stream_find_iter(reader)
+                    .next()
+                    .transpose()?
+                    .is_some())
+            }
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
@@ -395,6 +473,26 @@ impl SearchQuery {
                     None
                 }
             }
+            // The replacement depends on the syntax tree around the match, not only on its text.
+            SearchQuery::Structural { .. } => None,
+        }
+    }
+
+    /// Renders the replacement for the structural match spanning exactly `range` in `buffer`,
+    /// substituting the text captured by the pattern's metavariables into the replacement template.
+    /// Returns `None` for other queries, or when no replacement is set.
+    pub fn structural_replacement_for(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        match self {
+            SearchQuery::Structural {
+                pattern,
+                replacement: Some(replacement),
+                ..
+            } => buffer.structural_replacement(pattern, range, replacement),
+            _ => None,
         }
     }
 
@@ -493,6 +591,13 @@ impl SearchQuery {
                     }
                 }
             }
+
+            Self::Structural { pattern, .. } => {
+                let range = range_offset..range_offset + rope.len();
+                for mat in buffer.structural_matches(pattern, range) {
+                    matches.push(mat.start - range_offset..mat.end - range_offset);
+                }
+            }
         }
 
         matches
@@ -510,6 +615,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -517,6 +623,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -528,6 +635,9 @@ impl SearchQuery {
             Self::Regex {
                 include_ignored, ..
             } => *include_ignored,
+            Self::Structural {
+                include_ignored, ..
+            } => *include_ignored,
         }
     }
 
@@ -535,6 +645,10 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
     pub fn files_to_include(&self) -> &PathMatcher {
         self.as_inner().files_to_include()
     }
@@ -578,7 +692,9 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
         }
     }
 
@@ -591,7 +707,7 @@ impl SearchQuery {
             Self::Regex {
                 one_match_per_line, ..
             } => Some(*one_match_per_line),
-            Self::Text { .. } => None,
+            Self::Text { .. } | Self::Structural { .. } => None,
         }
     }
 }
@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result, bail};
 use collections::{HashMap, HashSet, VecDeque};
 use dap::DapRegistry;
 use fs::Fs;
@@ -157,6 +157,15 @@ pub enum TaskSourceKind {
     },
 }
 
+/// A task to run as part of a [`ResolvedTask`]'s dependency graph, see [`Inventory::task_dependency_graph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskGraphNode {
+    pub source_kind: TaskSourceKind,
+    pub task: ResolvedTask,
+    /// Indices of the nodes that have to succeed before this task is spawned, in `depends_on` order.
+    pub dependencies: Vec<usize>,
+}
+
 /// A collection of task contexts, derived from the current state of the workspace.
 /// Only contains worktrees that are visible and with their root being a directory.
 #[derive(Debug, Default)]
@@ -607,6 +616,65 @@ impl Inventory {
         }
     }
 
+    /// Returns the values the inputs of the template were given when a task from it was scheduled the last time, by input id.
+    pub fn last_input_values(
+        &self,
+        task_source_kind: &TaskSourceKind,
+        template: &TaskTemplate,
+    ) -> HashMap<String, String> {
+        self.last_scheduled_tasks
+            .iter()
+            .rev()
+            .find(|(kind, task)| {
+                kind == task_source_kind && task.original_task().label == template.label
+            })
+            .map(|(_, task)| task.input_values())
+            .unwrap_or_default()
+    }
+
+    /// Resolves the tasks the given one `depends_on`, transitively, with the context the task itself was resolved with.
+    /// Dependencies are looked up by label among the tasks of the task's worktree first, and the global tasks second.
+    ///
+    /// Returns the nodes in the order they can be spawned in: every node comes after its dependencies,
+    /// the task given is the last one. Each task is present once, even if several others depend on it.
+    /// Fails on unknown labels and dependency cycles.
+    pub fn task_dependency_graph(
+        &self,
+        task_source_kind: &TaskSourceKind,
+        resolved_task: &ResolvedTask,
+    ) -> Result<Vec<TaskGraphNode>> {
+        let worktree = match task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => None,
+        };
+        let templates = worktree
+            .into_iter()
+            .flat_map(|worktree| self.worktree_templates_from_settings(worktree))
+            .chain(self.global_templates_from_settings())
+            .collect::<Vec<_>>();
+        let task_context = resolved_task
+            .dependencies_context()
+            .cloned()
+            .unwrap_or_default();
+
+        let root_label = &resolved_task.original_task().label;
+        let mut builder = TaskGraphBuilder {
+            templates: &templates,
+            task_context: &task_context,
+            nodes: Vec::new(),
+            node_ids: HashMap::default(),
+            stack: vec![root_label.clone()],
+        };
+        let dependencies = builder.add_dependencies(&resolved_task.original_task().depends_on)?;
+        let mut nodes = builder.nodes;
+        nodes.push(TaskGraphNode {
+            source_kind: task_source_kind.clone(),
+            task: resolved_task.clone(),
+            dependencies,
+        });
+        Ok(nodes)
+    }
+
     /// Deletes a resolved task from history, using its id.
     /// A similar may still resurface in `used_and_current_resolved_tasks` when its [`TaskTemplate`] is resolved again.
     pub fn delete_previously_used(&mut self, id: &TaskId) {
@@ -804,6 +872,66 @@ impl Inventory {
     }
 }
 
+struct TaskGraphBuilder<'a> {
+    templates: &'a [(TaskSourceKind, TaskTemplate)],
+    task_context: &'a TaskContext,
+    nodes: Vec<TaskGraphNode>,
+    node_ids: HashMap<String, usize>,
+    /// Labels of the tasks whose dependencies are being resolved, to detect cycles.
+    stack: Vec<String>,
+}
+
+impl TaskGraphBuilder<'_> {
+    fn add_dependencies(&mut self, labels: &[String]) -> Result<Vec<usize>> {
+        labels.iter().map(|label| self.add_task(label)).collect()
+    }
+
+    fn add_task(&mut self, label: &str) -> Result<usize> {
+        if let Some(&node_id) = self.node_ids.get(label) {
+            return Ok(node_id);
+        }
+        if self.stack.iter().any(|parent| parent == label) {
+            bail!(
+                "Task dependency cycle: {} -> {label}",
+                self.stack.join(" -> ")
+            );
+        }
+        let (source_kind, template) = self
+            .templates
+            .iter()
+            .find(|(_, template)| template.label == label)
+            .with_context(|| {
+                format!(
+                    "Task `{}` depends on an unknown task `{label}`",
+                    self.stack.last().map_or("", String::as_str)
+                )
+            })?;
+        let task = template
+            .resolve_task(&source_kind.to_id_base(), self.task_context)
+            .with_context(|| format!("Failed to resolve task dependency `{label}`"))?;
+        // Dependencies get the values of the inputs of the task depending on them, but cannot ask for their own.
+        if let Some(input) = task.pending_inputs().next() {
+            bail!(
+                "Task dependency `{label}` has an input `{}` the tasks depending on it do not have",
+                input.id()
+            );
+        }
+
+        self.stack.push(label.to_owned());
+        let dependencies = self.add_dependencies(&template.depends_on);
+        self.stack.pop();
+
+        let node_id = self.nodes.len();
+        self.nodes.push(TaskGraphNode {
+            source_kind: source_kind.clone(),
+            task,
+            dependencies: dependencies?,
+        });
+        self.node_ids.insert(label.to_owned(), node_id);
+        Ok(node_id)
+    }
+}
+
 fn task_lru_comparator(
     (kind_a, task_a, lru_score_a): &(TaskSourceKind, ResolvedTask, u32),
     (kind_b, task_b, lru_score_b): &(TaskSourceKind, ResolvedTask, u32),
@@ -1555,6 +1683,125 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_task_dependency_graph(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let inventory = cx.update(|cx| Inventory::new(fs, cx));
+        let worktree = WorktreeId::from_usize(1);
+
+        inventory.update(cx, |inventory, _| {
+            inventory
+                .update_file_based_tasks(
+                    TaskSettingsLocation::Global(tasks_file()),
+                    Some(
+                        &json!([
+                            { "label": "fetch", "command": "git", "args": ["fetch"] },
+                            { "label": "loop a", "command": "a", "depends_on": ["loop b"] },
+                            { "label": "loop b", "command": "b", "depends_on": ["loop a"] },
+                        ])
+                        .to_string(),
+                    ),
+                )
+                .unwrap();
+            inventory
+                .update_file_based_tasks(
+                    TaskSettingsLocation::Worktree(SettingsLocation {
+                        worktree_id: worktree,
+                        path: Path::new(".zed"),
+                    }),
+                    Some(
+                        &json!([
+                            { "label": "server", "command": "cargo", "depends_on": ["fetch"] },
+                            { "label": "client", "command": "npm", "depends_on": ["fetch"] },
+                            {
+                                "label": "all",
+                                "depends_on": ["server", "client"],
+                                "depends_order": "sequence",
+                            },
+                            { "label": "missing", "command": "true", "depends_on": ["nope"] },
+                            { "label": "cycle", "command": "true", "depends_on": ["loop a"] },
+                        ])
+                        .to_string(),
+                    ),
+                )
+                .unwrap();
+        });
+
+        let resolve = |label: &str, cx: &mut TestAppContext| {
+            inventory.update(cx, |inventory, _| {
+                let (source_kind, template) = inventory
+                    .worktree_templates_from_settings(worktree)
+                    .find(|(_, template)| template.label == label)
+                    .unwrap();
+                let task = template
+                    .resolve_task(&source_kind.to_id_base(), &TaskContext::default())
+                    .unwrap();
+                inventory
+                    .task_dependency_graph(&source_kind, &task)
+                    .map(|nodes| {
+                        nodes
+                            .into_iter()
+                            .map(|node| (node.task.resolved_label, node.dependencies))
+                            .collect::<Vec<_>>()
+                    })
+            })
+        };
+
+        assert_eq!(
+            resolve("all", cx).unwrap(),
+            vec![
+                ("fetch".to_string(), vec![]),
+                ("server".to_string(), vec![0]),
+                ("client".to_string(), vec![0]),
+                ("all".to_string(), vec![1, 2]),
+            ],
+            "Shared dependencies should be resolved once, and come before their dependents"
+        );
+        assert!(resolve("missing", cx).is_err());
+        assert!(resolve("cycle", cx).is_err());
+    }
+
+    #[gpui::test]
+    async fn test_last_input_values(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let inventory = cx.update(|cx| Inventory::new(fs, cx));
+        let template = TaskTemplate {
+            label: "test".to_string(),
+            command: "cargo test $ZED_INPUT_filter".to_string(),
+            inputs: vec![task::TaskInput::PromptString {
+                id: "filter".to_string(),
+                description: None,
+                default: None,
+            }],
+            ..TaskTemplate::default()
+        };
+        let source_kind = TaskSourceKind::UserInput;
+        let task = template
+            .resolve_task(&source_kind.to_id_base(), &TaskContext::default())
+            .unwrap();
+
+        inventory.update(cx, |inventory, _| {
+            assert!(
+                inventory
+                    .last_input_values(&source_kind, &template)
+                    .is_empty()
+            );
+            for filter in ["parser", "lexer"] {
+                let task = task
+                    .with_input_values([("filter".to_string(), filter.to_string())])
+                    .unwrap();
+                inventory.task_scheduled(source_kind.clone(), task);
+            }
+            assert_eq!(
+                inventory.last_input_values(&source_kind, &template),
+                HashMap::from_iter([("filter".to_string(), "lexer".to_string())]),
+                "The values of the last run should be suggested"
+            );
+        });
+    }
+
     fn init_test(_cx: &mut TestAppContext) {
         zlog::init_test();
         TaskStore::init(None);
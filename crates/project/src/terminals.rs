@@ -1,9 +1,12 @@
 use anyhow::Result;
-use collections::HashMap;
-use gpui::{App, AppContext as _, Context, Entity, Task, WeakEntity};
+use collections::{HashMap, HashSet};
+use gpui::{App, AppContext as _, Context, Entity, EntityId, Task, WeakEntity};
 
 use itertools::Itertools as _;
-use language::LanguageName;
+use language::{
+    Diagnostic, DiagnosticEntry, DiagnosticSourceKind, LanguageName, PointUtf16, Unclipped,
+};
+use lsp::{DiagnosticSeverity, LanguageServerId, NumberOrString};
 use remote::RemoteClient;
 use settings::{Settings, SettingsLocation};
 use smol::channel::bounded;
@@ -12,16 +15,28 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
-use task::{Shell, ShellBuilder, ShellKind, SpawnInTerminal};
+use task::{Problem, ProblemSeverity, Shell, ShellBuilder, ShellKind, SpawnInTerminal, TaskId};
 use terminal::{
     TaskState, TaskStatus, Terminal, TerminalBuilder, terminal_settings::TerminalSettings,
 };
-use util::{get_default_system_shell, get_system_shell, maybe};
+use util::{ResultExt as _, get_default_system_shell, get_system_shell, maybe};
 
 use crate::{Project, ProjectPath};
 
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakEntity<terminal::Terminal>>,
+    /// Diagnostics reported by the problem matchers of each task's last run, by task id.
+    pub(crate) task_diagnostics: HashMap<TaskId, TaskDiagnostics>,
+}
+
+pub(crate) struct TaskDiagnostics {
+    /// The id the diagnostics are reported under, reserved for the task the same way it would be for a language server.
+    server_id: LanguageServerId,
+    /// Source of the diagnostics whose problem matcher doesn't name one.
+    source: String,
+    /// The terminal of the task's last run, which reports the problems.
+    terminal: EntityId,
+    problems: HashMap<PathBuf, Vec<Problem>>,
 }
 
 impl Project {
@@ -95,6 +110,9 @@ impl Project {
         env.extend(settings.env);
 
         let local_path = if is_via_remote { None } else { path.clone() };
+        // Paths reported by a remote task are on the remote host, where the diagnostics come from language servers only.
+        let problem_source = (!is_via_remote && !spawn_task.problem_matchers.is_empty())
+            .then(|| (spawn_task.id.clone(), spawn_task.label.clone()));
         let task_state = Some(TaskState {
             id: spawn_task.id,
             full_label: spawn_task.full_label,
@@ -105,6 +123,7 @@ impl Project {
             show_summary: spawn_task.show_summary,
             show_command: spawn_task.show_command,
             show_rerun: spawn_task.show_rerun,
+            problem_matchers: spawn_task.problem_matchers,
             completion_rx,
         });
         let remote_client = self.remote_client.clone();
@@ -230,6 +249,7 @@ impl Project {
                     settings.cursor_shape.unwrap_or_default(),
                     settings.alternate_scroll,
                     settings.max_scroll_history_lines,
+                    settings.shell_integration,
                     is_via_remote,
                     cx.entity_id().as_u64(),
                     Some(completion_tx),
@@ -257,12 +277,119 @@ impl Project {
                     })
                     .detach();
 
+                    if let Some((task_id, task_label)) = problem_source {
+                        this.watch_task_problems(task_id, task_label, &terminal_handle, cx);
+                    }
+
                     terminal_handle
                 })
             })?
         })
     }
 
+    /// Reports the problems found in the output of the task running in the terminal as diagnostics,
+    /// replacing the ones of the task's previous run.
+    fn watch_task_problems(
+        &mut self,
+        task_id: TaskId,
+        task_label: String,
+        terminal: &Entity<Terminal>,
+        cx: &mut Context<Self>,
+    ) {
+        let previous_run = self.terminals.task_diagnostics.remove(&task_id);
+        let server_id = match &previous_run {
+            Some(previous_run) => previous_run.server_id,
+            None => self.languages.next_language_server_id(),
+        };
+        if let Some(previous_run) = previous_run {
+            for path in previous_run.problems.into_keys() {
+                self.update_task_diagnostics(server_id, &previous_run.source, path, &[], cx);
+            }
+        }
+        self.terminals.task_diagnostics.insert(
+            task_id.clone(),
+            TaskDiagnostics {
+                server_id,
+                source: task_label,
+                terminal: terminal.entity_id(),
+                problems: HashMap::default(),
+            },
+        );
+
+        cx.subscribe(terminal, move |project, terminal, event, cx| {
+            if let terminal::Event::Wakeup = event {
+                let problems = terminal.read(cx).take_task_problems();
+                if !problems.is_empty() {
+                    project.add_task_problems(&task_id, terminal.entity_id(), problems, cx);
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn add_task_problems(
+        &mut self,
+        task_id: &TaskId,
+        terminal: EntityId,
+        problems: Vec<Problem>,
+        cx: &mut Context<Self>,
+    ) {
+        let root = self.first_project_directory(cx);
+        let Some(task_diagnostics) = self.terminals.task_diagnostics.get_mut(task_id) else {
+            return;
+        };
+        if task_diagnostics.terminal != terminal {
+            return;
+        }
+
+        let mut updated_paths = HashSet::default();
+        for problem in problems {
+            let path = match &root {
+                Some(root) if problem.path.is_relative() => root.join(&problem.path),
+                _ => problem.path.clone(),
+            };
+            task_diagnostics
+                .problems
+                .entry(path.clone())
+                .or_default()
+                .push(problem);
+            updated_paths.insert(path);
+        }
+
+        let server_id = task_diagnostics.server_id;
+        let source = task_diagnostics.source.clone();
+        let updates = updated_paths
+            .into_iter()
+            .map(|path| {
+                let problems = task_diagnostics.problems[&path].clone();
+                (path, problems)
+            })
+            .collect::<Vec<_>>();
+        for (path, problems) in updates {
+            self.update_task_diagnostics(server_id, &source, path, &problems, cx);
+        }
+    }
+
+    fn update_task_diagnostics(
+        &self,
+        server_id: LanguageServerId,
+        source: &str,
+        abs_path: PathBuf,
+        problems: &[Problem],
+        cx: &mut Context<Self>,
+    ) {
+        let diagnostics = problems
+            .iter()
+            .enumerate()
+            .map(|(group_id, problem)| task_problem_diagnostic(problem, source, group_id))
+            .collect();
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store
+                .update_diagnostic_entries(server_id, abs_path, None, None, diagnostics, cx)
+                .log_err();
+        });
+    }
+
     pub fn create_terminal_shell(
         &mut self,
         cwd: Option<PathBuf>,
@@ -358,6 +485,7 @@ impl Project {
                     settings.cursor_shape.unwrap_or_default(),
                     settings.alternate_scroll,
                     settings.max_scroll_history_lines,
+                    settings.shell_integration,
                     is_via_remote,
                     cx.entity_id().as_u64(),
                     None,
@@ -521,3 +649,47 @@ fn create_remote_shell(
         title_override: Some(format!("{} — Terminal", host).into()),
     })
 }
+
+fn task_problem_diagnostic(
+    problem: &Problem,
+    source: &str,
+    group_id: usize,
+) -> DiagnosticEntry<Unclipped<PointUtf16>> {
+    // Problems are reported with 1-based lines and columns; ones without a column span their whole line.
+    let row = problem.line.saturating_sub(1);
+    let start = PointUtf16::new(
+        row,
+        problem.column.map_or(0, |column| column.saturating_sub(1)),
+    );
+    let end_row = problem.end_line.map_or(row, |line| line.saturating_sub(1));
+    let end = match problem.end_column {
+        Some(column) => PointUtf16::new(end_row, column.saturating_sub(1)),
+        None if problem.column.is_none() || problem.end_line.is_some() => {
+            PointUtf16::new(end_row, u32::MAX)
+        }
+        None => start,
+    };
+    DiagnosticEntry {
+        range: Unclipped(start)..Unclipped(end),
+        diagnostic: Diagnostic {
+            source: Some(problem.source.clone().unwrap_or_else(|| source.to_string())),
+            source_kind: DiagnosticSourceKind::Other,
+            code: problem.code.clone().map(NumberOrString::String),
+            code_description: None,
+            severity: match problem.severity {
+                ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+            },
+            message: problem.message.clone(),
+            markdown: None,
+            group_id,
+            is_primary: true,
+            is_disk_based: true,
+            is_unnecessary: false,
+            underline: true,
+            data: None,
+        },
+    }
+}
@@ -141,6 +141,8 @@ messages!(
     (LanguageServerLog, Foreground),
     (LanguageServerPromptRequest, Foreground),
     (LanguageServerPromptResponse, Foreground),
+    (LanguageServerShowDocument, Foreground),
+    (LanguageServerShowDocumentResponse, Foreground),
     (LeaveChannelBuffer, Background),
     (LeaveChannelChat, Foreground),
     (LeaveProject, Foreground),
@@ -217,6 +219,12 @@ messages!(
     (ResolveInlayHintResponse, Background),
     (GetDocumentColor, Background),
     (GetDocumentColorResponse, Background),
+    (GetDocumentLinks, Background),
+    (GetDocumentLinksResponse, Background),
+    (ResolveDocumentLink, Background),
+    (ResolveDocumentLinkResponse, Background),
+    (GetInlineValues, Background),
+    (GetInlineValuesResponse, Background),
     (GetColorPresentation, Background),
     (GetColorPresentationResponse, Background),
     (RefreshCodeLens, Background),
@@ -228,6 +236,7 @@ messages!(
     (StopLanguageServers, Background),
     (RoomUpdated, Foreground),
     (SaveBuffer, Foreground),
+    (SetBufferLargeFileMode, Foreground),
     (SendChannelMessage, Background),
     (SendChannelMessageResponse, Background),
     (SetChannelMemberRole, Foreground),
@@ -388,6 +397,7 @@ request_messages!(
     (RejoinChannelBuffers, RejoinChannelBuffersResponse),
     (RejoinRoom, RejoinRoomResponse),
     (ReloadBuffers, ReloadBuffersResponse),
+    (SetBufferLargeFileMode, Ack),
     (RemoveChannelMember, Ack),
     (RemoveChannelMessage, Ack),
     (UpdateChannelMessage, Ack),
@@ -403,6 +413,9 @@ request_messages!(
     (ResolveInlayHint, ResolveInlayHintResponse),
     (GetDocumentColor, GetDocumentColorResponse),
     (GetColorPresentation, GetColorPresentationResponse),
+    (GetDocumentLinks, GetDocumentLinksResponse),
+    (ResolveDocumentLink, ResolveDocumentLinkResponse),
+    (GetInlineValues, GetInlineValuesResponse),
     (RespondToChannelInvite, Ack),
     (RespondToContactRequest, Ack),
     (SaveBuffer, BufferSaved),
@@ -451,6 +464,7 @@ request_messages!(
     (GetPermalinkToLine, GetPermalinkToLineResponse),
     (FlushBufferedMessages, Ack),
     (LanguageServerPromptRequest, LanguageServerPromptResponse),
+    (LanguageServerShowDocument, LanguageServerShowDocumentResponse),
     (GitGetBranches, GitBranchesResponse),
     (UpdateGitBranch, Ack),
     (ListToolchains, ListToolchainsResponse),
@@ -516,6 +530,8 @@ entity_messages!(
     CreateBufferForPeer,
     CreateProjectEntry,
     GetDocumentColor,
+    GetDocumentLinks,
+    GetInlineValues,
     DeleteProjectEntry,
     ExpandProjectEntry,
     ExpandAllForProjectEntry,
@@ -559,9 +575,11 @@ entity_messages!(
     RefreshInlayHints,
     RefreshCodeLens,
     ReloadBuffers,
+    SetBufferLargeFileMode,
     RemoveProjectCollaborator,
     RenameProjectEntry,
     ResolveCompletionDocumentation,
+    ResolveDocumentLink,
     ResolveInlayHint,
     SaveBuffer,
     Stage,
@@ -602,6 +620,7 @@ entity_messages!(
     OpenServerSettings,
     GetPermalinkToLine,
     LanguageServerPromptRequest,
+    LanguageServerShowDocument,
     GitGetBranches,
     UpdateGitBranch,
     ListToolchains,
@@ -7,11 +7,14 @@ use extension_host::headless_host::HeadlessExtensionStore;
 use fs::Fs;
 use gpui::{App, AppContext as _, AsyncApp, Context, Entity, PromptLevel};
 use http_client::HttpClient;
-use language::{Buffer, BufferEvent, LanguageRegistry, proto::serialize_operation};
+use language::{
+    Buffer, BufferEvent, LanguageRegistry,
+    proto::{serialize_anchor_range, serialize_operation},
+};
 use node_runtime::NodeRuntime;
 use project::{
     LspStore, LspStoreEvent, ManifestTree, PrettierStore, ProjectEnvironment, ProjectPath,
-    ToolchainStore, WorktreeId,
+    ShowDocumentTarget, ToolchainStore, WorktreeId,
     buffer_store::{BufferStore, BufferStoreEvent},
     debugger::{breakpoint_store::BreakpointStore, dap_store::DapStore},
     git_store::GitStore,
@@ -381,6 +384,31 @@ impl HeadlessProject {
                 })
                 .detach();
             }
+            LspStoreEvent::ShowDocument(request) => {
+                let (url, buffer_id, selection) = match &request.target {
+                    ShowDocumentTarget::Url(url) => (Some(url.clone()), None, None),
+                    ShowDocumentTarget::Buffer { buffer, selection } => (
+                        None,
+                        Some(buffer.read(cx).remote_id().to_proto()),
+                        selection.clone().map(serialize_anchor_range),
+                    ),
+                };
+                let response = self.session.request(proto::LanguageServerShowDocument {
+                    project_id: REMOTE_SERVER_PROJECT_ID,
+                    lsp_name: request.lsp_name.clone(),
+                    take_focus: request.take_focus,
+                    url,
+                    buffer_id,
+                    selection,
+                });
+                let request = request.clone();
+                cx.background_spawn(async move {
+                    let response = response.await?;
+                    request.respond(response.success);
+                    anyhow::Ok(())
+                })
+                .detach();
+            }
             _ => {}
         }
     }
@@ -20,6 +20,7 @@ use lsp::{CompletionContext, CompletionResponse, CompletionTriggerKind, Language
 use node_runtime::NodeRuntime;
 use project::{
     Project, ProjectPath,
+    project_settings::ProjectSettings,
     search::{SearchQuery, SearchResult},
 };
 use remote::RemoteClient;
@@ -725,6 +726,150 @@ async fn test_remote_cancel_language_server_work(
     }
 }
 
+#[gpui::test]
+async fn test_remote_show_document(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                ".git": {},
+                "README.md": "# project 1",
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }",
+                    "main.rs": "fn main() { one(); }"
+                }
+            },
+        }),
+    )
+    .await;
+
+    let (project, headless) = init_test(&fs, cx, server_cx).await;
+
+    fs.insert_tree(
+        path!("/code/project1/.zed"),
+        json!({
+            "settings.json": r#"
+          {
+            "languages": {"Rust":{"language_servers":["rust-analyzer"]}},
+            "lsp": {
+              "rust-analyzer": {
+                "binary": {
+                  "path": "~/.cargo/bin/rust-analyzer"
+                }
+              }
+            }
+          }"#
+        }),
+    )
+    .await;
+
+    cx.update_entity(&project, |project, _| {
+        project.languages().register_test_language(LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".into()],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        project.languages().register_fake_lsp_adapter(
+            "Rust",
+            FakeLspAdapter {
+                name: "rust-analyzer",
+                ..Default::default()
+            },
+        )
+    });
+
+    let mut fake_lsp = server_cx.update(|cx| {
+        headless.read(cx).languages.register_fake_language_server(
+            LanguageServerName("rust-analyzer".into()),
+            Default::default(),
+            None,
+        )
+    });
+
+    cx.run_until_parked();
+
+    let worktree_id = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap()
+        .0
+        .read_with(cx, |worktree, _| worktree.id());
+
+    cx.run_until_parked();
+
+    let (_buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_buffer_with_lsp((worktree_id, Path::new("src/lib.rs")), cx)
+        })
+        .await
+        .unwrap();
+
+    cx.run_until_parked();
+
+    let fake_lsp = fake_lsp.next().await.unwrap();
+
+    let shown_documents = Arc::new(std::sync::Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        let shown_documents = shown_documents.clone();
+        cx.subscribe(&project, move |_, event, cx| {
+            if let project::Event::ShowDocument(request) = event {
+                let shown = match &request.target {
+                    project::ShowDocumentTarget::Buffer { buffer, .. } => buffer
+                        .read(cx)
+                        .file()
+                        .unwrap()
+                        .path()
+                        .to_string_lossy()
+                        .to_string(),
+                    project::ShowDocumentTarget::Url(url) => url.clone(),
+                };
+                shown_documents.lock().unwrap().push(shown);
+                request.respond(true);
+            }
+        })
+        .detach();
+    });
+
+    let response = fake_lsp
+        .request::<lsp::request::ShowDocument>(lsp::ShowDocumentParams {
+            uri: lsp::Uri::from_file_path(path!("/code/project1/src/main.rs")).unwrap(),
+            external: None,
+            take_focus: Some(true),
+            selection: None,
+        })
+        .await
+        .into_response()
+        .unwrap();
+    assert!(response.success);
+
+    let response = fake_lsp
+        .request::<lsp::request::ShowDocument>(lsp::ShowDocumentParams {
+            uri: "https://zed.dev/docs".parse().unwrap(),
+            external: Some(true),
+            take_focus: None,
+            selection: None,
+        })
+        .await
+        .into_response()
+        .unwrap();
+    assert!(response.success);
+
+    assert_eq!(
+        &*shown_documents.lock().unwrap(),
+        &[
+            "src/main.rs".to_string(),
+            "https://zed.dev/docs".to_string()
+        ],
+        "Documents requested by the language server should be shown on the client"
+    );
+}
+
 #[gpui::test]
 async fn test_remote_reload(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let fs = FakeFs::new(server_cx.executor());
@@ -800,6 +945,68 @@ async fn test_remote_reload(cx: &mut TestAppContext, server_cx: &mut TestAppCont
     });
 }
 
+#[gpui::test]
+async fn test_remote_large_file_mode(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                ".git": {},
+                "README.md": "# project 1",
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }"
+                }
+            },
+        }),
+    )
+    .await;
+
+    let (project, headless) = init_test(&fs, cx, server_cx).await;
+    server_cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.large_file_threshold = 10;
+            });
+        });
+    });
+
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+    let worktree_id = cx.update(|cx| worktree.read(cx).id());
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, Path::new("src/lib.rs")), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let buffer_id = cx.read(|cx| buffer.read(cx).remote_id());
+    let server_buffer = server_cx.read(|cx| {
+        headless
+            .read(cx)
+            .buffer_store
+            .read(cx)
+            .get(buffer_id)
+            .unwrap()
+    });
+    assert!(cx.read(|cx| buffer.read(cx).large_file_mode()));
+    assert!(server_cx.read(|cx| server_buffer.read(cx).large_file_mode()));
+
+    buffer.update(cx, |buffer, cx| buffer.set_large_file_mode(false, cx));
+    cx.run_until_parked();
+    assert!(
+        !server_cx.read(|cx| server_buffer.read(cx).large_file_mode()),
+        "Leaving large-file mode on the client should leave it on the server"
+    );
+}
+
 #[gpui::test]
 async fn test_remote_resolve_path_in_buffer(
     cx: &mut TestAppContext,
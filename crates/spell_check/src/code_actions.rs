@@ -0,0 +1,130 @@
+use crate::{SpellChecker, words_to_check};
+use anyhow::{Result, anyhow};
+use editor::{CodeActionProvider, ExcerptId};
+use gpui::{App, AppContext as _, Entity, Task, Window};
+use language::{Buffer, LanguageServerId, Point};
+use project::{CodeAction, LspAction, ProjectTransaction};
+use serde::{Deserialize, Serialize};
+use std::{ops::Range, sync::Arc};
+use text::ToOffset as _;
+
+const SUGGESTION_LIMIT: usize = 5;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum SpellCheckAction {
+    Replace { text: String },
+    AddToDictionary { word: String },
+}
+
+/// Offers the spelling suggestions for the misspelled word at the cursor, and adding that word to
+/// the user's dictionary.
+pub(crate) struct SpellCheckCodeActionProvider;
+
+impl CodeActionProvider for SpellCheckCodeActionProvider {
+    fn id(&self) -> Arc<str> {
+        "spell_check".into()
+    }
+
+    fn code_actions(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<CodeAction>>> {
+        let Some(checker) = cx.global::<SpellChecker>().word_checker() else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let offset = range.start.to_offset(&snapshot);
+        let row = snapshot.offset_to_point(offset).row;
+        let line_range = Point::new(row, 0).to_offset(&snapshot)
+            ..Point::new(row, snapshot.line_len(row)).to_offset(&snapshot);
+        let word_range = snapshot
+            .natural_language_ranges(line_range)
+            .into_iter()
+            .find_map(|natural_language_range| {
+                let text = snapshot
+                    .text_for_range(natural_language_range.clone())
+                    .collect::<String>();
+                words_to_check(&text)
+                    .map(|word_range| {
+                        natural_language_range.start + word_range.start
+                            ..natural_language_range.start + word_range.end
+                    })
+                    .find(|word_range| word_range.start <= offset && offset <= word_range.end)
+            });
+        let Some(word_range) = word_range else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let word = snapshot
+            .text_for_range(word_range.clone())
+            .collect::<String>();
+        if checker.is_correct(&word) {
+            return Task::ready(Ok(Vec::new()));
+        }
+
+        let range = snapshot.anchor_before(word_range.start)..snapshot.anchor_after(word_range.end);
+        cx.background_spawn(async move {
+            let mut actions = checker
+                .suggest(&word, SUGGESTION_LIMIT)
+                .into_iter()
+                .map(|suggestion| {
+                    (
+                        format!("Change to \"{suggestion}\""),
+                        SpellCheckAction::Replace { text: suggestion },
+                    )
+                })
+                .collect::<Vec<_>>();
+            actions.push((
+                format!("Add \"{word}\" to Dictionary"),
+                SpellCheckAction::AddToDictionary { word },
+            ));
+            Ok(actions
+                .into_iter()
+                .map(|(title, action)| CodeAction {
+                    server_id: LanguageServerId(0),
+                    range: range.clone(),
+                    lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+                        title,
+                        kind: Some(lsp::CodeActionKind::QUICKFIX),
+                        data: serde_json::to_value(action).ok(),
+                        ..Default::default()
+                    })),
+                    resolved: true,
+                })
+                .collect())
+        })
+    }
+
+    fn apply_code_action(
+        &self,
+        buffer: Entity<Buffer>,
+        action: CodeAction,
+        _excerpt_id: ExcerptId,
+        _push_to_history: bool,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<ProjectTransaction>> {
+        let spell_check_action = match &action.lsp_action {
+            LspAction::Action(lsp_action) => lsp_action
+                .data
+                .clone()
+                .and_then(|data| serde_json::from_value::<SpellCheckAction>(data).ok()),
+            _ => None,
+        };
+        match spell_check_action {
+            Some(SpellCheckAction::Replace { text }) => {
+                buffer.update(cx, |buffer, cx| {
+                    buffer.edit([(action.range, text)], None, cx);
+                });
+            }
+            Some(SpellCheckAction::AddToDictionary { word }) => {
+                SpellChecker::add_to_user_dictionary(word, cx);
+            }
+            None => return Task::ready(Err(anyhow!("not a spell check action"))),
+        }
+        Task::ready(Ok(ProjectTransaction::default()))
+    }
+}
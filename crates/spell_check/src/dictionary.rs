@@ -0,0 +1,506 @@
+use anyhow::{Result, anyhow};
+use collections::{HashMap, HashSet};
+
+/// A word list read from a pair of Hunspell `.aff` and `.dic` files.
+///
+/// Every form the affix rules allow for a word is expanded up front, so checking a word is a
+/// single lookup. Compounding and morphological analysis are not supported.
+pub struct Dictionary {
+    words: HashSet<String>,
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FlagFormat {
+    Short,
+    Long,
+    Numeric,
+    Utf8,
+}
+
+struct AffixClass {
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+struct AffixRule {
+    strip: String,
+    add: String,
+    condition: Vec<ConditionPart>,
+}
+
+enum ConditionPart {
+    Any,
+    Char(char),
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Casing {
+    Lower,
+    Capitalized,
+    Upper,
+    Mixed,
+}
+
+impl Dictionary {
+    /// Reads a dictionary from the contents of its `.aff` and `.dic` files.
+    pub fn parse(aff: &[u8], dic: &[u8]) -> Result<Self> {
+        let is_latin1 = String::from_utf8_lossy(aff).lines().any(|line| {
+            let mut fields = line.split_whitespace();
+            fields.next() == Some("SET")
+                && fields
+                    .next()
+                    .is_some_and(|encoding| matches!(encoding, "ISO8859-1" | "ISO-8859-1"))
+        });
+        let decode = |bytes: &[u8]| -> String {
+            if is_latin1 {
+                bytes.iter().map(|&byte| byte as char).collect()
+            } else {
+                String::from_utf8_lossy(bytes).into_owned()
+            }
+        };
+        let aff = decode(aff);
+        let dic = decode(dic);
+
+        let mut flag_format = FlagFormat::Short;
+        let mut try_chars = Vec::new();
+        let mut replacements = Vec::new();
+        let mut aliases = Vec::new();
+        let mut aliases_header_seen = false;
+        let mut forbidden_flag = None;
+        let mut need_affix_flag = None;
+        let mut prefixes = HashMap::<String, AffixClass>::default();
+        let mut suffixes = HashMap::<String, AffixClass>::default();
+
+        for line in aff.lines() {
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                Some("FLAG") => {
+                    flag_format = match fields.next() {
+                        Some("long") => FlagFormat::Long,
+                        Some("num") => FlagFormat::Numeric,
+                        Some("UTF-8") => FlagFormat::Utf8,
+                        _ => FlagFormat::Short,
+                    }
+                }
+                Some("TRY") => try_chars = fields.next().unwrap_or_default().chars().collect(),
+                Some("REP") => {
+                    // The first `REP` line only holds the number of replacements.
+                    if let (Some(from), Some(to)) = (fields.next(), fields.next()) {
+                        replacements.push((from.replace('_', " "), to.replace('_', " ")));
+                    }
+                }
+                Some("AF") => {
+                    // Likewise, the first `AF` line only holds the number of aliases.
+                    if aliases_header_seen {
+                        aliases.push(fields.next().unwrap_or_default().to_string());
+                    }
+                    aliases_header_seen = true;
+                }
+                Some("FORBIDDENWORD") => forbidden_flag = fields.next().map(str::to_string),
+                Some("NEEDAFFIX") => need_affix_flag = fields.next().map(str::to_string),
+                Some(kind @ ("PFX" | "SFX")) => {
+                    let (Some(flag), Some(second), Some(third)) =
+                        (fields.next(), fields.next(), fields.next())
+                    else {
+                        continue;
+                    };
+                    let classes = if kind == "PFX" {
+                        &mut prefixes
+                    } else {
+                        &mut suffixes
+                    };
+                    match fields.next() {
+                        None => {
+                            classes.insert(
+                                flag.to_string(),
+                                AffixClass {
+                                    cross_product: second == "Y",
+                                    rules: Vec::new(),
+                                },
+                            );
+                        }
+                        Some(condition) => {
+                            let Some(class) = classes.get_mut(flag) else {
+                                continue;
+                            };
+                            // Flags after a slash continue the affix with further ones, which
+                            // isn't supported.
+                            let add = third.split('/').next().unwrap_or_default();
+                            class.rules.push(AffixRule {
+                                strip: if second == "0" { "" } else { second }.to_string(),
+                                add: if add == "0" { "" } else { add }.to_string(),
+                                condition: parse_condition(condition),
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut lines = dic.lines();
+        lines
+            .next()
+            .and_then(|count| count.trim().parse::<usize>().ok())
+            .ok_or_else(|| anyhow!("dictionary is missing its word count"))?;
+
+        let mut words = HashSet::default();
+        let mut forbidden_words = Vec::new();
+        for line in lines {
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (word, flags) = match entry.split_once('/') {
+                Some((word, flags)) if !aliases.is_empty() => {
+                    let alias = flags
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|index| aliases.get(index.checked_sub(1)?));
+                    (
+                        word,
+                        alias.map_or(Vec::new(), |flags| parse_flags(flags, flag_format)),
+                    )
+                }
+                Some((word, flags)) => (word, parse_flags(flags, flag_format)),
+                None => (entry, Vec::new()),
+            };
+            if word.is_empty() {
+                continue;
+            }
+            if forbidden_flag
+                .as_ref()
+                .is_some_and(|forbidden_flag| flags.contains(forbidden_flag))
+            {
+                forbidden_words.push(word.to_string());
+                continue;
+            }
+            if !need_affix_flag
+                .as_ref()
+                .is_some_and(|need_affix_flag| flags.contains(need_affix_flag))
+            {
+                words.insert(word.to_string());
+            }
+            expand_affixes(word, &flags, &prefixes, &suffixes, &mut words);
+        }
+        for word in forbidden_words {
+            words.remove(&word);
+        }
+
+        Ok(Self {
+            words,
+            try_chars,
+            replacements,
+        })
+    }
+
+    /// Returns whether `word` is spelled correctly.
+    ///
+    /// Words listed in lowercase are also accepted capitalized or in all caps.
+    pub fn check(&self, word: &str) -> bool {
+        let word = word.replace('’', "'");
+        if self.words.contains(&word) {
+            return true;
+        }
+        match Casing::of(&word) {
+            Casing::Lower | Casing::Mixed => false,
+            Casing::Capitalized => self.words.contains(&word.to_lowercase()),
+            Casing::Upper => {
+                let lowercase = word.to_lowercase();
+                self.words.contains(&capitalize(&lowercase)) || self.words.contains(&lowercase)
+            }
+        }
+    }
+
+    /// Returns up to `limit` correctly spelled words close to `word`, most likely first.
+    pub fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let casing = Casing::of(word);
+        let base = if casing == Casing::Mixed {
+            word.to_string()
+        } else {
+            word.to_lowercase()
+        };
+        let recase = |candidate: String| match casing {
+            Casing::Lower | Casing::Mixed => candidate,
+            Casing::Capitalized => capitalize(&candidate),
+            Casing::Upper => candidate.to_uppercase(),
+        };
+
+        let mut candidates = Vec::new();
+        for (from, to) in &self.replacements {
+            for (index, _) in base.match_indices(from.as_str()) {
+                candidates.push(format!(
+                    "{}{}{}",
+                    &base[..index],
+                    to,
+                    &base[index + from.len()..]
+                ));
+            }
+        }
+
+        let chars = base.chars().collect::<Vec<_>>();
+        let alphabet = if self.try_chars.is_empty() {
+            ('a'..='z').collect()
+        } else {
+            self.try_chars.clone()
+        };
+        let to_string = |chars: &[char]| chars.iter().collect::<String>();
+        for ix in 0..chars.len() {
+            for &c in &alphabet {
+                if c != chars[ix] {
+                    let mut replaced = chars.clone();
+                    replaced[ix] = c;
+                    candidates.push(to_string(&replaced));
+                }
+            }
+        }
+        for ix in 0..chars.len().saturating_sub(1) {
+            let mut swapped = chars.clone();
+            swapped.swap(ix, ix + 1);
+            candidates.push(to_string(&swapped));
+        }
+        for ix in 0..chars.len() {
+            let mut removed = chars.clone();
+            removed.remove(ix);
+            candidates.push(to_string(&removed));
+        }
+        for ix in 0..=chars.len() {
+            for &c in &alphabet {
+                let mut inserted = chars.clone();
+                inserted.insert(ix, c);
+                candidates.push(to_string(&inserted));
+            }
+        }
+        for ix in 1..chars.len() {
+            let (first, second) = chars.split_at(ix);
+            let (first, second) = (to_string(first), to_string(second));
+            if self.check(&first) && self.check(&second) {
+                candidates.push(format!("{first} {second}"));
+            }
+        }
+
+        let mut suggestions = Vec::new();
+        for candidate in candidates {
+            if suggestions.len() == limit {
+                break;
+            }
+            let candidate = recase(candidate);
+            if candidate != word
+                && !suggestions.contains(&candidate)
+                && candidate.split(' ').all(|part| self.check(part))
+            {
+                suggestions.push(candidate);
+            }
+        }
+        suggestions
+    }
+}
+
+impl Casing {
+    fn of(word: &str) -> Self {
+        let mut chars = word.chars().filter(|c| c.is_alphabetic());
+        let Some(first) = chars.next() else {
+            return Casing::Lower;
+        };
+        let rest_lowercase = chars.clone().all(|c| !c.is_uppercase());
+        let rest_uppercase = chars.all(|c| !c.is_lowercase());
+        if first.is_uppercase() {
+            if rest_lowercase {
+                Casing::Capitalized
+            } else if rest_uppercase {
+                Casing::Upper
+            } else {
+                Casing::Mixed
+            }
+        } else if rest_lowercase {
+            Casing::Lower
+        } else {
+            Casing::Mixed
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn parse_flags(flags: &str, format: FlagFormat) -> Vec<String> {
+    match format {
+        FlagFormat::Short | FlagFormat::Utf8 => flags.chars().map(String::from).collect(),
+        FlagFormat::Long => flags
+            .chars()
+            .collect::<Vec<_>>()
+            .chunks(2)
+            .map(|chunk| chunk.iter().collect())
+            .collect(),
+        FlagFormat::Numeric => flags.split(',').map(str::to_string).collect(),
+    }
+}
+
+fn parse_condition(condition: &str) -> Vec<ConditionPart> {
+    let mut parts = Vec::new();
+    if condition == "." {
+        return parts;
+    }
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        parts.push(match c {
+            '.' => ConditionPart::Any,
+            '[' => {
+                let mut negated = false;
+                let mut set = Vec::new();
+                for c in chars.by_ref() {
+                    match c {
+                        ']' => break,
+                        '^' if set.is_empty() && !negated => negated = true,
+                        c => set.push(c),
+                    }
+                }
+                if negated {
+                    ConditionPart::NoneOf(set)
+                } else {
+                    ConditionPart::OneOf(set)
+                }
+            }
+            c => ConditionPart::Char(c),
+        });
+    }
+    parts
+}
+
+impl ConditionPart {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            ConditionPart::Any => true,
+            ConditionPart::Char(expected) => c == *expected,
+            ConditionPart::OneOf(set) => set.contains(&c),
+            ConditionPart::NoneOf(set) => !set.contains(&c),
+        }
+    }
+}
+
+impl AffixRule {
+    fn apply_suffix(&self, word: &str) -> Option<String> {
+        let stem = word.strip_suffix(self.strip.as_str())?;
+        let mut chars = word.chars().rev();
+        let matches = self
+            .condition
+            .iter()
+            .rev()
+            .all(|part| chars.next().is_some_and(|c| part.matches(c)));
+        (matches && !(stem.is_empty() && self.add.is_empty()))
+            .then(|| format!("{stem}{}", self.add))
+    }
+
+    fn apply_prefix(&self, word: &str) -> Option<String> {
+        let stem = word.strip_prefix(self.strip.as_str())?;
+        let mut chars = word.chars();
+        let matches = self
+            .condition
+            .iter()
+            .all(|part| chars.next().is_some_and(|c| part.matches(c)));
+        (matches && !(stem.is_empty() && self.add.is_empty()))
+            .then(|| format!("{}{stem}", self.add))
+    }
+}
+
+fn expand_affixes(
+    word: &str,
+    flags: &[String],
+    prefixes: &HashMap<String, AffixClass>,
+    suffixes: &HashMap<String, AffixClass>,
+    words: &mut HashSet<String>,
+) {
+    let mut cross_product_forms = Vec::new();
+    for class in flags.iter().filter_map(|flag| suffixes.get(flag)) {
+        for form in class
+            .rules
+            .iter()
+            .filter_map(|rule| rule.apply_suffix(word))
+        {
+            if class.cross_product {
+                cross_product_forms.push(form.clone());
+            }
+            words.insert(form);
+        }
+    }
+    for class in flags.iter().filter_map(|flag| prefixes.get(flag)) {
+        for rule in &class.rules {
+            words.extend(rule.apply_prefix(word));
+            if class.cross_product {
+                words.extend(
+                    cross_product_forms
+                        .iter()
+                        .filter_map(|form| rule.apply_prefix(form)),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "
+SET UTF-8
+TRY esiarntolcdugmphbyfvkwzESIARNTOLCDUGMPHBYFVKWZ'
+REP 1
+REP f ph
+
+PFX U Y 1
+PFX U   0     un         .
+
+SFX S Y 2
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [aeiou]y
+
+SFX D Y 2
+SFX D   0     d          e
+SFX D   0     ed         [^e]
+";
+
+    const DIC: &str = "4
+play/SDU
+fly/S
+bake/D
+graph
+";
+
+    #[test]
+    fn test_affix_expansion() {
+        let dictionary = Dictionary::parse(AFF.as_bytes(), DIC.as_bytes()).unwrap();
+        for word in [
+            "play", "plays", "played", "unplay", "unplays", "unplayed", "fly", "flies", "bake",
+            "baked", "graph",
+        ] {
+            assert!(dictionary.check(word), "{word:?} should be accepted");
+        }
+        for word in ["plaies", "flys", "bakeed", "unfly", "graphs"] {
+            assert!(!dictionary.check(word), "{word:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_casing() {
+        let dictionary = Dictionary::parse(AFF.as_bytes(), DIC.as_bytes()).unwrap();
+        assert!(dictionary.check("Plays"));
+        assert!(dictionary.check("PLAYS"));
+        assert!(!dictionary.check("pLays"));
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let dictionary = Dictionary::parse(AFF.as_bytes(), DIC.as_bytes()).unwrap();
+        assert_eq!(dictionary.suggest("grafh", 3), ["graph"]);
+        assert_eq!(dictionary.suggest("Bakde", 3), ["Baked", "Bake"]);
+        assert_eq!(dictionary.suggest("playsbake", 3), ["plays bake"]);
+    }
+}
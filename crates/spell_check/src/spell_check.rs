@@ -0,0 +1,445 @@
+//! Offline spell checking for the editor.
+//!
+//! Words in comments, strings and prose files are checked against a Hunspell dictionary and the
+//! user's own `dictionary.txt`, and misspelled ones are underlined. Code actions offer the
+//! dictionary's suggestions and adding the word to the user's dictionary.
+
+mod code_actions;
+mod dictionary;
+mod spell_check_settings;
+
+use anyhow::{Context as _, Result};
+use collections::HashSet;
+use editor::{Addon, DisplayPoint, Editor, EditorEvent, display_map::DisplayRow};
+use fs::Fs;
+use futures::StreamExt as _;
+use gpui::{
+    App, AppContext as _, Context, Global, HighlightStyle, SharedString, Subscription, Task,
+    UnderlineStyle, Window, px,
+};
+use settings::{Settings as _, SettingsStore};
+use std::{any::Any, iter, ops::Range, path::PathBuf, rc::Rc, sync::Arc, time::Duration};
+use theme::ActiveTheme as _;
+use util::ResultExt as _;
+use workspace::notifications::{
+    NotificationId, dismiss_app_notification, show_app_notification,
+    simple_message_notification::MessageNotification,
+};
+
+pub use dictionary::Dictionary;
+pub use spell_check_settings::SpellCheckSettings;
+
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// How many rows to check when the editor hasn't been laid out yet.
+const FALLBACK_VISIBLE_ROWS: u32 = 100;
+
+/// The dictionary for the configured language and the words the user added to theirs.
+pub struct SpellChecker {
+    fs: Arc<dyn Fs>,
+    language: Option<String>,
+    dictionary: Option<Arc<Dictionary>>,
+    user_words: Arc<HashSet<String>>,
+}
+
+impl Global for SpellChecker {}
+
+/// A snapshot of the spell checker's word lists that can be used on a background thread.
+#[derive(Clone)]
+pub(crate) struct WordChecker {
+    dictionary: Arc<Dictionary>,
+    user_words: Arc<HashSet<String>>,
+}
+
+impl WordChecker {
+    pub(crate) fn is_correct(&self, word: &str) -> bool {
+        self.user_words.contains(word)
+            || self.user_words.contains(&word.to_lowercase())
+            || self.dictionary.check(word)
+    }
+
+    pub(crate) fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        self.dictionary.suggest(word, limit)
+    }
+}
+
+impl SpellChecker {
+    /// Returns the word lists to check against, or `None` when spell checking is disabled or no
+    /// dictionary could be loaded.
+    pub(crate) fn word_checker(&self) -> Option<WordChecker> {
+        Some(WordChecker {
+            dictionary: self.dictionary.clone()?,
+            user_words: self.user_words.clone(),
+        })
+    }
+
+    pub(crate) fn add_to_user_dictionary(word: String, cx: &mut App) {
+        let spell_checker = cx.global_mut::<SpellChecker>();
+        let mut user_words = (*spell_checker.user_words).clone();
+        user_words.insert(word.clone());
+        spell_checker.user_words = Arc::new(user_words);
+
+        let fs = spell_checker.fs.clone();
+        cx.background_spawn(async move {
+            let path = paths::user_dictionary_file();
+            let mut contents = if fs.is_file(path).await {
+                fs.load(path).await?
+            } else {
+                String::new()
+            };
+            if !contents.is_empty() && !contents.ends_with('\n') {
+                contents.push('\n');
+            }
+            contents.push_str(&word);
+            contents.push('\n');
+            fs.atomic_write(path.clone(), contents).await
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut App) {
+    SpellCheckSettings::register(cx);
+    cx.set_global(SpellChecker {
+        fs: fs.clone(),
+        language: None,
+        dictionary: None,
+        user_words: Arc::default(),
+    });
+    load_dictionary(cx);
+    cx.observe_global::<SettingsStore>(load_dictionary).detach();
+    load_user_dictionary(fs, cx);
+
+    cx.observe_new(|editor: &mut Editor, window, cx| {
+        if !editor.mode().is_full() {
+            return;
+        }
+        if let Some(window) = window {
+            SpellCheckAddon::register(editor, window, cx);
+        }
+    })
+    .detach();
+}
+
+/// Loads the dictionary for the configured language, or drops the current one when spell
+/// checking gets disabled.
+fn load_dictionary(cx: &mut App) {
+    let settings = SpellCheckSettings::get_global(cx);
+    let language = settings.enabled.then(|| settings.language.clone());
+    let spell_checker = cx.global::<SpellChecker>();
+    if spell_checker.language == language {
+        return;
+    }
+    let fs = spell_checker.fs.clone();
+    let spell_checker = cx.global_mut::<SpellChecker>();
+    spell_checker.language = language.clone();
+    spell_checker.dictionary = None;
+    let Some(language) = language else {
+        return;
+    };
+
+    let bundled = load_bundled_dictionary(&language, cx);
+    cx.spawn(async move |cx| {
+        let dictionary = cx
+            .background_spawn({
+                let language = language.clone();
+                async move {
+                    let (aff, dic) = match bundled {
+                        Some(bundled) => bundled,
+                        None => read_installed_dictionary(fs, &language).await?,
+                    };
+                    Dictionary::parse(&aff, &dic)
+                        .with_context(|| format!("parsing the {language:?} dictionary"))
+                }
+            })
+            .await;
+        cx.update(|cx| {
+            if cx.global::<SpellChecker>().language.as_ref() != Some(&language) {
+                return;
+            }
+            let notification_id = NotificationId::unique::<SpellChecker>();
+            match dictionary {
+                Ok(dictionary) => {
+                    cx.global_mut::<SpellChecker>().dictionary = Some(Arc::new(dictionary));
+                    dismiss_app_notification(&notification_id, cx);
+                }
+                Err(error) => {
+                    log::error!("{error:#}");
+                    let message: SharedString =
+                        format!("Spell checking is unavailable: {error:#}").into();
+                    show_app_notification(notification_id, cx, move |cx| {
+                        cx.new(|cx| MessageNotification::new(message.clone(), cx))
+                    });
+                }
+            }
+        })
+        .log_err();
+    })
+    .detach();
+}
+
+fn load_bundled_dictionary(language: &str, cx: &App) -> Option<(Vec<u8>, Vec<u8>)> {
+    let asset_source = cx.asset_source();
+    let aff = asset_source
+        .load(&format!("dictionaries/{language}.aff"))
+        .ok()??;
+    let dic = asset_source
+        .load(&format!("dictionaries/{language}.dic"))
+        .ok()??;
+    Some((aff.into_owned(), dic.into_owned()))
+}
+
+async fn read_installed_dictionary(fs: Arc<dyn Fs>, language: &str) -> Result<(Vec<u8>, Vec<u8>)> {
+    for dir in installed_dictionary_dirs() {
+        let aff_path = dir.join(format!("{language}.aff"));
+        let dic_path = dir.join(format!("{language}.dic"));
+        if fs.is_file(&aff_path).await && fs.is_file(&dic_path).await {
+            return Ok((
+                fs.load_bytes(&aff_path).await?,
+                fs.load_bytes(&dic_path).await?,
+            ));
+        }
+    }
+    anyhow::bail!("no Hunspell dictionary found for {language:?}")
+}
+
+fn installed_dictionary_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![paths::dictionaries_dir().clone()];
+    if cfg!(target_os = "macos") {
+        dirs.push(paths::home_dir().join("Library/Spelling"));
+        dirs.push(PathBuf::from("/Library/Spelling"));
+    } else if cfg!(any(target_os = "linux", target_os = "freebsd")) {
+        dirs.extend(
+            [
+                "/usr/share/hunspell",
+                "/usr/share/myspell",
+                "/usr/share/myspell/dicts",
+            ]
+            .map(PathBuf::from),
+        );
+    }
+    dirs
+}
+
+fn load_user_dictionary(fs: Arc<dyn Fs>, cx: &mut App) {
+    let mut user_dictionary_rx = settings::watch_config_file(
+        cx.background_executor(),
+        fs,
+        paths::user_dictionary_file().clone(),
+    );
+    cx.spawn(async move |cx| {
+        while let Some(contents) = user_dictionary_rx.next().await {
+            let user_words = contents
+                .lines()
+                .map(str::trim)
+                .filter(|word| !word.is_empty())
+                .map(str::to_string)
+                .collect::<HashSet<_>>();
+            cx.update_global(|spell_checker: &mut SpellChecker, _| {
+                spell_checker.user_words = Arc::new(user_words)
+            })
+            .log_err();
+        }
+    })
+    .detach();
+}
+
+/// Yields the byte ranges of the words in `text` that are worth checking, skipping identifiers,
+/// numbers, acronyms and paths, which are common in comments.
+pub fn words_to_check(text: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    let is_word_char = |c: char| c.is_alphanumeric() || matches!(c, '_' | '\'' | '’');
+    let mut offset = 0;
+    iter::from_fn(move || {
+        loop {
+            let start = offset + text[offset..].find(is_word_char)?;
+            let end = text[start..]
+                .find(|c| !is_word_char(c))
+                .map_or(text.len(), |len| start + len);
+            offset = end;
+
+            let token = &text[start..end];
+            let word = token.trim_matches(['\'', '’']);
+            if word.is_empty() {
+                continue;
+            }
+            let word_start = start + (token.len() - token.trim_start_matches(['\'', '’']).len());
+            let word_range = word_start..word_start + word.len();
+
+            let previous = text[..start].chars().next_back();
+            let mut following = text[end..].chars();
+            let next = following.next();
+            let after_next = following.next();
+            let is_part_of_path = previous
+                .is_some_and(|c| matches!(c, '.' | '/' | '\\' | ':' | '@' | '#' | '$' | '&'))
+                || next.is_some_and(|c| matches!(c, '(' | '<'))
+                || (next.is_some_and(|c| matches!(c, '.' | '/' | '\\' | ':' | '@'))
+                    && after_next
+                        .is_some_and(|c| c.is_alphanumeric() || matches!(c, '/' | ':' | '_')));
+            let is_identifier = word.contains(|c: char| c.is_numeric() || c == '_')
+                || word
+                    .chars()
+                    .zip(word.chars().skip(1))
+                    .any(|(a, b)| a.is_lowercase() && b.is_uppercase());
+            let is_acronym = word.chars().all(|c| !c.is_lowercase());
+            if word.chars().nth(1).is_none() || is_part_of_path || is_identifier || is_acronym {
+                continue;
+            }
+            return Some(word_range);
+        }
+    })
+}
+
+/// Underlines the misspelled words in the visible part of an editor.
+struct SpellCheckAddon {
+    refresh_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl Addon for SpellCheckAddon {
+    fn to_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn to_any_mut(&mut self) -> Option<&mut dyn Any> {
+        Some(self)
+    }
+}
+
+impl SpellCheckAddon {
+    fn register(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+        let subscriptions = vec![
+            cx.subscribe_in(
+                &cx.entity(),
+                window,
+                |editor, _, event: &EditorEvent, window, cx| match event {
+                    EditorEvent::BufferEdited
+                    | EditorEvent::Reparsed(_)
+                    | EditorEvent::ExcerptsAdded { .. }
+                    | EditorEvent::ScrollPositionChanged { .. } => {
+                        refresh_misspellings(editor, window, cx)
+                    }
+                    _ => {}
+                },
+            ),
+            cx.observe_global_in::<SpellChecker>(window, refresh_misspellings),
+        ];
+        editor.register_addon(SpellCheckAddon {
+            refresh_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        });
+        editor.add_code_action_provider(
+            Rc::new(code_actions::SpellCheckCodeActionProvider),
+            window,
+            cx,
+        );
+        refresh_misspellings(editor, window, cx);
+    }
+}
+
+fn refresh_misspellings(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+    let Some(checker) = cx.global::<SpellChecker>().word_checker() else {
+        editor.clear_highlights::<SpellCheckAddon>(cx);
+        return;
+    };
+
+    // Also check a screen's worth of rows on either side, so that the underlines are already in
+    // place when scrolling.
+    let snapshot = editor.snapshot(window, cx);
+    let display_snapshot = &snapshot.display_snapshot;
+    let visible_rows = editor
+        .visible_line_count()
+        .map_or(FALLBACK_VISIBLE_ROWS, |count| count.ceil() as u32);
+    let first_visible_row = snapshot.scroll_position().y as u32;
+    let start = DisplayPoint::new(
+        DisplayRow(first_visible_row.saturating_sub(visible_rows)),
+        0,
+    )
+    .to_point(display_snapshot);
+    let end = DisplayPoint::new(DisplayRow(first_visible_row + visible_rows * 2), 0)
+        .min(display_snapshot.max_point())
+        .to_point(display_snapshot);
+    let buffer_ranges = display_snapshot
+        .buffer_snapshot
+        .range_to_buffer_ranges(start..end)
+        .into_iter()
+        .map(|(buffer, range, excerpt_id)| (buffer.clone(), range, excerpt_id))
+        .collect::<Vec<_>>();
+
+    let refresh_task = cx.spawn(async move |editor, cx| {
+        cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+        let misspellings = cx
+            .background_spawn(async move {
+                let mut misspellings = Vec::new();
+                for (buffer, range, excerpt_id) in buffer_ranges {
+                    for natural_language_range in buffer.natural_language_ranges(range) {
+                        let text = buffer
+                            .text_for_range(natural_language_range.clone())
+                            .collect::<String>();
+                        for word_range in words_to_check(&text) {
+                            if checker.is_correct(&text[word_range.clone()]) {
+                                continue;
+                            }
+                            let start = natural_language_range.start + word_range.start;
+                            let end = natural_language_range.start + word_range.end;
+                            misspellings.push((
+                                excerpt_id,
+                                buffer.anchor_after(start)..buffer.anchor_before(end),
+                            ));
+                        }
+                    }
+                }
+                misspellings
+            })
+            .await;
+
+        editor
+            .update(cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let ranges = misspellings
+                    .into_iter()
+                    .filter_map(|(excerpt_id, range)| {
+                        Some(
+                            snapshot.anchor_in_excerpt(excerpt_id, range.start)?
+                                ..snapshot.anchor_in_excerpt(excerpt_id, range.end)?,
+                        )
+                    })
+                    .collect();
+                editor.highlight_text::<SpellCheckAddon>(
+                    ranges,
+                    HighlightStyle {
+                        underline: Some(UnderlineStyle {
+                            color: Some(cx.theme().status().info),
+                            thickness: px(1.),
+                            wavy: true,
+                        }),
+                        ..HighlightStyle::default()
+                    },
+                    cx,
+                );
+            })
+            .ok();
+    });
+    if let Some(addon) = editor.addon_mut::<SpellCheckAddon>() {
+        addon.refresh_task = refresh_task;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words_to_check() {
+        let text = "Don't check snake_case, camelCase, HTTP, v2 or paths like crate::module, \
+            foo.bar and `call()`, but 'quoted' words are fine.";
+        assert_eq!(
+            words_to_check(text)
+                .map(|range| &text[range])
+                .collect::<Vec<_>>(),
+            [
+                "Don't", "check", "or", "paths", "like", "and", "but", "quoted", "words", "are",
+                "fine"
+            ]
+        );
+    }
+}
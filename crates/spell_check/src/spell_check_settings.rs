@@ -0,0 +1,34 @@
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources, SettingsUi};
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct SpellCheckSettings {
+    pub enabled: bool,
+    pub language: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug, SettingsUi)]
+pub struct SpellCheckSettingsContent {
+    /// Whether to underline misspelled words in comments, strings and prose files.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The dictionary to check words against, named after its Hunspell `.aff` and `.dic` files.
+    ///
+    /// Default: "en_US"
+    pub language: Option<String>,
+}
+
+impl Settings for SpellCheckSettings {
+    const KEY: Option<&'static str> = Some("spell_check");
+
+    type FileContent = SpellCheckSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut gpui::App) -> Result<Self> {
+        sources.json_merge()
+    }
+
+    fn import_from_vscode(_vscode: &settings::VsCodeSettings, _current: &mut Self::FileContent) {}
+}
@@ -0,0 +1,625 @@
+//! Problem matchers find errors and warnings in the output of a task, so that they can be shown as diagnostics.
+//!
+//! A matcher is a sequence of regex patterns that have to match consecutive lines of the output,
+//! each pattern capturing parts of a problem: its file, location, severity, code and message.
+//! Zed has a few [`BuiltInProblemMatcher`]s for popular tools, and custom ones can be defined in the task templates.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use util::ResultExt as _;
+
+/// A way to find problems in a task's output: either a name of a built-in matcher or a custom definition.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcher {
+    BuiltIn(BuiltInProblemMatcher),
+    Custom(CustomProblemMatcher),
+}
+
+/// Problem matchers Zed ships with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltInProblemMatcher {
+    /// Errors and warnings of `rustc` and `cargo`.
+    Rustc,
+    /// Errors of the TypeScript compiler.
+    Tsc,
+    /// Errors, warnings and notes of `gcc` and `clang`.
+    Gcc,
+    /// Problems reported by ESLint's default, `stylish`, formatter.
+    Eslint,
+    /// Failures reported by `pytest`.
+    Pytest,
+}
+
+/// A problem matcher defined with regular expressions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CustomProblemMatcher {
+    /// Name of the tool reporting the problems, shown as their source. Defaults to the task's label.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// How to resolve the file paths reported by the tool.
+    #[serde(default)]
+    pub file_location: FileLocation,
+    /// Severity of the problems, if the patterns do not capture one.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+    /// Patterns that have to match consecutive lines of output to report a problem.
+    pub pattern: Vec<ProblemPattern>,
+}
+
+/// How to resolve the file paths reported by a tool.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileLocation {
+    /// Paths are relative to the task's working directory (default).
+    #[default]
+    Relative,
+    /// Paths are absolute.
+    Absolute,
+    /// Paths are relative to the given directory, itself relative to the task's working directory.
+    RelativeTo(String),
+}
+
+/// A regular expression for a line of a tool's output, and the indices of its capture groups to take parts of a problem from.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regular expression to match the line with.
+    pub regexp: String,
+    /// Capture group with the file path.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// Capture group with the line number, starting from 1.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// Capture group with the column number, starting from 1.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// Capture group with the line number the problem ends at.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// Capture group with the column number the problem ends at.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// Capture group with the severity: `error`, `warning`, `info` or `hint`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// Capture group with the problem's code.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// Capture group with the problem's message.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether this, last, pattern may match several lines in a row, each reporting a problem.
+    #[serde(default, rename = "loop")]
+    pub loop_: bool,
+}
+
+/// How severe a problem is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl ProblemSeverity {
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "error" | "fatal" | "e" => Some(Self::Error),
+            "warning" | "warn" | "w" => Some(Self::Warning),
+            "info" | "information" | "note" | "i" => Some(Self::Info),
+            "hint" | "help" => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// A problem found in a task's output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// Path of the file with the problem, resolved according to the matcher's [`FileLocation`].
+    pub path: PathBuf,
+    /// Line of the problem, starting from 1.
+    pub line: u32,
+    /// Column of the problem, starting from 1.
+    pub column: Option<u32>,
+    pub end_line: Option<u32>,
+    pub end_column: Option<u32>,
+    pub severity: ProblemSeverity,
+    pub code: Option<String>,
+    pub message: String,
+    /// The matcher's source, if it has one.
+    pub source: Option<String>,
+}
+
+impl ProblemMatcher {
+    fn definition(&self) -> CustomProblemMatcher {
+        match self {
+            Self::BuiltIn(built_in) => built_in.definition(),
+            Self::Custom(custom) => custom.clone(),
+        }
+    }
+}
+
+impl BuiltInProblemMatcher {
+    /// The definition of the built-in matcher.
+    pub fn definition(self) -> CustomProblemMatcher {
+        let (source, pattern) = match self {
+            Self::Rustc => (
+                "rustc",
+                vec![
+                    ProblemPattern {
+                        regexp: r"^(warning|error)(?:\[(\S+)\])?: (.*)$".to_string(),
+                        severity: Some(1),
+                        code: Some(2),
+                        message: Some(3),
+                        ..ProblemPattern::default()
+                    },
+                    ProblemPattern {
+                        regexp: r"^\s*--> (.+?):(\d+):(\d+)$".to_string(),
+                        file: Some(1),
+                        line: Some(2),
+                        column: Some(3),
+                        ..ProblemPattern::default()
+                    },
+                ],
+            ),
+            Self::Tsc => (
+                "tsc",
+                vec![ProblemPattern {
+                    regexp: r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+(TS\d+)\s*:\s*(.*)$"
+                        .to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    code: Some(5),
+                    message: Some(6),
+                    ..ProblemPattern::default()
+                }],
+            ),
+            Self::Gcc => (
+                "gcc",
+                vec![ProblemPattern {
+                    regexp: r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error|note):\s+(.*)$"
+                        .to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    severity: Some(4),
+                    message: Some(5),
+                    ..ProblemPattern::default()
+                }],
+            ),
+            Self::Eslint => (
+                "eslint",
+                vec![
+                    ProblemPattern {
+                        regexp: r"^(\S.*)$".to_string(),
+                        file: Some(1),
+                        ..ProblemPattern::default()
+                    },
+                    ProblemPattern {
+                        regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.*?)(?:\s\s+(\S+))?$"
+                            .to_string(),
+                        line: Some(1),
+                        column: Some(2),
+                        severity: Some(3),
+                        message: Some(4),
+                        code: Some(5),
+                        loop_: true,
+                        ..ProblemPattern::default()
+                    },
+                ],
+            ),
+            Self::Pytest => (
+                "pytest",
+                vec![ProblemPattern {
+                    regexp: r"^(\S+\.py):(\d+): (.+)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                }],
+            ),
+        };
+        CustomProblemMatcher {
+            source: Some(source.to_string()),
+            file_location: FileLocation::Relative,
+            severity: ProblemSeverity::Error,
+            pattern,
+        }
+    }
+}
+
+/// Runs a set of [`ProblemMatcher`]s over the lines of a task's output.
+pub struct ProblemCollector {
+    matchers: Vec<MatcherState>,
+}
+
+struct MatcherState {
+    patterns: Vec<(Regex, ProblemPattern)>,
+    base_dir: PathBuf,
+    resolve_paths: bool,
+    severity: ProblemSeverity,
+    source: Option<String>,
+    /// Index of the pattern to match the next line with.
+    next_pattern: usize,
+    /// Parts of the problem matched by the patterns before the next one.
+    matched: PartialProblem,
+}
+
+#[derive(Clone, Default)]
+struct PartialProblem {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<ProblemSeverity>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl ProblemCollector {
+    /// Creates a collector for the matchers given, with the paths they report resolved against `cwd`.
+    /// Matchers with invalid regular expressions are skipped.
+    pub fn new(matchers: &[ProblemMatcher], cwd: Option<&Path>) -> Self {
+        let cwd = cwd.unwrap_or(Path::new(""));
+        let matchers = matchers
+            .iter()
+            .filter_map(|matcher| {
+                let definition = matcher.definition();
+                let patterns = definition
+                    .pattern
+                    .into_iter()
+                    .map(|pattern| {
+                        let regex = Regex::new(&pattern.regexp).with_context(|| {
+                            format!("invalid problem matcher regex {:?}", pattern.regexp)
+                        })?;
+                        anyhow::Ok((regex, pattern))
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()
+                    .log_err()?;
+                if patterns.is_empty() {
+                    return None;
+                }
+                let (base_dir, resolve_paths) = match definition.file_location {
+                    FileLocation::Relative => (cwd.to_path_buf(), true),
+                    FileLocation::RelativeTo(dir) => (cwd.join(dir), true),
+                    FileLocation::Absolute => (PathBuf::new(), false),
+                };
+                Some(MatcherState {
+                    patterns,
+                    base_dir,
+                    resolve_paths,
+                    severity: definition.severity,
+                    source: definition.source,
+                    next_pattern: 0,
+                    matched: PartialProblem::default(),
+                })
+            })
+            .collect();
+        Self { matchers }
+    }
+
+    /// Whether there are any matchers to run.
+    pub fn is_empty(&self) -> bool {
+        self.matchers.is_empty()
+    }
+
+    /// Matches a line of output, without its line ending and escape sequences,
+    /// returning the problems it completes.
+    pub fn push_line(&mut self, line: &str) -> Vec<Problem> {
+        self.matchers
+            .iter_mut()
+            .filter_map(|matcher| matcher.push_line(line))
+            .collect()
+    }
+}
+
+impl MatcherState {
+    fn push_line(&mut self, line: &str) -> Option<Problem> {
+        if self.next_pattern > 0 {
+            let (regex, pattern) = &self.patterns[self.next_pattern];
+            if let Some(captures) = regex.captures(line) {
+                let mut problem = self.matched.clone();
+                problem.fill(pattern, &captures);
+                return self.matched_pattern(problem);
+            }
+            // The sequence got interrupted: start over, with this line possibly starting another problem.
+            self.next_pattern = 0;
+            self.matched = PartialProblem::default();
+        }
+
+        let (regex, pattern) = &self.patterns[0];
+        let captures = regex.captures(line)?;
+        let mut problem = PartialProblem::default();
+        problem.fill(pattern, &captures);
+        self.matched_pattern(problem)
+    }
+
+    fn matched_pattern(&mut self, problem: PartialProblem) -> Option<Problem> {
+        let last_pattern = self.patterns.len() - 1;
+        if self.next_pattern < last_pattern {
+            self.matched = problem;
+            self.next_pattern += 1;
+            return None;
+        }
+
+        // A looping pattern keeps matching the following lines, with the parts matched before it.
+        if !self.patterns[last_pattern].1.loop_ || last_pattern == 0 {
+            self.next_pattern = 0;
+            self.matched = PartialProblem::default();
+        }
+        self.finish(problem)
+    }
+
+    fn finish(&self, problem: PartialProblem) -> Option<Problem> {
+        let file = problem.file?;
+        let path = if self.resolve_paths {
+            self.base_dir.join(file.trim())
+        } else {
+            PathBuf::from(file.trim())
+        };
+        Some(Problem {
+            path,
+            line: problem.line?,
+            column: problem.column,
+            end_line: problem.end_line,
+            end_column: problem.end_column,
+            severity: problem.severity.unwrap_or(self.severity),
+            code: problem.code,
+            message: problem.message.unwrap_or_default(),
+            source: self.source.clone(),
+        })
+    }
+}
+
+impl PartialProblem {
+    fn fill(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let group = |index: Option<usize>| {
+            index
+                .and_then(|index| captures.get(index))
+                .map(|group| group.as_str())
+                .filter(|text| !text.is_empty())
+        };
+        let number = |index: Option<usize>| group(index).and_then(|text| text.parse().ok());
+
+        if let Some(file) = group(pattern.file) {
+            self.file = Some(file.to_string());
+        }
+        if let Some(line) = number(pattern.line) {
+            self.line = Some(line);
+        }
+        if let Some(column) = number(pattern.column) {
+            self.column = Some(column);
+        }
+        if let Some(end_line) = number(pattern.end_line) {
+            self.end_line = Some(end_line);
+        }
+        if let Some(end_column) = number(pattern.end_column) {
+            self.end_column = Some(end_column);
+        }
+        if let Some(severity) = group(pattern.severity).and_then(ProblemSeverity::parse) {
+            self.severity = Some(severity);
+        }
+        if let Some(code) = group(pattern.code) {
+            self.code = Some(code.to_string());
+        }
+        if let Some(message) = group(pattern.message) {
+            self.message = Some(message.trim().to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(matcher: BuiltInProblemMatcher, output: &str) -> Vec<Problem> {
+        let mut collector = ProblemCollector::new(
+            &[ProblemMatcher::BuiltIn(matcher)],
+            Some(Path::new("/root")),
+        );
+        output
+            .lines()
+            .flat_map(|line| collector.push_line(line))
+            .collect()
+    }
+
+    #[test]
+    fn test_rustc_problems() {
+        let problems = collect(
+            BuiltInProblemMatcher::Rustc,
+            r#"   Compiling example v0.1.0 (/root)
+error[E0308]: mismatched types
+ --> src/main.rs:4:18
+  |
+4 |     let x: u32 = "one";
+  |            ---   ^^^^^ expected `u32`, found `&str`
+
+warning: unused variable: `y`
+  --> src/lib.rs:10:9
+warning: `example` (bin "example") generated 1 warning
+error: could not compile `example` (bin "example") due to 1 previous error"#,
+        );
+        assert_eq!(
+            problems,
+            vec![
+                Problem {
+                    path: PathBuf::from("/root/src/main.rs"),
+                    line: 4,
+                    column: Some(18),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0308".to_string()),
+                    message: "mismatched types".to_string(),
+                    source: Some("rustc".to_string()),
+                },
+                Problem {
+                    path: PathBuf::from("/root/src/lib.rs"),
+                    line: 10,
+                    column: Some(9),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Warning,
+                    code: None,
+                    message: "unused variable: `y`".to_string(),
+                    source: Some("rustc".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_line_problems() {
+        let problems = collect(
+            BuiltInProblemMatcher::Tsc,
+            "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\n\
+             src/util.ts:12:1 - warning TS6133: 'x' is declared but its value is never read.",
+        );
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.to_string_lossy().into_owned(),
+                    problem.line,
+                    problem.column,
+                    problem.severity,
+                    problem.code.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/root/src/index.ts".to_string(),
+                    3,
+                    Some(7),
+                    ProblemSeverity::Error,
+                    Some("TS2322")
+                ),
+                (
+                    "/root/src/util.ts".to_string(),
+                    12,
+                    Some(1),
+                    ProblemSeverity::Warning,
+                    Some("TS6133")
+                ),
+            ]
+        );
+
+        let problems = collect(
+            BuiltInProblemMatcher::Gcc,
+            "main.c:5:3: warning: implicit declaration of function 'foo'\n\
+             main.c:9: fatal error: stdio.h: No such file or directory",
+        );
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (problem.line, problem.column, problem.severity))
+                .collect::<Vec<_>>(),
+            vec![
+                (5, Some(3), ProblemSeverity::Warning),
+                (9, None, ProblemSeverity::Error)
+            ]
+        );
+
+        let problems = collect(
+            BuiltInProblemMatcher::Pytest,
+            "tests/test_math.py:14: AssertionError",
+        );
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].message, "AssertionError");
+    }
+
+    #[test]
+    fn test_looping_pattern() {
+        let problems = collect(
+            BuiltInProblemMatcher::Eslint,
+            "/root/src/a.js
+  1:10  error    'foo' is defined but never used  no-unused-vars
+  3:1   warning  Unexpected console statement     no-console
+
+/root/src/b.js
+  7:5  error  Missing semicolon  semi
+
+✖ 3 problems (2 errors, 1 warning)",
+        );
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.to_string_lossy().into_owned(),
+                    problem.line,
+                    problem.message.as_str(),
+                    problem.code.as_deref()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/root/src/a.js".to_string(),
+                    1,
+                    "'foo' is defined but never used",
+                    Some("no-unused-vars")
+                ),
+                (
+                    "/root/src/a.js".to_string(),
+                    3,
+                    "Unexpected console statement",
+                    Some("no-console")
+                ),
+                (
+                    "/root/src/b.js".to_string(),
+                    7,
+                    "Missing semicolon",
+                    Some("semi")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_matcher() {
+        let matcher: ProblemMatcher = serde_json::from_value(serde_json::json!({
+            "source": "lint",
+            "file_location": { "relative_to": "pkg" },
+            "severity": "warning",
+            "pattern": [{
+                "regexp": "^(.+):(\\d+):(\\d+)-(\\d+): (.+)$",
+                "file": 1,
+                "line": 2,
+                "column": 3,
+                "end_column": 4,
+                "message": 5
+            }]
+        }))
+        .unwrap();
+        let mut collector = ProblemCollector::new(&[matcher], Some(Path::new("/root")));
+        assert_eq!(
+            collector.push_line("lib.go:3:4-9: unused import"),
+            vec![Problem {
+                path: PathBuf::from("/root/pkg/lib.go"),
+                line: 3,
+                column: Some(4),
+                end_line: None,
+                end_column: Some(9),
+                severity: ProblemSeverity::Warning,
+                code: None,
+                message: "unused import".to_string(),
+                source: Some("lint".to_string()),
+            }]
+        );
+        assert!(collector.push_line("ok").is_empty());
+    }
+}
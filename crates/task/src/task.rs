@@ -2,6 +2,7 @@
 
 mod adapter_schema;
 mod debug_format;
+mod problem_matcher;
 mod serde_helpers;
 mod shell_builder;
 pub mod static_source;
@@ -22,10 +23,14 @@ pub use debug_format::{
     AttachRequest, BuildTaskDefinition, DebugRequest, DebugScenario, DebugTaskFile, LaunchRequest,
     Request, TcpArgumentsTemplate, ZedDebugConfig,
 };
+pub use problem_matcher::{
+    BuiltInProblemMatcher, CustomProblemMatcher, FileLocation, Problem, ProblemCollector,
+    ProblemMatcher, ProblemPattern, ProblemSeverity,
+};
 pub use shell_builder::{ShellBuilder, ShellKind};
 pub use task_template::{
-    DebugArgsRequest, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
-    substitute_variables_in_map, substitute_variables_in_str,
+    DebugArgsRequest, DependsOrder, HideStrategy, RevealStrategy, TaskInput, TaskTemplate,
+    TaskTemplates, substitute_variables_in_map, substitute_variables_in_str,
 };
 pub use vscode_debug_format::VsCodeDebugTaskFile;
 pub use vscode_format::VsCodeTaskFile;
@@ -74,6 +79,8 @@ pub struct SpawnInTerminal {
     pub show_command: bool,
     /// Whether to show the rerun button in the terminal tab.
     pub show_rerun: bool,
+    /// How to find problems in the task's output, to show them as diagnostics.
+    pub problem_matchers: Vec<ProblemMatcher>,
 }
 
 impl SpawnInTerminal {
@@ -121,6 +128,10 @@ pub struct ResolvedTask {
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
     substituted_variables: HashSet<VariableName>,
+    /// The context the task got resolved with, kept around to resolve its dependencies with the same one,
+    /// and to resolve the task again once the values of its inputs are known.
+    /// Only present for tasks that have dependencies or inputs.
+    context: Option<TaskContext>,
     /// Further actions that need to take place after the resolved task is spawned,
     /// with all task variables resolved.
     pub resolved: SpawnInTerminal,
@@ -137,6 +148,55 @@ impl ResolvedTask {
         &self.substituted_variables
     }
 
+    /// The context to resolve the task's `depends_on` templates with, if it has any.
+    pub fn dependencies_context(&self) -> Option<&TaskContext> {
+        self.context
+            .as_ref()
+            .filter(|_| !self.original_task.depends_on.is_empty())
+    }
+
+    /// Inputs of the task that have no value yet, which the user has to be asked for before the task can be spawned.
+    pub fn pending_inputs(&self) -> impl Iterator<Item = &TaskInput> {
+        self.original_task
+            .inputs
+            .iter()
+            .filter(|input| self.input_value(input).is_none())
+    }
+
+    /// Values of the inputs the task got resolved with, by input id.
+    pub fn input_values(&self) -> HashMap<String, String> {
+        self.original_task
+            .inputs
+            .iter()
+            .filter_map(|input| Some((input.id().to_owned(), self.input_value(input)?.to_owned())))
+            .collect()
+    }
+
+    fn input_value(&self, input: &TaskInput) -> Option<&str> {
+        self.context
+            .as_ref()?
+            .task_variables
+            .get(&input.variable_name())
+    }
+
+    /// Resolves the task again, with the values given to its inputs, keeping its id.
+    /// Returns `None` if the template fails to resolve.
+    pub fn with_input_values(
+        &self,
+        values: impl IntoIterator<Item = (String, String)>,
+    ) -> Option<ResolvedTask> {
+        let mut context = self.context.clone().unwrap_or_default();
+        for (id, value) in values {
+            context
+                .task_variables
+                .insert(VariableName::Input(Cow::Owned(id)), value);
+        }
+        let mut task = self.original_task.resolve_task("", &context)?;
+        task.id = self.id.clone();
+        task.resolved.id = self.id.clone();
+        Some(task)
+    }
+
     /// A human-readable label to display in the UI.
     pub fn display_label(&self) -> &str {
         self.resolved.label.as_str()
@@ -176,6 +236,9 @@ pub enum VariableName {
     /// Custom variable, provided by the plugin or other external source.
     /// Will be printed with `CUSTOM_` prefix to avoid potential conflicts with other variables.
     Custom(Cow<'static, str>),
+    /// Value of one of the task's [`TaskInput`]s, given by the user before the task is spawned.
+    /// Will be printed with `INPUT_` prefix, followed by the input's id.
+    Input(Cow<'static, str>),
 }
 
 impl VariableName {
@@ -212,6 +275,10 @@ impl FromStr for VariableName {
                     without_prefix.strip_prefix(ZED_CUSTOM_VARIABLE_NAME_PREFIX)
                 {
                     Self::Custom(Cow::Owned(custom_name.to_owned()))
+                } else if let Some(input_id) =
+                    without_prefix.strip_prefix(ZED_INPUT_VARIABLE_NAME_PREFIX)
+                {
+                    Self::Input(Cow::Owned(input_id.to_owned()))
                 } else {
                     return Err(());
                 }
@@ -224,6 +291,7 @@ impl FromStr for VariableName {
 /// A prefix that all [`VariableName`] variants are prefixed with when used in environment variables and similar template contexts.
 pub const ZED_VARIABLE_NAME_PREFIX: &str = "ZED_";
 const ZED_CUSTOM_VARIABLE_NAME_PREFIX: &str = "CUSTOM_";
+const ZED_INPUT_VARIABLE_NAME_PREFIX: &str = "INPUT_";
 
 impl std::fmt::Display for VariableName {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -244,6 +312,10 @@ impl std::fmt::Display for VariableName {
                 f,
                 "{ZED_VARIABLE_NAME_PREFIX}{ZED_CUSTOM_VARIABLE_NAME_PREFIX}{s}"
             ),
+            Self::Input(id) => write!(
+                f,
+                "{ZED_VARIABLE_NAME_PREFIX}{ZED_INPUT_VARIABLE_NAME_PREFIX}{id}"
+            ),
         }
     }
 }
@@ -372,6 +444,10 @@ impl EnvVariableReplacer {
                 let variable_name = &right[1..];
                 return Some(format!("${{{variable_name}}}"));
             }
+            if left == "input" && !right.is_empty() {
+                let input = VariableName::Input(Cow::Owned(right[1..].to_owned()));
+                return Some(format!("${{{input}}}"));
+            }
             let (variable_name, default) = (left, right);
             let append_previous_default = |ret: &mut String| {
                 if !default.is_empty() {
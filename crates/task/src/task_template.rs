@@ -3,14 +3,15 @@ use collections::{HashMap, HashSet};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::path::PathBuf;
+use std::{borrow::Cow, path::PathBuf};
 use util::schemars::DefaultDenyUnknownFields;
 use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
+    AttachRequest, CustomProblemMatcher, FileLocation, ProblemMatcher, ResolvedTask, RevealTarget,
+    Shell, SpawnInTerminal, TaskContext, TaskId, VariableName, ZED_VARIABLE_NAME_PREFIX,
+    serde_helpers::non_empty_string_vec,
 };
 
 /// A template definition of a Zed task to run.
@@ -72,6 +73,100 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Labels of the tasks that have to finish successfully before this task is spawned.
+    /// A task with dependencies may omit its `command`, to only group other tasks.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// In which order to run the tasks from `depends_on`:
+    /// * `parallel` — spawn all dependencies at once (default)
+    /// * `sequence` — spawn dependencies one after another, in the order they are listed
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// How to find errors and warnings in the task's output, to show them as diagnostics
+    /// until the task runs again. Either names of the built-in matchers: `rustc`, `tsc`, `gcc`, `eslint`, `pytest`,
+    /// or custom matchers with a `pattern` of regular expressions.
+    #[serde(default)]
+    pub problem_matchers: Vec<ProblemMatcher>,
+    /// Values to ask the user for before the task is spawned, available in the task as `$ZED_INPUT_<id>`.
+    /// The values given the last time are suggested the next time the task is spawned.
+    #[serde(default)]
+    pub inputs: Vec<TaskInput>,
+}
+
+/// A value the user is asked for before the task is spawned.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskInput {
+    /// Asks to type the value in.
+    PromptString {
+        /// Name of the input, which makes its value available as `$ZED_INPUT_<id>`.
+        id: String,
+        /// What to tell the user when asking for the value.
+        #[serde(default)]
+        description: Option<String>,
+        /// The value to suggest when the input has no value from an earlier run.
+        #[serde(default)]
+        default: Option<String>,
+    },
+    /// Asks to pick the value from a list.
+    PickString {
+        /// Name of the input, which makes its value available as `$ZED_INPUT_<id>`.
+        id: String,
+        /// What to tell the user when asking for the value.
+        #[serde(default)]
+        description: Option<String>,
+        /// The values to pick from.
+        #[schemars(length(min = 1))]
+        options: Vec<String>,
+        /// The value to preselect when the input has no value from an earlier run.
+        #[serde(default)]
+        default: Option<String>,
+    },
+    /// Runs a command in the task's working directory and takes the value from its output.
+    /// If the command prints several lines, asks to pick one of them.
+    CommandOutput {
+        /// Name of the input, which makes its value available as `$ZED_INPUT_<id>`.
+        id: String,
+        /// What to tell the user when asking to pick one of the lines.
+        #[serde(default)]
+        description: Option<String>,
+        /// Executable command to spawn.
+        command: String,
+        /// Arguments to the command.
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+impl TaskInput {
+    /// Name of the input, unique within its task.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::PromptString { id, .. }
+            | Self::PickString { id, .. }
+            | Self::CommandOutput { id, .. } => id,
+        }
+    }
+
+    /// What to tell the user when asking for the value, defaults to the input's id.
+    pub fn description(&self) -> &str {
+        match self {
+            Self::PromptString {
+                id, description, ..
+            }
+            | Self::PickString {
+                id, description, ..
+            }
+            | Self::CommandOutput {
+                id, description, ..
+            } => description.as_deref().unwrap_or(id),
+        }
+    }
+
+    /// The variable the input's value is substituted for.
+    pub fn variable_name(&self) -> VariableName {
+        VariableName::Input(Cow::Owned(self.id().to_owned()))
+    }
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -109,6 +204,17 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// In which order to run the dependencies of a task.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Spawn all dependencies at once, and wait for all of them to succeed.
+    #[default]
+    Parallel,
+    /// Spawn dependencies one after another, stopping at the first one that fails.
+    Sequence,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -133,13 +239,25 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty()
+            || (self.command.trim().is_empty() && self.depends_on.is_empty())
+        {
             return None;
         }
 
         let mut variable_names = HashMap::default();
         let mut substituted_variables = HashSet::default();
-        let task_variables = cx
+        let pending_inputs = self
+            .inputs
+            .iter()
+            .map(TaskInput::variable_name)
+            .filter(|variable| cx.task_variables.get(variable).is_none())
+            .map(|variable| {
+                let template_value = variable.template_value();
+                (variable, template_value)
+            })
+            .collect::<Vec<_>>();
+        let mut task_variables = cx
             .task_variables
             .0
             .iter()
@@ -151,6 +269,12 @@ impl TaskTemplate {
                 (key_string, value.as_str())
             })
             .collect::<HashMap<_, _>>();
+        // Inputs the user was not asked for yet are left as they are, until the task is resolved again with their values.
+        for (variable, template_value) in &pending_inputs {
+            let key_string = variable.to_string();
+            variable_names.insert(key_string.clone(), variable.clone());
+            task_variables.insert(key_string, template_value.as_str());
+        }
         let truncated_variables = truncate_variables(&task_variables);
         let cwd = match self.cwd.as_deref() {
             Some(cwd) => {
@@ -213,12 +337,45 @@ impl TaskTemplate {
             &mut substituted_variables,
         )?;
 
+        let problem_matchers = self
+            .problem_matchers
+            .iter()
+            .map(|matcher| match matcher {
+                ProblemMatcher::Custom(
+                    custom @ CustomProblemMatcher {
+                        file_location: FileLocation::RelativeTo(directory),
+                        ..
+                    },
+                ) => {
+                    let directory = substitute_all_template_variables_in_str(
+                        directory,
+                        &task_variables,
+                        &variable_names,
+                        &mut substituted_variables,
+                    )?;
+                    Some(ProblemMatcher::Custom(CustomProblemMatcher {
+                        file_location: FileLocation::RelativeTo(directory),
+                        ..custom.clone()
+                    }))
+                }
+                matcher => Some(matcher.clone()),
+            })
+            .collect::<Option<Vec<_>>>()?;
+
         let task_hash = to_hex_hash(self)
             .context("hashing task template")
             .log_err()?;
-        let variables_hash = to_hex_hash(&task_variables)
-            .context("hashing task variables")
-            .log_err()?;
+        // Inputs are left out, for the task to keep its id (and terminal) whatever the user gives them.
+        let variables_hash = to_hex_hash(
+            task_variables
+                .iter()
+                .filter(|(name, _)| {
+                    !matches!(variable_names.get(*name), Some(VariableName::Input(_)))
+                })
+                .collect::<HashMap<_, _>>(),
+        )
+        .context("hashing task variables")
+        .log_err()?;
         let id = TaskId(format!("{id_base}_{task_hash}_{variables_hash}"));
 
         let env = {
@@ -245,6 +402,7 @@ impl TaskTemplate {
             id: id.clone(),
             substituted_variables,
             original_task: self.clone(),
+            context: (!self.depends_on.is_empty() || !self.inputs.is_empty()).then(|| cx.clone()),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
                 id,
@@ -271,6 +429,7 @@ impl TaskTemplate {
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
+                problem_matchers,
             },
         })
     }
@@ -468,6 +627,122 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolving_templates_that_only_group_dependencies() {
+        let group = TaskTemplate {
+            label: "build all".to_string(),
+            depends_on: vec!["build server".to_string(), "build client".to_string()],
+            depends_order: DependsOrder::Sequence,
+            ..TaskTemplate::default()
+        };
+        let context = TaskContext {
+            cwd: Some(PathBuf::from("/dir")),
+            ..TaskContext::default()
+        };
+        let resolved = group
+            .resolve_task(TEST_ID_BASE, &context)
+            .expect("should resolve a task without a command if it has dependencies");
+        assert_eq!(resolved.resolved.label, "build all");
+        assert_eq!(resolved.dependencies_context(), Some(&context));
+
+        let task = TaskTemplate {
+            label: "build server".to_string(),
+            command: "cargo build".to_string(),
+            ..TaskTemplate::default()
+        };
+        let resolved = task.resolve_task(TEST_ID_BASE, &context).unwrap();
+        assert_eq!(
+            resolved.dependencies_context(),
+            None,
+            "should not keep the context around for tasks without dependencies"
+        );
+    }
+
+    #[test]
+    fn test_resolving_templates_with_inputs() {
+        let task = TaskTemplate {
+            label: "test $ZED_INPUT_filter".to_string(),
+            command: "cargo".to_string(),
+            args: vec![
+                "test".to_string(),
+                "-p".to_string(),
+                "$ZED_INPUT_package".to_string(),
+                "${ZED_INPUT_filter}".to_string(),
+            ],
+            inputs: vec![
+                TaskInput::PromptString {
+                    id: "filter".to_string(),
+                    description: Some("Test name filter".to_string()),
+                    default: None,
+                },
+                TaskInput::PickString {
+                    id: "package".to_string(),
+                    description: None,
+                    options: vec!["task".to_string(), "project".to_string()],
+                    default: None,
+                },
+            ],
+            ..TaskTemplate::default()
+        };
+        let mut context = TaskContext::default();
+        context.task_variables.insert(
+            VariableName::Input(Cow::Borrowed("package")),
+            "task".to_string(),
+        );
+
+        let resolved = task
+            .resolve_task(TEST_ID_BASE, &context)
+            .expect("should resolve a task before its inputs are given");
+        assert_eq!(
+            resolved
+                .pending_inputs()
+                .map(|input| input.description())
+                .collect::<Vec<_>>(),
+            vec!["Test name filter"],
+            "only the inputs missing from the context should be pending"
+        );
+        assert_eq!(resolved.resolved.label, "test $ZED_INPUT_filter");
+        assert_eq!(
+            resolved.resolved.args,
+            vec!["test", "-p", "task", "$ZED_INPUT_filter"]
+        );
+
+        let with_filter = resolved
+            .with_input_values([("filter".to_string(), "parsing".to_string())])
+            .unwrap();
+        assert_eq!(with_filter.pending_inputs().count(), 0);
+        assert_eq!(with_filter.resolved.label, "test parsing");
+        assert_eq!(
+            with_filter.resolved.args,
+            vec!["test", "-p", "task", "parsing"]
+        );
+        assert_eq!(
+            with_filter
+                .resolved
+                .env
+                .get("ZED_INPUT_filter")
+                .map(String::as_str),
+            Some("parsing")
+        );
+        assert_eq!(
+            with_filter.input_values(),
+            HashMap::from_iter([
+                ("filter".to_string(), "parsing".to_string()),
+                ("package".to_string(), "task".to_string()),
+            ])
+        );
+        assert_eq!(
+            with_filter.id, resolved.id,
+            "values of the inputs should not change the task id"
+        );
+
+        let with_other_filter = resolved
+            .with_input_values([("filter".to_string(), "lexing".to_string())])
+            .unwrap();
+        assert_eq!(with_other_filter.resolved.label, "test lexing");
+        assert_eq!(with_other_filter.id, resolved.id);
+    }
+
     #[test]
     fn test_template_cwd_resolution() {
         let task_without_cwd = TaskTemplate {
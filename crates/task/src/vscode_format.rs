@@ -1,9 +1,13 @@
-use anyhow::bail;
+use anyhow::{Context as _, bail};
 use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    BuiltInProblemMatcher, CustomProblemMatcher, DependsOrder, EnvVariableReplacer, FileLocation,
+    ProblemMatcher, ProblemPattern, ProblemSeverity, TaskInput, TaskTemplate, TaskTemplates,
+    VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -24,6 +28,201 @@ struct VsCodeTaskDefinition {
     options: Option<TaskOptions>,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+    fn into_vec(self) -> Vec<T> {
+        match self {
+            Self::One(item) => vec![item],
+            Self::Many(items) => items,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemMatcher {
+    Named(String),
+    Definition(VsCodeProblemMatcherDefinition),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemMatcherDefinition {
+    base: Option<String>,
+    owner: Option<String>,
+    source: Option<String>,
+    file_location: Option<OneOrMany<String>>,
+    severity: Option<String>,
+    pattern: Option<OneOrMany<VsCodeProblemPattern>>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    #[serde(default, rename = "loop")]
+    loop_: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum VsCodeTaskInput {
+    PromptString {
+        id: String,
+        description: Option<String>,
+        default: Option<String>,
+    },
+    PickString {
+        id: String,
+        description: Option<String>,
+        options: Vec<VsCodePickStringOption>,
+        default: Option<String>,
+    },
+    /// Runs a VS Code command, which Zed has no equivalent for.
+    Command { id: String },
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodePickStringOption {
+    Value(String),
+    Labeled { value: String },
+}
+
+impl VsCodeTaskInput {
+    fn into_zed_format(self) -> anyhow::Result<TaskInput> {
+        Ok(match self {
+            Self::PromptString {
+                id,
+                description,
+                default,
+            } => TaskInput::PromptString {
+                id,
+                description,
+                default,
+            },
+            Self::PickString {
+                id,
+                description,
+                options,
+                default,
+            } => TaskInput::PickString {
+                id,
+                description,
+                options: options
+                    .into_iter()
+                    .map(|option| match option {
+                        VsCodePickStringOption::Value(value)
+                        | VsCodePickStringOption::Labeled { value } => value,
+                    })
+                    .collect(),
+                default,
+            },
+            Self::Command { id } => bail!("Unsupported `command` type of input `{id}`"),
+        })
+    }
+}
+
+/// Whether any of the fields of the template that get variables substituted refers to the variable.
+fn template_refers_to(template: &TaskTemplate, variable: &VariableName) -> bool {
+    let variable = format!("${{{variable}}}");
+    [&template.label, &template.command]
+        .into_iter()
+        .chain(&template.args)
+        .chain(&template.cwd)
+        .chain(template.env.values())
+        .any(|field| field.contains(&variable))
+}
+
+impl VsCodeProblemMatcher {
+    fn into_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<ProblemMatcher> {
+        let definition = match self {
+            Self::Named(name) => {
+                return built_in_problem_matcher(&name).map(ProblemMatcher::BuiltIn);
+            }
+            Self::Definition(definition) => definition,
+        };
+
+        let mut matcher = match (definition.pattern, &definition.base) {
+            (Some(pattern), _) => CustomProblemMatcher {
+                pattern: pattern
+                    .into_vec()
+                    .into_iter()
+                    .map(|pattern| ProblemPattern {
+                        regexp: pattern.regexp,
+                        file: pattern.file,
+                        line: pattern.line,
+                        column: pattern.column,
+                        end_line: pattern.end_line,
+                        end_column: pattern.end_column,
+                        severity: pattern.severity,
+                        code: pattern.code,
+                        message: pattern.message,
+                        loop_: pattern.loop_,
+                    })
+                    .collect(),
+                ..CustomProblemMatcher::default()
+            },
+            (None, Some(base)) => {
+                let base = built_in_problem_matcher(base)?;
+                if definition.file_location.is_none()
+                    && definition.severity.is_none()
+                    && definition.source.is_none()
+                    && definition.owner.is_none()
+                {
+                    return Ok(ProblemMatcher::BuiltIn(base));
+                }
+                base.definition()
+            }
+            (None, None) => bail!("Problem matcher has neither a `pattern` nor a `base`"),
+        };
+        if let Some(source) = definition.source.or(definition.owner) {
+            matcher.source = Some(source);
+        }
+        if let Some(severity) = definition
+            .severity
+            .as_deref()
+            .and_then(ProblemSeverity::parse)
+        {
+            matcher.severity = severity;
+        }
+        if let Some(file_location) = definition.file_location {
+            let mut file_location = file_location.into_vec().into_iter();
+            matcher.file_location = match (file_location.next().as_deref(), file_location.next()) {
+                (Some("absolute"), _) => FileLocation::Absolute,
+                (_, Some(directory)) => FileLocation::RelativeTo(replacer.replace(&directory)),
+                _ => FileLocation::Relative,
+            };
+        }
+        Ok(ProblemMatcher::Custom(matcher))
+    }
+}
+
+fn built_in_problem_matcher(name: &str) -> anyhow::Result<BuiltInProblemMatcher> {
+    match name.trim_start_matches('$') {
+        "rustc" | "rustc-watch" => Ok(BuiltInProblemMatcher::Rustc),
+        "tsc" | "tsc-watch" => Ok(BuiltInProblemMatcher::Tsc),
+        "gcc" => Ok(BuiltInProblemMatcher::Gcc),
+        "eslint-stylish" => Ok(BuiltInProblemMatcher::Eslint),
+        "pytest" => Ok(BuiltInProblemMatcher::Pytest),
+        _ => bail!("Unsupported problem matcher `{name}`"),
+    }
+}
+
 #[derive(Clone, Deserialize, PartialEq, Debug)]
 #[serde(tag = "type")]
 #[serde(rename_all = "camelCase")]
@@ -46,25 +245,52 @@ impl VsCodeTaskDefinition {
         self,
         replacer: &EnvVariableReplacer,
     ) -> anyhow::Result<Option<TaskTemplate>> {
-        if self.other_attributes.contains_key("dependsOn") {
-            log::warn!(
-                "Skipping deserializing of a task `{}` with the unsupported `dependsOn` key",
+        let depends_on = match self.other_attributes.get("dependsOn") {
+            None => Vec::new(),
+            Some(serde_json_lenient::Value::String(label)) => vec![label.clone()],
+            Some(serde_json_lenient::Value::Array(labels)) => labels
+                .iter()
+                .map(|label| {
+                    label.as_str().map(ToOwned::to_owned).with_context(|| {
+                        format!(
+                            "Unsupported `dependsOn` entry {label} in task `{}`",
+                            self.label
+                        )
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?,
+            Some(other) => bail!(
+                "Unsupported `dependsOn` value {other} in task `{}`",
                 self.label
-            );
-            return Ok(None);
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+            ),
         };
-
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        let depends_order = match self
+            .other_attributes
+            .get("dependsOrder")
+            .and_then(|order| order.as_str())
+        {
+            Some("sequence") => DependsOrder::Sequence,
+            _ => DependsOrder::Parallel,
+        };
+        let problem_matchers = match self.other_attributes.get("problemMatcher") {
+            Some(problem_matchers) => serde_json_lenient::from_value::<
+                OneOrMany<VsCodeProblemMatcher>,
+            >(problem_matchers.clone())
+            .with_context(|| format!("Invalid `problemMatcher` in task `{}`", self.label))?
+            .into_vec()
+            .into_iter()
+            .filter_map(|matcher| matcher.into_zed_format(replacer).log_err())
+            .collect(),
+            None => Vec::new(),
+        };
+        // `type` might not be set in tasks that only group other tasks with `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
+        // as that way we can provide more specific description of why deserialization failed.
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
@@ -73,6 +299,9 @@ impl VsCodeTaskDefinition {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order,
+            problem_matchers,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
@@ -87,6 +316,8 @@ impl VsCodeTaskDefinition {
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct VsCodeTaskFile {
     tasks: Vec<VsCodeTaskDefinition>,
+    #[serde(default)]
+    inputs: Vec<VsCodeTaskInput>,
 }
 
 impl TryFrom<VsCodeTaskFile> for TaskTemplates {
@@ -105,6 +336,11 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
                 VariableName::SelectedText.to_string(),
             ),
         ]));
+        let inputs = value
+            .inputs
+            .into_iter()
+            .filter_map(|input| input.into_zed_format().log_err())
+            .collect::<Vec<_>>();
         let templates = value
             .tasks
             .into_iter()
@@ -114,6 +350,15 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
                     .log_err()
                     .flatten()
             })
+            .map(|mut template| {
+                // Inputs are shared by all tasks of the file, while Zed asks a task for its own ones only.
+                template.inputs = inputs
+                    .iter()
+                    .filter(|input| template_refers_to(&template, &input.variable_name()))
+                    .cloned()
+                    .collect();
+                template
+            })
             .collect();
         Ok(Self(templates))
     }
@@ -124,7 +369,8 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        BuiltInProblemMatcher, CustomProblemMatcher, DependsOrder, FileLocation, ProblemMatcher,
+        ProblemPattern, ProblemSeverity, TaskInput, TaskTemplate, TaskTemplates, VsCodeTaskFile,
         vscode_format::{Command, VsCodeTaskDefinition},
     };
 
@@ -227,6 +473,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
@@ -238,18 +485,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 ..Default::default()
             },
         ];
@@ -325,37 +575,240 @@ mod tests {
             .iter()
             .zip(expected)
             .for_each(|(lhs, rhs)| compare_without_other_attributes(lhs.clone(), rhs));
+        let editors_code_tsc = ProblemMatcher::Custom(CustomProblemMatcher {
+            file_location: FileLocation::RelativeTo(
+                "${ZED_WORKTREE_ROOT}/editors/code/".to_string(),
+            ),
+            ..BuiltInProblemMatcher::Tsc.definition()
+        });
         let expected = vec![
             TaskTemplate {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matchers: vec![editors_code_tsc.clone()],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: vec![editors_code_tsc.clone()],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: vec![editors_code_tsc.clone()],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)],
                 ..Default::default()
             },
         ];
         let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
         assert_eq!(tasks.0, expected);
     }
+
+    #[test]
+    fn can_deserialize_depends_on() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "Lint",
+                        "type": "shell",
+                        "command": "eslint",
+                        "dependsOn": "Build"
+                    },
+                    {
+                        "label": "Check",
+                        "dependsOn": ["Build", "Lint"],
+                        "dependsOrder": "sequence"
+                    },
+                    {
+                        "label": "Broken",
+                        "dependsOn": [{ "type": "npm", "script": "build" }]
+                    },
+                    {
+                        "label": "Nothing to run"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "Lint".to_string(),
+                    command: "eslint".to_string(),
+                    depends_on: vec!["Build".to_string()],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "Check".to_string(),
+                    depends_on: vec!["Build".to_string(), "Lint".to_string()],
+                    depends_order: DependsOrder::Sequence,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn can_deserialize_problem_matchers() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "lint",
+                        "type": "shell",
+                        "command": "golangci-lint run",
+                        "problemMatcher": [
+                            "$gcc",
+                            "$unknown",
+                            {
+                                "owner": "go",
+                                "fileLocation": "absolute",
+                                "severity": "warning",
+                                "pattern": {
+                                    "regexp": "^(.+):(\\d+):(\\d+): (.+)$",
+                                    "file": 1,
+                                    "line": 2,
+                                    "column": 3,
+                                    "message": 4
+                                }
+                            }
+                        ]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![TaskTemplate {
+                label: "lint".to_string(),
+                command: "golangci-lint run".to_string(),
+                problem_matchers: vec![
+                    ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Gcc),
+                    ProblemMatcher::Custom(CustomProblemMatcher {
+                        source: Some("go".to_string()),
+                        file_location: FileLocation::Absolute,
+                        severity: ProblemSeverity::Warning,
+                        pattern: vec![ProblemPattern {
+                            regexp: r"^(.+):(\d+):(\d+): (.+)$".to_string(),
+                            file: Some(1),
+                            line: Some(2),
+                            column: Some(3),
+                            message: Some(4),
+                            ..ProblemPattern::default()
+                        }],
+                    }),
+                ],
+                ..Default::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn can_deserialize_inputs() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "test",
+                        "type": "shell",
+                        "command": "cargo test -p ${input:package} ${input:filter}"
+                    },
+                    {
+                        "label": "build",
+                        "type": "shell",
+                        "command": "cargo build",
+                        "args": ["--profile", "${input:profile}"]
+                    }
+                ],
+                "inputs": [
+                    {
+                        "id": "filter",
+                        "type": "promptString",
+                        "description": "Test name filter",
+                        "default": "tests::"
+                    },
+                    {
+                        "id": "package",
+                        "type": "pickString",
+                        "options": ["task", { "label": "Project", "value": "project" }]
+                    },
+                    {
+                        "id": "profile",
+                        "type": "command",
+                        "command": "extension.pickProfile"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "test".to_string(),
+                    command: "cargo test -p ${ZED_INPUT_package} ${ZED_INPUT_filter}".to_string(),
+                    inputs: vec![
+                        TaskInput::PromptString {
+                            id: "filter".to_string(),
+                            description: Some("Test name filter".to_string()),
+                            default: Some("tests::".to_string()),
+                        },
+                        TaskInput::PickString {
+                            id: "package".to_string(),
+                            description: None,
+                            options: vec!["task".to_string(), "project".to_string()],
+                            default: None,
+                        },
+                    ],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "build".to_string(),
+                    command: "cargo build".to_string(),
+                    args: vec!["--profile".to_string(), "${ZED_INPUT_profile}".to_string()],
+                    ..Default::default()
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,378 @@
+//! Asks the user for the values of the [`TaskInput`]s of a task, before it is spawned.
+
+use std::{cmp, path::Path, sync::Arc};
+
+use anyhow::{Context as _, Result, bail};
+use collections::HashMap;
+use editor::Editor;
+use futures::channel::oneshot;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    App, AsyncWindowContext, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    Render, SharedString, Task, WeakEntity, Window, rems,
+};
+use picker::{Picker, PickerDelegate};
+use task::{ResolvedTask, TaskInput};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, TaskInputsProvider, Workspace};
+
+/// Asks for the inputs one after another, with a text prompt or a picker depending on the kind of the input.
+pub(crate) struct TaskInputs;
+
+impl TaskInputsProvider for TaskInputs {
+    fn request_inputs(
+        &self,
+        task: &ResolvedTask,
+        last_values: HashMap<String, String>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Result<Option<HashMap<String, String>>>> {
+        let inputs = task.pending_inputs().cloned().collect::<Vec<_>>();
+        let task_label = SharedString::from(task.display_label().to_owned());
+        let cwd = task.resolved.cwd.clone();
+        let env = task.resolved.env.clone();
+
+        cx.spawn_in(window, async move |workspace, cx| {
+            let mut values = HashMap::default();
+            for input in inputs {
+                let last_value = last_values.get(input.id()).cloned();
+                let title = SharedString::from(input.description().to_owned());
+                let value = match &input {
+                    TaskInput::PromptString { default, .. } => {
+                        let text = last_value.or_else(|| default.clone()).unwrap_or_default();
+                        prompt_string(&workspace, task_label.clone(), title, text, cx).await?
+                    }
+                    TaskInput::PickString {
+                        options, default, ..
+                    } => {
+                        let selected = last_value.or_else(|| default.clone());
+                        pick_string(&workspace, title, options.clone(), selected, cx).await?
+                    }
+                    TaskInput::CommandOutput { command, args, .. } => {
+                        let mut lines = command_input_lines(
+                            &workspace,
+                            command,
+                            args,
+                            cwd.as_deref(),
+                            &env,
+                            cx,
+                        )
+                        .await
+                        .with_context(|| {
+                            format!(
+                                "Failed to get input `{}` of task `{task_label}`",
+                                input.id()
+                            )
+                        })?;
+                        if lines.len() == 1 {
+                            lines.pop()
+                        } else {
+                            pick_string(&workspace, title, lines, last_value, cx).await?
+                        }
+                    }
+                };
+                let Some(value) = value else {
+                    return Ok(None);
+                };
+                values.insert(input.id().to_owned(), value);
+            }
+            Ok(Some(values))
+        })
+    }
+}
+
+async fn prompt_string(
+    workspace: &WeakEntity<Workspace>,
+    task_label: SharedString,
+    title: SharedString,
+    text: String,
+    cx: &mut AsyncWindowContext,
+) -> Result<Option<String>> {
+    let (tx, rx) = oneshot::channel();
+    workspace.update_in(cx, |workspace, window, cx| {
+        workspace.toggle_modal(window, cx, |window, cx| {
+            TaskInputPrompt::new(task_label, title, text, tx, window, cx)
+        })
+    })?;
+    Ok(rx.await.ok())
+}
+
+async fn pick_string(
+    workspace: &WeakEntity<Workspace>,
+    title: SharedString,
+    options: Vec<String>,
+    selected: Option<String>,
+    cx: &mut AsyncWindowContext,
+) -> Result<Option<String>> {
+    let (tx, rx) = oneshot::channel();
+    workspace.update_in(cx, |workspace, window, cx| {
+        workspace.toggle_modal(window, cx, |window, cx| {
+            let delegate = TaskInputPickerDelegate::new(title, options, selected, tx);
+            Picker::uniform_list(delegate, window, cx).width(rems(34.))
+        })
+    })?;
+    Ok(rx.await.ok())
+}
+
+/// Runs the command of a [`TaskInput::CommandOutput`] with the task's environment, returning the non-empty lines it printed.
+async fn command_input_lines(
+    workspace: &WeakEntity<Workspace>,
+    command: &str,
+    args: &[String],
+    cwd: Option<&Path>,
+    env: &HashMap<String, String>,
+    cx: &mut AsyncWindowContext,
+) -> Result<Vec<String>> {
+    let is_local =
+        workspace.read_with(cx, |workspace, cx| workspace.project().read(cx).is_local())?;
+    if !is_local {
+        bail!("Commands for task inputs can only run in local projects");
+    }
+
+    let mut process = util::command::new_smol_command(command);
+    process.args(args).envs(env);
+    if let Some(cwd) = cwd {
+        process.current_dir(cwd);
+    }
+    let output = process
+        .output()
+        .await
+        .with_context(|| format!("running `{command}`"))?;
+    if !output.status.success() {
+        bail!(
+            "`{command}` failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let lines = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(ToOwned::to_owned)
+        .collect::<Vec<_>>();
+    if lines.is_empty() {
+        bail!("`{command}` printed nothing");
+    }
+    Ok(lines)
+}
+
+/// A modal to type the value of a [`TaskInput::PromptString`] in.
+struct TaskInputPrompt {
+    task_label: SharedString,
+    title: SharedString,
+    editor: Entity<Editor>,
+    tx: Option<oneshot::Sender<String>>,
+}
+
+impl TaskInputPrompt {
+    fn new(
+        task_label: SharedString,
+        title: SharedString,
+        text: String,
+        tx: oneshot::Sender<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_text(text, window, cx);
+            editor.select_all(&Default::default(), window, cx);
+            editor
+        });
+        Self {
+            task_label,
+            title,
+            editor,
+            tx: Some(tx),
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(tx) = self.tx.take() {
+            tx.send(self.editor.read(cx).text(cx)).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for TaskInputPrompt {}
+impl EventEmitter<DismissEvent> for TaskInputPrompt {}
+
+impl Focusable for TaskInputPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for TaskInputPrompt {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .elevation_2(cx)
+            .key_context("TaskInputPrompt")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .child(Label::new(self.title.clone()))
+                    .child(
+                        Label::new(self.task_label.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+    }
+}
+
+/// Picks the value of a [`TaskInput::PickString`], or one of the lines printed for a [`TaskInput::CommandOutput`].
+struct TaskInputPickerDelegate {
+    title: Arc<str>,
+    options: Vec<String>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    tx: Option<oneshot::Sender<String>>,
+}
+
+impl TaskInputPickerDelegate {
+    fn new(
+        title: SharedString,
+        options: Vec<String>,
+        selected: Option<String>,
+        tx: oneshot::Sender<String>,
+    ) -> Self {
+        let selected_index = selected
+            .and_then(|selected| options.iter().position(|option| option == &selected))
+            .unwrap_or(0);
+        Self {
+            title: Arc::from(title.as_ref()),
+            options,
+            matches: Vec::new(),
+            selected_index,
+            tx: Some(tx),
+        }
+    }
+}
+
+impl PickerDelegate for TaskInputPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        self.title.clone()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let candidates = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(ix, option)| StringMatchCandidate::new(ix, option))
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    1000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = cmp::min(
+                        delegate.selected_index,
+                        delegate.matches.len().saturating_sub(1),
+                    );
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(hit) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        if let Some(tx) = self.tx.take() {
+            tx.send(self.options[hit.candidate_id].clone()).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = &self.matches[ix];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(
+                    hit.string.clone(),
+                    hit.positions.clone(),
+                )),
+        )
+    }
+}
@@ -0,0 +1,162 @@
+//! A pty whose output is rewritten before Alacritty parses it, to pick out the escape sequences
+//! Alacritty ignores: inline images (see `terminal_images`) and shell integration's command marks
+//! (see `terminal_marks`). The output of tasks with problem matchers is also scanned for problems
+//! (see `terminal_problems`).
+
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write as _},
+    sync::Arc,
+};
+
+use alacritty_terminal::{
+    event::{OnResize, WindowSize},
+    tty::{ChildEvent, EventedPty, EventedReadWrite},
+};
+use polling::{Event as PollEvent, PollMode, Poller};
+use util::ResultExt as _;
+
+use crate::{
+    terminal_images::{ImageScanner, TerminalImages},
+    terminal_marks::{CommandHistory, MAX_EXPANSION, ShellMarkScanner},
+    terminal_problems::ProblemScanner,
+};
+
+pub(crate) const ESC: u8 = 0x1b;
+pub(crate) const BEL: u8 = 0x07;
+pub(crate) const CAN: u8 = 0x18;
+pub(crate) const SUB: u8 = 0x1a;
+
+const READ_BUFFER_SIZE: usize = 0x1_0000;
+
+/// A pty whose output is passed through an [`ImageScanner`] and then a [`ShellMarkScanner`],
+/// and to a [`ProblemScanner`] as is, if there is one.
+pub(crate) struct ScannedPty<P> {
+    pty: P,
+    images: ImageScanner,
+    marks: ShellMarkScanner,
+    problems: Option<ProblemScanner>,
+    read_buffer: Box<[u8]>,
+    scanned: Vec<u8>,
+    pending: VecDeque<u8>,
+}
+
+impl<P> ScannedPty<P> {
+    pub fn new(
+        pty: P,
+        window_size: WindowSize,
+        images: TerminalImages,
+        history: CommandHistory,
+        problems: Option<ProblemScanner>,
+        allow_image_files: bool,
+    ) -> Self {
+        Self {
+            pty,
+            images: ImageScanner::new(images, window_size, allow_image_files),
+            marks: ShellMarkScanner::new(history),
+            problems,
+            read_buffer: vec![0; READ_BUFFER_SIZE].into_boxed_slice(),
+            scanned: Vec::new(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn finish_problems(&mut self) {
+        if let Some(problems) = &mut self.problems {
+            problems.finish();
+        }
+    }
+}
+
+impl<P: EventedReadWrite> Read for ScannedPty<P> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Alacritty stops reading once the pty would block, so only report that once the pty
+        // really would, rather than when a read contained nothing but sequences we took out.
+        //
+        // Once it has parsed enough, Alacritty waits for the pty to have more output before
+        // reading again, so everything scanned from one read of the pty must fit in `buf`. The
+        // image scanner at most doubles what it passes through, plus the cells of the images it
+        // places, which get the other half of `buf`.
+        while self.pending.is_empty() {
+            let len = (buf.len() / (4 * MAX_EXPANSION)).clamp(1, self.read_buffer.len());
+            let read = match self.pty.reader().read(&mut self.read_buffer[..len]) {
+                Ok(0) => {
+                    self.finish_problems();
+                    return Ok(0);
+                }
+                Ok(read) => read,
+                Err(error) => {
+                    // Linux reports the end of the output of an exited child as an error.
+                    if error.kind() != io::ErrorKind::WouldBlock
+                        && error.kind() != io::ErrorKind::Interrupted
+                    {
+                        self.finish_problems();
+                    }
+                    return Err(error);
+                }
+            };
+            if let Some(problems) = &mut self.problems {
+                problems.scan(&self.read_buffer[..read]);
+            }
+            self.images
+                .scan(&self.read_buffer[..read], &mut self.scanned, buf.len() / 2);
+            self.marks.scan(&self.scanned, &mut self.pending);
+            self.scanned.clear();
+
+            let responses = self.images.take_responses();
+            if !responses.is_empty() {
+                self.pty.writer().write_all(&responses).log_err();
+            }
+        }
+        self.pending.make_contiguous();
+        self.pending.read(buf)
+    }
+}
+
+impl<P: EventedReadWrite> EventedReadWrite for ScannedPty<P> {
+    type Reader = Self;
+    type Writer = P::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: PollEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        unsafe { self.pty.register(poll, interest, mode) }
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: PollEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poll, interest, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl<P: EventedPty> EventedPty for ScannedPty<P> {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl<P: OnResize> OnResize for ScannedPty<P> {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.images.resize(window_size);
+        self.pty.on_resize(window_size)
+    }
+}
@@ -0,0 +1,112 @@
+//! Startup scripts that make shells report their prompts and commands, which the terminal turns
+//! into command marks (see `terminal_marks`).
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use util::ResultExt as _;
+
+const SCRIPTS: &[(&str, &str)] = &[
+    ("zed.bash", include_str!("shell_integration/zed.bash")),
+    ("zed.fish", include_str!("shell_integration/zed.fish")),
+    ("zsh/.zshenv", include_str!("shell_integration/zsh/.zshenv")),
+    (
+        "zsh/.zprofile",
+        include_str!("shell_integration/zsh/.zprofile"),
+    ),
+    ("zsh/.zshrc", include_str!("shell_integration/zsh/.zshrc")),
+    ("zsh/.zlogin", include_str!("shell_integration/zsh/.zlogin")),
+    ("zsh/zed.zsh", include_str!("shell_integration/zsh/zed.zsh")),
+];
+
+/// How to start a shell so that it loads its integration script.
+pub(crate) struct ShellIntegration {
+    pub program: String,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+}
+
+impl ShellIntegration {
+    /// Returns how to start `program` with shell integration, or `None` if it isn't a shell we
+    /// have a script for. `login` asks for a login shell, as the system shell is started on macOS.
+    pub fn for_shell(program: String, login: bool, env: &HashMap<String, String>) -> Option<Self> {
+        let name = Path::new(&program).file_name()?.to_str()?;
+        let scripts_dir = scripts_dir()?;
+        let mut args = Vec::new();
+        let mut integration_env = Vec::new();
+        match name {
+            "bash" => {
+                if login {
+                    integration_env.push(("ZED_SHELL_LOGIN".to_string(), "1".to_string()));
+                }
+                args.push("--init-file".to_string());
+                args.push(scripts_dir.join("zed.bash").to_string_lossy().into_owned());
+            }
+            "zsh" => {
+                if login {
+                    args.push("-l".to_string());
+                }
+                let user_zdotdir = env
+                    .get("ZDOTDIR")
+                    .cloned()
+                    .or_else(|| std::env::var("ZDOTDIR").ok());
+                if let Some(user_zdotdir) = user_zdotdir {
+                    integration_env.push(("ZED_USER_ZDOTDIR".to_string(), user_zdotdir));
+                }
+                integration_env.push((
+                    "ZDOTDIR".to_string(),
+                    scripts_dir.join("zsh").to_string_lossy().into_owned(),
+                ));
+            }
+            "fish" => {
+                if login {
+                    args.push("-l".to_string());
+                }
+                let script = scripts_dir.join("zed.fish");
+                args.push("--init-command".to_string());
+                args.push(format!(
+                    "source '{}'",
+                    script
+                        .to_string_lossy()
+                        .replace('\\', "\\\\")
+                        .replace('\'', "\\'")
+                ));
+            }
+            _ => return None,
+        }
+        Some(Self {
+            program,
+            args,
+            env: integration_env,
+        })
+    }
+}
+
+/// Writes the scripts to disk the first time they're needed, so that shells can load them.
+fn scripts_dir() -> Option<&'static Path> {
+    static SCRIPTS_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    SCRIPTS_DIR
+        .get_or_init(|| write_scripts(paths::data_dir().join("shell_integration")).log_err())
+        .as_deref()
+}
+
+fn write_scripts(dir: PathBuf) -> Result<PathBuf> {
+    for (name, contents) in SCRIPTS {
+        let path = dir.join(name);
+        if fs::read_to_string(&path).is_ok_and(|existing| existing == *contents) {
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("creating shell integration directory {parent:?}"))?;
+        }
+        fs::write(&path, contents)
+            .with_context(|| format!("writing shell integration script {path:?}"))?;
+    }
+    Ok(dir)
+}
@@ -0,0 +1,76 @@
+# Zed shell integration for bash, loaded with `bash --init-file`.
+#
+# Reports prompts, command lines and exit statuses to the terminal with OSC 133 and OSC 633.
+
+# `--init-file` replaces the startup files bash would otherwise read, so read them here.
+if [[ "$ZED_SHELL_LOGIN" == 1 ]]; then
+    unset ZED_SHELL_LOGIN
+    [[ -r /etc/profile ]] && . /etc/profile
+    for __zed_file in ~/.bash_profile ~/.bash_login ~/.profile; do
+        if [[ -r "$__zed_file" ]]; then
+            . "$__zed_file"
+            break
+        fi
+    done
+    unset __zed_file
+else
+    [[ -r ~/.bashrc ]] && . ~/.bashrc
+fi
+
+if [[ -n "$__zed_shell_integration" || $- != *i* ]]; then
+    return
+fi
+__zed_shell_integration=1
+__zed_at_prompt=0
+__zed_in_command=0
+
+__zed_escape() {
+    local escaped="${1//\\/\\\\}"
+    escaped="${escaped//;/\\x3b}"
+    escaped="${escaped//$'\n'/\\x0a}"
+    builtin printf '%s' "$escaped"
+}
+
+# Runs first in PROMPT_COMMAND, to report how the last command exited.
+__zed_precmd() {
+    local exit_status=$?
+    if [[ "$__zed_in_command" == 1 ]]; then
+        builtin printf '\e]133;D;%s\a' "$exit_status"
+    fi
+    __zed_in_command=0
+    return "$exit_status"
+}
+
+# Runs last in PROMPT_COMMAND, once any prompt framework has set PS1.
+__zed_prompt_end() {
+    local exit_status=$?
+    if [[ "$PS1" != *'\e]133;A'* ]]; then
+        PS1='\[\e]133;A\a\]'"$PS1"'\[\e]133;B\a\]'
+    fi
+    __zed_at_prompt=1
+    return "$exit_status"
+}
+
+# Runs before every simple command, but only reports the first one run from a prompt.
+__zed_preexec() {
+    if [[ "$__zed_at_prompt" != 1 || -n "$COMP_LINE" || "$BASH_COMMAND" == __zed_precmd* ]]; then
+        return
+    fi
+    __zed_at_prompt=0
+    __zed_in_command=1
+    local command_line
+    command_line="$(HISTTIMEFORMAT= builtin history 1)"
+    if [[ "$command_line" =~ ^[[:space:]]*[0-9]+[*[:space:]]+(.*)$ ]]; then
+        command_line="${BASH_REMATCH[1]}"
+    else
+        command_line="$BASH_COMMAND"
+    fi
+    builtin printf '\e]633;E;%s\a\e]133;C\a' "$(__zed_escape "$command_line")"
+}
+
+PROMPT_COMMAND="__zed_precmd${PROMPT_COMMAND:+; $PROMPT_COMMAND}; __zed_prompt_end"
+# Another DEBUG trap, such as bash-preexec's, is left in place, at the cost of commands not
+# being reported as they start.
+if [[ -z "$(trap -p DEBUG)" ]]; then
+    trap '__zed_preexec' DEBUG
+fi
@@ -0,0 +1,32 @@
+# Zed shell integration for fish, loaded with `fish --init-command`.
+#
+# Reports prompts, command lines and exit statuses to the terminal with OSC 133 and OSC 633.
+
+if status is-interactive; and not set -q __zed_shell_integration
+    set -g __zed_shell_integration 1
+    set -g __zed_in_command 0
+
+    function __zed_escape
+        string replace -a -- '\\' '\\\\' $argv | string replace -a -- ';' '\\x3b' | string join -- '\\x0a'
+    end
+
+    function __zed_preexec --on-event fish_preexec
+        set -g __zed_in_command 1
+        printf '\e]633;E;%s\a\e]133;C\a' (__zed_escape $argv)
+    end
+
+    function __zed_postexec --on-event fish_postexec
+        set -l exit_status $status
+        if test "$__zed_in_command" = 1
+            printf '\e]133;D;%s\a' $exit_status
+        end
+        set -g __zed_in_command 0
+    end
+
+    functions -q fish_prompt; and functions -c fish_prompt __zed_user_fish_prompt
+    function fish_prompt
+        printf '\e]133;A\a'
+        functions -q __zed_user_fish_prompt; and __zed_user_fish_prompt
+        printf '\e]133;B\a'
+    end
+end
@@ -3,7 +3,14 @@ pub mod mappings;
 pub use alacritty_terminal;
 
 mod pty_info;
+mod scanned_pty;
+#[cfg(unix)]
+mod shell_integration;
+mod terminal_history;
 mod terminal_hyperlinks;
+mod terminal_images;
+mod terminal_marks;
+mod terminal_problems;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
@@ -40,11 +47,17 @@ use mappings::mouse::{
 use collections::{HashMap, VecDeque};
 use futures::StreamExt;
 use pty_info::PtyProcessInfo;
+use scanned_pty::ScannedPty;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
 use smol::channel::{Receiver, Sender};
-use task::{HideStrategy, Shell, TaskId};
+use task::{HideStrategy, Problem, ProblemCollector, ProblemMatcher, Shell, TaskId};
 use terminal_hyperlinks::RegexSearches;
+pub use terminal_images::ImagePlacement;
+use terminal_images::TerminalImages;
+use terminal_marks::CommandHistory;
+pub use terminal_marks::{CommandStatus, PromptMark, cell_hyperlink};
+use terminal_problems::{ProblemScanner, TaskProblems};
 use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
 use theme::{ActiveTheme, Theme};
 use urlencoding;
@@ -103,6 +116,16 @@ actions!(
         ToggleViMode,
         /// Selects all text in the terminal.
         SelectAll,
+        /// Scrolls up to the previous shell prompt.
+        ScrollToPreviousPrompt,
+        /// Scrolls down to the next shell prompt.
+        ScrollToNextPrompt,
+        /// Selects the output of the last command shown in the terminal.
+        SelectCommandOutput,
+        /// Copies the output of the last command shown in the terminal to the clipboard.
+        CopyCommandOutput,
+        /// Runs the last command shown in the terminal again.
+        RerunCommand,
     ]
 );
 
@@ -168,6 +191,12 @@ enum InternalEvent {
     ToggleViMode,
     ViMotion(ViMotion),
     MoveViCursorToAlacPoint(AlacPoint),
+    // Shell integration events
+    ScrollToPrompt { forward: bool },
+    SelectCommandOutput,
+    CopyCommandOutput,
+    RerunCommand,
+    RestoreHistory(String),
 }
 
 ///A translation struct for Alacritty to communicate with us from their event loop
@@ -350,6 +379,7 @@ impl TerminalBuilder {
         cursor_shape: CursorShape,
         alternate_scroll: AlternateScroll,
         max_scroll_history_lines: Option<usize>,
+        shell_integration: bool,
         is_ssh_terminal: bool,
         window_id: u64,
         completion_tx: Option<Sender<Option<ExitStatus>>>,
@@ -408,6 +438,33 @@ impl TerminalBuilder {
         };
         let terminal_title_override = shell_params.as_ref().and_then(|e| e.title_override.clone());
 
+        // Shells we have an integration script for report where their prompts, commands and
+        // output are, unless they were started with arguments we'd have to second-guess.
+        #[cfg(unix)]
+        let (shell_params, integration_env) = {
+            let program = match &shell {
+                _ if !shell_integration || task.is_some() => None,
+                Shell::System => Some((util::get_system_shell(), cfg!(target_os = "macos"))),
+                Shell::Program(program) => Some((program.clone(), false)),
+                Shell::WithArguments { .. } => None,
+            };
+            match program.and_then(|(program, login)| {
+                shell_integration::ShellIntegration::for_shell(program, login, &env)
+            }) {
+                Some(integration) => (
+                    Some(ShellParams {
+                        program: integration.program,
+                        args: Some(integration.args),
+                        title_override: None,
+                    }),
+                    integration.env,
+                ),
+                None => (shell_params, Vec::new()),
+            }
+        };
+        #[cfg(not(unix))]
+        let integration_env = Vec::new();
+
         #[cfg(windows)]
         let shell_program = shell_params.as_ref().map(|params| {
             use util::ResultExt;
@@ -428,7 +485,7 @@ impl TerminalBuilder {
                     .clone()
                     .or_else(|| Some(home_dir().to_path_buf())),
                 drain_on_exit: true,
-                env: env.clone().into_iter().collect(),
+                env: env.clone().into_iter().chain(integration_env).collect(),
             }
         };
 
@@ -479,6 +536,23 @@ impl TerminalBuilder {
         };
 
         let pty_info = PtyProcessInfo::new(&pty);
+        let images = TerminalImages::default();
+        let command_history = CommandHistory::default();
+        let task_problems = TaskProblems::default();
+        let problem_scanner = task
+            .as_ref()
+            .map(|task| ProblemCollector::new(&task.problem_matchers, working_directory.as_deref()))
+            .filter(|collector| !collector.is_empty())
+            .map(|collector| ProblemScanner::new(collector, task_problems.clone()));
+        let pty = ScannedPty::new(
+            pty,
+            TerminalBounds::default().into(),
+            images.clone(),
+            command_history.clone(),
+            problem_scanner,
+            // Image files named by a remote program aren't on this machine.
+            !is_ssh_terminal,
+        );
 
         //And connect them together
         let event_loop = EventLoop::new(
@@ -521,12 +595,16 @@ impl TerminalBuilder {
             #[cfg(windows)]
             shell_program,
             activation_script: activation_script.clone(),
+            images,
+            command_history,
+            task_problems,
             template: CopyTemplate {
                 shell,
                 env,
                 cursor_shape,
                 alternate_scroll,
                 max_scroll_history_lines,
+                shell_integration,
                 window_id,
             },
         };
@@ -660,6 +738,10 @@ pub struct TerminalContent {
     pub last_hovered_word: Option<HoveredWord>,
     pub scrolled_to_top: bool,
     pub scrolled_to_bottom: bool,
+    /// Prompts in the viewport that a command was run from, as reported by shell integration.
+    pub prompt_marks: Vec<PromptMark>,
+    /// Inline images printed by programs that are at least partly in the viewport.
+    pub images: Vec<ImagePlacement>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -686,6 +768,8 @@ impl Default for TerminalContent {
             last_hovered_word: None,
             scrolled_to_top: false,
             scrolled_to_bottom: false,
+            prompt_marks: Vec::new(),
+            images: Vec::new(),
         }
     }
 }
@@ -723,6 +807,9 @@ pub struct Terminal {
     shell_program: Option<String>,
     template: CopyTemplate,
     activation_script: Vec<String>,
+    images: TerminalImages,
+    command_history: CommandHistory,
+    task_problems: TaskProblems,
 }
 
 struct CopyTemplate {
@@ -731,6 +818,7 @@ struct CopyTemplate {
     cursor_shape: CursorShape,
     alternate_scroll: AlternateScroll,
     max_scroll_history_lines: Option<usize>,
+    shell_integration: bool,
     window_id: u64,
 }
 
@@ -745,6 +833,7 @@ pub struct TaskState {
     pub show_summary: bool,
     pub show_command: bool,
     pub show_rerun: bool,
+    pub problem_matchers: Vec<ProblemMatcher>,
 }
 
 /// A status of the current terminal tab's task.
@@ -1009,6 +1098,61 @@ impl Terminal {
             InternalEvent::ViMotion(motion) => {
                 term.vi_motion(*motion);
             }
+            &InternalEvent::ScrollToPrompt { forward } => {
+                let display_offset = term.grid().display_offset() as i32;
+                let viewport_top = Line(-display_offset);
+                match terminal_marks::find_prompt_start(term, viewport_top, forward) {
+                    Some(line) => {
+                        let new_display_offset = (-line.0).clamp(0, term.history_size() as i32);
+                        term.scroll_display(AlacScroll::Delta(new_display_offset - display_offset));
+                    }
+                    None if forward => term.scroll_display(AlacScroll::Bottom),
+                    None => {}
+                }
+                self.refresh_hovered_word(window);
+            }
+            InternalEvent::SelectCommandOutput | InternalEvent::CopyCommandOutput => {
+                let Some(output) = self.last_command_output(term) else {
+                    return;
+                };
+                if let InternalEvent::CopyCommandOutput = event {
+                    let text = term.bounds_to_string(*output.start(), *output.end());
+                    cx.write_to_clipboard(ClipboardItem::new_string(text));
+                } else {
+                    term.scroll_to_point(*output.start());
+                    self.events.push_back(InternalEvent::SetSelection(Some((
+                        make_selection(&output),
+                        *output.end(),
+                    ))));
+                }
+            }
+            InternalEvent::RestoreHistory(history) => {
+                terminal_history::prepend_history(term, history);
+                cx.emit(Event::Wakeup);
+            }
+            InternalEvent::RerunCommand => {
+                // Typing over a running command would send the text to it instead of the shell.
+                if self.command_history.is_command_running() {
+                    return;
+                }
+                let viewport_bottom =
+                    Line(term.screen_lines() as i32 - 1 - term.grid().display_offset() as i32);
+                let Some((command_id, line)) =
+                    terminal_marks::last_command_at(term, &self.command_history, viewport_bottom)
+                else {
+                    return;
+                };
+                let command_line = self
+                    .command_history
+                    .get(command_id)
+                    .and_then(|record| record.command_line)
+                    .or_else(|| terminal_marks::command_input(term, command_id, line));
+                if let Some(command_line) = command_line {
+                    term.scroll_display(AlacScroll::Bottom);
+                    term.selection = None;
+                    self.write_to_pty(format!("{command_line}\r").into_bytes());
+                }
+            }
             InternalEvent::FindHyperlink(position, open) => {
                 let prev_hovered_word = self.last_content.last_hovered_word.take();
 
@@ -1068,6 +1212,15 @@ impl Terminal {
         }
     }
 
+    /// Returns the output of the last command that started at or above the bottom of the viewport.
+    fn last_command_output(&self, term: &Term<ZedListener>) -> Option<RangeInclusive<AlacPoint>> {
+        let viewport_bottom =
+            Line(term.screen_lines() as i32 - 1 - term.grid().display_offset() as i32);
+        let (command_id, line) =
+            terminal_marks::last_command_at(term, &self.command_history, viewport_bottom)?;
+        terminal_marks::command_output(term, command_id, line)
+    }
+
     fn update_selected_word(
         &mut self,
         prev_word: Option<HoveredWord>,
@@ -1217,6 +1370,46 @@ impl Terminal {
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
     }
 
+    pub fn scroll_to_previous_prompt(&mut self) {
+        self.events
+            .push_back(InternalEvent::ScrollToPrompt { forward: false });
+    }
+
+    pub fn scroll_to_next_prompt(&mut self) {
+        self.events
+            .push_back(InternalEvent::ScrollToPrompt { forward: true });
+    }
+
+    pub fn select_command_output(&mut self) {
+        self.events.push_back(InternalEvent::SelectCommandOutput);
+    }
+
+    pub fn copy_command_output(&mut self) {
+        self.events.push_back(InternalEvent::CopyCommandOutput);
+    }
+
+    pub fn rerun_command(&mut self) {
+        self.events.push_back(InternalEvent::RerunCommand);
+    }
+
+    /// Returns the text printed above the shell's current prompt, up to the scroll history limit,
+    /// to be shown again with [`Self::restore_history`] after a restart.
+    pub fn history_text(&self) -> Option<String> {
+        let max_lines = self
+            .template
+            .max_scroll_history_lines
+            .unwrap_or(DEFAULT_SCROLL_HISTORY_LINES)
+            .min(MAX_SCROLL_HISTORY_LINES);
+        terminal_history::history_text(&self.term.lock_unfair(), max_lines)
+    }
+
+    /// Shows the history of a previous terminal in the scrollback, above whatever the shell
+    /// has printed so far.
+    pub fn restore_history(&mut self, history: String) {
+        self.events
+            .push_back(InternalEvent::RestoreHistory(history));
+    }
+
     pub fn scrolled_to_top(&self) -> bool {
         self.last_content.scrolled_to_top
     }
@@ -1393,10 +1586,25 @@ impl Terminal {
             self.process_terminal_event(&e, &mut terminal, window, cx)
         }
 
-        self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.images.collect(&terminal);
+        for image in self.images.take_evicted() {
+            cx.drop_image(image, Some(window));
+        }
+
+        self.last_content = Self::make_content(
+            &terminal,
+            &self.last_content,
+            &self.images,
+            &self.command_history,
+        );
     }
 
-    fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
+    fn make_content(
+        term: &Term<ZedListener>,
+        last_content: &TerminalContent,
+        images: &TerminalImages,
+        command_history: &CommandHistory,
+    ) -> TerminalContent {
         let content = term.renderable_content();
 
         // Pre-allocate with estimated size to reduce reallocations
@@ -1426,6 +1634,8 @@ impl Terminal {
             last_hovered_word: last_content.last_hovered_word.clone(),
             scrolled_to_top: content.display_offset == term.history_size(),
             scrolled_to_bottom: content.display_offset == 0,
+            prompt_marks: terminal_marks::visible_prompt_marks(term, command_history),
+            images: images.visible_placements(term),
         }
     }
 
@@ -1715,7 +1925,7 @@ impl Terminal {
             if self.selection_phase == SelectionPhase::Ended {
                 let mouse_cell_index =
                     content_index_for_mouse(position, &self.last_content.terminal_bounds);
-                if let Some(link) = self.last_content.cells[mouse_cell_index].hyperlink() {
+                if let Some(link) = cell_hyperlink(&self.last_content.cells[mouse_cell_index]) {
                     cx.open_url(link.uri());
                 } else if e.modifiers.secondary() {
                     self.events
@@ -1891,6 +2101,11 @@ impl Terminal {
         self.task.as_ref()
     }
 
+    /// Takes the problems the task's problem matchers found in its output since the last call.
+    pub fn take_task_problems(&self) -> Vec<Problem> {
+        self.task_problems.take()
+    }
+
     pub fn wait_for_completed_task(&self, cx: &App) -> Task<Option<ExitStatus>> {
         if let Some(task) = self.task() {
             if task.status == TaskStatus::Running {
@@ -1987,6 +2202,7 @@ impl Terminal {
             self.template.cursor_shape,
             self.template.alternate_scroll,
             self.template.max_scroll_history_lines,
+            self.template.shell_integration,
             self.is_ssh_terminal,
             self.template.window_id,
             None,
@@ -2220,6 +2436,7 @@ mod tests {
                 AlternateScroll::On,
                 None,
                 false,
+                false,
                 0,
                 Some(completion_tx),
                 cx,
@@ -0,0 +1,159 @@
+//! Saving a terminal's scrollback as text, and restoring it into a new terminal after a restart.
+
+use alacritty_terminal::{
+    Term,
+    event::{EventListener, VoidListener},
+    grid::{Dimensions, Row},
+    index::{Column, Line, Point as AlacPoint},
+    term::{
+        Config, TermMode,
+        cell::{Cell, Flags},
+    },
+    vte::ansi::Processor,
+};
+use std::cmp;
+
+use crate::MAX_SCROLL_HISTORY_LINES;
+
+const RESTORED_HISTORY_LABEL: &str = " Restored from the previous session ";
+
+/// Returns the text above the cursor, which holds the shell's current prompt, up to `max_lines`
+/// lines of it. History restored from a previous session isn't included, so that it isn't
+/// restored again and again.
+pub(crate) fn history_text<T: EventListener>(term: &Term<T>, max_lines: usize) -> Option<String> {
+    // The primary screen's history can't be reached while a full screen program is running.
+    if max_lines == 0 || term.mode().contains(TermMode::ALT_SCREEN) {
+        return None;
+    }
+    let end = term.grid().cursor.point.line - 1;
+    let restored_end = restored_history_end(term, end);
+    let start = cmp::max(
+        restored_end.map_or(term.topmost_line(), |line| line + 1),
+        end - (max_lines - 1) as i32,
+    );
+    if end < start {
+        return None;
+    }
+    let text = term.bounds_to_string(
+        AlacPoint::new(start, Column(0)),
+        AlacPoint::new(end, term.last_column()),
+    );
+    let text = text.trim_end();
+    (!text.trim_start().is_empty()).then(|| text.to_string())
+}
+
+/// Returns the last line of the label that follows restored history, if it's still above `end`.
+fn restored_history_end<T: EventListener>(term: &Term<T>, end: Line) -> Option<Line> {
+    let label = RESTORED_HISTORY_LABEL.trim();
+    let last_column = term.last_column();
+    let mut line = (term.topmost_line().0..=end.0)
+        .map(Line)
+        .rev()
+        .find(|&line| {
+            let row = &term.grid()[line];
+            if !row[Column(0)].flags.contains(Flags::INVERSE) {
+                return false;
+            }
+            let text = (0..term.columns())
+                .map(|column| row[Column(column)].c)
+                .collect::<String>();
+            let text = text.trim();
+            !text.is_empty() && label.starts_with(text)
+        })?;
+    // The label wraps onto more lines in narrow terminals.
+    while line < end
+        && term.grid()[line][last_column]
+            .flags
+            .contains(Flags::WRAPLINE)
+    {
+        line += 1;
+    }
+    Some(line)
+}
+
+/// Adds `history` to the scrollback, dimmed and followed by a label, just above the top of the
+/// screen, so that it sits above anything the new shell has already printed.
+pub(crate) fn prepend_history<T: EventListener>(term: &mut Term<T>, history: &str) {
+    let screen_lines = term.screen_lines();
+
+    // Lay the history out in a scratch terminal of the same size, so that long lines wrap and
+    // wide characters take up two cells, as they did when they were first printed.
+    let config = Config {
+        scrolling_history: MAX_SCROLL_HISTORY_LINES,
+        ..Config::default()
+    };
+    let mut scratch = Term::new(config, &*term, VoidListener);
+    let mut processor: Processor = Processor::new();
+    let text = format!(
+        "\x1b[2m{}\r\n\x1b[0;7m{RESTORED_HISTORY_LABEL}\x1b[0m\r\n",
+        history.replace('\n', "\r\n")
+    );
+    processor.advance(&mut scratch, text.as_bytes());
+    let rows = (scratch.topmost_line().0..scratch.grid().cursor.point.line.0)
+        .map(|line| scratch.grid()[Line(line)].clone())
+        .collect::<Vec<Row<Cell>>>();
+
+    // Scrolling the whole screen up pushes its top row into the scrollback, so put each history
+    // row there in turn, then put back what was on the screen.
+    let grid = term.grid_mut();
+    let screen = (0..screen_lines as i32)
+        .map(|line| grid[Line(line)].clone())
+        .collect::<Vec<_>>();
+    let region = Line(0)..Line(screen_lines as i32);
+    for row in rows {
+        grid[Line(0)] = row;
+        grid.scroll_up(&region, 1);
+    }
+    for (line, row) in screen.into_iter().enumerate() {
+        grid[Line(line as i32)] = row;
+    }
+    term.selection = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::term::test::TermSize;
+
+    fn line_text<T: EventListener>(term: &Term<T>, line: i32) -> String {
+        term.bounds_to_string(
+            AlacPoint::new(Line(line), Column(0)),
+            AlacPoint::new(Line(line), term.last_column()),
+        )
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let mut processor: Processor = Processor::new();
+        let mut term = Term::new(Config::default(), &TermSize::new(40, 3), VoidListener);
+        processor.advance(&mut term, b"$ make\r\nbuilding\r\ndone\r\n$ ");
+        let history = history_text(&term, 100).unwrap();
+        assert_eq!(history, "$ make\nbuilding\ndone");
+        assert_eq!(history_text(&term, 2).unwrap(), "building\ndone");
+
+        let mut restored = Term::new(Config::default(), &TermSize::new(40, 3), VoidListener);
+        processor.advance(&mut restored, b"$ ");
+        prepend_history(&mut restored, &history);
+        assert_eq!(restored.grid().history_size(), 4);
+        assert_eq!(line_text(&restored, -4), "$ make");
+        assert_eq!(line_text(&restored, -3), "building");
+        assert_eq!(line_text(&restored, -2), "done");
+        assert_eq!(
+            line_text(&restored, -1).trim(),
+            RESTORED_HISTORY_LABEL.trim()
+        );
+        assert_eq!(line_text(&restored, 0), "$");
+        assert_eq!(
+            restored.grid().cursor.point,
+            AlacPoint::new(Line(0), Column(2))
+        );
+
+        // Restored history isn't saved again, but what was printed after it is.
+        processor.advance(&mut restored, b"ls\r\nfile\r\n$ ");
+        assert_eq!(history_text(&restored, 100).unwrap(), "$ ls\nfile");
+
+        let mut empty = Term::new(Config::default(), &TermSize::new(40, 3), VoidListener);
+        processor.advance(&mut empty, b"$ ");
+        assert_eq!(history_text(&empty, 100), None);
+    }
+}
@@ -8,6 +8,8 @@ use alacritty_terminal::{
 use regex::Regex;
 use std::{ops::Index, sync::LazyLock};
 
+use crate::terminal_marks::cell_hyperlink;
+
 const URL_REGEX: &str = r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`']+"#;
 // Optional suffix matches MSBuild diagnostic suffixes for path parsing in PathLikeWithPosition
 // https://learn.microsoft.com/en-us/visualstudio/msbuild/msbuild-diagnostic-format-for-tasks
@@ -51,12 +53,12 @@ pub(super) fn find_from_grid_point<T: EventListener>(
     regex_searches: &mut RegexSearches,
 ) -> Option<(String, bool, Match)> {
     let grid = term.grid();
-    let link = grid.index(point).hyperlink();
+    let link = cell_hyperlink(grid.index(point));
     let found_word = if let Some(ref url) = link {
         let mut min_index = point;
         loop {
             let new_min_index = min_index.sub(term, Boundary::Cursor, 1);
-            if new_min_index == min_index || grid.index(new_min_index).hyperlink() != link {
+            if new_min_index == min_index || cell_hyperlink(grid.index(new_min_index)) != link {
                 break;
             } else {
                 min_index = new_min_index
@@ -66,7 +68,7 @@ pub(super) fn find_from_grid_point<T: EventListener>(
         let mut max_index = point;
         loop {
             let new_max_index = max_index.add(term, Boundary::Cursor, 1);
-            if new_max_index == max_index || grid.index(new_max_index).hyperlink() != link {
+            if new_max_index == max_index || cell_hyperlink(grid.index(new_max_index)) != link {
                 break;
             } else {
                 max_index = new_max_index
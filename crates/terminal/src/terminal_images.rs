@@ -0,0 +1,1426 @@
+//! Inline images printed with the Kitty graphics protocol, Sixel, or iTerm2's inline images.
+//!
+//! Alacritty ignores these sequences, so an [`ImageScanner`] takes them out of the pty's output
+//! before Alacritty parses it. It decodes the image and prints blank cells in its place, linked
+//! to the image with OSC 8 hyperlinks using the `zed-image:` scheme. The image is anchored to
+//! those cells: it scrolls with them, and it goes away when they are overwritten or leave the
+//! scrollback. The terminal element then draws the image over the cells.
+//!
+//! Decoded images are kept in [`TerminalImages`], which holds at most [`MAX_IMAGE_MEMORY`] bytes
+//! of them. When a new image doesn't fit, the oldest images are evicted first. Images whose cells
+//! have gone are dropped as new images arrive.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{Cursor, Read as _},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, mpsc},
+    thread,
+    time::Duration,
+};
+
+use alacritty_terminal::{
+    Term,
+    event::{EventListener, WindowSize},
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    term::{TermMode, cell::Hyperlink},
+};
+use anyhow::{Context as _, Result, anyhow, bail};
+use base64::{
+    Engine as _, alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+use collections::{HashMap, HashSet};
+use flate2::read::ZlibDecoder;
+use gpui::RenderImage;
+use image::{Frame, ImageFormat, ImageReader, Limits, Rgba, RgbaImage};
+use util::ResultExt as _;
+
+use crate::scanned_pty::{BEL, CAN, ESC, SUB};
+
+const IMAGE_URI_PREFIX: &str = "zed-image:";
+
+/// How many bytes of decoded images each terminal may keep, the same as Kitty's default quota.
+const MAX_IMAGE_MEMORY: usize = 320 * 1024 * 1024;
+
+/// Image sequences longer than this are dropped rather than buffered.
+const MAX_PAYLOAD_LEN: usize = 128 * 1024 * 1024;
+
+/// The widest and tallest image, in pixels, that will be decoded.
+const MAX_IMAGE_DIMENSION: u32 = 10_000;
+
+/// The most lines an image may take up, which bounds how many blank lines are printed for it.
+const MAX_IMAGE_LINES: usize = 1_000;
+
+/// An upper bound on how many bytes are printed for each line of an image besides its blank
+/// cells: the line break and the hyperlink around the cells, plus the command mark that shell
+/// integration may reopen after the link.
+const PLACEHOLDER_LINE_OVERHEAD: usize = 128;
+
+/// How long to wait for an image file to be read before giving up on it.
+const FILE_READ_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Image files are never read from these directories, whose files may block or never end.
+const SPECIAL_FILE_DIRS: &[&str] = &["/dev", "/proc", "/sys"];
+
+/// How many images may be placed before looking for ones whose cells have gone.
+const MIN_COLLECTION_THRESHOLD: usize = 16;
+
+/// Padding is optional in the base64 payloads of both Kitty's and iTerm2's protocols.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// The default Sixel palette, that of the VT340, in percentages of each RGB component.
+const SIXEL_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [20, 20, 80],
+    [80, 13, 13],
+    [20, 80, 20],
+    [80, 20, 80],
+    [20, 80, 80],
+    [80, 80, 20],
+    [53, 53, 53],
+    [26, 26, 26],
+    [33, 33, 60],
+    [60, 26, 26],
+    [33, 60, 33],
+    [60, 33, 60],
+    [33, 60, 60],
+    [60, 60, 33],
+    [80, 80, 80],
+];
+
+/// The link on each cell of an image, naming the image and which of its lines the cell is on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ImageLink {
+    id: u64,
+    line: usize,
+}
+
+impl ImageLink {
+    fn uri(&self) -> String {
+        format!("{IMAGE_URI_PREFIX}{}:{}", self.id, self.line)
+    }
+
+    fn parse(uri: &str) -> Option<Self> {
+        let (id, line) = uri.strip_prefix(IMAGE_URI_PREFIX)?.split_once(':')?;
+        Some(Self {
+            id: id.parse().ok()?,
+            line: line.parse().ok()?,
+        })
+    }
+}
+
+/// Whether a hyperlink anchors an inline image rather than having been printed by a program.
+pub(crate) fn is_image_link(hyperlink: &Hyperlink) -> bool {
+    hyperlink.uri().starts_with(IMAGE_URI_PREFIX)
+}
+
+/// The image links on the cells of `line`, with the column each of them starts at.
+fn line_image_links<T: EventListener>(
+    term: &Term<T>,
+    line: Line,
+) -> impl Iterator<Item = (Column, ImageLink)> + '_ {
+    let row = &term.grid()[line];
+    let mut previous = None;
+    (0..term.columns()).map(Column).filter_map(move |column| {
+        let link = row[column]
+            .hyperlink()
+            .and_then(|hyperlink| ImageLink::parse(hyperlink.uri()));
+        let starts = link != previous;
+        previous = link;
+        Some((column, link.filter(|_| starts)?))
+    })
+}
+
+/// An image shown in the terminal's viewport.
+#[derive(Clone, Debug)]
+pub struct ImagePlacement {
+    /// The grid cell at the image's top left corner, which may be above the viewport.
+    pub point: AlacPoint,
+    /// The width of the image, in cells.
+    pub width: f32,
+    /// The height of the image, in lines.
+    pub height: f32,
+    pub image: Arc<RenderImage>,
+}
+
+/// How much of the grid an image takes up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ImageSize {
+    /// The number of cells printed for each line of the image.
+    columns: usize,
+    lines: usize,
+    /// The size the image is drawn at, in cells, which may leave part of the last ones empty.
+    width: f32,
+    height: f32,
+}
+
+struct Placement {
+    image: Arc<RenderImage>,
+    kitty_image_id: Option<u32>,
+    size: ImageSize,
+}
+
+struct KittyImage {
+    image: Arc<RenderImage>,
+    /// When the image was transmitted, in the same sequence as placement ids.
+    age: u64,
+}
+
+#[derive(Default)]
+struct ImageStore {
+    placements: BTreeMap<u64, Placement>,
+    /// Images transmitted with the Kitty graphics protocol, by the id the program gave them.
+    kitty_images: HashMap<u32, KittyImage>,
+    next_id: u64,
+    /// Images that have been removed, which the terminal removes from the sprite atlas.
+    evicted: Vec<Arc<RenderImage>>,
+    /// How many placements were left the last time the ones whose cells had gone were dropped.
+    collected_len: usize,
+}
+
+impl ImageStore {
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn memory_use(&self) -> usize {
+        let mut seen = HashSet::default();
+        self.placements
+            .values()
+            .map(|placement| &placement.image)
+            .chain(
+                self.kitty_images
+                    .values()
+                    .map(|kitty_image| &kitty_image.image),
+            )
+            .filter(|image| seen.insert(Arc::as_ptr(image)))
+            .map(|image| image_bytes(image))
+            .sum()
+    }
+
+    /// Evicts the oldest images until `bytes` more fit.
+    fn make_room(&mut self, bytes: usize) {
+        while self.memory_use() + bytes > MAX_IMAGE_MEMORY {
+            let oldest_placement = self.placements.first_key_value().map(|(&id, _)| id);
+            let oldest_kitty_image = self
+                .kitty_images
+                .iter()
+                .min_by_key(|(_, kitty_image)| kitty_image.age)
+                .map(|(&id, kitty_image)| (id, kitty_image.age));
+            let image = match (oldest_placement, oldest_kitty_image) {
+                (Some(placement_id), Some((_, age))) if placement_id < age => {
+                    self.placements.remove(&placement_id).map(|p| p.image)
+                }
+                (Some(placement_id), None) => {
+                    self.placements.remove(&placement_id).map(|p| p.image)
+                }
+                (_, Some((kitty_image_id, _))) => self
+                    .kitty_images
+                    .remove(&kitty_image_id)
+                    .map(|kitty_image| kitty_image.image),
+                (None, None) => break,
+            };
+            self.evicted.extend(image);
+        }
+    }
+}
+
+/// The images shown in a terminal, shared between the pty reader thread that decodes them and the
+/// terminal that draws them.
+#[derive(Clone, Default)]
+pub(crate) struct TerminalImages(Arc<Mutex<ImageStore>>);
+
+impl TerminalImages {
+    fn insert_kitty_image(&self, kitty_image_id: u32, image: Arc<RenderImage>) {
+        let mut store = self.0.lock().unwrap();
+        store.make_room(image_bytes(&image));
+        let age = store.next_id();
+        if let Some(replaced) = store
+            .kitty_images
+            .insert(kitty_image_id, KittyImage { image, age })
+        {
+            store.evicted.push(replaced.image);
+        }
+    }
+
+    fn kitty_image(&self, kitty_image_id: u32) -> Option<Arc<RenderImage>> {
+        let store = self.0.lock().unwrap();
+        Some(store.kitty_images.get(&kitty_image_id)?.image.clone())
+    }
+
+    /// Deletes the placements of the Kitty image with the given id, or of every image, and with
+    /// `free_data`, the transmitted images themselves.
+    fn delete_kitty_images(&self, kitty_image_id: Option<u32>, free_data: bool) {
+        let mut store = self.0.lock().unwrap();
+        let ImageStore {
+            placements,
+            kitty_images,
+            evicted,
+            ..
+        } = &mut *store;
+        placements.retain(|_, placement| {
+            let retain = kitty_image_id.is_some_and(|id| placement.kitty_image_id != Some(id));
+            if !retain {
+                evicted.push(placement.image.clone());
+            }
+            retain
+        });
+        if free_data {
+            kitty_images.retain(|&id, kitty_image| {
+                let retain = kitty_image_id.is_some_and(|kitty_image_id| id != kitty_image_id);
+                if !retain {
+                    evicted.push(kitty_image.image.clone());
+                }
+                retain
+            });
+        }
+    }
+
+    fn place(&self, image: Arc<RenderImage>, kitty_image_id: Option<u32>, size: ImageSize) -> u64 {
+        let mut store = self.0.lock().unwrap();
+        store.make_room(image_bytes(&image));
+        let id = store.next_id();
+        store.placements.insert(
+            id,
+            Placement {
+                image,
+                kitty_image_id,
+                size,
+            },
+        );
+        id
+    }
+
+    /// Drops the images whose cells have been overwritten or have left the scrollback, once
+    /// enough images have been placed since this last looked for them.
+    pub fn collect<T: EventListener>(&self, term: &Term<T>) {
+        let mut store = self.0.lock().unwrap();
+        // The primary screen's cells can't be reached while the alternate screen is shown.
+        if store.placements.len() < (store.collected_len * 2).max(MIN_COLLECTION_THRESHOLD)
+            || term.mode().contains(TermMode::ALT_SCREEN)
+        {
+            return;
+        }
+        let referenced = (term.topmost_line().0..=term.bottommost_line().0)
+            .flat_map(|line| line_image_links(term, Line(line)).map(|(_, link)| link.id))
+            .collect::<HashSet<_>>();
+        let ImageStore {
+            placements,
+            evicted,
+            ..
+        } = &mut *store;
+        placements.retain(|id, placement| {
+            let retain = referenced.contains(id);
+            if !retain {
+                evicted.push(placement.image.clone());
+            }
+            retain
+        });
+        store.collected_len = store.placements.len();
+    }
+
+    /// Takes the images that have been removed since this was last called.
+    pub fn take_evicted(&self) -> Vec<Arc<RenderImage>> {
+        std::mem::take(&mut self.0.lock().unwrap().evicted)
+    }
+
+    pub fn visible_placements<T: EventListener>(&self, term: &Term<T>) -> Vec<ImagePlacement> {
+        let store = self.0.lock().unwrap();
+        if store.placements.is_empty() {
+            return Vec::new();
+        }
+        let top = -(term.grid().display_offset() as i32);
+        let bottom = top + term.screen_lines() as i32 - 1;
+        let mut seen = HashSet::default();
+        let mut visible = Vec::new();
+        for line in (top..=bottom).map(Line) {
+            for (column, link) in line_image_links(term, line) {
+                if !seen.insert(link.id) {
+                    continue;
+                }
+                let Some(placement) = store.placements.get(&link.id) else {
+                    continue;
+                };
+                // Only an image's first line starts at its column; the rest start at the left
+                // edge, so find the first line if it's still on the grid.
+                let top_line = line - link.line as i32;
+                let column = if link.line == 0 {
+                    column
+                } else if top_line >= term.topmost_line() {
+                    line_image_links(term, top_line)
+                        .find(|(_, top_link)| top_link.id == link.id)
+                        .map_or(Column(0), |(column, _)| column)
+                } else {
+                    Column(0)
+                };
+                visible.push(ImagePlacement {
+                    point: AlacPoint::new(top_line, column),
+                    width: placement.size.width,
+                    height: placement.size.height,
+                    image: placement.image.clone(),
+                });
+            }
+        }
+        visible
+    }
+}
+
+fn image_bytes(image: &RenderImage) -> usize {
+    let size = image.size(0);
+    size.width.0 as usize * size.height.0 as usize * 4
+}
+
+fn render_image(mut image: RgbaImage) -> Arc<RenderImage> {
+    // Convert from RGBA to BGRA.
+    for pixel in image.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    Arc::new(RenderImage::new(vec![Frame::new(image)]))
+}
+
+/// Decodes an image file's contents, refusing to allocate more than the terminal would keep of
+/// it, whatever its header claims.
+fn decode_image(data: &[u8], format: Option<ImageFormat>) -> Result<RgbaImage> {
+    let mut reader = ImageReader::new(Cursor::new(data));
+    match format {
+        Some(format) => reader.set_format(format),
+        None => reader = reader.with_guessed_format()?,
+    }
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    limits.max_alloc = Some(MAX_IMAGE_MEMORY as u64);
+    reader.limits(limits);
+
+    let image = reader.decode()?;
+    // Converting the image to RGBA may take more memory than decoding it did.
+    check_dimensions(image.width(), image.height())?;
+    Ok(image.into_rgba8())
+}
+
+fn check_dimensions(width: u32, height: u32) -> Result<()> {
+    if width == 0 || height == 0 {
+        bail!("EINVAL:image is empty");
+    }
+    if width > MAX_IMAGE_DIMENSION
+        || height > MAX_IMAGE_DIMENSION
+        || width as usize * height as usize * 4 > MAX_IMAGE_MEMORY
+    {
+        bail!("EFBIG:image of {width}x{height} pixels is too large");
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SequenceKind {
+    /// An application program command, which Kitty's graphics protocol uses.
+    Apc,
+    /// A device control string, which Sixel images are.
+    Dcs,
+    /// An operating system command, which iTerm2's inline images are.
+    Osc,
+}
+
+impl SequenceKind {
+    fn introducer(self) -> u8 {
+        match self {
+            Self::Apc => b'_',
+            Self::Dcs => b'P',
+            Self::Osc => b']',
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    /// After an `ESC` that may start an image sequence.
+    Escape,
+    /// Inside a sequence that may be an image, which is being buffered.
+    Sequence(SequenceKind),
+    /// After an `ESC` inside a buffered sequence, which is expected to be `ESC \`.
+    SequenceEscape(SequenceKind),
+    /// Inside a sequence that isn't an image, which is being passed through.
+    Passthrough,
+    /// Inside an image sequence too long to buffer, which is being dropped.
+    Discard,
+}
+
+/// A command in the Kitty graphics protocol, from the keys in its control data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct KittyCommand {
+    action: u8,
+    format: u32,
+    medium: u8,
+    compressed: bool,
+    width: u32,
+    height: u32,
+    image_id: u32,
+    more: bool,
+    columns: Option<usize>,
+    lines: Option<usize>,
+    quiet: u32,
+    delete: u8,
+}
+
+impl KittyCommand {
+    fn parse(control: &[u8]) -> Self {
+        let mut command = Self {
+            action: b't',
+            format: 32,
+            medium: b'd',
+            compressed: false,
+            width: 0,
+            height: 0,
+            image_id: 0,
+            more: false,
+            columns: None,
+            lines: None,
+            quiet: 0,
+            delete: b'a',
+        };
+        for pair in control.split(|&byte| byte == b',') {
+            let Some((&key, value)) = pair.split_first() else {
+                continue;
+            };
+            let Some(value) = value.strip_prefix(b"=") else {
+                continue;
+            };
+            let number = std::str::from_utf8(value)
+                .ok()
+                .and_then(|value| value.parse::<u32>().ok());
+            let character = value.first().copied();
+            match key {
+                b'a' => command.action = character.unwrap_or(command.action),
+                b'f' => command.format = number.unwrap_or(command.format),
+                b't' => command.medium = character.unwrap_or(command.medium),
+                b'o' => command.compressed = character == Some(b'z'),
+                b's' => command.width = number.unwrap_or_default(),
+                b'v' => command.height = number.unwrap_or_default(),
+                b'i' => command.image_id = number.unwrap_or_default(),
+                b'm' => command.more = number == Some(1),
+                b'c' => command.columns = number.filter(|&n| n > 0).map(|n| n as usize),
+                b'r' => command.lines = number.filter(|&n| n > 0).map(|n| n as usize),
+                b'q' => command.quiet = number.unwrap_or_default(),
+                b'd' => command.delete = character.unwrap_or(command.delete),
+                _ => {}
+            }
+        }
+        command
+    }
+}
+
+/// An image being sent with the Kitty graphics protocol in several chunks.
+struct KittyTransmission {
+    command: KittyCommand,
+    payload: Vec<u8>,
+}
+
+/// Takes inline images out of the output of a pty, replacing each with the blank cells it's
+/// drawn over.
+///
+/// Like Kitty, this leaves the cursor just after the last cell of the image, except for Sixel
+/// images, after which it moves to the start of the next line, as it does in xterm.
+pub(crate) struct ImageScanner {
+    state: ScanState,
+    sequence: Vec<u8>,
+    kitty_transmission: Option<KittyTransmission>,
+    window_size: WindowSize,
+    images: TerminalImages,
+    /// Whether Kitty images may be read from files, which only makes sense when the program
+    /// runs on this machine.
+    allow_files: bool,
+    /// How many more bytes may be printed for the cells of images in the current scan.
+    placeholder_budget: usize,
+    /// Replies to Kitty graphics commands, to be written back to the pty.
+    responses: Vec<u8>,
+}
+
+impl ImageScanner {
+    pub fn new(images: TerminalImages, window_size: WindowSize, allow_files: bool) -> Self {
+        Self {
+            state: ScanState::Ground,
+            sequence: Vec::new(),
+            kitty_transmission: None,
+            window_size,
+            images,
+            allow_files,
+            placeholder_budget: 0,
+            responses: Vec::new(),
+        }
+    }
+
+    pub fn resize(&mut self, window_size: WindowSize) {
+        self.window_size = window_size;
+    }
+
+    pub fn take_responses(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.responses)
+    }
+
+    /// Scans `input`, printing at most `max_placeholder_len` bytes for the cells of the images
+    /// in it. Images are scaled down to fit, and dropped if they can't.
+    pub fn scan(&mut self, input: &[u8], output: &mut Vec<u8>, max_placeholder_len: usize) {
+        self.placeholder_budget = max_placeholder_len;
+        for &byte in input {
+            self.scan_byte(byte, output);
+        }
+    }
+
+    fn scan_byte(&mut self, byte: u8, output: &mut Vec<u8>) {
+        match self.state {
+            ScanState::Ground => {
+                if byte == ESC {
+                    self.state = ScanState::Escape;
+                } else {
+                    output.push(byte);
+                }
+            }
+            ScanState::Escape => {
+                let kind = match byte {
+                    b'_' => Some(SequenceKind::Apc),
+                    b'P' => Some(SequenceKind::Dcs),
+                    b']' => Some(SequenceKind::Osc),
+                    _ => None,
+                };
+                if let Some(kind) = kind {
+                    self.sequence.clear();
+                    self.state = ScanState::Sequence(kind);
+                } else {
+                    output.push(ESC);
+                    self.state = ScanState::Ground;
+                    self.scan_byte(byte, output);
+                }
+            }
+            ScanState::Sequence(kind) => match byte {
+                ESC => self.state = ScanState::SequenceEscape(kind),
+                BEL if kind == SequenceKind::Osc => {
+                    self.finish_sequence(kind, output);
+                    self.state = ScanState::Ground;
+                }
+                CAN | SUB => {
+                    self.sequence.clear();
+                    output.push(byte);
+                    self.state = ScanState::Ground;
+                }
+                _ => {
+                    self.sequence.push(byte);
+                    if !may_be_image(kind, &self.sequence) {
+                        output.extend([ESC, kind.introducer()]);
+                        output.append(&mut self.sequence);
+                        self.state = ScanState::Passthrough;
+                    } else if self.sequence.len() > MAX_PAYLOAD_LEN {
+                        self.sequence = Vec::new();
+                        self.state = ScanState::Discard;
+                    }
+                }
+            },
+            ScanState::SequenceEscape(kind) => {
+                self.finish_sequence(kind, output);
+                if byte == b'\\' {
+                    self.state = ScanState::Ground;
+                } else {
+                    // Any escape terminates a sequence and starts a new one.
+                    self.state = ScanState::Escape;
+                    self.scan_byte(byte, output);
+                }
+            }
+            ScanState::Passthrough => match byte {
+                ESC => self.state = ScanState::Escape,
+                BEL | CAN | SUB => {
+                    output.push(byte);
+                    self.state = ScanState::Ground;
+                }
+                _ => output.push(byte),
+            },
+            ScanState::Discard => match byte {
+                ESC => self.state = ScanState::Escape,
+                BEL | CAN | SUB => self.state = ScanState::Ground,
+                _ => {}
+            },
+        }
+    }
+
+    fn finish_sequence(&mut self, kind: SequenceKind, output: &mut Vec<u8>) {
+        let sequence = std::mem::take(&mut self.sequence);
+        match kind {
+            SequenceKind::Apc => {
+                if let Some(body) = sequence.strip_prefix(b"G") {
+                    self.handle_kitty(body, output);
+                    return;
+                }
+            }
+            SequenceKind::Dcs => {
+                if let Some(start) = sequence.iter().position(|&byte| byte == b'q') {
+                    decode_sixel(&sequence[start + 1..])
+                        .and_then(|image| {
+                            let size = self.image_size(&image, None, None, false);
+                            self.place(render_image(image), None, size, output)?;
+                            output.extend_from_slice(b"\r\n");
+                            Ok(())
+                        })
+                        .log_err();
+                    return;
+                }
+            }
+            SequenceKind::Osc => {
+                if let Some(file) = sequence.strip_prefix(b"1337;File=") {
+                    self.handle_iterm_image(file, output).log_err();
+                    return;
+                }
+            }
+        }
+        // A sequence that ended before it could be told apart from an image.
+        output.extend([ESC, kind.introducer()]);
+        output.extend(sequence);
+        output.extend([ESC, b'\\']);
+    }
+
+    fn handle_kitty(&mut self, body: &[u8], output: &mut Vec<u8>) {
+        let (control, payload) = match body.iter().position(|&byte| byte == b';') {
+            Some(semicolon) => (&body[..semicolon], &body[semicolon + 1..]),
+            None => (body, &[][..]),
+        };
+        let command = KittyCommand::parse(control);
+        // Only the first chunk of an image has the keys that describe it.
+        let (command, payload) = match self.kitty_transmission.take() {
+            Some(mut transmission) => {
+                transmission.payload.extend_from_slice(payload);
+                if transmission.payload.len() > MAX_PAYLOAD_LEN {
+                    return;
+                }
+                if command.more {
+                    self.kitty_transmission = Some(transmission);
+                    return;
+                }
+                (transmission.command, transmission.payload)
+            }
+            None if command.more => {
+                self.kitty_transmission = Some(KittyTransmission {
+                    command,
+                    payload: payload.to_vec(),
+                });
+                return;
+            }
+            None => (command, payload.to_vec()),
+        };
+
+        let result = match command.action {
+            b'q' => decode_kitty_image(&command, &payload, self.allow_files).map(drop),
+            b't' | b'T' => {
+                decode_kitty_image(&command, &payload, self.allow_files).and_then(|image| {
+                    let size = self.kitty_image_size(&command, &image);
+                    let image = render_image(image);
+                    if command.image_id != 0 {
+                        self.images
+                            .insert_kitty_image(command.image_id, image.clone());
+                    }
+                    if command.action == b'T' {
+                        let kitty_image_id = Some(command.image_id).filter(|&id| id != 0);
+                        self.place(image, kitty_image_id, size, output)?;
+                    }
+                    Ok(())
+                })
+            }
+            b'p' => self
+                .images
+                .kitty_image(command.image_id)
+                .ok_or_else(|| anyhow!("ENOENT:no image with id {}", command.image_id))
+                .and_then(|image| {
+                    let size = self.image_size_in_pixels(
+                        image.size(0).width.0 as u32,
+                        image.size(0).height.0 as u32,
+                        command.columns.map(|columns| columns as f32),
+                        command.lines.map(|lines| lines as f32),
+                        command.columns.is_some() && command.lines.is_some(),
+                    );
+                    self.place(image, Some(command.image_id), size, output)
+                }),
+            b'd' => {
+                let free_data = command.delete.is_ascii_uppercase();
+                match command.delete.to_ascii_lowercase() {
+                    b'a' => self.images.delete_kitty_images(None, free_data),
+                    b'i' => self
+                        .images
+                        .delete_kitty_images(Some(command.image_id), free_data),
+                    _ => {}
+                }
+                return;
+            }
+            action => Err(anyhow!("EINVAL:unsupported action {}", action as char)),
+        };
+
+        // Programs give images ids to be told whether they were shown, and can ask to only hear
+        // about errors (`q=1`) or nothing at all (`q=2`).
+        if command.image_id != 0 {
+            let message = match result {
+                Ok(()) if command.quiet == 0 => "OK".to_string(),
+                Err(error) if command.quiet < 2 => error.to_string().replace(ESC as char, ""),
+                _ => return,
+            };
+            self.responses.extend_from_slice(
+                format!("\x1b_Gi={};{message}\x1b\\", command.image_id).as_bytes(),
+            );
+        }
+    }
+
+    fn handle_iterm_image(&mut self, file: &[u8], output: &mut Vec<u8>) -> Result<()> {
+        let colon = file
+            .iter()
+            .position(|&byte| byte == b':')
+            .context("inline image has no contents")?;
+        let arguments = std::str::from_utf8(&file[..colon])?;
+        let arguments = arguments
+            .split(';')
+            .filter_map(|argument| argument.split_once('='))
+            .collect::<HashMap<_, _>>();
+        // Files that aren't inline are downloads, which aren't supported.
+        if arguments.get("inline") != Some(&"1") {
+            return Ok(());
+        }
+        let data = BASE64.decode(&file[colon + 1..])?;
+        let image = decode_image(&data, None)?;
+
+        let cell_width = f32::from(self.window_size.cell_width.max(1));
+        let cell_height = f32::from(self.window_size.cell_height.max(1));
+        let columns = arguments
+            .get("width")
+            .and_then(|width| iterm_dimension(width, cell_width, self.window_size.num_cols));
+        let lines = arguments
+            .get("height")
+            .and_then(|height| iterm_dimension(height, cell_height, self.window_size.num_lines));
+        let stretch = arguments.get("preserveAspectRatio") == Some(&"0");
+        let size = self.image_size(&image, columns, lines, stretch);
+        self.place(render_image(image), None, size, output)
+    }
+
+    fn kitty_image_size(&self, command: &KittyCommand, image: &RgbaImage) -> ImageSize {
+        // Kitty stretches images to fill the cells they're given when given both dimensions.
+        let stretch = command.columns.is_some() && command.lines.is_some();
+        self.image_size(
+            image,
+            command.columns.map(|columns| columns as f32),
+            command.lines.map(|lines| lines as f32),
+            stretch,
+        )
+    }
+
+    fn image_size(
+        &self,
+        image: &RgbaImage,
+        columns: Option<f32>,
+        lines: Option<f32>,
+        stretch: bool,
+    ) -> ImageSize {
+        self.image_size_in_pixels(image.width(), image.height(), columns, lines, stretch)
+    }
+
+    /// Works out how many cells an image takes up, scaling it to fit in `columns` and `lines` if
+    /// either is given, keeping its aspect ratio unless `stretch` is set.
+    fn image_size_in_pixels(
+        &self,
+        pixel_width: u32,
+        pixel_height: u32,
+        columns: Option<f32>,
+        lines: Option<f32>,
+        stretch: bool,
+    ) -> ImageSize {
+        let natural_width = pixel_width as f32 / f32::from(self.window_size.cell_width.max(1));
+        let natural_height = pixel_height as f32 / f32::from(self.window_size.cell_height.max(1));
+        let (width, height) = match (columns, lines) {
+            (Some(columns), Some(lines)) if stretch => (columns, lines),
+            (Some(columns), Some(lines)) => {
+                let scale = (columns / natural_width).min(lines / natural_height);
+                (natural_width * scale, natural_height * scale)
+            }
+            (Some(columns), None) => (columns, natural_height * columns / natural_width),
+            (None, Some(lines)) => (natural_width * lines / natural_height, lines),
+            (None, None) => (natural_width, natural_height),
+        };
+        let (reserved_width, reserved_height) = match (columns, lines) {
+            (Some(columns), Some(lines)) => (columns, lines),
+            _ => (width, height),
+        };
+
+        // Images wider than the terminal are scaled down rather than wrapped onto the next line,
+        // and so are images taller than the lines that may still be printed in this scan.
+        let max_columns = self.window_size.num_cols.max(1) as usize;
+        let max_lines = (self.placeholder_budget / (max_columns + PLACEHOLDER_LINE_OVERHEAD))
+            .clamp(1, MAX_IMAGE_LINES);
+        let scale = (max_columns as f32 / reserved_width)
+            .min(max_lines as f32 / reserved_height)
+            .min(1.);
+        ImageSize {
+            columns: ((reserved_width * scale).ceil() as usize).clamp(1, max_columns),
+            lines: ((reserved_height * scale).ceil() as usize).clamp(1, max_lines),
+            width: width * scale,
+            height: height * scale,
+        }
+    }
+
+    /// Prints the blank cells an image is drawn over, each line of them linked to the image.
+    fn place(
+        &mut self,
+        image: Arc<RenderImage>,
+        kitty_image_id: Option<u32>,
+        size: ImageSize,
+        output: &mut Vec<u8>,
+    ) -> Result<()> {
+        let len = size.lines * (size.columns + PLACEHOLDER_LINE_OVERHEAD);
+        if len > self.placeholder_budget {
+            bail!("ENOSPC:too many images at once");
+        }
+        self.placeholder_budget -= len;
+        let id = self.images.place(image, kitty_image_id, size);
+        for line in 0..size.lines {
+            if line > 0 {
+                output.extend_from_slice(b"\r\n");
+            }
+            write_osc(
+                output,
+                format!("8;;{}", ImageLink { id, line }.uri()).as_bytes(),
+            );
+            output.resize(output.len() + size.columns, b' ');
+            write_osc(output, b"8;;");
+        }
+        Ok(())
+    }
+}
+
+/// Whether the start of a sequence may be that of an image, and should keep being buffered.
+fn may_be_image(kind: SequenceKind, sequence: &[u8]) -> bool {
+    match kind {
+        SequenceKind::Apc => sequence.starts_with(b"G"),
+        SequenceKind::Dcs => match sequence.iter().position(|&byte| byte == b'q') {
+            Some(start) => sequence[..start]
+                .iter()
+                .all(|&byte| byte.is_ascii_digit() || byte == b';'),
+            None => sequence
+                .iter()
+                .all(|&byte| byte.is_ascii_digit() || byte == b';'),
+        },
+        SequenceKind::Osc => {
+            let prefix = b"1337;File=".as_slice();
+            prefix.starts_with(sequence) || sequence.starts_with(prefix)
+        }
+    }
+}
+
+fn write_osc(output: &mut Vec<u8>, sequence: &[u8]) {
+    output.extend([ESC, b']']);
+    output.extend_from_slice(sequence);
+    output.extend([ESC, b'\\']);
+}
+
+fn decode_kitty_image(
+    command: &KittyCommand,
+    payload: &[u8],
+    allow_files: bool,
+) -> Result<RgbaImage> {
+    let data = match command.medium {
+        b'd' => BASE64.decode(payload).context("EINVAL:invalid base64")?,
+        b'f' | b't' if allow_files => {
+            let path = PathBuf::from(String::from_utf8(BASE64.decode(payload)?)?);
+            read_image_file(path, command.medium == b't')?
+        }
+        medium => bail!("EINVAL:unsupported transmission medium {}", medium as char),
+    };
+    let data = if command.compressed {
+        let mut decompressed = Vec::new();
+        ZlibDecoder::new(data.as_slice())
+            .take(MAX_IMAGE_MEMORY as u64)
+            .read_to_end(&mut decompressed)
+            .context("EINVAL:invalid zlib data")?;
+        decompressed
+    } else {
+        data
+    };
+
+    let image = match command.format {
+        100 => decode_image(&data, Some(ImageFormat::Png)).context("EBADPNG:invalid PNG")?,
+        24 | 32 => {
+            check_dimensions(command.width, command.height)?;
+            let channels = if command.format == 24 { 3 } else { 4 };
+            let len = command.width as usize * command.height as usize * channels;
+            if data.len() < len {
+                bail!(
+                    "ENODATA:expected {len} bytes of pixel data, got {}",
+                    data.len()
+                );
+            }
+            let pixels = if command.format == 24 {
+                data[..len]
+                    .chunks_exact(3)
+                    .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 0xff])
+                    .collect()
+            } else {
+                data[..len].to_vec()
+            };
+            RgbaImage::from_raw(command.width, command.height, pixels)
+                .context("EINVAL:invalid pixel data")?
+        }
+        format => bail!("EINVAL:unsupported format {format}"),
+    };
+    check_dimensions(image.width(), image.height())?;
+    Ok(image)
+}
+
+/// Reads an image file on another thread, so that a file that's slow to read can't hold up the
+/// terminal's output for longer than [`FILE_READ_TIMEOUT`].
+fn read_image_file(path: PathBuf, is_temporary: bool) -> Result<Vec<u8>> {
+    let (tx, rx) = mpsc::sync_channel(1);
+    thread::Builder::new()
+        .name("terminal image file".to_string())
+        .spawn({
+            let path = path.clone();
+            move || tx.send(read_regular_file(&path, is_temporary)).ok()
+        })
+        .context("EIO:spawning a thread")?;
+    rx.recv_timeout(FILE_READ_TIMEOUT)
+        .map_err(|_| anyhow!("EIO:timed out reading {path:?}"))?
+}
+
+fn read_regular_file(path: &Path, is_temporary: bool) -> Result<Vec<u8>> {
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("EBADF:resolving {path:?}"))?;
+    if SPECIAL_FILE_DIRS.iter().any(|dir| path.starts_with(dir)) {
+        bail!("EPERM:{path:?} is a special file");
+    }
+    // Check the file's type before opening it, as opening a FIFO blocks.
+    if !fs::metadata(&path)?.is_file() {
+        bail!("EBADF:{path:?} isn't a regular file");
+    }
+    let mut options = fs::OpenOptions::new();
+    options.read(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::custom_flags(&mut options, libc::O_NONBLOCK);
+    let file = options
+        .open(&path)
+        .with_context(|| format!("EBADF:opening {path:?}"))?;
+    // The file may have been replaced since its type was checked.
+    if !file.metadata()?.is_file() {
+        bail!("EBADF:{path:?} isn't a regular file");
+    }
+    let mut data = Vec::new();
+    file.take(MAX_PAYLOAD_LEN as u64).read_to_end(&mut data)?;
+
+    // Like Kitty, only delete temporary files that are clearly meant for this protocol.
+    let temp_dir = std::env::temp_dir();
+    let temp_dir = temp_dir.canonicalize().unwrap_or(temp_dir);
+    if is_temporary
+        && path.starts_with(temp_dir)
+        && path.to_string_lossy().contains("tty-graphics-protocol")
+    {
+        fs::remove_file(path).log_err();
+    }
+    Ok(data)
+}
+
+/// Parses an iTerm2 image dimension: a number of cells, pixels (`px`), or a percentage of the
+/// terminal (`%`), in cells. `auto` and anything else leave the dimension to the image.
+fn iterm_dimension(dimension: &str, cell_size: f32, cells: u16) -> Option<f32> {
+    let cells_in_dimension = if let Some(pixels) = dimension.strip_suffix("px") {
+        pixels.parse::<f32>().ok()? / cell_size
+    } else if let Some(percent) = dimension.strip_suffix('%') {
+        percent.parse::<f32>().ok()? * f32::from(cells) / 100.
+    } else {
+        dimension.parse::<f32>().ok()?
+    };
+    (cells_in_dimension > 0.).then_some(cells_in_dimension)
+}
+
+/// Reads the numeric parameters that follow a Sixel control character, like `#1;2;100;0;0`.
+fn sixel_parameters(data: &mut std::iter::Peekable<impl Iterator<Item = u8>>) -> Vec<usize> {
+    let mut parameters = vec![0usize];
+    while let Some(&byte) = data.peek() {
+        match byte {
+            b'0'..=b'9' => {
+                let parameter = parameters.last_mut().unwrap();
+                *parameter = parameter
+                    .saturating_mul(10)
+                    .saturating_add((byte - b'0') as usize);
+            }
+            b';' => parameters.push(0),
+            _ => break,
+        }
+        data.next();
+    }
+    parameters
+}
+
+fn sixel_color(space: usize, x: usize, y: usize, z: usize) -> Option<Rgba<u8>> {
+    let percent = |value: usize| (value.min(100) * 255 / 100) as u8;
+    match space {
+        // Hue, lightness and saturation, where a hue of 0 is blue rather than red.
+        1 => {
+            let hue = ((x + 240) % 360) as f32 / 60.;
+            let lightness = y.min(100) as f32 / 100.;
+            let saturation = z.min(100) as f32 / 100.;
+            let chroma = (1. - (2. * lightness - 1.).abs()) * saturation;
+            let second = chroma * (1. - (hue % 2. - 1.).abs());
+            let (r, g, b) = match hue as u32 {
+                0 => (chroma, second, 0.),
+                1 => (second, chroma, 0.),
+                2 => (0., chroma, second),
+                3 => (0., second, chroma),
+                4 => (second, 0., chroma),
+                _ => (chroma, 0., second),
+            };
+            let m = lightness - chroma / 2.;
+            let channel = |value: f32| ((value + m) * 255.).round() as u8;
+            Some(Rgba([channel(r), channel(g), channel(b), 0xff]))
+        }
+        2 => Some(Rgba([percent(x), percent(y), percent(z), 0xff])),
+        _ => None,
+    }
+}
+
+/// Decodes the data of a Sixel image, which follows the `q` that starts it.
+///
+/// Pixels the image leaves unset are transparent, so the terminal's background shows through
+/// them, and the pixel aspect ratio is always taken to be 1:1.
+fn decode_sixel(data: &[u8]) -> Result<RgbaImage> {
+    let mut palette = [Rgba([0, 0, 0, 0xff]); 256];
+    for (color, [r, g, b]) in palette.iter_mut().zip(SIXEL_PALETTE) {
+        *color = sixel_color(2, r as usize, g as usize, b as usize).unwrap();
+    }
+    let mut color = 0;
+    let mut rows: Vec<Vec<Rgba<u8>>> = Vec::new();
+    let (mut x, mut band) = (0, 0);
+    let (mut width, mut height) = (0, 0);
+
+    let mut data = data.iter().copied().peekable();
+    while let Some(byte) = data.next() {
+        let (sixel, count) = match byte {
+            b'"' => {
+                // Raster attributes: the pixel aspect ratio, then the image's size.
+                if let [_, _, raster_width, raster_height] = sixel_parameters(&mut data)[..] {
+                    width = width.max(raster_width);
+                    height = height.max(raster_height);
+                }
+                continue;
+            }
+            b'#' => {
+                match sixel_parameters(&mut data)[..] {
+                    [register] => color = register % palette.len(),
+                    [register, space, first, second, third] => {
+                        color = register % palette.len();
+                        if let Some(defined) = sixel_color(space, first, second, third) {
+                            palette[color] = defined;
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            b'!' => {
+                let count = sixel_parameters(&mut data)[0].max(1);
+                match data.next() {
+                    Some(sixel @ b'?'..=b'~') => (sixel, count),
+                    _ => continue,
+                }
+            }
+            b'$' => {
+                x = 0;
+                continue;
+            }
+            b'-' => {
+                x = 0;
+                band += 1;
+                continue;
+            }
+            b'?'..=b'~' => (byte, 1),
+            _ => continue,
+        };
+
+        if x + count > MAX_IMAGE_DIMENSION as usize || (band + 1) * 6 > MAX_IMAGE_DIMENSION as usize
+        {
+            bail!("sixel image is too large");
+        }
+        let bits = sixel - b'?';
+        for bit in 0..6 {
+            if bits & (1 << bit) == 0 {
+                continue;
+            }
+            let y = band * 6 + bit;
+            if rows.len() <= y {
+                rows.resize_with(y + 1, Vec::new);
+            }
+            let row = &mut rows[y];
+            if row.len() < x + count {
+                row.resize(x + count, Rgba([0, 0, 0, 0]));
+            }
+            row[x..x + count].fill(palette[color]);
+            height = height.max(y + 1);
+        }
+        x += count;
+        width = width.max(x);
+    }
+
+    let (width, height) = (
+        width.min(MAX_IMAGE_DIMENSION as usize) as u32,
+        height.min(MAX_IMAGE_DIMENSION as usize) as u32,
+    );
+    check_dimensions(width, height)?;
+    Ok(RgbaImage::from_fn(width, height, |x, y| {
+        rows.get(y as usize)
+            .and_then(|row| row.get(x as usize))
+            .copied()
+            .unwrap_or(Rgba([0, 0, 0, 0]))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::{
+        event::VoidListener,
+        term::{Config, test::TermSize},
+        vte::ansi::Processor,
+    };
+
+    const WINDOW_SIZE: WindowSize = WindowSize {
+        num_lines: 24,
+        num_cols: 80,
+        cell_width: 10,
+        cell_height: 20,
+    };
+
+    fn scan(scanner: &mut ImageScanner, input: &[u8]) -> Vec<u8> {
+        let mut output = Vec::new();
+        scanner.scan(input, &mut output, usize::MAX);
+        output
+    }
+
+    fn placeholder(id: u64, lines: usize, columns: usize) -> String {
+        (0..lines)
+            .map(|line| {
+                format!(
+                    "\x1b]8;;zed-image:{id}:{line}\x1b\\{}\x1b]8;;\x1b\\",
+                    " ".repeat(columns)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\r\n")
+    }
+
+    fn kitty_rgba(control: &str, width: u32, height: u32) -> String {
+        let pixels = vec![0xff; (width * height * 4) as usize];
+        format!(
+            "\x1b_G{control},f=32,s={width},v={height};{}\x1b\\",
+            BASE64.encode(pixels)
+        )
+    }
+
+    #[test]
+    fn test_other_sequences_pass_through() {
+        let mut scanner = ImageScanner::new(TerminalImages::default(), WINDOW_SIZE, true);
+        let input = b"\x1b]0;title\x07a\x1bP+q544e\x1b\\\x1b[31mb\x1b]8;;https://zed.dev\x1b\\c\x1b_X\x1b\\";
+        assert_eq!(scan(&mut scanner, input), input);
+        assert!(scanner.take_responses().is_empty());
+    }
+
+    #[test]
+    fn test_kitty_images() {
+        let images = TerminalImages::default();
+        let mut scanner = ImageScanner::new(images.clone(), WINDOW_SIZE, true);
+
+        // A 20x40 pixel image takes up two cells on each of two lines, and is reported as shown.
+        let output = scan(&mut scanner, kitty_rgba("a=T,i=7", 20, 40).as_bytes());
+        assert_eq!(String::from_utf8(output).unwrap(), placeholder(1, 2, 2));
+        assert_eq!(scanner.take_responses(), b"\x1b_Gi=7;OK\x1b\\");
+
+        // Images can be sent in chunks, split anywhere across reads.
+        let pixels = BASE64.encode(vec![0xff; 30 * 20 * 4]);
+        let (first, second) = pixels.split_at(400);
+        let input =
+            format!("\x1b_Ga=T,f=32,s=30,v=20,c=6,m=1;{first}\x1b\\\x1b_Gm=0;{second}\x1b\\");
+        let mut output = Vec::new();
+        for byte in input.as_bytes() {
+            output.extend(scan(&mut scanner, &[*byte]));
+        }
+        assert_eq!(String::from_utf8(output).unwrap(), placeholder(2, 2, 6));
+        assert!(scanner.take_responses().is_empty());
+
+        // A stored image can be shown again, and queries don't show anything.
+        let output = scan(&mut scanner, b"\x1b_Ga=p,i=7,q=1\x1b\\");
+        assert_eq!(String::from_utf8(output).unwrap(), placeholder(3, 2, 2));
+        let output = scan(&mut scanner, kitty_rgba("a=q,i=31", 1, 1).as_bytes());
+        assert!(output.is_empty());
+        assert_eq!(scanner.take_responses(), b"\x1b_Gi=31;OK\x1b\\");
+        let output = scan(&mut scanner, b"\x1b_Ga=p,i=8\x1b\\");
+        assert!(output.is_empty());
+        assert!(scanner.take_responses().starts_with(b"\x1b_Gi=8;ENOENT:"));
+
+        // Deleting an image's data removes it and its placements.
+        scan(&mut scanner, b"\x1b_Ga=d,d=I,i=7\x1b\\");
+        assert!(images.kitty_image(7).is_none());
+        assert_eq!(images.take_evicted().len(), 3);
+    }
+
+    #[test]
+    fn test_kitty_image_files() {
+        let dir = std::env::temp_dir().join(format!("zed-terminal-images-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("tty-graphics-protocol-image");
+        let transmit = |medium: char, path: &Path| {
+            format!(
+                "\x1b_Ga=T,i=5,f=32,s=10,v=20,t={medium};{}\x1b\\",
+                BASE64.encode(path.to_str().unwrap())
+            )
+        };
+
+        // Files are read only if the program runs on this machine.
+        fs::write(&file, vec![0xff; 10 * 20 * 4]).unwrap();
+        let mut scanner = ImageScanner::new(TerminalImages::default(), WINDOW_SIZE, false);
+        assert!(scan(&mut scanner, transmit('f', &file).as_bytes()).is_empty());
+        assert!(scanner.take_responses().starts_with(b"\x1b_Gi=5;EINVAL:"));
+
+        let mut scanner = ImageScanner::new(TerminalImages::default(), WINDOW_SIZE, true);
+        let output = scan(&mut scanner, transmit('f', &file).as_bytes());
+        assert_eq!(String::from_utf8(output).unwrap(), placeholder(1, 1, 1));
+        assert_eq!(scanner.take_responses(), b"\x1b_Gi=5;OK\x1b\\");
+        assert!(file.exists());
+
+        // Temporary files are deleted once they're read.
+        let output = scan(&mut scanner, transmit('t', &file).as_bytes());
+        assert_eq!(String::from_utf8(output).unwrap(), placeholder(3, 1, 1));
+        assert!(!file.exists());
+
+        // Only regular files are read, and never special files.
+        assert!(scan(&mut scanner, transmit('f', &dir).as_bytes()).is_empty());
+        assert!(scanner.take_responses().starts_with(b"\x1b_Gi=5;EBADF:"));
+        #[cfg(unix)]
+        {
+            assert!(
+                scan(
+                    &mut scanner,
+                    transmit('f', Path::new("/dev/zero")).as_bytes()
+                )
+                .is_empty()
+            );
+            assert!(scanner.take_responses().starts_with(b"\x1b_Gi=5;EPERM:"));
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_placeholder_budget() {
+        let mut scanner = ImageScanner::new(TerminalImages::default(), WINDOW_SIZE, true);
+        let line_len = WINDOW_SIZE.num_cols as usize + PLACEHOLDER_LINE_OVERHEAD;
+
+        // Images taller than the lines that may be printed are scaled down to fit.
+        let mut output = Vec::new();
+        let input = kitty_rgba("a=T,i=4", 20, 400);
+        scanner.scan(input.as_bytes(), &mut output, 5 * line_len);
+        assert_eq!(String::from_utf8(output).unwrap(), placeholder(1, 5, 1));
+
+        // And dropped once not even a line fits.
+        let mut output = Vec::new();
+        scanner.scan(input.as_bytes(), &mut output, PLACEHOLDER_LINE_OVERHEAD);
+        assert!(output.is_empty());
+        assert!(
+            scanner
+                .take_responses()
+                .ends_with(b"\x1b_Gi=4;ENOSPC:too many images at once\x1b\\")
+        );
+    }
+
+    #[test]
+    fn test_sixel_images() {
+        let image = decode_sixel(b"#1;2;100;0;0#1!3~-#2;2;0;0;100@").unwrap();
+        assert_eq!(image.dimensions(), (3, 7));
+        assert_eq!(*image.get_pixel(2, 5), Rgba([255, 0, 0, 255]));
+        assert_eq!(*image.get_pixel(0, 6), Rgba([0, 0, 255, 255]));
+        assert_eq!(*image.get_pixel(1, 6), Rgba([0, 0, 0, 0]));
+
+        let mut scanner = ImageScanner::new(TerminalImages::default(), WINDOW_SIZE, true);
+        let output = scan(&mut scanner, b"a\x1bPq\"1;1;25;30#0~\x1b\\b");
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!("a{}\r\nb", placeholder(0, 2, 3))
+        );
+    }
+
+    #[test]
+    fn test_iterm_images() {
+        let mut png = Vec::new();
+        RgbaImage::new(2, 2)
+            .write_to(&mut std::io::Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let png = BASE64.encode(png);
+
+        let mut scanner = ImageScanner::new(TerminalImages::default(), WINDOW_SIZE, true);
+        let input = format!("\x1b]1337;File=inline=1;width=3:{png}\x07");
+        let output = scan(&mut scanner, input.as_bytes());
+        assert_eq!(String::from_utf8(output).unwrap(), placeholder(0, 2, 3));
+
+        // Files that aren't shown inline are downloads, which are dropped.
+        let input = format!("\x1b]1337;File=name=eA==:{png}\x1b\\");
+        assert!(scan(&mut scanner, input.as_bytes()).is_empty());
+    }
+
+    #[test]
+    fn test_decoding_limits() {
+        let encode = |width, height| {
+            let mut png = Vec::new();
+            RgbaImage::new(width, height)
+                .write_to(&mut std::io::Cursor::new(&mut png), ImageFormat::Png)
+                .unwrap();
+            png
+        };
+
+        let png = encode(MAX_IMAGE_DIMENSION, 1);
+        assert!(decode_image(&png, Some(ImageFormat::Png)).is_ok());
+        assert!(decode_image(&png, None).is_ok());
+
+        // Images wider than the limit are refused before their pixels are allocated.
+        let png = encode(MAX_IMAGE_DIMENSION + 1, 1);
+        assert!(decode_image(&png, Some(ImageFormat::Png)).is_err());
+        assert!(decode_image(&png, None).is_err());
+
+        let mut scanner = ImageScanner::new(TerminalImages::default(), WINDOW_SIZE, true);
+        let input = format!("\x1b_Ga=T,f=100,i=9;{}\x1b\\", BASE64.encode(&png));
+        assert!(scan(&mut scanner, input.as_bytes()).is_empty());
+        assert!(scanner.take_responses().starts_with(b"\x1b_Gi=9;EBADPNG:"));
+    }
+
+    #[test]
+    fn test_images_on_grid() {
+        let images = TerminalImages::default();
+        let mut scanner = ImageScanner::new(images.clone(), WINDOW_SIZE, true);
+        let mut processor: Processor = Processor::new();
+        let mut term = Term::new(Config::default(), &TermSize::new(80, 4), VoidListener);
+
+        let input = format!("$ icat\r\n  {}\r\n$ ", kitty_rgba("a=T", 20, 60));
+        processor.advance(&mut term, &scan(&mut scanner, input.as_bytes()));
+        let placements = images.visible_placements(&term);
+        assert_eq!(placements.len(), 1);
+        assert_eq!(placements[0].point, AlacPoint::new(Line(0), Column(2)));
+        assert_eq!((placements[0].width, placements[0].height), (2., 3.));
+        assert_eq!(term.grid().cursor.point, AlacPoint::new(Line(3), Column(2)));
+
+        // Once the top of the image scrolls out of view, it's still drawn from where it was.
+        processor.advance(&mut term, b"\r\n\r\n");
+        let placements = images.visible_placements(&term);
+        assert_eq!(placements[0].point, AlacPoint::new(Line(-2), Column(2)));
+
+        // Once it's overwritten, it goes once enough other images have been placed.
+        processor.advance(&mut term, b"\x1b[2J\x1b[3J");
+        assert!(images.visible_placements(&term).is_empty());
+        for _ in 0..MIN_COLLECTION_THRESHOLD {
+            scan(&mut scanner, b"\x1b_Ga=T,f=24,s=1,v=1;AAAA\x1b\\");
+        }
+        images.collect(&term);
+        assert_eq!(images.take_evicted().len(), MIN_COLLECTION_THRESHOLD + 1);
+    }
+}
@@ -0,0 +1,724 @@
+//! Command marks reported by shell integration.
+//!
+//! Shells running our integration scripts (or any shell speaking the same protocol) announce
+//! where prompts, command lines and command output begin with OSC 133 sequences, and the command
+//! line itself with VS Code's OSC 633 `E` sequence. Alacritty ignores these, so the bytes read from
+//! the pty go through a [`ShellMarkScanner`] first, which replaces them with OSC 8 hyperlinks using
+//! the `zed-command-mark:` scheme. Alacritty then stores the marks on the grid cells they cover,
+//! where they move with scrollback and reflow like any other cell attribute, and where the rest of
+//! this module can find them again.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ops::RangeInclusive,
+    sync::{Arc, Mutex},
+};
+
+use alacritty_terminal::{
+    Term,
+    event::EventListener,
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    term::cell::{Cell, Flags, Hyperlink},
+};
+
+use crate::{
+    scanned_pty::{BEL, CAN, ESC, SUB},
+    terminal_images,
+};
+
+const MARK_URI_PREFIX: &str = "zed-command-mark:";
+
+/// How many commands to remember the exit status and command line of.
+const MAX_COMMAND_RECORDS: usize = 10_000;
+
+/// Sequences longer than this are passed through untouched rather than buffered.
+const MAX_SEQUENCE_LEN: usize = 4096;
+
+/// An upper bound on how much the scanner can grow its input, used to read no more from the pty
+/// than fits in the caller's buffer once rewritten. The shortest sequence the scanner replaces is
+/// `ESC ] 8 ; ; BEL`, which becomes a link close followed by a link open of at most 54 bytes.
+pub(crate) const MAX_EXPANSION: usize = 16;
+
+/// The part of a command a grid cell belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum MarkKind {
+    Prompt,
+    Input,
+    Output,
+}
+
+impl MarkKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            MarkKind::Prompt => "prompt",
+            MarkKind::Input => "input",
+            MarkKind::Output => "output",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct CommandMark {
+    pub kind: MarkKind,
+    pub command_id: u64,
+}
+
+impl CommandMark {
+    fn uri(&self) -> String {
+        format!(
+            "{MARK_URI_PREFIX}{}/{}",
+            self.kind.as_str(),
+            self.command_id
+        )
+    }
+
+    fn parse(uri: &str) -> Option<Self> {
+        let (kind, command_id) = uri.strip_prefix(MARK_URI_PREFIX)?.split_once('/')?;
+        let kind = match kind {
+            "prompt" => MarkKind::Prompt,
+            "input" => MarkKind::Input,
+            "output" => MarkKind::Output,
+            _ => return None,
+        };
+        Some(Self {
+            kind,
+            command_id: command_id.parse().ok()?,
+        })
+    }
+}
+
+/// Whether a hyperlink was injected by shell integration rather than printed by a program.
+fn is_command_mark(hyperlink: &Hyperlink) -> bool {
+    hyperlink.uri().starts_with(MARK_URI_PREFIX)
+}
+
+/// Returns the hyperlink a program attached to `cell`, ignoring command marks and the links that
+/// anchor inline images.
+pub fn cell_hyperlink(cell: &Cell) -> Option<Hyperlink> {
+    cell.hyperlink().filter(|hyperlink| {
+        !is_command_mark(hyperlink) && !terminal_images::is_image_link(hyperlink)
+    })
+}
+
+fn cell_mark(cell: &Cell) -> Option<CommandMark> {
+    CommandMark::parse(cell.hyperlink()?.uri())
+}
+
+/// How a command that has been run from a prompt has fared.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CommandRecord {
+    pub command_line: Option<String>,
+    pub started: bool,
+    pub finished: bool,
+    pub exit_code: Option<i32>,
+}
+
+impl CommandRecord {
+    fn status(&self) -> Option<CommandStatus> {
+        if !self.started {
+            None
+        } else if !self.finished {
+            Some(CommandStatus::Running)
+        } else {
+            match self.exit_code? {
+                0 => Some(CommandStatus::Succeeded),
+                _ => Some(CommandStatus::Failed),
+            }
+        }
+    }
+}
+
+/// What the shell reported about each command, shared between the pty reader thread that
+/// records it and the terminal that displays it.
+#[derive(Clone, Default)]
+pub(crate) struct CommandHistory(Arc<Mutex<BTreeMap<u64, CommandRecord>>>);
+
+impl CommandHistory {
+    fn update(&self, command_id: u64, f: impl FnOnce(&mut CommandRecord)) {
+        let mut records = self.0.lock().unwrap();
+        f(records.entry(command_id).or_default());
+        while records.len() > MAX_COMMAND_RECORDS {
+            records.pop_first();
+        }
+    }
+
+    pub fn get(&self, command_id: u64) -> Option<CommandRecord> {
+        self.0.lock().unwrap().get(&command_id).cloned()
+    }
+
+    pub fn status(&self, command_id: u64) -> Option<CommandStatus> {
+        self.0.lock().unwrap().get(&command_id)?.status()
+    }
+
+    /// Whether the most recent command is still running, so that the shell isn't at a prompt.
+    pub fn is_command_running(&self) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .last_key_value()
+            .is_some_and(|(_, record)| record.status() == Some(CommandStatus::Running))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    /// After an `ESC` that may start an OSC sequence.
+    Escape,
+    /// Inside an OSC sequence that may be one we rewrite, which is being buffered.
+    Osc,
+    /// After an `ESC` inside a buffered OSC sequence, which is expected to be `ESC \`.
+    OscEscape,
+    /// Inside an OSC sequence we don't rewrite, which is being passed through.
+    Passthrough,
+}
+
+/// Rewrites shell integration sequences in the output of a pty into command mark hyperlinks.
+///
+/// Sequences may be split across reads, so the scanner keeps any partial sequence it has seen
+/// until the rest of it arrives.
+pub(crate) struct ShellMarkScanner {
+    state: ScanState,
+    sequence: Vec<u8>,
+    current_mark: Option<CommandMark>,
+    next_command_id: u64,
+    history: CommandHistory,
+}
+
+impl ShellMarkScanner {
+    pub fn new(history: CommandHistory) -> Self {
+        Self {
+            state: ScanState::Ground,
+            sequence: Vec::new(),
+            current_mark: None,
+            next_command_id: 0,
+            history,
+        }
+    }
+
+    pub fn scan(&mut self, input: &[u8], output: &mut VecDeque<u8>) {
+        for &byte in input {
+            self.scan_byte(byte, output);
+        }
+    }
+
+    fn scan_byte(&mut self, byte: u8, output: &mut VecDeque<u8>) {
+        match self.state {
+            ScanState::Ground => {
+                if byte == ESC {
+                    self.state = ScanState::Escape;
+                } else {
+                    output.push_back(byte);
+                }
+            }
+            ScanState::Escape => {
+                if byte == b']' {
+                    self.sequence.clear();
+                    self.state = ScanState::Osc;
+                } else {
+                    output.push_back(ESC);
+                    self.state = ScanState::Ground;
+                    self.scan_byte(byte, output);
+                }
+            }
+            ScanState::Osc => match byte {
+                BEL => {
+                    self.finish_sequence(output);
+                    self.state = ScanState::Ground;
+                }
+                ESC => self.state = ScanState::OscEscape,
+                CAN | SUB => {
+                    self.flush_sequence(output);
+                    output.push_back(byte);
+                    self.state = ScanState::Ground;
+                }
+                _ => {
+                    self.sequence.push(byte);
+                    if !may_rewrite(&self.sequence) || self.sequence.len() > MAX_SEQUENCE_LEN {
+                        self.flush_sequence(output);
+                        self.state = ScanState::Passthrough;
+                    }
+                }
+            },
+            ScanState::OscEscape => {
+                self.finish_sequence(output);
+                if byte == b'\\' {
+                    self.state = ScanState::Ground;
+                } else {
+                    // Any escape terminates an OSC sequence and starts a new one.
+                    self.state = ScanState::Escape;
+                    self.scan_byte(byte, output);
+                }
+            }
+            ScanState::Passthrough => match byte {
+                ESC => self.state = ScanState::Escape,
+                BEL | CAN | SUB => {
+                    output.push_back(byte);
+                    self.state = ScanState::Ground;
+                }
+                _ => output.push_back(byte),
+            },
+        }
+    }
+
+    /// Writes out the buffered start of a sequence that turned out not to need rewriting.
+    fn flush_sequence(&mut self, output: &mut VecDeque<u8>) {
+        output.extend([ESC, b']']);
+        output.extend(self.sequence.drain(..));
+    }
+
+    fn finish_sequence(&mut self, output: &mut VecDeque<u8>) {
+        let sequence = std::mem::take(&mut self.sequence);
+        if let Some(payload) = sequence
+            .strip_prefix(b"133;")
+            .or_else(|| sequence.strip_prefix(b"633;"))
+        {
+            self.handle_mark(payload, output);
+        } else if let Some(link) = sequence.strip_prefix(b"8;") {
+            write_osc(output, &sequence);
+            // Closing a program's link also ends the command mark, so reopen it.
+            let is_close = link
+                .iter()
+                .position(|&byte| byte == b';')
+                .is_some_and(|uri_start| uri_start + 1 == link.len());
+            if is_close && let Some(mark) = self.current_mark {
+                write_mark(output, Some(mark));
+            }
+        } else {
+            output.extend([ESC, b']']);
+            output.extend(sequence);
+            output.extend([ESC, b'\\']);
+        }
+    }
+
+    fn handle_mark(&mut self, payload: &[u8], output: &mut VecDeque<u8>) {
+        let mut params = payload.splitn(2, |&byte| byte == b';');
+        let kind = params.next().unwrap_or_default();
+        let argument = params.next();
+        match kind {
+            b"A" => {
+                if let Some(mark) = self.current_mark {
+                    self.history.update(mark.command_id, |record| {
+                        record.finished = record.started;
+                    });
+                }
+                let command_id = self.next_command_id;
+                self.next_command_id += 1;
+                self.history.update(command_id, |_| {});
+                self.set_mark(MarkKind::Prompt, command_id, output);
+            }
+            b"B" => {
+                let command_id = self.current_command_id();
+                self.set_mark(MarkKind::Input, command_id, output);
+            }
+            b"C" => {
+                let command_id = self.current_command_id();
+                self.history
+                    .update(command_id, |record| record.started = true);
+                self.set_mark(MarkKind::Output, command_id, output);
+            }
+            b"D" => {
+                if let Some(mark) = self.current_mark.take() {
+                    let exit_code = argument
+                        .and_then(|argument| std::str::from_utf8(argument).ok())
+                        .and_then(|argument| argument.split(';').next()?.parse().ok());
+                    self.history.update(mark.command_id, |record| {
+                        record.finished = record.started;
+                        record.exit_code = exit_code;
+                    });
+                    write_mark(output, None);
+                }
+            }
+            b"E" => {
+                if let Some(mark) = self.current_mark
+                    && let Some(argument) = argument
+                {
+                    let command_line = argument
+                        .split(|&byte| byte == b';')
+                        .next()
+                        .unwrap_or_default();
+                    let command_line = unescape_command_line(command_line);
+                    self.history.update(mark.command_id, |record| {
+                        record.command_line = Some(command_line);
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the command the shell is currently reporting on, starting one if the scanner
+    /// missed its prompt.
+    fn current_command_id(&mut self) -> u64 {
+        match self.current_mark {
+            Some(mark) => mark.command_id,
+            None => {
+                let command_id = self.next_command_id;
+                self.next_command_id += 1;
+                command_id
+            }
+        }
+    }
+
+    fn set_mark(&mut self, kind: MarkKind, command_id: u64, output: &mut VecDeque<u8>) {
+        let mark = CommandMark { kind, command_id };
+        self.current_mark = Some(mark);
+        write_mark(output, Some(mark));
+    }
+}
+
+fn may_rewrite(sequence: &[u8]) -> bool {
+    [b"133;".as_slice(), b"633;", b"8;"]
+        .iter()
+        .any(|prefix| prefix.starts_with(sequence) || sequence.starts_with(prefix))
+}
+
+fn write_osc(output: &mut VecDeque<u8>, sequence: &[u8]) {
+    output.extend([ESC, b']']);
+    output.extend(sequence);
+    output.extend([ESC, b'\\']);
+}
+
+fn write_mark(output: &mut VecDeque<u8>, mark: Option<CommandMark>) {
+    let uri = mark.map(|mark| mark.uri()).unwrap_or_default();
+    write_osc(output, format!("8;;{uri}").as_bytes());
+}
+
+/// Reverses the escaping of command lines in OSC 633 `E` sequences, in which backslashes are
+/// doubled and other bytes, such as `;`, may be written as `\xAB`.
+fn unescape_command_line(escaped: &[u8]) -> String {
+    let mut bytes = Vec::with_capacity(escaped.len());
+    let mut rest = escaped;
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        if byte != b'\\' {
+            bytes.push(byte);
+        } else if let Some(tail) = rest.strip_prefix(b"\\") {
+            bytes.push(b'\\');
+            rest = tail;
+        } else if let Some(digits) = rest.strip_prefix(b"x").and_then(|tail| tail.get(..2))
+            && let Some(escaped_byte) = std::str::from_utf8(digits)
+                .ok()
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+        {
+            bytes.push(escaped_byte);
+            rest = &rest[3..];
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn line_marks<T: EventListener>(
+    term: &Term<T>,
+    line: Line,
+) -> impl Iterator<Item = CommandMark> + '_ {
+    let row = &term.grid()[line];
+    (0..term.columns()).filter_map(move |column| cell_mark(&row[Column(column)]))
+}
+
+/// Returns the command whose prompt starts on `line`.
+pub(crate) fn prompt_start<T: EventListener>(term: &Term<T>, line: Line) -> Option<u64> {
+    let command_id = line_marks(term, line)
+        .find(|mark| mark.kind == MarkKind::Prompt)?
+        .command_id;
+    let continues_prompt = line > term.topmost_line()
+        && line_marks(term, line - 1)
+            .any(|mark| mark.kind == MarkKind::Prompt && mark.command_id == command_id);
+    (!continues_prompt).then_some(command_id)
+}
+
+/// Finds the closest line after (or before) `line` on which a prompt starts.
+pub(crate) fn find_prompt_start<T: EventListener>(
+    term: &Term<T>,
+    line: Line,
+    forward: bool,
+) -> Option<Line> {
+    if forward {
+        (line.0 + 1..=term.bottommost_line().0)
+            .map(Line)
+            .find(|&line| prompt_start(term, line).is_some())
+    } else {
+        (term.topmost_line().0..line.0)
+            .rev()
+            .map(Line)
+            .find(|&line| prompt_start(term, line).is_some())
+    }
+}
+
+/// Returns the last command run from a prompt that starts at or above `line`, along with a line
+/// it covers.
+pub(crate) fn last_command_at<T: EventListener>(
+    term: &Term<T>,
+    history: &CommandHistory,
+    line: Line,
+) -> Option<(u64, Line)> {
+    let line = line.min(term.bottommost_line());
+    (term.topmost_line().0..=line.0)
+        .rev()
+        .map(Line)
+        .find_map(|line| {
+            let command_id = line_marks(term, line)
+                .map(|mark| mark.command_id)
+                .find(|&command_id| history.status(command_id).is_some())?;
+            Some((command_id, line))
+        })
+}
+
+/// Returns the lines covered by a command, given one of them.
+fn command_lines<T: EventListener>(
+    term: &Term<T>,
+    command_id: u64,
+    line: Line,
+) -> RangeInclusive<Line> {
+    let belongs_to_command =
+        |line: Line| line_marks(term, line).all(|mark| mark.command_id == command_id);
+    let mut start = line;
+    while start > term.topmost_line() && belongs_to_command(start - 1) {
+        start -= 1;
+    }
+    let mut end = line;
+    while end < term.bottommost_line() && belongs_to_command(end + 1) {
+        end += 1;
+    }
+    start..=end
+}
+
+fn command_cells<T: EventListener>(
+    term: &Term<T>,
+    command_id: u64,
+    line: Line,
+    kind: MarkKind,
+) -> impl Iterator<Item = (AlacPoint, &Cell)> + '_ {
+    let lines = command_lines(term, command_id, line);
+    (lines.start().0..=lines.end().0).flat_map(move |line| {
+        let row = &term.grid()[Line(line)];
+        (0..term.columns()).filter_map(move |column| {
+            let cell = &row[Column(column)];
+            let mark = cell_mark(cell)?;
+            (mark.command_id == command_id && mark.kind == kind)
+                .then(|| (AlacPoint::new(Line(line), Column(column)), cell))
+        })
+    })
+}
+
+/// Returns the span of the grid covered by a command's output, given a line the command covers.
+pub(crate) fn command_output<T: EventListener>(
+    term: &Term<T>,
+    command_id: u64,
+    line: Line,
+) -> Option<RangeInclusive<AlacPoint>> {
+    let mut cells = command_cells(term, command_id, line, MarkKind::Output);
+    let (start, _) = cells.next()?;
+    let end = cells.last().map_or(start, |(end, _)| end);
+    Some(start..=end)
+}
+
+/// Returns the command line as it was echoed after the prompt, given a line the command covers.
+pub(crate) fn command_input<T: EventListener>(
+    term: &Term<T>,
+    command_id: u64,
+    line: Line,
+) -> Option<String> {
+    let mut input = String::new();
+    let mut last_line = None;
+    for (point, cell) in command_cells(term, command_id, line, MarkKind::Input) {
+        if let Some(last_line) = last_line
+            && last_line != point.line
+            && !term.grid()[last_line][term.last_column()]
+                .flags
+                .contains(Flags::WRAPLINE)
+        {
+            input.truncate(input.trim_end().len());
+            input.push('\n');
+        }
+        last_line = Some(point.line);
+        if !cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+            input.push(cell.c);
+        }
+    }
+    let input = input.trim();
+    (!input.is_empty()).then(|| input.to_string())
+}
+
+/// A prompt shown in the terminal's viewport, for the gutter to show the status of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PromptMark {
+    pub line: Line,
+    pub status: CommandStatus,
+}
+
+pub(crate) fn visible_prompt_marks<T: EventListener>(
+    term: &Term<T>,
+    history: &CommandHistory,
+) -> Vec<PromptMark> {
+    let top = -(term.grid().display_offset() as i32);
+    let bottom = top + term.screen_lines() as i32 - 1;
+    (top..=bottom)
+        .map(Line)
+        .filter_map(|line| {
+            let status = history.status(prompt_start(term, line)?)?;
+            Some(PromptMark { line, status })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alacritty_terminal::{
+        event::VoidListener,
+        term::{Config, test::TermSize},
+        vte::ansi::Processor,
+    };
+
+    fn scan(scanner: &mut ShellMarkScanner, input: &[u8]) -> Vec<u8> {
+        let mut output = VecDeque::new();
+        scanner.scan(input, &mut output);
+        output.into()
+    }
+
+    fn open(kind: &str, command_id: u64) -> String {
+        format!("\x1b]8;;zed-command-mark:{kind}/{command_id}\x1b\\")
+    }
+
+    const CLOSE: &str = "\x1b]8;;\x1b\\";
+
+    #[test]
+    fn test_rewrite_marks() {
+        let history = CommandHistory::default();
+        let mut scanner = ShellMarkScanner::new(history.clone());
+        let output = scan(
+            &mut scanner,
+            b"\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]633;E;ls\x07\x1b]133;C\x07a\r\n\x1b]133;D;1\x07",
+        );
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!(
+                "{}$ {}ls\r\n{}a\r\n{CLOSE}",
+                open("prompt", 0),
+                open("input", 0),
+                open("output", 0)
+            )
+        );
+        let record = history.get(0).unwrap();
+        assert_eq!(record.command_line.as_deref(), Some("ls"));
+        assert_eq!(record.exit_code, Some(1));
+        assert_eq!(history.status(0), Some(CommandStatus::Failed));
+        assert!(!history.is_command_running());
+
+        let output = scan(&mut scanner, b"\x1b]133;A\x1b\\$ \x1b]133;C\x1b\\");
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!("{}$ {}", open("prompt", 1), open("output", 1))
+        );
+        assert_eq!(history.status(1), Some(CommandStatus::Running));
+        assert!(history.is_command_running());
+    }
+
+    #[test]
+    fn test_sequences_split_across_reads() {
+        let history = CommandHistory::default();
+        let mut scanner = ShellMarkScanner::new(history.clone());
+        let input = b"\x1b[31mred\x1b]0;title\x07\x1b]133;A\x1b\\$ \x1b]133;C\x07\x1b]133;D;0\x07";
+        let mut output = Vec::new();
+        for byte in input {
+            output.extend(scan(&mut scanner, &[*byte]));
+        }
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!(
+                "\x1b[31mred\x1b]0;title\x07{}$ {}{CLOSE}",
+                open("prompt", 0),
+                open("output", 0)
+            )
+        );
+        assert_eq!(history.status(0), Some(CommandStatus::Succeeded));
+    }
+
+    #[test]
+    fn test_program_hyperlinks() {
+        let mut scanner = ShellMarkScanner::new(CommandHistory::default());
+        scan(&mut scanner, b"\x1b]133;A\x07\x1b]133;C\x07");
+        let output = scan(
+            &mut scanner,
+            b"\x1b]8;;https://zed.dev\x1b\\zed\x1b]8;;\x1b\\!",
+        );
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!(
+                "\x1b]8;;https://zed.dev\x1b\\zed{CLOSE}{}!",
+                open("output", 0)
+            )
+        );
+    }
+
+    #[test]
+    fn test_unescape_command_line() {
+        assert_eq!(unescape_command_line(br"echo a\x3bb"), "echo a;b");
+        assert_eq!(unescape_command_line(br"printf '\\n'"), r"printf '\n'");
+        assert_eq!(unescape_command_line(br"trailing\"), r"trailing\");
+    }
+
+    #[test]
+    fn test_marks_on_grid() {
+        let history = CommandHistory::default();
+        let mut scanner = ShellMarkScanner::new(history.clone());
+        let mut term = Term::new(Config::default(), &TermSize::new(20, 10), VoidListener);
+        let mut processor: Processor = Processor::new();
+        let output = scan(
+            &mut scanner,
+            b"\x1b]133;A\x07$ \x1b]133;B\x07echo hi\r\n\x1b]133;C\x07hi\r\nthere\r\n\x1b]133;D;0\x07\
+              \x1b]133;A\x07$ \x1b]133;B\x07false\r\n\x1b]133;C\x07\x1b]133;D;1\x07\
+              \x1b]133;A\x07$ \x1b]133;B\x07",
+        );
+        processor.advance(&mut term, &output);
+
+        assert_eq!(prompt_start(&term, Line(0)), Some(0));
+        assert_eq!(prompt_start(&term, Line(1)), None);
+        assert_eq!(prompt_start(&term, Line(3)), Some(1));
+        assert_eq!(prompt_start(&term, Line(4)), Some(2));
+        assert_eq!(find_prompt_start(&term, Line(0), true), Some(Line(3)));
+        assert_eq!(find_prompt_start(&term, Line(3), false), Some(Line(0)));
+        assert_eq!(find_prompt_start(&term, Line(0), false), None);
+
+        assert_eq!(
+            visible_prompt_marks(&term, &history),
+            [
+                PromptMark {
+                    line: Line(0),
+                    status: CommandStatus::Succeeded
+                },
+                PromptMark {
+                    line: Line(3),
+                    status: CommandStatus::Failed
+                },
+            ]
+        );
+
+        assert_eq!(
+            last_command_at(&term, &history, Line(9)),
+            Some((1, Line(3)))
+        );
+        assert_eq!(
+            last_command_at(&term, &history, Line(2)),
+            Some((0, Line(2)))
+        );
+        assert_eq!(
+            command_output(&term, 0, Line(2)),
+            Some(AlacPoint::new(Line(1), Column(0))..=AlacPoint::new(Line(2), Column(4)))
+        );
+        assert_eq!(command_output(&term, 1, Line(3)), None);
+        assert_eq!(command_input(&term, 0, Line(0)).as_deref(), Some("echo hi"));
+        assert_eq!(command_input(&term, 1, Line(3)).as_deref(), Some("false"));
+    }
+}
@@ -0,0 +1,169 @@
+//! Problems found in the output of a task by its problem matchers.
+//!
+//! The bytes read from a task's pty also go through a [`ProblemScanner`], which drops escape
+//! sequences, splits the output into lines and runs the task's [`ProblemCollector`] over them.
+//! The problems found are queued in [`TaskProblems`] until the terminal hands them out.
+
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+};
+
+use task::{Problem, ProblemCollector};
+
+use crate::scanned_pty::{BEL, CAN, ESC, SUB};
+
+/// Lines longer than this are cut, as no problem matcher needs more of them.
+const MAX_LINE_LEN: usize = 4096;
+
+/// Problems found in the output of a task, shared between the pty reader thread that finds them
+/// and the terminal that hands them out.
+#[derive(Clone, Default)]
+pub(crate) struct TaskProblems(Arc<Mutex<Vec<Problem>>>);
+
+impl TaskProblems {
+    pub fn take(&self) -> Vec<Problem> {
+        mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    Escape,
+    /// Inside a CSI sequence, up to its final byte.
+    Csi,
+    /// Inside an OSC, DCS, APC, PM or SOS string, up to its terminator.
+    String,
+    /// After an `ESC` inside a string, expected to be `ESC \`.
+    StringEscape,
+}
+
+/// Turns the output of a pty into lines of plain text, and matches problems in them.
+pub(crate) struct ProblemScanner {
+    collector: ProblemCollector,
+    problems: TaskProblems,
+    state: ScanState,
+    line: Vec<u8>,
+    /// Whether the last byte was a carriage return, which rewrites the line unless a line feed follows.
+    carriage_return: bool,
+}
+
+impl ProblemScanner {
+    pub fn new(collector: ProblemCollector, problems: TaskProblems) -> Self {
+        Self {
+            collector,
+            problems,
+            state: ScanState::Ground,
+            line: Vec::new(),
+            carriage_return: false,
+        }
+    }
+
+    pub fn scan(&mut self, input: &[u8]) {
+        for &byte in input {
+            self.state = match self.state {
+                ScanState::Ground if byte == ESC => ScanState::Escape,
+                ScanState::Ground => {
+                    self.print(byte);
+                    ScanState::Ground
+                }
+                ScanState::Escape => match byte {
+                    b'[' => ScanState::Csi,
+                    b']' | b'P' | b'_' | b'^' | b'X' => ScanState::String,
+                    _ => ScanState::Ground,
+                },
+                ScanState::Csi => match byte {
+                    0x40..=0x7e | CAN | SUB => ScanState::Ground,
+                    _ => ScanState::Csi,
+                },
+                ScanState::String => match byte {
+                    BEL | CAN | SUB => ScanState::Ground,
+                    ESC => ScanState::StringEscape,
+                    _ => ScanState::String,
+                },
+                ScanState::StringEscape => ScanState::Ground,
+            };
+        }
+    }
+
+    /// Matches whatever is left of the last line, once the output has ended.
+    pub fn finish(&mut self) {
+        if !self.line.is_empty() {
+            self.finish_line();
+        }
+    }
+
+    fn print(&mut self, byte: u8) {
+        match byte {
+            b'\n' => self.finish_line(),
+            b'\r' => self.carriage_return = true,
+            b'\t' => self.push(b' '),
+            byte if byte < 0x20 || byte == 0x7f => {}
+            byte => self.push(byte),
+        }
+    }
+
+    fn push(&mut self, byte: u8) {
+        if mem::take(&mut self.carriage_return) {
+            self.line.clear();
+        }
+        if self.line.len() < MAX_LINE_LEN {
+            self.line.push(byte);
+        }
+    }
+
+    fn finish_line(&mut self) {
+        self.carriage_return = false;
+        let line = String::from_utf8_lossy(&self.line);
+        let problems = self.collector.push_line(&line);
+        self.line.clear();
+        if !problems.is_empty() {
+            self.problems.0.lock().unwrap().extend(problems);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use task::{BuiltInProblemMatcher, ProblemMatcher};
+
+    use super::*;
+
+    #[test]
+    fn test_scanning_colored_output() {
+        let problems = TaskProblems::default();
+        let collector = ProblemCollector::new(
+            &[ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)],
+            Some(Path::new("/root")),
+        );
+        let mut scanner = ProblemScanner::new(collector, problems.clone());
+
+        let output = b"\x1b]133;C\x07   Compiling example\r    Building [=>  ] 1/2\r\n\
+            \x1b[0m\x1b[1m\x1b[91merror[E0425]\x1b[0m\x1b[1m: cannot find value `x`\x1b[0m\r\n\
+            \x1b[0m  \x1b[0m\x1b[0m\x1b[1m\x1b[94m--> \x1b[0m\x1b[0msrc/main.rs:2:5\x1b[0m\r\n";
+        for chunk in output.chunks(7) {
+            scanner.scan(chunk);
+        }
+        scanner.scan(b"warning: unused\n --> src/lib.rs:1:1");
+        scanner.finish();
+
+        let problems = problems.take();
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.to_string_lossy().into_owned(),
+                    problem.line,
+                    problem.message.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("/root/src/main.rs".to_string(), 2, "cannot find value `x`"),
+                ("/root/src/lib.rs".to_string(), 1, "unused"),
+            ]
+        );
+    }
+}
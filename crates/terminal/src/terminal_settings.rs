@@ -47,6 +47,8 @@ pub struct TerminalSettings {
     pub default_height: Pixels,
     pub detect_venv: VenvSettings,
     pub max_scroll_history_lines: Option<usize>,
+    pub shell_integration: bool,
+    pub restore_scrollback: bool,
     pub toolbar: Toolbar,
     pub scrollbar: ScrollbarSettings,
     pub minimum_contrast: f32,
@@ -231,6 +233,17 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: 10_000
     pub max_scroll_history_lines: Option<usize>,
+    /// Whether to start bash, zsh and fish with a script that reports prompts, commands and
+    /// their exit status, to show in the gutter and to move between prompts.
+    /// Existing terminals will not pick up this change until they are recreated.
+    ///
+    /// Default: true
+    pub shell_integration: Option<bool>,
+    /// Whether to save the scrollback of terminals, and show it again when their workspace
+    /// is restored.
+    ///
+    /// Default: true
+    pub restore_scrollback: Option<bool>,
     /// Toolbar related settings
     pub toolbar: Option<ToolbarContent>,
     /// Scrollbar-related settings
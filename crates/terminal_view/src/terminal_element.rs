@@ -1,19 +1,19 @@
 use editor::{CursorLayout, EditorSettings, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, DispatchPhase,
-    Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle, FontWeight,
-    GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity,
-    IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels,
-    Point, ShapedLine, StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun, TextStyle,
-    UTF16Selection, UnderlineStyle, WeakEntity, WhiteSpace, Window, div, fill, point, px, relative,
-    size,
+    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, Corners,
+    DispatchPhase, Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle,
+    FontWeight, GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement,
+    Interactivity, IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton,
+    MouseMoveEvent, Pixels, Point, ShapedLine, StatefulInteractiveElement, StrikethroughStyle,
+    Styled, TextRun, TextStyle, UTF16Selection, UnderlineStyle, WeakEntity, WhiteSpace, Window,
+    div, fill, point, px, relative, size,
 };
 use itertools::Itertools;
 use language::CursorShape;
 use settings::Settings;
 use std::time::Instant;
 use terminal::{
-    IndexedCell, Terminal, TerminalBounds, TerminalContent,
+    CommandStatus, ImagePlacement, IndexedCell, Terminal, TerminalBounds, TerminalContent,
     alacritty_terminal::{
         grid::Dimensions,
         index::Point as AlacPoint,
@@ -23,6 +23,7 @@ use terminal::{
             CursorShape as AlacCursorShape, NamedColor,
         },
     },
+    cell_hyperlink,
     terminal_settings::TerminalSettings,
 };
 use theme::{ActiveTheme, Theme, ThemeSettings};
@@ -49,6 +50,10 @@ pub struct LayoutState {
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
     gutter: Pixels,
+    /// The display lines of prompts that ran a command, and the color showing how it exited.
+    prompt_marks: Vec<(i32, Hsla)>,
+    /// Inline images, positioned by display line like `prompt_marks`.
+    images: Vec<ImagePlacement>,
     block_below_cursor_element: Option<AnyElement>,
     base_text_style: TextStyle,
     content_mode: ContentMode,
@@ -544,7 +549,7 @@ impl TerminalElement {
         }
 
         let underline = (flags.intersects(Flags::ALL_UNDERLINES)
-            || indexed.cell.hyperlink().is_some())
+            || cell_hyperlink(&indexed.cell).is_some())
         .then(|| UnderlineStyle {
             color: Some(fg),
             thickness: Pixels::from(1.0),
@@ -1015,10 +1020,31 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    prompt_marks,
+                    images,
                     ..
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
+                let prompt_marks = prompt_marks
+                    .iter()
+                    .map(|mark| {
+                        let color = match mark.status {
+                            CommandStatus::Running => theme.colors().icon_muted,
+                            CommandStatus::Succeeded => theme.status().success,
+                            CommandStatus::Failed => theme.status().error,
+                        };
+                        (mark.line.0 + display_offset as i32, color)
+                    })
+                    .collect();
+                let images = images
+                    .iter()
+                    .map(|placement| {
+                        let mut placement = placement.clone();
+                        placement.point.line.0 += display_offset as i32;
+                        placement
+                    })
+                    .collect();
 
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
@@ -1165,6 +1191,8 @@ impl Element for TerminalElement {
                     display_offset,
                     hyperlink_tooltip,
                     gutter,
+                    prompt_marks,
+                    images,
                     block_below_cursor_element,
                     base_text_style: text_style,
                     content_mode,
@@ -1251,6 +1279,37 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout.dimensions, window);
                     }
 
+                    let line_height = layout.dimensions.line_height;
+                    for &(line, color) in &layout.prompt_marks {
+                        let mark_origin = point(
+                            bounds.origin.x + layout.gutter * 0.25,
+                            origin.y + line_height * (line as f32 + 0.2),
+                        );
+                        let mark_size = size(layout.gutter * 0.5, line_height * 0.6);
+                        window.paint_quad(fill(Bounds::new(mark_origin, mark_size), color));
+                    }
+
+                    for placement in &layout.images {
+                        let image_origin = point(
+                            origin.x
+                                + layout.dimensions.cell_width * placement.point.column.0 as f32,
+                            origin.y + line_height * placement.point.line.0 as f32,
+                        );
+                        let image_size = size(
+                            layout.dimensions.cell_width * placement.width,
+                            line_height * placement.height,
+                        );
+                        window
+                            .paint_image(
+                                Bounds::new(image_origin, image_size),
+                                Corners::default(),
+                                placement.image.clone(),
+                                0,
+                                false,
+                            )
+                            .log_err();
+                    }
+
                     for (relative_highlighted_range, color) in
                         layout.relative_highlighted_ranges.iter()
                     {
@@ -1472,7 +1531,7 @@ pub fn is_blank(cell: &IndexedCell) -> bool {
         return false;
     }
 
-    if cell.hyperlink().is_some() {
+    if cell_hyperlink(cell).is_some() {
         return false;
     }
 
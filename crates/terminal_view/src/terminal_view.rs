@@ -19,8 +19,9 @@ use project::{Project, search::SearchQuery};
 use schemars::JsonSchema;
 use task::TaskId;
 use terminal::{
-    Clear, Copy, Event, HoveredWord, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp,
-    ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskState,
+    Clear, Copy, CopyCommandOutput, Event, HoveredWord, MaybeNavigationTarget, Paste, RerunCommand,
+    ScrollLineDown, ScrollLineUp, ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToNextPrompt,
+    ScrollToPreviousPrompt, ScrollToTop, SelectCommandOutput, ShowCharacterPalette, TaskState,
     TaskStatus, Terminal, TerminalBounds, ToggleViMode,
     alacritty_terminal::{
         index::Point,
@@ -126,6 +127,7 @@ pub struct TerminalView {
     mode: TerminalMode,
     blinking_terminal_enabled: bool,
     cwd_serialized: bool,
+    restore_scrollback: bool,
     blinking_paused: bool,
     blink_epoch: usize,
     hover: Option<HoverTarget>,
@@ -238,6 +240,7 @@ impl TerminalView {
             .unwrap_or_default();
 
         let scroll_handle = TerminalScrollHandle::new(terminal.read(cx));
+        let item_id = cx.entity_id().as_u64();
 
         Self {
             terminal,
@@ -263,17 +266,40 @@ impl TerminalView {
             show_scrollbar: !Self::should_autohide_scrollbar(cx),
             hide_scrollbar_task: None,
             cwd_serialized: false,
+            restore_scrollback: TerminalSettings::get_global(cx).restore_scrollback,
             marked_text: None,
             marked_range_utf16: None,
             _subscriptions: vec![
                 focus_in,
                 focus_out,
                 cx.observe_global::<SettingsStore>(Self::settings_changed),
+                // The scrollback is only captured once the terminal goes away, as that's costly.
+                cx.on_app_quit(move |this, cx| this.save_scrollback(item_id, cx)),
+                cx.on_release(move |this, cx| this.save_scrollback(item_id, cx).detach()),
             ],
             _terminal_subscriptions: terminal_subscriptions,
         }
     }
 
+    /// Saves the terminal's history so that it can be shown again when the workspace is restored.
+    fn save_scrollback(&self, item_id: workspace::ItemId, cx: &App) -> Task<()> {
+        let terminal = self.terminal.read(cx);
+        let Some(workspace_id) = self.workspace_id.filter(|_| terminal.task().is_none()) else {
+            return Task::ready(());
+        };
+        // Saving nothing clears the scrollback saved before it was disabled.
+        let scrollback = self
+            .restore_scrollback
+            .then(|| terminal.history_text())
+            .flatten();
+        cx.background_spawn(async move {
+            TERMINAL_DB
+                .save_scrollback(item_id, workspace_id, scrollback)
+                .await
+                .log_err();
+        })
+    }
+
     /// Enable 'embedded' mode where the terminal displays the full content with an optional limit of lines.
     pub fn set_embedded_mode(
         &mut self,
@@ -426,6 +452,7 @@ impl TerminalView {
         let settings = TerminalSettings::get_global(cx);
         let breadcrumb_visibility_changed = self.show_breadcrumbs != settings.toolbar.breadcrumbs;
         self.show_breadcrumbs = settings.toolbar.breadcrumbs;
+        self.restore_scrollback = settings.restore_scrollback;
 
         let new_cursor_shape = settings.cursor_shape.unwrap_or_default();
         let old_cursor_shape = self.cursor_shape;
@@ -602,6 +629,53 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_prompt(
+        &mut self,
+        _: &ScrollToPreviousPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_prompt());
+        cx.notify();
+    }
+
+    fn scroll_to_next_prompt(
+        &mut self,
+        _: &ScrollToNextPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_prompt());
+        cx.notify();
+    }
+
+    fn select_command_output(
+        &mut self,
+        _: &SelectCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.select_command_output());
+        cx.notify();
+    }
+
+    fn copy_command_output(
+        &mut self,
+        _: &CopyCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.copy_command_output());
+    }
+
+    fn rerun_command(&mut self, _: &RerunCommand, _: &mut Window, cx: &mut Context<Self>) {
+        self.terminal.update(cx, |term, _| term.rerun_command());
+    }
+
     fn toggle_vi_mode(&mut self, _: &ToggleViMode, _: &mut Window, cx: &mut Context<Self>) {
         self.terminal.update(cx, |term, _| term.toggle_vi_mode());
         cx.notify();
@@ -1184,6 +1258,11 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_prompt))
+            .on_action(cx.listener(TerminalView::scroll_to_next_prompt))
+            .on_action(cx.listener(TerminalView::select_command_output))
+            .on_action(cx.listener(TerminalView::copy_command_output))
+            .on_action(cx.listener(TerminalView::rerun_command))
             .on_action(cx.listener(TerminalView::toggle_vi_mode))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
@@ -1488,9 +1567,22 @@ impl SerializableItem for TerminalView {
                 .ok()
                 .flatten();
 
+            let scrollback = cx
+                .update(|_, cx| TerminalSettings::get_global(cx).restore_scrollback)?
+                .then(|| {
+                    TERMINAL_DB
+                        .get_scrollback(item_id, workspace_id)
+                        .log_err()
+                        .flatten()
+                })
+                .flatten();
+
             let terminal = project
                 .update(cx, |project, cx| project.create_terminal_shell(cwd, cx))?
                 .await?;
+            if let Some(scrollback) = scrollback {
+                terminal.update(cx, |terminal, _| terminal.restore_history(scrollback))?;
+            }
             cx.update(|window, cx| {
                 cx.new(|cx| {
                     TerminalView::new(
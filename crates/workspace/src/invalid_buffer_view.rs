@@ -5,7 +5,7 @@ use ui::{
     App, Button, ButtonCommon, ButtonStyle, Clickable, Context, FluentBuilder, InteractiveElement,
     KeyBinding, ParentElement, Render, SharedString, Styled as _, Window, h_flex, v_flex,
 };
-use zed_actions::workspace::OpenWithSystem;
+use zed_actions::{hex_editor, workspace::OpenWithSystem};
 
 use crate::Item;
 
@@ -90,20 +90,38 @@ impl Render for InvalidBufferView {
                         .gap_2()
                         .child(h_flex().justify_center().child("Unsupported file type"))
                         .when(self.is_local, |contents| {
-                            contents.child(
-                                h_flex().justify_center().child(
-                                    Button::new("open-with-system", "Open in Default App")
-                                        .on_click(move |_, _, cx| {
-                                            cx.open_with_system(&abs_path);
-                                        })
-                                        .style(ButtonStyle::Outlined)
-                                        .key_binding(KeyBinding::for_action(
-                                            &OpenWithSystem,
-                                            window,
-                                            cx,
-                                        )),
-                                ),
-                            )
+                            contents
+                                .child(
+                                    h_flex().justify_center().child(
+                                        Button::new("open-with-system", "Open in Default App")
+                                            .on_click(move |_, _, cx| {
+                                                cx.open_with_system(&abs_path);
+                                            })
+                                            .style(ButtonStyle::Outlined)
+                                            .key_binding(KeyBinding::for_action(
+                                                &OpenWithSystem,
+                                                window,
+                                                cx,
+                                            )),
+                                    ),
+                                )
+                                .child(
+                                    h_flex().justify_center().child(
+                                        Button::new("open-in-hex-editor", "Open in Hex Editor")
+                                            .on_click(|_, window, cx| {
+                                                window.dispatch_action(
+                                                    Box::new(hex_editor::OpenActiveFile),
+                                                    cx,
+                                                );
+                                            })
+                                            .style(ButtonStyle::Outlined)
+                                            .key_binding(KeyBinding::for_action(
+                                                &hex_editor::OpenActiveFile,
+                                                window,
+                                                cx,
+                                            )),
+                                    ),
+                                )
                         }),
                 ),
             )
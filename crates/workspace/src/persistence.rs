@@ -16,7 +16,10 @@ use db::{
     sqlez_macros::sql,
 };
 use gpui::{Axis, Bounds, Task, WindowBounds, WindowId, point, size};
-use project::debugger::breakpoint_store::{BreakpointState, SourceBreakpoint};
+use project::{
+    bookmark_store::SerializedBookmark,
+    debugger::breakpoint_store::{BreakpointState, SourceBreakpoint},
+};
 
 use language::{LanguageName, Toolchain};
 use project::WorktreeId;
@@ -711,6 +714,17 @@ impl Domain for WorkspaceDb {
 
             CREATE UNIQUE INDEX ix_workspaces_location ON workspaces(remote_connection_id, paths);
         ),
+        sql!(
+            CREATE TABLE bookmarks (
+                workspace_id INTEGER NOT NULL,
+                path TEXT NOT NULL,
+                bookmark_row INTEGER NOT NULL,
+                label TEXT,
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            );
+        ),
     ];
 
     // Allow recovering from bad migration that was initially shipped to nightly
@@ -830,6 +844,7 @@ impl WorkspaceDb {
             docks,
             session_id: None,
             breakpoints: self.breakpoints(workspace_id),
+            bookmarks: self.bookmarks(workspace_id),
             window_id,
         })
     }
@@ -880,6 +895,38 @@ impl WorkspaceDb {
         }
     }
 
+    fn bookmarks(&self, workspace_id: WorkspaceId) -> BTreeMap<Arc<Path>, Vec<SerializedBookmark>> {
+        let bookmarks: Result<Vec<(PathBuf, u32, Option<String>)>> = self
+            .select_bound(sql! {
+                SELECT path, bookmark_row, label
+                FROM bookmarks
+                WHERE workspace_id = ?
+                ORDER BY path, bookmark_row
+            })
+            .and_then(|mut prepared_statement| (prepared_statement)(workspace_id));
+
+        match bookmarks {
+            Ok(bookmarks) => {
+                let mut map: BTreeMap<Arc<Path>, Vec<SerializedBookmark>> = Default::default();
+                for (path, row, label) in bookmarks {
+                    let path: Arc<Path> = path.into();
+                    map.entry(path.clone())
+                        .or_default()
+                        .push(SerializedBookmark {
+                            path,
+                            row,
+                            label: label.map(Arc::from),
+                        });
+                }
+                map
+            }
+            Err(msg) => {
+                log::error!("Bookmarks query failed with msg: {msg}");
+                Default::default()
+            }
+        }
+    }
+
     /// Saves a workspace using the worktree roots. Will garbage collect any workspaces
     /// that used this workspace previously
     pub(crate) async fn save_workspace(&self, workspace: SerializedWorkspace) {
@@ -936,6 +983,27 @@ impl WorkspaceDb {
                     }
                 }
 
+                conn.exec_bound(
+                    sql!(
+                        DELETE FROM bookmarks WHERE workspace_id = ?1;
+                    )
+                )?(workspace.id).context("Clearing old bookmarks")?;
+
+                for (path, bookmarks) in workspace.bookmarks {
+                    for bookmark in bookmarks {
+                        conn.exec_bound(sql!(
+                            INSERT INTO bookmarks (workspace_id, path, bookmark_row, label)
+                            VALUES (?1, ?2, ?3, ?4);
+                        ))?((
+                            workspace.id,
+                            path.as_ref(),
+                            bookmark.row,
+                            bookmark.label,
+                        ))
+                        .context("Storing bookmark")?;
+                    }
+                }
+
                 conn.exec_bound(sql!(
                     DELETE
                     FROM workspaces
@@ -1795,6 +1863,7 @@ mod tests {
                 );
                 map
             },
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1874,6 +1943,52 @@ mod tests {
         assert_eq!(loaded_breakpoints[4].path, Arc::from(path));
     }
 
+    #[gpui::test]
+    async fn test_bookmarks() {
+        zlog::init_test();
+
+        let db = WorkspaceDb::open_test_db("test_bookmarks").await;
+        let id = db.next_id().await.unwrap();
+
+        let path: Arc<Path> = Arc::from(Path::new("/tmp/test.rs"));
+        let bookmarks = vec![
+            SerializedBookmark {
+                path: path.clone(),
+                row: 3,
+                label: None,
+            },
+            SerializedBookmark {
+                path: path.clone(),
+                row: 42,
+                label: Some("entry point".into()),
+            },
+        ];
+
+        let mut workspace = SerializedWorkspace {
+            id,
+            paths: PathList::new(&["/tmp"]),
+            location: SerializedWorkspaceLocation::Local,
+            center_group: Default::default(),
+            window_bounds: Default::default(),
+            display: Default::default(),
+            docks: Default::default(),
+            centered_layout: false,
+            breakpoints: Default::default(),
+            bookmarks: BTreeMap::from_iter([(path.clone(), bookmarks.clone())]),
+            session_id: None,
+            window_id: None,
+        };
+
+        db.save_workspace(workspace.clone()).await;
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert_eq!(loaded.bookmarks.get(&path), Some(&bookmarks));
+
+        workspace.bookmarks.clear();
+        db.save_workspace(workspace).await;
+        let loaded = db.workspace_for_roots(&["/tmp"]).unwrap();
+        assert!(loaded.bookmarks.is_empty());
+    }
+
     #[gpui::test]
     async fn test_remove_last_breakpoint() {
         zlog::init_test();
@@ -1915,6 +2030,7 @@ mod tests {
                 );
                 map
             },
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -1948,6 +2064,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: collections::BTreeMap::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -2045,6 +2162,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -2059,6 +2177,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -2162,6 +2281,7 @@ mod tests {
             center_group,
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2195,6 +2315,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2212,6 +2333,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: Some(2),
         };
@@ -2250,6 +2372,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2290,6 +2413,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(10),
         };
@@ -2304,6 +2428,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-1".to_owned()),
             window_id: Some(20),
         };
@@ -2318,6 +2443,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(30),
         };
@@ -2332,6 +2458,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: None,
             window_id: None,
         };
@@ -2357,6 +2484,7 @@ mod tests {
             docks: Default::default(),
             centered_layout: false,
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             session_id: Some("session-id-2".to_owned()),
             window_id: Some(50),
         };
@@ -2368,6 +2496,7 @@ mod tests {
             center_group: Default::default(),
             window_bounds: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             display: Default::default(),
             docks: Default::default(),
             centered_layout: false,
@@ -2421,6 +2550,7 @@ mod tests {
             display: Default::default(),
             docks: Default::default(),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             centered_layout: false,
             session_id: None,
             window_id: None,
@@ -2457,6 +2587,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             window_id: Some(window_id),
         })
         .collect::<Vec<_>>();
@@ -2554,6 +2685,7 @@ mod tests {
             centered_layout: false,
             session_id: Some("one-session".to_owned()),
             breakpoints: Default::default(),
+            bookmarks: Default::default(),
             window_id: Some(window_id),
         })
         .collect::<Vec<_>>();
@@ -1,11 +1,15 @@
 use std::process::ExitStatus;
 
 use anyhow::Result;
+use futures::{
+    FutureExt as _,
+    future::{LocalBoxFuture, Shared, join_all},
+};
 use gpui::{AppContext, Context, Entity, Task};
 use language::Buffer;
 use project::{TaskSourceKind, WorktreeId};
 use remote::ConnectionState;
-use task::{DebugScenario, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
+use task::{DebugScenario, DependsOrder, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
 use ui::Window;
 
 use crate::Workspace;
@@ -54,6 +58,17 @@ impl Workspace {
         window: &mut Window,
         cx: &mut Context<Workspace>,
     ) {
+        if resolved_task.pending_inputs().next().is_some() {
+            self.schedule_task_with_inputs(
+                task_source_kind,
+                resolved_task,
+                omit_history,
+                window,
+                cx,
+            );
+            return;
+        }
+
         let spawn_in_terminal = resolved_task.resolved.clone();
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
@@ -65,12 +80,17 @@ impl Workspace {
                     project.task_store().read(cx).task_inventory().cloned()
                 {
                     task_inventory.update(cx, |inventory, _| {
-                        inventory.task_scheduled(task_source_kind, resolved_task);
+                        inventory.task_scheduled(task_source_kind.clone(), resolved_task.clone());
                     })
                 }
             });
         }
 
+        if !resolved_task.original_task().depends_on.is_empty() {
+            self.schedule_task_with_dependencies(task_source_kind, resolved_task, window, cx);
+            return;
+        }
+
         if let Some(terminal_provider) = self.terminal_provider.as_ref() {
             let task_status = terminal_provider.spawn(spawn_in_terminal, window, cx);
             let task = cx.background_spawn(async move {
@@ -90,6 +110,172 @@ impl Workspace {
         }
     }
 
+    /// Asks the user for the values of the task's inputs, then resolves the task with them and schedules it.
+    fn schedule_task_with_inputs(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        omit_history: bool,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(task_inputs_provider) = self.task_inputs_provider.as_ref() else {
+            log::warn!(
+                "Cannot ask for the inputs of task `{}`",
+                resolved_task.resolved_label
+            );
+            return;
+        };
+        let last_values = self
+            .project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .map(|inventory| {
+                inventory
+                    .read(cx)
+                    .last_input_values(&task_source_kind, resolved_task.original_task())
+            })
+            .unwrap_or_default();
+        let input_values =
+            task_inputs_provider.request_inputs(&resolved_task, last_values, window, cx);
+
+        let task = cx.spawn_in(window, async move |workspace, cx| {
+            let input_values = match input_values.await {
+                Ok(Some(input_values)) => input_values,
+                Ok(None) => return,
+                Err(e) => {
+                    workspace
+                        .update(cx, |workspace, cx| workspace.show_error(&e, cx))
+                        .ok();
+                    return;
+                }
+            };
+            let Some(resolved_task) = resolved_task.with_input_values(input_values) else {
+                log::error!(
+                    "Failed to resolve task `{}` with its inputs",
+                    resolved_task.resolved_label
+                );
+                return;
+            };
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.schedule_resolved_task(
+                        task_source_kind,
+                        resolved_task,
+                        omit_history,
+                        window,
+                        cx,
+                    )
+                })
+                .ok();
+        });
+        self.scheduled_tasks.push(task);
+    }
+
+    /// Spawns every task of the dependency graph in its own terminal, each one after its dependencies succeeded.
+    /// If any dependency fails, the tasks depending on it are not spawned.
+    fn schedule_task_with_dependencies(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(task_inventory) = self
+            .project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned()
+        else {
+            return;
+        };
+        let nodes = match task_inventory
+            .read(cx)
+            .task_dependency_graph(&task_source_kind, &resolved_task)
+        {
+            Ok(nodes) => nodes,
+            Err(e) => {
+                self.show_error(&e, cx);
+                return;
+            }
+        };
+
+        let task = cx.spawn_in(window, async move |workspace, cx| {
+            let mut runs = Vec::<Shared<LocalBoxFuture<'static, bool>>>::new();
+            for node in nodes {
+                let dependencies = node
+                    .dependencies
+                    .iter()
+                    .map(|&ix| runs[ix].clone())
+                    .collect::<Vec<_>>();
+                let depends_order = node.task.original_task().depends_order;
+                let workspace = workspace.clone();
+                let mut cx = cx.clone();
+                let run = async move {
+                    let dependencies_succeeded = match depends_order {
+                        DependsOrder::Parallel => join_all(dependencies)
+                            .await
+                            .into_iter()
+                            .all(|succeeded| succeeded),
+                        DependsOrder::Sequence => {
+                            let mut succeeded = true;
+                            for dependency in dependencies {
+                                if !dependency.await {
+                                    succeeded = false;
+                                    break;
+                                }
+                            }
+                            succeeded
+                        }
+                    };
+                    let label = node.task.resolved_label;
+                    if !dependencies_succeeded {
+                        log::warn!(
+                            "Not spawning task `{label}`, as one of its dependencies failed"
+                        );
+                        return false;
+                    }
+                    if node.task.original_task().command.trim().is_empty() {
+                        return true;
+                    }
+
+                    let Ok(task_status) = workspace.update_in(&mut cx, |workspace, window, cx| {
+                        workspace.spawn_in_terminal(node.task.resolved, window, cx)
+                    }) else {
+                        return false;
+                    };
+                    match task_status.await {
+                        Some(Ok(status)) if status.success() => {
+                            log::debug!("Task `{label}` succeeded");
+                            true
+                        }
+                        Some(Ok(status)) => {
+                            log::debug!("Task `{label}` failed, code: {:?}", status.code());
+                            false
+                        }
+                        Some(Err(e)) => {
+                            log::error!("Task `{label}` spawn failed: {e:#}");
+                            false
+                        }
+                        None => {
+                            log::debug!("Task `{label}` got cancelled");
+                            false
+                        }
+                    }
+                };
+                runs.push(run.boxed_local().shared());
+            }
+            if let Some(run) = runs.pop() {
+                run.await;
+            }
+        });
+        self.scheduled_tasks.push(task);
+    }
+
     pub fn start_debug_session(
         &mut self,
         scenario: DebugScenario,
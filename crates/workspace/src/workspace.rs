@@ -72,6 +72,7 @@ pub use persistence::{
 use postage::stream::Stream;
 use project::{
     DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath, Worktree, WorktreeId,
+    bookmark_store::BookmarkStoreEvent,
     debugger::{breakpoint_store::BreakpointStoreEvent, session::ThreadStatus},
 };
 use remote::{RemoteClientDelegate, RemoteConnectionOptions, remote_client::ConnectionIdentifier};
@@ -100,7 +101,7 @@ use std::{
     sync::{Arc, LazyLock, Weak, atomic::AtomicUsize},
     time::Duration,
 };
-use task::{DebugScenario, SpawnInTerminal, TaskContext};
+use task::{DebugScenario, ResolvedTask, SpawnInTerminal, TaskContext};
 use theme::{ActiveTheme, SystemAppearance, ThemeSettings};
 pub use toolbar::{Toolbar, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
 pub use ui;
@@ -143,6 +144,18 @@ pub trait TerminalProvider {
     ) -> Task<Option<Result<ExitStatus>>>;
 }
 
+pub trait TaskInputsProvider {
+    /// Asks the user for the values of the task's pending inputs, suggesting the `last_values` given to them.
+    /// Resolves to `None` if the user dismissed any of the questions.
+    fn request_inputs(
+        &self,
+        task: &ResolvedTask,
+        last_values: HashMap<String, String>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Result<Option<HashMap<String, String>>>>;
+}
+
 pub trait DebuggerProvider {
     // `active_buffer` is used to resolve build task's name against language-specific tasks.
     fn start_session(
@@ -1141,6 +1154,7 @@ pub struct Workspace {
     on_prompt_for_new_path: Option<PromptForNewPath>,
     on_prompt_for_open_path: Option<PromptForOpenPath>,
     terminal_provider: Option<Box<dyn TerminalProvider>>,
+    task_inputs_provider: Option<Box<dyn TaskInputsProvider>>,
     debugger_provider: Option<Arc<dyn DebuggerProvider>>,
     serializable_items_tx: UnboundedSender<Box<dyn SerializableItemHandle>>,
     _items_serializer: Task<Result<()>>,
@@ -1270,7 +1284,21 @@ impl Workspace {
                 | BreakpointStoreEvent::BreakpointsCleared(_) => {
                     workspace.serialize_workspace(window, cx);
                 }
-                BreakpointStoreEvent::SetDebugLine | BreakpointStoreEvent::ClearDebugLines => {}
+                BreakpointStoreEvent::FunctionBreakpointsUpdated
+                | BreakpointStoreEvent::SetDebugLine
+                | BreakpointStoreEvent::ClearDebugLines => {}
+            },
+        )
+        .detach();
+
+        cx.subscribe_in(
+            &project.read(cx).bookmark_store(),
+            window,
+            |workspace, _, event, window, cx| match event {
+                BookmarkStoreEvent::BookmarksUpdated(_)
+                | BookmarkStoreEvent::BookmarksCleared(_) => {
+                    workspace.serialize_workspace(window, cx);
+                }
             },
         )
         .detach();
@@ -1471,6 +1499,7 @@ impl Workspace {
             on_prompt_for_new_path: None,
             on_prompt_for_open_path: None,
             terminal_provider: None,
+            task_inputs_provider: None,
             debugger_provider: None,
             serializable_items_tx,
             _items_serializer,
@@ -2029,6 +2058,10 @@ impl Workspace {
         self.terminal_provider = Some(Box::new(provider));
     }
 
+    pub fn set_task_inputs_provider(&mut self, provider: impl TaskInputsProvider + 'static) {
+        self.task_inputs_provider = Some(Box::new(provider));
+    }
+
     pub fn set_debugger_provider(&mut self, provider: impl DebuggerProvider + 'static) {
         self.debugger_provider = Some(Arc::new(provider));
     }
@@ -5227,6 +5260,12 @@ impl Workspace {
                         .read(cx)
                         .all_source_breakpoints(cx)
                 });
+                let bookmarks = self.project.update(cx, |project, cx| {
+                    project
+                        .bookmark_store()
+                        .read(cx)
+                        .all_serialized_bookmarks(cx)
+                });
 
                 let center_group = build_serialized_pane_group(&self.center.root, window, cx);
                 let docks = build_serialized_docks(self, window, cx);
@@ -5242,6 +5281,7 @@ impl Workspace {
                     centered_layout: self.centered_layout,
                     session_id: self.session_id.clone(),
                     breakpoints,
+                    bookmarks,
                     window_id: Some(window.window_handle().window_id().as_u64()),
                 };
 
@@ -5427,6 +5467,14 @@ impl Workspace {
                         })
                 })?
                 .await;
+            project
+                .update(cx, |project, cx| {
+                    project.bookmark_store().update(cx, |bookmark_store, cx| {
+                        bookmark_store.with_serialized_bookmarks(serialized_workspace.bookmarks, cx)
+                    })
+                })?
+                .await
+                .log_err();
 
             // Clean up all the items that have _not_ been loaded. Our ItemIds aren't stable. That means
             // after loading the items, we might have different items and in order to avoid
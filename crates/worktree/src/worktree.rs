@@ -7,7 +7,7 @@ use ::ignore::gitignore::{Gitignore, GitignoreBuilder};
 use anyhow::{Context as _, Result, anyhow};
 use clock::ReplicaId;
 use collections::{HashMap, HashSet, VecDeque};
-use fs::{Fs, MTime, PathEvent, RemoveOptions, Watcher, copy_recursive, read_dir_items};
+use fs::{Encoding, Fs, MTime, PathEvent, RemoveOptions, Watcher, copy_recursive, read_dir_items};
 use futures::{
     FutureExt as _, Stream, StreamExt,
     channel::{
@@ -52,6 +52,7 @@ use std::{
     ffi::OsStr,
     fmt,
     future::Future,
+    io,
     mem::{self},
     ops::{Deref, DerefMut},
     path::{Component, Path, PathBuf},
@@ -100,6 +101,14 @@ pub enum CreatedEntry {
 pub struct LoadedFile {
     pub file: Arc<File>,
     pub text: String,
+    pub encoding: Encoding,
+}
+
+pub struct LoadedLargeFile {
+    pub file: Arc<File>,
+    pub text: Rope,
+    pub line_ending: LineEnding,
+    pub encoding: Encoding,
 }
 
 pub struct LoadedBinaryFile {
@@ -476,6 +485,12 @@ pub enum Event {
 
 const EMPTY_PATH: &str = "";
 
+/// Files of this size or larger aren't loaded at all. See the comment in [`LocalWorktree::load_file`].
+const FILE_SIZE_MAX: u64 = 6 * 1024 * 1024 * 1024; // 6GB
+
+/// How many bytes are read at a time when streaming a large file from disk.
+const LARGE_FILE_CHUNK_LEN: usize = 1024 * 1024;
+
 impl EventEmitter<Event> for Worktree {}
 
 impl Worktree {
@@ -812,6 +827,21 @@ impl Worktree {
         }
     }
 
+    /// Loads a file by streaming it from disk in chunks, for files too large to comfortably
+    /// read into memory all at once.
+    pub fn load_large_file(
+        &self,
+        path: &Path,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<LoadedLargeFile>> {
+        match self {
+            Worktree::Local(this) => this.load_large_file(path, cx),
+            Worktree::Remote(_) => {
+                Task::ready(Err(anyhow!("remote worktrees can't yet load files")))
+            }
+        }
+    }
+
     pub fn load_binary_file(
         &self,
         path: &Path,
@@ -830,10 +860,11 @@ impl Worktree {
         path: &Path,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &Context<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         match self {
-            Worktree::Local(this) => this.write_file(path, text, line_ending, cx),
+            Worktree::Local(this) => this.write_file(path, text, line_ending, encoding, cx),
             Worktree::Remote(_) => {
                 Task::ready(Err(anyhow!("remote worktree can't yet write files")))
             }
@@ -1521,14 +1552,13 @@ impl LocalWorktree {
             //       5GB seems to be more reasonable, peaking at ~16GB, while 6GB jumps up to >24GB which seems like a
             //       reasonable limit
             {
-                const FILE_SIZE_MAX: u64 = 6 * 1024 * 1024 * 1024; // 6GB
                 if let Ok(Some(metadata)) = fs.metadata(&abs_path).await
                     && metadata.len >= FILE_SIZE_MAX
                 {
                     anyhow::bail!("File is too large to load");
                 }
             }
-            let text = fs.load(&abs_path).await?;
+            let (text, encoding) = fs.load_with_encoding(&abs_path, None).await?;
 
             let worktree = this.upgrade().context("worktree was dropped")?;
             let file = match entry.await? {
@@ -1556,7 +1586,69 @@ impl LocalWorktree {
                 }
             };
 
-            Ok(LoadedFile { file, text })
+            Ok(LoadedFile {
+                file,
+                text,
+                encoding,
+            })
+        })
+    }
+
+    fn load_large_file(
+        &self,
+        path: &Path,
+        cx: &Context<Worktree>,
+    ) -> Task<Result<LoadedLargeFile>> {
+        let path = Arc::from(path);
+        let abs_path = self.absolutize(&path);
+        let fs = self.fs.clone();
+        let entry = self.refresh_entry(path.clone(), None, cx);
+        let is_private = self.is_path_private(path.as_ref());
+
+        let worktree = cx.weak_entity();
+        cx.background_spawn(async move {
+            let abs_path = abs_path?;
+            if let Ok(Some(metadata)) = fs.metadata(&abs_path).await
+                && metadata.len >= FILE_SIZE_MAX
+            {
+                anyhow::bail!("File is too large to load");
+            }
+            let reader = fs.open_sync(&abs_path).await?;
+            let (text, line_ending, encoding) =
+                read_large_file(reader, None).with_context(|| format!("reading {abs_path:?}"))?;
+
+            let worktree = worktree.upgrade().context("worktree was dropped")?;
+            let file = match entry.await? {
+                Some(entry) => File::for_entry(entry, worktree),
+                None => {
+                    let metadata = fs
+                        .metadata(&abs_path)
+                        .await
+                        .with_context(|| {
+                            format!("Loading metadata for excluded file {abs_path:?}")
+                        })?
+                        .with_context(|| {
+                            format!("Excluded file {abs_path:?} got removed during loading")
+                        })?;
+                    Arc::new(File {
+                        entry_id: None,
+                        worktree,
+                        path,
+                        disk_state: DiskState::Present {
+                            mtime: metadata.mtime,
+                        },
+                        is_local: true,
+                        is_private,
+                    })
+                }
+            };
+
+            Ok(LoadedLargeFile {
+                file,
+                text,
+                line_ending,
+                encoding,
+            })
         })
     }
 
@@ -1643,6 +1735,7 @@ impl LocalWorktree {
         path: impl Into<Arc<Path>>,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &Context<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         let path = path.into();
@@ -1655,7 +1748,10 @@ impl LocalWorktree {
         let write = cx.background_spawn({
             let fs = fs.clone();
             let abs_path = abs_path.clone();
-            async move { fs.save(&abs_path, &text, line_ending).await }
+            async move {
+                fs.save_with_encoding(&abs_path, &text, line_ending, encoding)
+                    .await
+            }
         });
 
         cx.spawn(async move |this, cx| {
@@ -3187,6 +3283,65 @@ impl BackgroundScannerState {
     }
 }
 
+/// Decodes a file chunk by chunk, normalizing line endings as it goes, so that the whole file
+/// is never held in memory as both raw bytes and text. The encoding is detected from the start
+/// of the file unless one is given.
+fn read_large_file(
+    mut reader: impl io::Read,
+    encoding: Option<Encoding>,
+) -> Result<(Rope, LineEnding, Encoding)> {
+    let mut bytes = vec![0; LARGE_FILE_CHUNK_LEN];
+    let mut len = read_chunk(&mut reader, &mut bytes)?;
+    let encoding = match encoding {
+        Some(encoding) => encoding,
+        None => Encoding::detect_prefix(&bytes[..len])
+            .context("file does not appear to be a text file")?,
+    };
+    let mut decoder = encoding.streaming_decoder();
+
+    let mut text = Rope::new();
+    let mut line_ending = None;
+    let mut chunk = String::new();
+    loop {
+        let last = len < bytes.len();
+        decoder.decode_chunk(&bytes[..len], last, &mut chunk)?;
+
+        // A trailing carriage return may be the first half of a CRLF split across chunks.
+        let carriage_return = !last && chunk.ends_with('\r');
+        if carriage_return {
+            chunk.pop();
+        }
+        line_ending.get_or_insert_with(|| LineEnding::detect(&chunk));
+        LineEnding::normalize(&mut chunk);
+        text.push(&chunk);
+        chunk.clear();
+        if carriage_return {
+            chunk.push('\r');
+        }
+
+        if last {
+            break;
+        }
+        len = read_chunk(&mut reader, &mut bytes)?;
+    }
+
+    Ok((text, line_ending.unwrap_or_default(), encoding))
+}
+
+/// Fills the buffer from the reader, stopping early only at the end of the file.
+fn read_chunk(reader: &mut impl io::Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buffer.len() {
+        match reader.read(&mut buffer[len..]) {
+            Ok(0) => break,
+            Ok(read) => len += read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(len)
+}
+
 async fn is_git_dir(path: &Path, fs: &dyn Fs) -> bool {
     if path.file_name() == Some(*DOT_GIT) {
         return true;
@@ -3345,12 +3500,39 @@ impl language::LocalFile for File {
         cx.background_spawn(async move { fs.load(&abs_path?).await })
     }
 
+    fn load_with_encoding(&self, encoding: Encoding, cx: &App) -> Task<Result<String>> {
+        let worktree = self.worktree.read(cx).as_local().unwrap();
+        let abs_path = worktree.absolutize(&self.path);
+        let fs = worktree.fs.clone();
+        cx.background_spawn(async move {
+            let (text, _) = fs.load_with_encoding(&abs_path?, Some(encoding)).await?;
+            Ok(text)
+        })
+    }
+
     fn load_bytes(&self, cx: &App) -> Task<Result<Vec<u8>>> {
         let worktree = self.worktree.read(cx).as_local().unwrap();
         let abs_path = worktree.absolutize(&self.path);
         let fs = worktree.fs.clone();
         cx.background_spawn(async move { fs.load_bytes(&abs_path?).await })
     }
+
+    fn load_large_with_encoding(
+        &self,
+        encoding: Encoding,
+        cx: &App,
+    ) -> Task<Result<(Rope, LineEnding)>> {
+        let worktree = self.worktree.read(cx).as_local().unwrap();
+        let abs_path = worktree.absolutize(&self.path);
+        let fs = worktree.fs.clone();
+        cx.background_spawn(async move {
+            let abs_path = abs_path?;
+            let reader = fs.open_sync(&abs_path).await?;
+            let (text, line_ending, _) = read_large_file(reader, Some(encoding))
+                .with_context(|| format!("reading {abs_path:?}"))?;
+            Ok((text, line_ending))
+        })
+    }
 }
 
 impl File {
@@ -598,6 +598,7 @@ pub fn main() {
         app_state.languages.set_theme(cx.theme().clone());
         editor::init(cx);
         image_viewer::init(cx);
+        hex_editor::init(cx);
         repl::notebook::init(cx);
         diagnostics::init(cx);
 
@@ -606,6 +607,10 @@ pub fn main() {
         ui_prompt::init(cx);
 
         go_to_line::init(cx);
+        bookmarks::init(cx);
+        local_history::init(app_state.fs.clone(), cx);
+        editor_macros::init(app_state.fs.clone(), cx);
+        spell_check::init(app_state.fs.clone(), cx);
         file_finder::init(cx);
         tab_switcher::init(cx);
         outline::init(cx);
@@ -620,6 +625,7 @@ pub fn main() {
         terminal_view::init(cx);
         journal::init(app_state.clone(), cx);
         language_selector::init(cx);
+        encoding_selector::init(cx);
         toolchain_selector::init(cx);
         theme_selector::init(cx);
         settings_profile_selector::init(cx);
@@ -0,0 +1,99 @@
+use editor::Editor;
+use gpui::{Entity, EventEmitter, Subscription};
+use language::{Buffer, BufferEvent};
+use ui::prelude::*;
+use workspace::item::ItemHandle;
+use workspace::{ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
+
+/// Explains why features are missing from files opened in large-file mode, and lets them be
+/// turned back on.
+pub struct LargeFileBanner {
+    buffer: Option<Entity<Buffer>>,
+    _buffer_subscription: Option<Subscription>,
+}
+
+impl LargeFileBanner {
+    pub fn new() -> Self {
+        Self {
+            buffer: None,
+            _buffer_subscription: None,
+        }
+    }
+
+    fn location(&self, cx: &App) -> ToolbarItemLocation {
+        if self
+            .buffer
+            .as_ref()
+            .is_some_and(|buffer| buffer.read(cx).large_file_mode())
+        {
+            ToolbarItemLocation::Secondary
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+}
+
+impl EventEmitter<ToolbarItemEvent> for LargeFileBanner {}
+
+impl ToolbarItemView for LargeFileBanner {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> ToolbarItemLocation {
+        self.buffer = active_pane_item
+            .and_then(|item| item.act_as::<Editor>(cx))
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton());
+        self._buffer_subscription = self.buffer.as_ref().map(|buffer| {
+            cx.subscribe(buffer, |this, _, event, cx| {
+                if let BufferEvent::LargeFileModeChanged = event {
+                    cx.emit(ToolbarItemEvent::ChangeLocation(this.location(cx)));
+                    cx.notify();
+                }
+            })
+        });
+        self.location(cx)
+    }
+}
+
+impl Render for LargeFileBanner {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let buffer = self.buffer.clone();
+        h_flex()
+            .py_1()
+            .pl_2()
+            .pr_1()
+            .justify_between()
+            .bg(cx.theme().status().info_background.opacity(0.6))
+            .border_1()
+            .border_color(cx.theme().colors().border_variant)
+            .rounded_sm()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .overflow_hidden()
+                    .child(
+                        Icon::new(IconName::Info)
+                            .size(IconSize::XSmall)
+                            .color(Color::Info),
+                    )
+                    .child(
+                        Label::new(
+                            "This file is large, so syntax highlighting, language servers, \
+                            git diffs and soft wrap are turned off.",
+                        )
+                        .size(LabelSize::Small),
+                    ),
+            )
+            .child(
+                Button::new("enable-large-file-features", "Enable All Features").on_click(
+                    move |_, _, cx| {
+                        if let Some(buffer) = &buffer {
+                            buffer.update(cx, |buffer, cx| buffer.set_large_file_mode(false, cx));
+                        }
+                    },
+                ),
+            )
+    }
+}
@@ -183,6 +183,18 @@ pub mod git {
     );
 }
 
+pub mod hex_editor {
+    use gpui::actions;
+
+    actions!(
+        hex_editor,
+        [
+            /// Opens the active file in the hex editor.
+            OpenActiveFile
+        ]
+    );
+}
+
 pub mod jj {
     use gpui::actions;
 